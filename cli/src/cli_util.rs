@@ -20,17 +20,20 @@ use std::env;
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::Debug;
+use std::fs;
 use std::io;
 use std::io::Write as _;
 use std::iter;
 use std::mem;
 use std::path::Path;
 use std::path::PathBuf;
+use std::process;
 use std::process::ExitCode;
 use std::rc::Rc;
 use std::str;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use std::time::SystemTime;
 
 use bstr::ByteVec as _;
@@ -64,14 +67,21 @@ use jj_lib::config::ConfigMigrationRule;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
+use jj_lib::conflicts::ConflictMarkerOverride;
+use jj_lib::conflicts::ConflictMarkerOverrides;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+#[cfg(target_os = "linux")]
+use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::id_prefix::IdPrefixContext;
+#[cfg(target_os = "linux")]
+use jj_lib::local_working_copy::LocalWorkingCopy;
 use jj_lib::matchers::Matcher;
+use jj_lib::merge::ConflictKind;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId;
@@ -90,6 +100,7 @@ use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::repo::RepoLoader;
+use jj_lib::repo::RepoLoaderError;
 use jj_lib::repo::StoreFactories;
 use jj_lib::repo::StoreLoadError;
 use jj_lib::repo_path::RepoPath;
@@ -115,10 +126,13 @@ use jj_lib::settings::HumanByteSize;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
 use jj_lib::transaction::Transaction;
+use jj_lib::transaction::UnpublishedOperation;
 use jj_lib::view::View;
 use jj_lib::working_copy;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::SnapshotFileAction;
+use jj_lib::working_copy::SnapshotFilePolicy;
 use jj_lib::working_copy::SnapshotOptions;
 use jj_lib::working_copy::SnapshotStats;
 use jj_lib::working_copy::UntrackedReason;
@@ -182,6 +196,9 @@ use crate::ui::Ui;
 
 const SHORT_CHANGE_ID_TEMPLATE_TEXT: &str = "format_short_change_id(self.change_id())";
 
+/// Minimum interval between automatic garbage collections.
+const AUTO_GC_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
 #[derive(Clone)]
 struct ChromeTracingFlushGuard {
     _inner: Option<Rc<tracing_chrome::FlushGuard>>,
@@ -511,6 +528,17 @@ impl CommandHelper {
             .map_err(|err| map_workspace_load_error(err, None))
     }
 
+    /// Loads the repo located at the specified path. The operation heads are
+    /// the ones shared by the workspaces, not the ones of a sandbox workspace.
+    pub fn load_repo_loader_at(&self, repo_path: &Path) -> Result<RepoLoader, CommandError> {
+        RepoLoader::init_from_file_system(
+            &self.data.settings,
+            repo_path,
+            &self.data.store_factories,
+        )
+        .map_err(|err| map_workspace_load_error(err.into(), None))
+    }
+
     /// Note that unless you have a good reason not to do so, you should always
     /// call [`print_snapshot_stats`] with the [`SnapshotStats`] returned by
     /// this function to present possible untracked files to the user.
@@ -660,11 +688,9 @@ impl CommandHelper {
                             )?;
                         }
                     }
-                    Ok(tx
-                        .write("reconcile divergent operations")
-                        .leave_unpublished()
-                        .operation()
-                        .clone())
+                    let unpublished_op = tx.write("reconcile divergent operations");
+                    print_operation_sign_error(ui, &unpublished_op)?;
+                    Ok(unpublished_op.leave_unpublished().operation().clone())
                 },
             )
         }
@@ -785,6 +811,8 @@ pub struct WorkspaceCommandEnvironment {
     immutable_heads_expression: Rc<UserRevsetExpression>,
     short_prefixes_expression: Option<Rc<UserRevsetExpression>>,
     conflict_marker_style: ConflictMarkerStyle,
+    conflict_marker_overrides: Vec<ConflictMarkerOverride>,
+    snapshot_file_policies: Vec<SnapshotFilePolicy>,
 }
 
 impl WorkspaceCommandEnvironment {
@@ -807,6 +835,8 @@ impl WorkspaceCommandEnvironment {
             immutable_heads_expression: RevsetExpression::root(),
             short_prefixes_expression: None,
             conflict_marker_style: settings.get("ui.conflict-marker-style")?,
+            conflict_marker_overrides: load_conflict_marker_overrides(ui, settings)?,
+            snapshot_file_policies: load_snapshot_file_policies(ui, settings)?,
         };
         env.immutable_heads_expression = env.load_immutable_heads_expression(ui)?;
         env.short_prefixes_expression = env.load_short_prefixes_expression(ui)?;
@@ -869,6 +899,32 @@ impl WorkspaceCommandEnvironment {
         self.conflict_marker_style
     }
 
+    /// User-configured per-path conflict marker style and length
+    pub fn conflict_marker_overrides(&self) -> &[ConflictMarkerOverride] {
+        &self.conflict_marker_overrides
+    }
+
+    /// Chooser of the conflict marker style and length of each path
+    pub fn conflict_markers(&self) -> ConflictMarkerOverrides {
+        ConflictMarkerOverrides::new(self.conflict_marker_style, &self.conflict_marker_overrides)
+    }
+
+    /// User-configured policies deciding how to snapshot matching files
+    pub fn snapshot_file_policies(&self) -> &[SnapshotFilePolicy] {
+        &self.snapshot_file_policies
+    }
+
+    /// Files that may be stored as pointers by the snapshot policies
+    pub fn pointer_paths(&self) -> FilesetExpression {
+        FilesetExpression::union_all(
+            self.snapshot_file_policies
+                .iter()
+                .filter(|policy| policy.action == SnapshotFileAction::Pointer)
+                .map(|policy| policy.paths.clone())
+                .collect(),
+        )
+    }
+
     fn load_immutable_heads_expression(
         &self,
         ui: &Ui,
@@ -1084,7 +1140,8 @@ impl WorkspaceCommandHelper {
             let hint = if self.env.command.global_args().ignore_working_copy {
                 "Don't use --ignore-working-copy."
             } else {
-                "Don't use --at-op."
+                "Don't use --at-op, or use `jj workspace add --sandbox` to create a writable \
+                 workspace at the operation."
             };
             Err(user_error_with_hint(
                 "This command must be able to update the working copy.",
@@ -1171,7 +1228,7 @@ impl WorkspaceCommandHelper {
             // state to it without updating working copy files.
             locked_ws.locked_wc().reset(&new_git_head_commit)?;
             tx.repo_mut().rebase_descendants()?;
-            self.user_repo = ReadonlyUserRepo::new(tx.commit("import git head")?);
+            self.user_repo = ReadonlyUserRepo::new(commit_transaction(ui, tx, "import git head")?);
             locked_ws.finish(self.user_repo.repo.op_id().clone())?;
             if old_git_head.is_present() {
                 writeln!(
@@ -1243,6 +1300,31 @@ impl WorkspaceCommandHelper {
         self.workspace.working_copy()
     }
 
+    /// Starts the inotify filesystem monitor daemon in the background unless
+    /// it's already running. Returns whether the daemon was started.
+    #[cfg(target_os = "linux")]
+    pub fn start_inotify_daemon(&self) -> Result<bool, CommandError> {
+        let Some(wc) = self
+            .working_copy()
+            .as_any()
+            .downcast_ref::<LocalWorkingCopy>()
+        else {
+            return Err(user_error(
+                "The inotify filesystem monitor requires a standard local-disk working copy",
+            ));
+        };
+        let fsmonitor = wc.inotify_fsmonitor();
+        if fsmonitor.is_daemon_running().map_err(user_error)? {
+            return Ok(false);
+        }
+        let mut command = process::Command::new(env::current_exe()?);
+        command
+            .args(["debug", "fsmonitor", "run"])
+            .current_dir(self.workspace_root());
+        fsmonitor.start_daemon(command).map_err(user_error)?;
+        Ok(true)
+    }
+
     pub fn env(&self) -> &WorkspaceCommandEnvironment {
         &self.env
     }
@@ -1250,6 +1332,9 @@ impl WorkspaceCommandHelper {
     pub fn checkout_options(&self) -> CheckoutOptions {
         CheckoutOptions {
             conflict_marker_style: self.env.conflict_marker_style(),
+            conflict_marker_overrides: self.env.conflict_marker_overrides().to_vec(),
+            submodule_store: Some(self.repo().submodule_store().clone()),
+            pointer_paths: self.env.pointer_paths(),
         }
     }
 
@@ -1404,6 +1489,9 @@ to the current parents may contain changes from multiple commits.
             start_tracking_matcher,
             max_new_file_size,
             conflict_marker_style,
+            conflict_marker_overrides: self.env.conflict_marker_overrides().to_vec(),
+            submodule_store: Some(self.repo().submodule_store().clone()),
+            file_policies: self.env.snapshot_file_policies().to_vec(),
         })
     }
 
@@ -1893,6 +1981,16 @@ to the current parents may contain changes from multiple commits.
         let options = self
             .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)
             .map_err(snapshot_command_error)?;
+        // The daemon won't have recorded anything yet, but it will be ready
+        // for subsequent snapshots.
+        #[cfg(target_os = "linux")]
+        if options.fsmonitor_settings == FsmonitorSettings::Inotify {
+            if let Err(err) = self.start_inotify_daemon() {
+                let err: &dyn std::error::Error = err.error.as_ref();
+                let message = iter::successors(Some(err), |err| err.source()).format(": ");
+                writeln!(ui.warning_default(), "{message}").ok();
+            }
+        }
 
         // Compare working-copy tree and operation with repo's, and reload as needed.
         let mut locked_ws = self
@@ -1974,6 +2072,11 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             mut_repo
                 .set_wc_commit(workspace_id, commit.id().clone())
                 .map_err(snapshot_command_error)?;
+            if wc_commit.has_conflict().map_err(snapshot_command_error)? {
+                let old_tree = wc_commit.tree().map_err(snapshot_command_error)?;
+                let new_tree = commit.tree().map_err(snapshot_command_error)?;
+                record_conflict_resolutions(ui, &old_tree, &new_tree);
+            }
 
             // Rebase descendants
             let num_rebased = mut_repo
@@ -1994,8 +2097,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                     .map_err(snapshot_command_error)?;
             }
 
-            let repo = tx
-                .commit("snapshot working copy")
+            let repo = commit_transaction(ui, tx, "snapshot working copy")
                 .map_err(snapshot_command_error)?;
             self.user_repo = ReadonlyUserRepo::new(repo);
         }
@@ -2075,6 +2177,52 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         if num_rebased > 0 {
             writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
         }
+        // The same file may be merged more than once
+        let driver_failures = tx
+            .repo()
+            .store()
+            .merge_drivers()
+            .take_failures()
+            .into_iter()
+            .unique_by(|failure| (failure.driver.clone(), failure.path.clone()))
+            .collect_vec();
+        for failure in &driver_failures {
+            let mut writer = ui.warning_default();
+            write!(
+                writer,
+                "Merge driver {} failed on {}",
+                failure.driver,
+                self.format_file_path(&failure.path)
+            )?;
+            let error: &dyn std::error::Error = &failure.error;
+            for err in iter::successors(Some(error), |err| err.source()) {
+                write!(writer, ": {err}")?;
+            }
+            writeln!(writer)?;
+        }
+        if !driver_failures.is_empty() {
+            writeln!(
+                ui.hint_default(),
+                "The files were merged by the built-in merge instead."
+            )?;
+        }
+        let reused_paths = tx
+            .repo()
+            .reused_resolutions()
+            .iter()
+            .map(|reused| &reused.path)
+            .sorted()
+            .dedup()
+            .collect_vec();
+        if !reused_paths.is_empty() {
+            writeln!(
+                ui.status(),
+                "Resolved conflicts using recorded resolutions in these files:"
+            )?;
+            for path in reused_paths {
+                writeln!(ui.status(), "  {}", self.format_file_path(path))?;
+            }
+        }
 
         for (workspace_id, wc_commit_id) in tx.repo().view().wc_commit_ids().clone().iter().sorted()
         //sorting otherwise non deterministic order (bad for tests)
@@ -2118,7 +2266,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             crate::git_util::print_failed_git_export(ui, &refs)?;
         }
 
-        self.user_repo = ReadonlyUserRepo::new(tx.commit(description)?);
+        self.user_repo = ReadonlyUserRepo::new(commit_transaction(ui, tx, description)?);
 
         // Update working copy before reporting repo changes, so that
         // potential errors while reporting changes (broken pipe, etc)
@@ -2133,6 +2281,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         }
 
         self.report_repo_changes(ui, &old_repo)?;
+        self.maybe_spawn_auto_gc(ui)?;
 
         let settings = self.settings();
         let missing_user_name = settings.user_name().is_empty();
@@ -2164,6 +2313,55 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         Ok(())
     }
 
+    /// Spawns `jj util gc` in the background if `gc.auto` is enabled and the
+    /// previous automatic garbage collection was run long enough ago.
+    fn maybe_spawn_auto_gc(&self, ui: &Ui) -> Result<(), CommandError> {
+        // The command has already succeeded, so config errors shouldn't fail
+        // it.
+        match self.settings().get_bool("gc.auto") {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(err) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Skipped automatic garbage collection: {err}"
+                )?;
+                return Ok(());
+            }
+        }
+        let stamp_path = self.repo_path().join("auto_gc_stamp");
+        let is_due = match fs::metadata(&stamp_path).and_then(|metadata| metadata.modified()) {
+            Ok(mtime) => !mtime
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed < AUTO_GC_INTERVAL),
+            Err(_) => true,
+        };
+        if !is_due {
+            return Ok(());
+        }
+        // Renew the stamp first so that concurrent commands wouldn't spawn
+        // another gc process.
+        let spawned = fs::write(&stamp_path, "").and_then(|()| {
+            let jj_path = env::current_exe()?;
+            // The working copy mustn't be snapshotted or updated behind the
+            // user's back. The head operation is loaded since gc refuses to
+            // run at any other operation. `--auto` still prunes operations by
+            // the retention policy, and only remaps the working-copy operation.
+            process::Command::new(jj_path)
+                .args(["util", "gc", "--auto", "--quiet", "--ignore-working-copy"])
+                .arg("--repository")
+                .arg(self.workspace_root())
+                .stdin(process::Stdio::null())
+                .stdout(process::Stdio::null())
+                .stderr(process::Stdio::null())
+                .spawn()
+        });
+        if let Err(err) = spawned {
+            tracing::warn!(?err, "failed to spawn automatic garbage collection");
+        }
+        Ok(())
+    }
+
     /// Inform the user about important changes to the repo since the previous
     /// operation (when `old_repo` was loaded).
     fn report_repo_changes(
@@ -2400,6 +2598,11 @@ impl WorkspaceCommandTransaction<'_> {
         self.tx.repo_mut()
     }
 
+    pub fn merge_operation(&mut self, other_op: Operation) -> Result<(), RepoLoaderError> {
+        self.id_prefix_context.take(); // invalidate
+        self.tx.merge_operation(other_op)
+    }
+
     pub fn check_out(&mut self, commit: &Commit) -> Result<Commit, CheckOutCommitError> {
         let workspace_id = self.helper.workspace_id().to_owned();
         self.id_prefix_context.take(); // invalidate
@@ -2532,6 +2735,9 @@ jj git init --colocate",
             err @ (StoreLoadError::ReadError { .. } | StoreLoadError::Backend(_)),
         ) => internal_error_with_message("The repository appears broken or inaccessible", err),
         WorkspaceLoadError::StoreLoadError(StoreLoadError::Signing(err)) => user_error(err),
+        WorkspaceLoadError::StoreLoadError(
+            StoreLoadError::MergeDrivers(err) | StoreLoadError::RecordedResolutions(err),
+        ) => err.into(),
         WorkspaceLoadError::WorkingCopyState(err) => internal_error(err),
         WorkspaceLoadError::NonUnicodePath | WorkspaceLoadError::Path(_) => user_error(err),
     }
@@ -2593,6 +2799,22 @@ fn update_stale_working_copy(
     Ok(stats)
 }
 
+/// Records the resolutions of conflicts which are resolved in the `new_tree`
+/// if `rerere.enabled` is set. Failures are reported as warnings.
+pub fn record_conflict_resolutions(ui: &Ui, old_tree: &MergedTree, new_tree: &MergedTree) {
+    let store = old_tree.store();
+    let Some(resolutions) = store.recorded_resolutions() else {
+        return;
+    };
+    if let Err(err) = resolutions.record_tree(store, old_tree, new_tree) {
+        writeln!(
+            ui.warning_default(),
+            "Failed to record conflict resolutions: {err}"
+        )
+        .ok();
+    }
+}
+
 #[instrument(skip_all)]
 pub fn print_conflicted_paths(
     conflicts: Vec<(RepoPathBuf, BackendResult<MergedTreeValue>)>,
@@ -2661,7 +2883,14 @@ pub fn print_conflicted_paths(
                     if sides > 2 { "difficult" } else { "normal" },
                 ),
             )?;
-            write!(formatter, " conflict")?;
+            let kind = match conflict.conflict_kind() {
+                ConflictKind::Content => "conflict",
+                ConflictKind::ExecutableBit => "executable bit conflict",
+                ConflictKind::Symlink => "symlink conflict",
+                ConflictKind::FileDirectory => "file/directory conflict",
+                ConflictKind::GitSubmodule => "Git submodule conflict",
+            };
+            write!(formatter, " {kind}")?;
 
             if !seen_objects.is_empty() {
                 write!(formatter, " including ")?;
@@ -2700,10 +2929,14 @@ fn build_untracked_reason_message(reason: &UntrackedReason) -> Option<String> {
                  ({max_size} bytes)",
             ))
         }
-        // Paths with UntrackedReason::FileNotAutoTracked shouldn't be warned about
-        // every time we make a snapshot. These paths will be printed by
-        // "jj status" instead.
-        UntrackedReason::FileNotAutoTracked => None,
+        UntrackedReason::FileSkippedByPolicy { policy } => {
+            Some(format!("skipped by snapshot policy `{policy}`"))
+        }
+        // Paths with UntrackedReason::FileNotAutoTracked or
+        // FileRequiresExplicitTracking shouldn't be warned about every time we
+        // make a snapshot. These paths will be printed by "jj status" instead.
+        UntrackedReason::FileNotAutoTracked
+        | UntrackedReason::FileRequiresExplicitTracking { .. } => None,
     }
 }
 
@@ -2742,7 +2975,9 @@ pub fn print_snapshot_stats(
         .values()
         .filter_map(|reason| match reason {
             UntrackedReason::FileTooLarge { size, .. } => Some(size),
-            UntrackedReason::FileNotAutoTracked => None,
+            UntrackedReason::FileNotAutoTracked
+            | UntrackedReason::FileSkippedByPolicy { .. }
+            | UntrackedReason::FileRequiresExplicitTracking { .. } => None,
         });
     if let Some(size) = large_files_sizes.max() {
         writedoc!(
@@ -2916,6 +3151,90 @@ pub fn load_template_aliases(
     Ok(aliases_map)
 }
 
+/// Loads the `ui.conflict-marker-overrides` table, which is keyed by filesets.
+/// Entries from the config with higher precedence come first.
+fn load_conflict_marker_overrides(
+    ui: &Ui,
+    settings: &UserSettings,
+) -> Result<Vec<ConflictMarkerOverride>, CommandError> {
+    settings
+        .table_keys("ui.conflict-marker-overrides")
+        .map(|pattern| -> Result<_, CommandError> {
+            let key = |field| ["ui", "conflict-marker-overrides", pattern, field];
+            let mut diagnostics = FilesetDiagnostics::new();
+            let paths = fileset::parse(
+                &mut diagnostics,
+                pattern,
+                &RepoPathUiConverter::Fs {
+                    cwd: "".into(),
+                    base: "".into(),
+                },
+            )
+            .map_err(|err| {
+                config_error_with_message(
+                    format!("Invalid fileset `{pattern}` in `ui.conflict-marker-overrides`"),
+                    err,
+                )
+            })?;
+            print_parse_diagnostics(
+                ui,
+                &format!("In `ui.conflict-marker-overrides` key `{pattern}`"),
+                &diagnostics,
+            )?;
+            Ok(ConflictMarkerOverride {
+                paths,
+                style: settings.get(key("style")).optional()?,
+                min_len: settings.get(key("length")).optional()?,
+            })
+        })
+        .try_collect()
+}
+
+/// Loads the `snapshot.policies` config table. Policies are sorted by
+/// name.
+fn load_snapshot_file_policies(
+    ui: &Ui,
+    settings: &UserSettings,
+) -> Result<Vec<SnapshotFilePolicy>, CommandError> {
+    settings
+        .table_keys("snapshot.policies")
+        .sorted()
+        .map(|name| -> Result<_, CommandError> {
+            let key = |field| ["snapshot", "policies", name, field];
+            let mut diagnostics = FilesetDiagnostics::new();
+            let paths = match settings.get_string(key("paths")).optional()? {
+                Some(pattern) => fileset::parse(
+                    &mut diagnostics,
+                    &pattern,
+                    &RepoPathUiConverter::Fs {
+                        cwd: "".into(),
+                        base: "".into(),
+                    },
+                )?,
+                None => FilesetExpression::all(),
+            };
+            print_parse_diagnostics(
+                ui,
+                &format!("In `snapshot.policies.{name}.paths`"),
+                &diagnostics,
+            )?;
+            let min_size = settings
+                .get_value_with(key("min-size"), |value| {
+                    HumanByteSize::try_from(value).map(|HumanByteSize(size)| size)
+                })
+                .optional()?;
+            let binary_only = settings.get_bool(key("binary")).optional()?;
+            Ok(SnapshotFilePolicy {
+                name: name.to_owned(),
+                paths,
+                min_size,
+                binary_only: binary_only.unwrap_or(false),
+                action: settings.get(key("action"))?,
+            })
+        })
+        .try_collect()
+}
+
 /// Helper to reformat content of log-like commands.
 #[derive(Clone, Debug)]
 pub struct LogContentFormat {
@@ -2975,6 +3294,42 @@ pub fn short_operation_hash(operation_id: &OperationId) -> String {
     format!("{operation_id:.12}")
 }
 
+/// Writes and publishes the transaction. If the operation couldn't be signed,
+/// it's recorded unsigned and a warning is printed.
+pub fn commit_transaction(
+    ui: &Ui,
+    tx: Transaction,
+    description: impl Into<String>,
+) -> Result<Arc<ReadonlyRepo>, CommandError> {
+    let unpublished_op = tx.write(description);
+    print_operation_sign_error(ui, &unpublished_op)?;
+    Ok(unpublished_op.publish()?)
+}
+
+/// Warns about the rewritten operations that were recorded unsigned because
+/// they couldn't be signed again.
+pub fn print_unsigned_operations(ui: &Ui, unsigned_count: usize) -> io::Result<()> {
+    if unsigned_count > 0 {
+        writeln!(
+            ui.warning_default(),
+            "Failed to sign {unsigned_count} rewritten operations. They were recorded unsigned."
+        )?;
+    }
+    Ok(())
+}
+
+fn print_operation_sign_error(ui: &Ui, unpublished_op: &UnpublishedOperation) -> io::Result<()> {
+    if let Some(err) = unpublished_op.sign_error() {
+        writeln!(
+            ui.warning_default(),
+            "Failed to sign operation {}: {}",
+            short_operation_hash(unpublished_op.operation().id()),
+            iter::successors(Some(err as &dyn std::error::Error), |err| err.source()).format(": "),
+        )?;
+    }
+    Ok(())
+}
+
 /// Wrapper around a `DiffEditor` to conditionally start interactive session.
 #[derive(Clone, Debug)]
 pub enum DiffSelector {
@@ -3131,6 +3486,10 @@ pub struct GlobalArgs {
     /// earlier operation. Doing that is equivalent to having run concurrent
     /// commands starting at the earlier operation. There's rarely a reason to
     /// do that, but it is possible.
+    ///
+    /// To edit files at an earlier operation, use
+    /// `jj --at-op=<operation ID> workspace add --sandbox` to create a scratch
+    /// workspace there.
     #[arg(
         long,
         visible_alias = "at-op",
@@ -3554,6 +3913,8 @@ impl CliRunner {
     pub fn init() -> Self {
         let tracing_subscription = TracingSubscription::init();
         crate::cleanup_guard::init();
+        let mut revset_extensions = RevsetExtensions::default();
+        revset_extensions.add_custom_function("bisect_candidates", revset_util::bisect_candidates);
         CliRunner {
             tracing_subscription,
             app: crate::commands::default_app(),
@@ -3562,7 +3923,7 @@ impl CliRunner {
             store_factories: StoreFactories::default(),
             working_copy_factories: default_working_copy_factories(),
             workspace_loader_factory: Box::new(DefaultWorkspaceLoaderFactory),
-            revset_extensions: Default::default(),
+            revset_extensions,
             commit_template_extensions: vec![],
             operation_template_extensions: vec![],
             dispatch_fn: Box::new(crate::commands::run_command),
@@ -24,6 +24,7 @@ use std::sync::Arc;
 use itertools::Itertools as _;
 use jj_lib::absorb::AbsorbError;
 use jj_lib::backend::BackendError;
+use jj_lib::bisect::BisectionError;
 use jj_lib::config::ConfigFileSaveError;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLoadError;
@@ -33,11 +34,14 @@ use jj_lib::fileset::FilePatternParseError;
 use jj_lib::fileset::FilesetParseError;
 use jj_lib::fileset::FilesetParseErrorKind;
 use jj_lib::gitignore::GitIgnoreError;
+use jj_lib::op_bundle::OpBundleError;
 use jj_lib::op_heads_store::OpHeadResolutionError;
 use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::opset::OpsetParseError;
+use jj_lib::opset::OpsetParseErrorKind;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
 use jj_lib::repo::RepoLoaderError;
@@ -50,6 +54,7 @@ use jj_lib::revset::RevsetParseError;
 use jj_lib::revset::RevsetParseErrorKind;
 use jj_lib::revset::RevsetResolutionError;
 use jj_lib::str_util::StringPatternParseError;
+use jj_lib::submodule_store::SubmoduleStoreError;
 use jj_lib::view::RenameWorkspaceError;
 use jj_lib::working_copy::RecoverWorkspaceError;
 use jj_lib::working_copy::ResetError;
@@ -346,6 +351,9 @@ impl From<WorkspaceInitError> for CommandError {
             WorkspaceInitError::OpHeadsStore(err) => {
                 user_error_with_message("Failed to record initial operation", err)
             }
+            WorkspaceInitError::RepoLoader(err) => {
+                internal_error_with_message("Failed to load the repo", err)
+            }
             WorkspaceInitError::Backend(err) => {
                 user_error_with_message("Failed to access the repository", err)
             }
@@ -353,6 +361,8 @@ impl From<WorkspaceInitError> for CommandError {
                 internal_error_with_message("Failed to access the repository", err)
             }
             WorkspaceInitError::SignInit(err) => user_error(err),
+            WorkspaceInitError::MergeDrivers(err)
+            | WorkspaceInitError::RecordedResolutions(err) => err.into(),
         }
     }
 }
@@ -370,6 +380,7 @@ impl From<OpHeadResolutionError> for CommandError {
 impl From<OpsetEvaluationError> for CommandError {
     fn from(err: OpsetEvaluationError) -> Self {
         match err {
+            OpsetEvaluationError::OpsetParse(err) => err.into(),
             OpsetEvaluationError::OpsetResolution(err) => {
                 let hint = opset_resolution_error_hint(&err);
                 let mut cmd_err = user_error(err);
@@ -383,12 +394,34 @@ impl From<OpsetEvaluationError> for CommandError {
     }
 }
 
+impl From<OpsetParseError> for CommandError {
+    fn from(err: OpsetParseError) -> Self {
+        let hint = opset_parse_error_hint(&err);
+        let mut cmd_err = user_error_with_message(
+            format!("Failed to parse operation set: {}", err.kind()),
+            err,
+        );
+        cmd_err.extend_hints(hint);
+        cmd_err
+    }
+}
+
 impl From<SnapshotError> for CommandError {
     fn from(err: SnapshotError) -> Self {
         internal_error_with_message("Failed to snapshot the working copy", err)
     }
 }
 
+impl From<OpBundleError> for CommandError {
+    fn from(err: OpBundleError) -> Self {
+        match err {
+            OpBundleError::Backend(err) => err.into(),
+            OpBundleError::OpStore(err) => err.into(),
+            err => user_error(err),
+        }
+    }
+}
+
 impl From<OpStoreError> for CommandError {
     fn from(err: OpStoreError) -> Self {
         internal_error_with_message("Failed to load an operation", err)
@@ -407,6 +440,17 @@ impl From<ResetError> for CommandError {
     }
 }
 
+impl From<SubmoduleStoreError> for CommandError {
+    fn from(err: SubmoduleStoreError) -> Self {
+        match err {
+            SubmoduleStoreError::Backend(err) => err.into(),
+            err @ (SubmoduleStoreError::NotInitialized { .. }
+            | SubmoduleStoreError::Fetch { .. }) => user_error(err),
+            err @ SubmoduleStoreError::Other { .. } => internal_error(err),
+        }
+    }
+}
+
 impl From<DiffEditError> for CommandError {
     fn from(err: DiffEditError) -> Self {
         user_error_with_message("Failed to edit diff", err)
@@ -430,6 +474,12 @@ impl From<ConflictResolveError> for CommandError {
         match err {
             ConflictResolveError::Backend(err) => err.into(),
             ConflictResolveError::Io(err) => err.into(),
+            ConflictResolveError::ConflictTooComplicated { .. } => {
+                user_error_with_message("Failed to resolve conflicts", err).hinted(
+                    "Use `:builtin` to pick lines from any side, or `:ours` to pick the first \
+                     side.",
+                )
+            }
             _ => user_error_with_message("Failed to resolve conflicts", err),
         }
     }
@@ -504,8 +554,9 @@ mod git {
                 | GitImportError::MissingRefAncestor { .. } => Some(
                     "\
 Is this Git repository a partial clone (cloned with the --filter argument)?
-jj currently does not support partial clones. To use jj with this repository, try re-cloning with \
-                     the full repository contents."
+jj can fetch missing file contents on demand, but not missing commits. To use jj with this \
+                     repository, try re-cloning with `jj git clone --filter=blob:none` or with the full \
+                     repository contents."
                         .to_string(),
                 ),
                 GitImportError::RemoteReservedForLocalGitRepo => {
@@ -545,6 +596,11 @@ jj currently does not support partial clones. To use jj with this repository, tr
                     "Run `jj git remote rename` to give a different name.",
                 ),
                 GitFetchError::InvalidBranchPattern(_) => user_error(err),
+                GitFetchError::InvalidTagPattern(_) => user_error(err),
+                GitFetchError::PartialCloneUnsupported => user_error_with_hint(
+                    err,
+                    "Set `git.subprocess = true` to fetch through a git subprocess.",
+                ),
                 GitFetchError::InternalGitError(err) => map_git2_error(err),
                 GitFetchError::Subprocess(_) => user_error(err),
             }
@@ -579,6 +635,11 @@ jj currently does not support partial clones. To use jj with this repository, tr
                      it to be, and push again.",
                 ),
                 GitPushError::RefUpdateRejected(_) => user_error(err),
+                GitPushError::RefUpdateRejectedByRemote(_) => user_error(err),
+                GitPushError::AtomicPushUnsupported => user_error_with_hint(
+                    err,
+                    "Set `git.subprocess = true` to push through a git subprocess.",
+                ),
                 GitPushError::InternalGitError(err) => map_git2_error(err),
                 GitPushError::Subprocess(_) => user_error(err),
                 GitPushError::UnexpectedBackend(_) => user_error(err),
@@ -624,6 +685,19 @@ jj currently does not support partial clones. To use jj with this repository, tr
     }
 }
 
+impl From<BisectionError> for CommandError {
+    fn from(err: BisectionError) -> Self {
+        match err {
+            BisectionError::InconsistentEvaluations => user_error_with_hint(
+                err,
+                "Mark the revisions again with `jj bisect good` or `jj bisect bad`, or use `jj \
+                 bisect reset` to start over.",
+            ),
+            BisectionError::Evaluation(err) => err.into(),
+        }
+    }
+}
+
 impl From<RevsetEvaluationError> for CommandError {
     fn from(err: RevsetEvaluationError) -> Self {
         user_error(err)
@@ -810,6 +884,21 @@ fn fileset_parse_error_hint(err: &FilesetParseError) -> Option<String> {
     }
 }
 
+fn opset_parse_error_hint(err: &OpsetParseError) -> Option<String> {
+    match err.kind() {
+        OpsetParseErrorKind::SyntaxError => Some(String::from(
+            "See https://jj-vcs.github.io/jj/latest/operation-log/ for operation set syntax.",
+        )),
+        OpsetParseErrorKind::NoSuchFunction {
+            name: _,
+            candidates,
+        } => format_similarity_hint(candidates),
+        OpsetParseErrorKind::InvalidArguments { .. } | OpsetParseErrorKind::Expression(_) => {
+            find_source_parse_error_hint(&err)
+        }
+    }
+}
+
 fn opset_resolution_error_hint(err: &OpsetResolutionError) -> Option<String> {
     match err {
         OpsetResolutionError::MultipleOperations {
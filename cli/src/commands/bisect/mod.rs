@@ -0,0 +1,218 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod bad;
+mod good;
+mod reset;
+mod run;
+mod skip;
+mod start;
+
+use std::io::Write as _;
+
+use clap::Subcommand;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectionState;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Evaluation;
+use jj_lib::commit::Commit;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use self::bad::cmd_bisect_bad;
+use self::bad::BisectBadArgs;
+use self::good::cmd_bisect_good;
+use self::good::BisectGoodArgs;
+use self::reset::cmd_bisect_reset;
+use self::reset::BisectResetArgs;
+use self::run::cmd_bisect_run;
+use self::run::BisectRunArgs;
+use self::skip::cmd_bisect_skip;
+use self::skip::BisectSkipArgs;
+use self::start::cmd_bisect_start;
+use self::start::BisectStartArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::revset_util::bisection_state_path;
+use crate::ui::Ui;
+
+/// Find the first bad revision by binary search
+///
+/// Start a bisection with `jj bisect start`, specifying a bad revision and
+/// optionally some good revisions. The candidates are the ancestors of the
+/// bad revisions which aren't ancestors of the good revisions. The root
+/// commit is assumed to be good.
+///
+/// At each step, a new working-copy commit is created on top of the revision
+/// to test. Mark it with `jj bisect good`, `jj bisect bad`, or `jj bisect
+/// skip`, or let `jj bisect run` test the revisions with a command. The
+/// remaining candidates can be shown by `jj log -r 'bisect_candidates()'`.
+///
+/// The bisection state is stored in the workspace outside of the operation
+/// log, so it isn't affected by `jj undo`. Use `jj bisect reset` to end
+/// the bisection.
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum BisectCommand {
+    Bad(BisectBadArgs),
+    Good(BisectGoodArgs),
+    Reset(BisectResetArgs),
+    Run(BisectRunArgs),
+    Skip(BisectSkipArgs),
+    Start(BisectStartArgs),
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &BisectCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        BisectCommand::Bad(args) => cmd_bisect_bad(ui, command, args),
+        BisectCommand::Good(args) => cmd_bisect_good(ui, command, args),
+        BisectCommand::Reset(args) => cmd_bisect_reset(ui, command, args),
+        BisectCommand::Run(args) => cmd_bisect_run(ui, command, args),
+        BisectCommand::Skip(args) => cmd_bisect_skip(ui, command, args),
+        BisectCommand::Start(args) => cmd_bisect_start(ui, command, args),
+    }
+}
+
+fn load_state(workspace_command: &WorkspaceCommandHelper) -> Result<BisectionState, CommandError> {
+    let path = bisection_state_path(workspace_command.workspace_root());
+    match BisectionState::load(&path).map_err(user_error)? {
+        Some(state) => Ok(state),
+        None => Err(user_error_with_hint(
+            "No bisection in progress",
+            "Use `jj bisect start` to start bisecting.",
+        )),
+    }
+}
+
+fn save_state(
+    workspace_command: &WorkspaceCommandHelper,
+    state: &BisectionState,
+) -> Result<(), CommandError> {
+    let path = bisection_state_path(workspace_command.workspace_root());
+    state.save(&path)?;
+    Ok(())
+}
+
+/// Marks the given revisions, or the revision being tested if none given.
+fn mark_revisions(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    revisions: &[RevisionArg],
+    evaluation: Evaluation,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut state = load_state(&workspace_command)?;
+    let commit_ids: Vec<CommitId> = if revisions.is_empty() {
+        match state.next_step(workspace_command.repo().as_ref())? {
+            BisectionStep::Evaluate { commit_id, .. } => vec![commit_id],
+            _ => return Err(user_error("No revision is being tested")),
+        }
+    } else {
+        workspace_command
+            .parse_union_revsets(ui, revisions)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?
+    };
+    for commit_id in &commit_ids {
+        state.mark(commit_id.clone(), evaluation);
+    }
+    save_state(&workspace_command, &state)?;
+    writeln!(
+        ui.status(),
+        "Marked {} revisions as {}.",
+        commit_ids.len(),
+        describe_evaluation(evaluation)
+    )?;
+    check_out_next(ui, &mut workspace_command, &state)?;
+    Ok(())
+}
+
+fn describe_evaluation(evaluation: Evaluation) -> &'static str {
+    match evaluation {
+        Evaluation::Good => "good",
+        Evaluation::Bad => "bad",
+        Evaluation::Skip => "skipped",
+    }
+}
+
+/// Checks out the next revision to test, or reports the result of the
+/// bisection. Returns the revision to test.
+fn check_out_next(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    state: &BisectionState,
+) -> Result<Option<Commit>, CommandError> {
+    let repo = workspace_command.repo().clone();
+    match state.next_step(repo.as_ref())? {
+        BisectionStep::Evaluate {
+            commit_id,
+            num_untested,
+        } => {
+            let commit = repo.store().get_commit(&commit_id)?;
+            if let Some(mut formatter) = ui.status_formatter() {
+                write!(
+                    formatter,
+                    "Bisecting: {num_untested} untested revisions left. Testing "
+                )?;
+                workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+                writeln!(formatter)?;
+            }
+            let mut tx = workspace_command.start_transaction();
+            tx.check_out(&commit)?;
+            tx.finish(ui, format!("bisect: check out commit {}", commit_id.hex()))?;
+            Ok(Some(commit))
+        }
+        BisectionStep::Found(commit_ids) => {
+            if let Some(mut formatter) = ui.status_formatter() {
+                if commit_ids.is_empty() {
+                    writeln!(formatter, "None of the candidate revisions is bad.")?;
+                } else {
+                    writeln!(formatter, "The first bad revision is:")?;
+                }
+                for commit_id in &commit_ids {
+                    let commit = repo.store().get_commit(commit_id)?;
+                    write!(formatter, "  ")?;
+                    workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+                    writeln!(formatter)?;
+                }
+            }
+            Ok(None)
+        }
+        BisectionStep::Indeterminate(commit_ids) => {
+            if let Some(mut formatter) = ui.status_formatter() {
+                writeln!(
+                    formatter,
+                    "Some revisions were skipped. The first bad revision is one of:"
+                )?;
+                for commit_id in &commit_ids {
+                    let commit = repo.store().get_commit(commit_id)?;
+                    write!(formatter, "  ")?;
+                    workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+                    writeln!(formatter)?;
+                }
+            }
+            Ok(None)
+        }
+    }
+}
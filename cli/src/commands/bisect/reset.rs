@@ -0,0 +1,45 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+
+use jj_lib::file_util::IoResultExt as _;
+use tracing::instrument;
+
+use super::load_state;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::revset_util::bisection_state_path;
+use crate::ui::Ui;
+
+/// End the bisection
+///
+/// The working copy is left at the last tested revision.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BisectResetArgs {}
+
+#[instrument(skip_all)]
+pub fn cmd_bisect_reset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectResetArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    load_state(&workspace_command)?;
+    let path = bisection_state_path(workspace_command.workspace_root());
+    fs::remove_file(&path).context(&path)?;
+    writeln!(ui.status(), "Ended the bisection.")?;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::process::Command;
+
+use jj_lib::bisect::Evaluation;
+use jj_lib::object_id::ObjectId as _;
+use tracing::instrument;
+
+use super::check_out_next;
+use super::describe_evaluation;
+use super::load_state;
+use super::save_state;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Test the remaining revisions with a command
+///
+/// The command is run in the working copy by the shell. The revision is good
+/// if the command exits with 0, skipped if it exits with 125, and bad if it
+/// exits with any other status less than 128. Other statuses (including
+/// termination by signal) abort the bisection.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BisectRunArgs {
+    /// The command to run
+    #[arg(value_name = "COMMAND")]
+    shell_command: String,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_bisect_run(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectRunArgs,
+) -> Result<(), CommandError> {
+    loop {
+        // Reload the workspace to snapshot files written by the last run.
+        let mut workspace_command = command.workspace_helper(ui)?;
+        let mut state = load_state(&workspace_command)?;
+        let Some(commit) = check_out_next(ui, &mut workspace_command, &state)? else {
+            return Ok(());
+        };
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C");
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c");
+            cmd
+        };
+        cmd.arg(&args.shell_command)
+            .current_dir(command.cwd())
+            .env("JJ_CHANGE_ID", commit.change_id().reverse_hex())
+            .env("JJ_COMMIT_ID", commit.id().hex());
+        let status = cmd
+            .status()
+            .map_err(|err| user_error_with_message("Failed to run command", err))?;
+        let evaluation = match status.code() {
+            Some(0) => Evaluation::Good,
+            Some(125) => Evaluation::Skip,
+            Some(1..=127) => Evaluation::Bad,
+            _ => {
+                return Err(user_error(format!(
+                    "Command failed with {status}; aborting the bisection"
+                )));
+            }
+        };
+        state.mark(commit.id().clone(), evaluation);
+        save_state(&workspace_command, &state)?;
+        writeln!(
+            ui.status(),
+            "Marked the revision as {}.",
+            describe_evaluation(evaluation)
+        )?;
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::bisect::BisectionState;
+use jj_lib::bisect::Evaluation;
+use tracing::instrument;
+
+use super::check_out_next;
+use super::save_state;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::revset_util::bisection_state_path;
+use crate::ui::Ui;
+
+/// Start a bisection
+///
+/// The first revision to test is checked out immediately.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BisectStartArgs {
+    /// Revisions known to be bad
+    #[arg(long, short, value_name = "REVSETS", default_value = "@")]
+    bad: Vec<RevisionArg>,
+    /// Revisions known to be good
+    #[arg(long, short, value_name = "REVSETS")]
+    good: Vec<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_bisect_start(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectStartArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if bisection_state_path(workspace_command.workspace_root()).exists() {
+        return Err(user_error_with_hint(
+            "A bisection is already in progress",
+            "Use `jj bisect reset` to end it first.",
+        ));
+    }
+    let mut state = BisectionState::new();
+    let good_ids: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.good)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let bad_ids: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.bad)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    if bad_ids.is_empty() {
+        return Err(user_error("No bad revisions were given"));
+    }
+    for commit_id in good_ids {
+        state.mark(commit_id, Evaluation::Good);
+    }
+    for commit_id in bad_ids {
+        state.mark(commit_id, Evaluation::Bad);
+    }
+    save_state(&workspace_command, &state)?;
+    check_out_next(ui, &mut workspace_command, &state)?;
+    Ok(())
+}
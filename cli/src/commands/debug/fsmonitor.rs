@@ -0,0 +1,131 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+#[cfg(target_os = "linux")]
+use std::io::Write as _;
+#[cfg(target_os = "linux")]
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+
+use clap::Subcommand;
+#[cfg(target_os = "linux")]
+use jj_lib::fsmonitor::FsmonitorSettings;
+
+#[cfg(target_os = "linux")]
+use super::check_local_disk_wc;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+#[cfg(target_os = "linux")]
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Manage the built-in inotify filesystem monitor
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugFsmonitorCommand {
+    /// Check whether the filesystem monitor is enabled and its daemon running
+    Status,
+    /// Start the filesystem monitor daemon in the background
+    Start,
+    /// Stop the filesystem monitor daemon
+    Stop,
+    /// Run the filesystem monitor daemon in the foreground
+    Run,
+}
+
+#[cfg(target_os = "linux")]
+pub fn cmd_debug_fsmonitor(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugFsmonitorCommand,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let wc = check_local_disk_wc(workspace_command.working_copy().as_any())?;
+    let fsmonitor = wc.inotify_fsmonitor();
+    let map_err = |err| user_error_with_message("Filesystem monitor error", err);
+    match subcommand {
+        DebugFsmonitorCommand::Status => {
+            if workspace_command.settings().fsmonitor_settings()? == FsmonitorSettings::Inotify {
+                writeln!(
+                    ui.stdout(),
+                    "The inotify filesystem monitor is enabled via `core.fsmonitor`."
+                )?;
+            } else {
+                writeln!(
+                    ui.stdout(),
+                    r#"The inotify filesystem monitor is disabled. Set `core.fsmonitor="inotify"` to enable."#
+                )?;
+            }
+            let Some(pid) = fsmonitor.daemon_pid().map_err(map_err)? else {
+                writeln!(ui.stdout(), "The daemon is not running.")?;
+                return Ok(());
+            };
+            writeln!(ui.stdout(), "The daemon is running (pid {pid}).")?;
+            match wc.query_inotify()? {
+                (_, Some(changed_files)) => writeln!(
+                    ui.stdout(),
+                    "Changed paths since the last snapshot: {}",
+                    changed_files.len()
+                )?,
+                (_, None) => writeln!(
+                    ui.stdout(),
+                    "The next snapshot will scan the whole working copy."
+                )?,
+            }
+        }
+        DebugFsmonitorCommand::Start => {
+            if !workspace_command.start_inotify_daemon()? {
+                writeln!(ui.status(), "The daemon is already running.")?;
+                return Ok(());
+            }
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !fsmonitor.is_daemon_running().map_err(map_err)? {
+                if Instant::now() >= deadline {
+                    return Err(user_error(format!(
+                        "The daemon didn't start. See {} for details.",
+                        fsmonitor.log_path().display()
+                    )));
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            writeln!(ui.status(), "Started the daemon.")?;
+        }
+        DebugFsmonitorCommand::Stop => {
+            if fsmonitor.stop_daemon().map_err(map_err)? {
+                writeln!(ui.status(), "Stopped the daemon.")?;
+            } else {
+                writeln!(ui.status(), "The daemon is not running.")?;
+            }
+        }
+        DebugFsmonitorCommand::Run => {
+            fsmonitor.run_daemon().map_err(map_err)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn cmd_debug_fsmonitor(
+    _ui: &mut Ui,
+    _command: &CommandHelper,
+    _subcommand: &DebugFsmonitorCommand,
+) -> Result<(), CommandError> {
+    Err(user_error(
+        "The inotify filesystem monitor is only supported on Linux",
+    ))
+}
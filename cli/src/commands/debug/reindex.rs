@@ -26,12 +26,23 @@ use crate::ui::Ui;
 
 /// Rebuild commit index
 #[derive(clap::Args, Clone, Debug)]
-pub struct DebugReindexArgs {}
+pub struct DebugReindexArgs {
+    /// Also index paths changed by each commit
+    ///
+    /// The changed-path index speeds up evaluation of `files()` and
+    /// `diff_contains()` revsets. Commits created later aren't covered until
+    /// the index is rebuilt.
+    #[arg(long)]
+    changed_paths: bool,
+    /// Remove the changed-path index
+    #[arg(long, conflicts_with = "changed_paths")]
+    no_changed_paths: bool,
+}
 
 pub fn cmd_debug_reindex(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &DebugReindexArgs,
+    args: &DebugReindexArgs,
 ) -> Result<(), CommandError> {
     // Resolve the operation without loading the repo. The index might have to
     // be rebuilt while loading the repo.
@@ -41,6 +52,15 @@ pub fn cmd_debug_reindex(
     let index_store = repo_loader.index_store();
     if let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() {
         default_index_store.reinit().map_err(internal_error)?;
+        if args.changed_paths {
+            default_index_store
+                .enable_changed_path_index()
+                .map_err(internal_error)?;
+        } else if args.no_changed_paths {
+            default_index_store
+                .disable_changed_path_index()
+                .map_err(internal_error)?;
+        }
         let default_index = default_index_store
             .build_index_at_operation(&op, repo_loader.store())
             .map_err(internal_error)?;
@@ -49,6 +69,9 @@ pub fn cmd_debug_reindex(
             "Finished indexing {:?} commits.",
             default_index.as_composite().stats().num_commits
         )?;
+        if default_index_store.is_changed_path_index_enabled() {
+            writeln!(ui.status(), "Indexed paths changed by the commits.")?;
+        }
     } else {
         return Err(user_error(format!(
             "Cannot reindex indexes of type '{}'",
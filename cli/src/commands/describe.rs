@@ -21,6 +21,8 @@ use itertools::Itertools;
 use jj_lib::backend::Signature;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::object_id::ObjectId;
+use jj_lib::trailer::add_trailers;
+use jj_lib::trailer::Trailer;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -34,6 +36,7 @@ use crate::description_util::edit_multiple_descriptions;
 use crate::description_util::join_message_paragraphs;
 use crate::description_util::ParsedBulkEditMessage;
 use crate::text_util::parse_author;
+use crate::text_util::parse_trailer;
 use crate::ui::Ui;
 
 /// Update the change description or other metadata
@@ -104,6 +107,15 @@ pub(crate) struct DescribeArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Add a trailer to the description
+    ///
+    /// The trailer should be in the `Key: value` form. It's appended to the
+    /// trailer block at the end of the new description unless the description
+    /// already has the same trailer. This option can be repeated.
+    ///
+    /// $ jj describe --no-edit --add-trailer 'Reviewed-by: Foo Bar <foo@bar.com>'
+    #[arg(long, value_name = "TRAILER", value_parser = parse_trailer)]
+    add_trailer: Vec<Trailer>,
 }
 
 #[instrument(skip_all)]
@@ -242,6 +254,9 @@ pub(crate) fn cmd_describe(
     // `transform_descendants` below unnecessarily.
     let commit_descriptions: HashMap<_, _> = commit_descriptions
         .into_iter()
+        .map(|(commit, new_description)| {
+            (commit, add_trailers(&new_description, &args.add_trailer))
+        })
         .filter(|(commit, new_description)| {
             new_description != commit.description()
                 || args.reset_author
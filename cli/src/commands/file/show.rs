@@ -18,7 +18,8 @@ use std::io::Write;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::backend::BackendResult;
-use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::choose_materialized_conflict_marker_len;
+use jj_lib::conflicts::materialize_merge_result_with_marker_len;
 use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::fileset::FilePattern;
@@ -118,6 +119,7 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
     entries: impl IntoIterator<Item = (P, BackendResult<MergedTreeValue>)>,
 ) -> Result<(), CommandError> {
     let repo = workspace_command.repo();
+    let conflict_markers = workspace_command.env().conflict_markers();
     for (path, result) in entries {
         let value = result?;
         let materialized = materialize_tree_value(repo.store(), path.as_ref(), value).block_on()?;
@@ -134,9 +136,12 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
                 io::copy(&mut reader, &mut ui.stdout_formatter().as_mut())?;
             }
             MaterializedTreeValue::FileConflict { contents, .. } => {
-                materialize_merge_result(
+                let conflict_marker_len = choose_materialized_conflict_marker_len(&contents)
+                    .max(conflict_markers.min_len(path.as_ref()));
+                materialize_merge_result_with_marker_len(
                     &contents,
-                    workspace_command.env().conflict_marker_style(),
+                    conflict_markers.style(path.as_ref()),
+                    conflict_marker_len,
                     &mut ui.stdout_formatter(),
                 )?;
             }
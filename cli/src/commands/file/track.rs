@@ -18,10 +18,12 @@ use std::io::Write;
 use indoc::writedoc;
 use itertools::Itertools;
 use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::working_copy::SnapshotFileAction;
 use jj_lib::working_copy::SnapshotStats;
 use jj_lib::working_copy::UntrackedReason;
 use tracing::instrument;
 
+use crate::cli_util::commit_transaction;
 use crate::cli_util::print_untracked_files;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -53,7 +55,15 @@ pub(crate) fn cmd_file_track(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
-    let options = workspace_command.snapshot_options_with_start_tracking_matcher(&matcher)?;
+    let mut options = workspace_command.snapshot_options_with_start_tracking_matcher(&matcher)?;
+    // Explicitly specified paths override the policies leaving files
+    // untracked.
+    options.file_policies.retain(|policy| {
+        !matches!(
+            policy.action,
+            SnapshotFileAction::Skip | SnapshotFileAction::Manual
+        )
+    });
 
     let mut tx = workspace_command.start_transaction().into_inner();
     let (mut locked_ws, _wc_commit) = workspace_command.start_working_copy_mutation()?;
@@ -62,7 +72,7 @@ pub(crate) fn cmd_file_track(
     if num_rebased > 0 {
         writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
     }
-    let repo = tx.commit("track paths")?;
+    let repo = commit_transaction(ui, tx, "track paths")?;
     locked_ws.finish(repo.op_id().clone())?;
     print_track_snapshot_stats(
         ui,
@@ -97,7 +107,9 @@ pub fn print_track_snapshot_stats(
         .iter()
         .filter_map(|(path, reason)| match reason {
             UntrackedReason::FileTooLarge { size, .. } => Some((path, *size)),
-            UntrackedReason::FileNotAutoTracked => None,
+            UntrackedReason::FileNotAutoTracked
+            | UntrackedReason::FileSkippedByPolicy { .. }
+            | UntrackedReason::FileRequiresExplicitTracking { .. } => None,
         })
         .unzip();
     if let Some(size) = sizes.iter().max() {
@@ -21,6 +21,7 @@ use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::repo::Repo;
 use tracing::instrument;
 
+use crate::cli_util::commit_transaction;
 use crate::cli_util::print_snapshot_stats;
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error_with_hint;
@@ -109,7 +110,7 @@ Make sure they're ignored, then try again.",
     if num_rebased > 0 {
         writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
     }
-    let repo = tx.commit("untrack paths")?;
+    let repo = commit_transaction(ui, tx, "untrack paths")?;
     locked_ws.finish(repo.op_id().clone())?;
     print_snapshot_stats(ui, &stats, workspace_command.env().path_converter())?;
     Ok(())
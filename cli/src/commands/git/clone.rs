@@ -64,6 +64,14 @@ pub struct GitCloneArgs {
     /// Create a shallow clone of the given depth
     #[arg(long)]
     depth: Option<NonZeroU32>,
+    /// Create a partial clone, omitting the objects excluded by the given
+    /// filter spec (e.g. `blob:none`)
+    ///
+    /// The omitted objects are fetched from the remote on demand, and later
+    /// fetches from the remote use the same filter. Requires `git.subprocess`
+    /// to be enabled.
+    #[arg(long, value_name = "FILTER_SPEC")]
+    filter: Option<String>,
 }
 
 fn clone_destination_for_source(source: &str) -> Option<&str> {
@@ -119,7 +127,13 @@ pub fn cmd_git_clone(
         let workspace_command = init_workspace(ui, command, &canonical_wc_path, args.colocate)?;
         let mut workspace_command =
             configure_remote(ui, command, workspace_command, remote_name, &source)?;
-        let default_branch = fetch_new_remote(ui, &mut workspace_command, remote_name, args.depth)?;
+        let default_branch = fetch_new_remote(
+            ui,
+            &mut workspace_command,
+            remote_name,
+            args.depth,
+            args.filter.as_deref(),
+        )?;
         Ok((workspace_command, default_branch))
     })();
     if clone_result.is_err() {
@@ -213,6 +227,7 @@ fn fetch_new_remote(
     workspace_command: &mut WorkspaceCommandHelper,
     remote_name: &str,
     depth: Option<NonZeroU32>,
+    filter: Option<&str>,
 ) -> Result<Option<String>, CommandError> {
     writeln!(
         ui.status(),
@@ -223,7 +238,13 @@ fn fetch_new_remote(
     let mut fetch_tx = workspace_command.start_transaction();
     let mut git_fetch = GitFetch::new(fetch_tx.repo_mut(), &git_settings)?;
     with_remote_git_callbacks(ui, |cb| {
-        git_fetch.fetch(remote_name, &[StringPattern::everything()], cb, depth)
+        git_fetch.fetch(
+            remote_name,
+            &[StringPattern::everything()],
+            cb,
+            depth,
+            filter,
+        )
     })?;
     let default_branch =
         with_remote_git_callbacks(ui, |cb| git_fetch.get_default_branch(remote_name, cb))?;
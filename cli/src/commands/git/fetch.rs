@@ -53,6 +53,19 @@ pub struct GitFetchArgs {
         add = ArgValueCandidates::new(complete::bookmarks),
     )]
     branch: Vec<StringPattern>,
+    /// Also fetch these tags, overwriting the local tags of the same names
+    ///
+    /// Tags pointing to the fetched commits are fetched anyway, but existing
+    /// local tags aren't updated. By default, the specified name matches
+    /// exactly. Use `glob:` prefix to expand `*` as a glob, e.g. `--tag
+    /// 'glob:v1.*'`. Other wildcard characters such as `?` are *not*
+    /// supported.
+    #[arg(
+        long,
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::tags),
+    )]
+    tag: Vec<StringPattern>,
     /// The remote to fetch from (only named remotes are supported, can be
     /// repeated)
     ///
@@ -128,7 +141,7 @@ pub fn cmd_git_fetch(
         .collect_vec();
 
     let mut tx = workspace_command.start_transaction();
-    do_git_fetch(ui, &mut tx, &remotes, &args.branch)?;
+    do_git_fetch(ui, &mut tx, &remotes, &args.branch, &args.tag)?;
     tx.finish(
         ui,
         format!("fetch from git remote(s) {}", remotes.iter().join(",")),
@@ -174,14 +187,20 @@ fn do_git_fetch(
     tx: &mut WorkspaceCommandTransaction,
     remotes: &[&str],
     branch_names: &[StringPattern],
+    tag_names: &[StringPattern],
 ) -> Result<(), CommandError> {
     let git_settings = tx.settings().git_settings()?;
     let mut git_fetch = GitFetch::new(tx.repo_mut(), &git_settings)?;
 
     for remote_name in remotes {
         with_remote_git_callbacks(ui, |callbacks| {
-            git_fetch.fetch(remote_name, branch_names, callbacks, None)
+            git_fetch.fetch(remote_name, branch_names, callbacks, None, None)
         })?;
+        if !tag_names.is_empty() {
+            with_remote_git_callbacks(ui, |callbacks| {
+                git_fetch.fetch_tags(remote_name, tag_names, callbacks)
+            })?;
+        }
     }
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
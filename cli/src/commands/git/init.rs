@@ -27,6 +27,7 @@ use jj_lib::repo::Repo;
 use jj_lib::workspace::Workspace;
 
 use super::write_repository_level_trunk_alias;
+use crate::cli_util::commit_transaction;
 use crate::cli_util::print_trackable_remote_bookmarks;
 use crate::cli_util::start_repo_transaction;
 use crate::cli_util::CommandHelper;
@@ -224,7 +225,7 @@ fn init_git_refs(
         let failed_refs = git::export_refs(tx.repo_mut())?;
         print_failed_git_export(ui, &failed_refs)?;
     }
-    let repo = tx.commit("import git refs")?;
+    let repo = commit_transaction(ui, tx, "import git refs")?;
     writeln!(
         ui.status(),
         "Done importing changes from the underlying Git repo."
@@ -28,6 +28,7 @@ use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
+use jj_lib::git::GitPushError;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::refs::classify_bookmark_push_action;
@@ -49,6 +50,7 @@ use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
@@ -61,7 +63,8 @@ use crate::ui::Ui;
 /// By default, pushes tracking bookmarks pointing to
 /// `remote_bookmarks(remote=<remote>)..@`. Use `--bookmark` to push specific
 /// bookmarks. Use `--all` to push all bookmarks. Use `--change` to generate
-/// bookmark names based on the change IDs of specific commits.
+/// bookmark names based on the change IDs of specific commits. Use `--tag` to
+/// push tags.
 ///
 /// Unlike in Git, the remote to push to is not derived from the tracked remote
 /// bookmarks. Use `--remote` to select the remote Git repository by name. There
@@ -79,7 +82,7 @@ use crate::ui::Ui;
 ///     https://jj-vcs.github.io/jj/latest/bookmarks/#conflicts
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions"]).multiple(true)))]
+#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions", "tag"]).multiple(true)))]
 #[command(group(ArgGroup::new("what").args(&["all", "deleted", "tracked"]).conflicts_with("specific")))]
 pub struct GitPushArgs {
     /// The remote to push to (only named remotes are supported)
@@ -168,6 +171,26 @@ pub struct GitPushArgs {
         add = ArgValueCandidates::new(complete::mutable_revisions)
     )]
     change: Vec<RevisionArg>,
+    /// Push this tag, or tags matching a pattern (can be repeated)
+    ///
+    /// Tags which already exist on the remote are never overwritten. By
+    /// default, the specified name matches exactly. Use `glob:` prefix to
+    /// select tags by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets#string-patterns
+    #[arg(
+        long,
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::tags),
+    )]
+    tag: Vec<StringPattern>,
+    /// Update either all of the bookmarks on the remote or none of them
+    ///
+    /// This defaults to the `git.push-atomic` setting. Atomic pushes require
+    /// `git.subprocess` to be enabled.
+    #[arg(long)]
+    atomic: bool,
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
@@ -180,6 +203,13 @@ fn make_bookmark_term(bookmark_names: &[impl fmt::Display]) -> String {
     }
 }
 
+fn make_tag_term(tag_names: &[impl fmt::Display]) -> String {
+    match tag_names {
+        [tag_name] => format!("tag {tag_name}"),
+        tag_names => format!("tags {}", tag_names.iter().join(", ")),
+    }
+}
+
 const DEFAULT_REMOTE: &str = "origin";
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -206,6 +236,7 @@ pub fn cmd_git_push(
     let view = tx.repo().view();
     let tx_description;
     let mut bookmark_updates = vec![];
+    let mut tag_updates = vec![];
     if args.all {
         for (bookmark_name, targets) in view.local_remote_bookmarks(&remote) {
             let allow_new = true; // implied by --all
@@ -291,8 +322,10 @@ pub fn cmd_git_push(
             }
         }
 
-        let use_default_revset =
-            args.bookmark.is_empty() && args.change.is_empty() && args.revisions.is_empty();
+        let use_default_revset = args.bookmark.is_empty()
+            && args.change.is_empty()
+            && args.revisions.is_empty()
+            && args.tag.is_empty();
         let bookmarks_targeted = find_bookmarks_targeted_by_revisions(
             ui,
             tx.base_workspace_helper(),
@@ -311,18 +344,28 @@ pub fn cmd_git_push(
             }
         }
 
-        tx_description = format!(
-            "push {} to git remote {}",
-            make_bookmark_term(
+        tag_updates = find_tags_to_push(tx.repo().view(), &args.tag)?;
+
+        let mut terms = vec![];
+        if !bookmark_updates.is_empty() {
+            terms.push(make_bookmark_term(
                 &bookmark_updates
                     .iter()
                     .map(|(bookmark, _)| bookmark.as_str())
-                    .collect_vec()
-            ),
-            &remote
-        );
+                    .collect_vec(),
+            ));
+        }
+        if !tag_updates.is_empty() {
+            terms.push(make_tag_term(
+                &tag_updates
+                    .iter()
+                    .map(|(tag, _)| tag.as_str())
+                    .collect_vec(),
+            ));
+        }
+        tx_description = format!("push {} to git remote {}", terms.join(" and "), &remote);
     }
-    if bookmark_updates.is_empty() {
+    if bookmark_updates.is_empty() && tag_updates.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
         return Ok(());
     }
@@ -332,8 +375,22 @@ pub fn cmd_git_push(
     } else {
         None
     };
-    let commits_to_sign =
-        validate_commits_ready_to_push(ui, &bookmark_updates, &remote, &tx, args, sign_behavior)?;
+    let tag_heads = tag_updates.iter().map(|(_, id)| id.clone()).collect_vec();
+    let new_heads = bookmark_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .chain(tag_heads.iter().cloned())
+        .collect_vec();
+    let mut commits_to_sign =
+        validate_commits_ready_to_push(ui, new_heads, &remote, &tx, args, sign_behavior)?;
+    // Tagged commits can't be signed since rewriting them would leave the tags
+    // behind.
+    let index = tx.repo().index();
+    commits_to_sign.retain(|commit| {
+        !tag_heads
+            .iter()
+            .any(|tag_head| index.is_ancestor(commit.id(), tag_head))
+    });
     if !args.dry_run && !commits_to_sign.is_empty() {
         if let Some(sign_behavior) = sign_behavior {
             let num_updated_signatures = commits_to_sign.len();
@@ -362,6 +419,13 @@ pub fn cmd_git_push(
     if let Some(mut formatter) = ui.status_formatter() {
         writeln!(formatter, "Changes to push to {remote}:")?;
         print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &bookmark_updates)?;
+        for (tag_name, target) in &tag_updates {
+            writeln!(
+                formatter,
+                "  Add tag {tag_name} to {}",
+                short_commit_hash(target)
+            )?;
+        }
     }
 
     if args.dry_run {
@@ -369,14 +433,41 @@ pub fn cmd_git_push(
         return Ok(());
     }
 
+    let mut git_settings = tx.settings().git_settings()?;
+    git_settings.push_atomic |= args.atomic;
     let targets = GitBranchPushTargets {
         branch_updates: bookmark_updates,
     };
-    let git_settings = tx.settings().git_settings()?;
     with_remote_git_callbacks(ui, |cb| {
-        git::push_branches(tx.repo_mut(), &git_settings, &remote, &targets, cb)
+        git::push_branches_and_tags(
+            tx.repo_mut(),
+            &git_settings,
+            &remote,
+            &targets,
+            &tag_updates,
+            cb,
+        )
+    })
+    .map_err(|err| match err {
+        GitPushError::RefInUnexpectedLocation(refs)
+            if refs.iter().all(|name| name.starts_with("refs/tags/")) =>
+        {
+            user_error_with_hint(
+                format!(
+                    "Refusing to overwrite tags which already exist on the remote: {}",
+                    refs.iter()
+                        .map(|name| name.strip_prefix("refs/tags/").unwrap_or(name))
+                        .join(", ")
+                ),
+                "Delete the tags on the remote first, or push them under different names.",
+            )
+        }
+        err => err.into(),
     })?;
-    tx.finish(ui, tx_description)?;
+    // Pushing tags doesn't record anything in the repo.
+    if tx.repo().has_changes() {
+        tx.finish(ui, tx_description)?;
+    }
     Ok(())
 }
 
@@ -386,7 +477,7 @@ pub fn cmd_git_push(
 /// Returns the list of commits which need to be signed.
 fn validate_commits_ready_to_push(
     ui: &Ui,
-    bookmark_updates: &[(String, BookmarkPushUpdate)],
+    new_heads: Vec<CommitId>,
     remote: &str,
     tx: &WorkspaceCommandTransaction,
     args: &GitPushArgs,
@@ -395,10 +486,6 @@ fn validate_commits_ready_to_push(
     let workspace_helper = tx.base_workspace_helper();
     let repo = workspace_helper.repo();
 
-    let new_heads = bookmark_updates
-        .iter()
-        .filter_map(|(_, update)| update.new_target.clone())
-        .collect_vec();
     let old_heads = repo
         .view()
         .remote_bookmarks(remote)
@@ -753,6 +840,41 @@ fn find_bookmarks_to_push<'a>(
     }
 }
 
+fn find_tags_to_push(
+    view: &View,
+    tag_patterns: &[StringPattern],
+) -> Result<Vec<(String, CommitId)>, CommandError> {
+    let mut matching_tags = vec![];
+    let mut unmatched_patterns = vec![];
+    for pattern in tag_patterns {
+        let mut matches = view.tags_matching(pattern).peekable();
+        if matches.peek().is_none() {
+            unmatched_patterns.push(pattern);
+        }
+        for (name, target) in matches {
+            let Some(id) = target.as_normal() else {
+                return Err(user_error(format!("Tag {name} is conflicted")));
+            };
+            matching_tags.push((name.to_owned(), id.clone()));
+        }
+    }
+    match &unmatched_patterns[..] {
+        [] => {}
+        [pattern] if pattern.is_exact() => {
+            return Err(user_error(format!("No such tag: {pattern}")));
+        }
+        patterns => {
+            return Err(user_error(format!(
+                "No matching tags for patterns: {}",
+                patterns.iter().join(", ")
+            )));
+        }
+    }
+    matching_tags.sort_unstable();
+    matching_tags.dedup();
+    Ok(matching_tags)
+}
+
 fn find_bookmarks_targeted_by_revisions<'a>(
     ui: &Ui,
     workspace_command: &'a WorkspaceCommandHelper,
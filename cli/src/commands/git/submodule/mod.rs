@@ -0,0 +1,76 @@
+// Copyright 2023 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod print_gitmodules;
+mod status;
+mod update;
+
+use clap::Subcommand;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::TreeValue;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo_path::RepoPathBuf;
+
+use self::print_gitmodules::cmd_git_submodule_print_gitmodules;
+use self::print_gitmodules::GitSubmodulePrintGitmodulesArgs;
+use self::status::cmd_git_submodule_status;
+use self::status::GitSubmoduleStatusArgs;
+use self::update::cmd_git_submodule_update;
+use self::update::GitSubmoduleUpdateArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Manage Git submodules
+///
+/// The repositories of the submodules are stored inside the `.jj/` directory.
+/// Changes to the files in a checked-out submodule are recorded as new
+/// submodule commits when the working copy is snapshotted.
+#[derive(Subcommand, Clone, Debug)]
+pub enum GitSubmoduleCommand {
+    /// Print the relevant contents from .gitmodules. For debugging purposes
+    /// only.
+    PrintGitmodules(GitSubmodulePrintGitmodulesArgs),
+    Status(GitSubmoduleStatusArgs),
+    Update(GitSubmoduleUpdateArgs),
+}
+
+pub fn cmd_git_submodule(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &GitSubmoduleCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        GitSubmoduleCommand::PrintGitmodules(args) => {
+            cmd_git_submodule_print_gitmodules(ui, command, args)
+        }
+        GitSubmoduleCommand::Status(args) => cmd_git_submodule_status(ui, command, args),
+        GitSubmoduleCommand::Update(args) => cmd_git_submodule_update(ui, command, args),
+    }
+}
+
+/// Returns the submodule paths and commits recorded in the `tree`.
+fn submodule_entries(
+    tree: &MergedTree,
+    matcher: &dyn Matcher,
+) -> Result<Vec<(RepoPathBuf, CommitId)>, CommandError> {
+    let mut entries = vec![];
+    for (path, value) in tree.entries_matching(matcher) {
+        if let Some(Some(TreeValue::GitSubmodule(id))) = value?.as_resolved() {
+            entries.push((path, id.clone()));
+        }
+    }
+    Ok(entries)
+}
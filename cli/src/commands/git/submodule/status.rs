@@ -0,0 +1,86 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::backend::BackendError;
+use jj_lib::repo::Repo as _;
+use jj_lib::submodule::is_submodule_populated;
+use jj_lib::submodule::SubmoduleNames;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Show the state of the Git submodules in the working copy
+///
+/// Each submodule is listed with the commit recorded in the working-copy
+/// commit, followed by one of the following states:
+///
+/// * `not initialized`: The submodule repository doesn't exist yet.
+///
+/// * `not fetched`: The submodule commit hasn't been fetched.
+///
+/// * `not checked out`: The submodule directory is empty.
+///
+/// * `checked out`: The files of the submodule commit are in the working copy.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitSubmoduleStatusArgs {
+    /// Only show submodules matching these paths
+    #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+}
+
+pub fn cmd_git_submodule_status(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitSubmoduleStatusArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let wc_commit_id = workspace_command
+        .get_wc_commit_id()
+        .ok_or_else(|| user_error("This command requires a working copy"))?;
+    let tree = repo.store().get_commit(wc_commit_id)?.tree()?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let names = SubmoduleNames::from_tree(&tree);
+    let mut formatter = ui.stdout_formatter();
+    for (path, commit_id) in super::submodule_entries(&tree, matcher.as_ref())? {
+        let name = names.get(&path);
+        let disk_dir = path
+            .to_fs_path(workspace_command.workspace_root())
+            .map_err(user_error)?;
+        let state = match repo.submodule_store().get_submodule(&name)? {
+            None => "not initialized",
+            Some(store) => match store.get_commit(&commit_id) {
+                Err(BackendError::ObjectNotFound { .. }) => "not fetched",
+                Err(err) => return Err(err.into()),
+                Ok(_) if is_submodule_populated(&disk_dir) => "checked out",
+                Ok(_) => "not checked out",
+            },
+        };
+        write!(formatter, "{} ", workspace_command.format_file_path(&path))?;
+        write!(
+            formatter.labeled("commit_id"),
+            "{}",
+            short_commit_hash(&commit_id)
+        )?;
+        writeln!(formatter, " ({state})")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,157 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::sync::Arc;
+
+use jj_lib::backend::BackendError;
+use jj_lib::backend::CommitId;
+use jj_lib::git;
+use jj_lib::repo::Repo as _;
+use jj_lib::store::Store;
+use jj_lib::submodule::check_out_submodule;
+use jj_lib::submodule::is_submodule_populated;
+use jj_lib::submodule::read_gitmodules;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error_with_message;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Fetch and check out the Git submodules in the working copy
+///
+/// The repositories of the submodules listed in `.gitmodules` are initialized
+/// as needed, and the submodule commits recorded in the working-copy commit
+/// are fetched from the configured URLs if they're missing. Submodules which
+/// aren't checked out yet are then populated. Submodules which are already
+/// checked out are left alone.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitSubmoduleUpdateArgs {
+    /// Only update submodules matching these paths
+    #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+}
+
+pub fn cmd_git_submodule_update(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitSubmoduleUpdateArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let wc_commit_id = workspace_command
+        .get_wc_commit_id()
+        .ok_or_else(|| user_error("This command requires a working copy"))?;
+    let tree = repo.store().get_commit(wc_commit_id)?.tree()?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let gitmodules = read_gitmodules(&tree)
+        .map_err(|err| user_error_with_message("Failed to read .gitmodules", err))?;
+    let submodule_store = repo.submodule_store();
+    for (path, commit_id) in super::submodule_entries(&tree, matcher.as_ref())? {
+        let ui_path = workspace_command.format_file_path(&path);
+        let Some(config) = gitmodules
+            .values()
+            .find(|config| config.path == path.as_internal_file_string())
+        else {
+            writeln!(
+                ui.warning_default(),
+                "Skipping submodule {ui_path} which isn't configured in .gitmodules"
+            )?;
+            continue;
+        };
+        let disk_dir = path
+            .to_fs_path(workspace_command.workspace_root())
+            .map_err(user_error)?;
+        if is_submodule_populated(&disk_dir) {
+            continue;
+        }
+        let store = submodule_store.init_submodule(&config.name)?;
+        if !has_commit(&store, &commit_id)? {
+            let url = resolve_submodule_url(repo.store(), &config.url)?;
+            writeln!(
+                ui.status(),
+                "Fetching submodule {name} from {url}",
+                name = config.name
+            )?;
+            submodule_store.fetch_submodule(&config.name, &url)?;
+            if !has_commit(&store, &commit_id)? {
+                writeln!(
+                    ui.warning_default(),
+                    "Submodule commit {hash} doesn't exist in {url}",
+                    hash = short_commit_hash(&commit_id)
+                )?;
+                continue;
+            }
+        }
+        // The directory isn't populated, so there are no files to skip.
+        check_out_submodule(&store, &disk_dir, None, &commit_id).map_err(|err| {
+            internal_error_with_message(format!("Failed to check out submodule {ui_path}"), err)
+        })?;
+        writeln!(
+            ui.status(),
+            "Checked out submodule {ui_path} at {hash}",
+            hash = short_commit_hash(&commit_id)
+        )?;
+    }
+    Ok(())
+}
+
+fn has_commit(store: &Arc<Store>, commit_id: &CommitId) -> Result<bool, CommandError> {
+    match store.get_commit(commit_id) {
+        Ok(_) => Ok(true),
+        Err(BackendError::ObjectNotFound { .. }) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Resolves a URL relative to the URL of the `origin` remote like Git does.
+fn resolve_submodule_url(store: &Store, url: &str) -> Result<String, CommandError> {
+    if !url.starts_with("./") && !url.starts_with("../") {
+        return Ok(url.to_owned());
+    }
+    let git_repo = git::get_git_repo(store)?;
+    let base_url = git_repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|remote| {
+            remote
+                .url(gix::remote::Direction::Fetch)
+                .map(|url| url.to_bstring().to_string())
+        })
+        .ok_or_else(|| {
+            user_error(format!(
+                "Cannot resolve relative submodule URL {url} without an `origin` remote"
+            ))
+        })?;
+    let mut base = base_url.trim_end_matches('/').to_owned();
+    let mut rest = url;
+    loop {
+        if let Some(tail) = rest.strip_prefix("./") {
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("../") {
+            if let Some(pos) = base.rfind('/') {
+                base.truncate(pos);
+            }
+            rest = tail;
+        } else {
+            break;
+        }
+    }
+    Ok(format!("{base}/{rest}"))
+}
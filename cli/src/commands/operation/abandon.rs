@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::io::Write as _;
 use std::iter;
 use std::slice;
@@ -19,7 +20,15 @@ use std::slice;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::op_walk;
+use jj_lib::op_walk::OpsetEvaluationError;
+use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::operation::Operation;
+use jj_lib::opset;
+use jj_lib::opset::OpsetExpression;
+use jj_lib::signing::OperationSigner;
+use jj_lib::time_util::DatePatternContext;
 
+use crate::cli_util::print_unsigned_operations;
 use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::cli_error;
@@ -37,15 +46,30 @@ use crate::ui::Ui;
 /// To discard recent operations, use `jj op restore <operation ID>` followed
 /// by `jj op abandon <operation ID>..@-`.
 ///
+/// Any other operation set can be abandoned as well. For example,
+/// `jj op abandon 'snapshot() & ..@-'` abandons all working-copy snapshot
+/// operations but the current one. The descendants will be reparented onto the
+/// nearest remaining ancestors.
+///
 /// The abandoned operations, commits, and other unreachable objects can later
 /// be garbage collected by using `jj util gc` command.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationAbandonArgs {
-    /// The operation or operation range to abandon
+    /// The operation, operation range, or operation set to abandon
     #[arg(add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 }
 
+/// Operations to be abandoned.
+enum AbandonPlan {
+    /// Reparents the descendants of the head operations onto the root
+    /// operation.
+    Range(Operation, Vec<Operation>),
+    /// Removes arbitrary set of operations. The descendants are reparented
+    /// onto the nearest retained ancestors.
+    Set(Vec<Operation>),
+}
+
 pub fn cmd_op_abandon(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -63,30 +87,49 @@ pub fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
-    let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
-    let (abandon_root_op, abandon_head_ops) =
-        if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
-            let root_op = if root_op_str.is_empty() {
-                repo_loader.root_operation()
-            } else {
-                resolve_op(root_op_str)?
+    let context = DatePatternContext::from(chrono::Local::now());
+    let expression = opset::parse(&args.operation, &context)?;
+    let evaluate = |expression: &OpsetExpression| {
+        op_walk::evaluate_opset_at(op_store, &current_head_ops, expression)
+    };
+    let plan = match expression.as_ref() {
+        OpsetExpression::Range { roots, heads } => {
+            let root_op = match evaluate(roots)?.as_slice() {
+                [op] => op.clone(),
+                [] => Err(OpsetEvaluationError::from(
+                    OpsetResolutionError::EmptyOperations(args.operation.clone()),
+                ))?,
+                ops => Err(OpsetEvaluationError::from(
+                    OpsetResolutionError::MultipleOperations {
+                        expr: args.operation.clone(),
+                        candidates: ops.iter().map(|op| op.id().clone()).collect(),
+                    },
+                ))?,
             };
-            let head_ops = if head_op_str.is_empty() {
-                current_head_ops.clone()
-            } else {
-                vec![resolve_op(head_op_str)?]
-            };
-            (root_op, head_ops)
-        } else {
-            let op = resolve_op(&args.operation)?;
-            let parent_ops: Vec<_> = op.parents().try_collect()?;
-            let parent_op = match parent_ops.len() {
-                0 => return Err(user_error("Cannot abandon the root operation")),
-                1 => parent_ops.into_iter().next().unwrap(),
-                _ => return Err(user_error("Cannot abandon a merge operation")),
-            };
-            (parent_op, vec![op])
-        };
+            AbandonPlan::Range(root_op, evaluate(heads)?)
+        }
+        _ => match evaluate(&expression)?.as_slice() {
+            [] => AbandonPlan::Set(vec![]),
+            [op] => {
+                let parent_ops: Vec<_> = op.parents().try_collect()?;
+                let parent_op = match parent_ops.len() {
+                    0 => return Err(user_error("Cannot abandon the root operation")),
+                    1 => parent_ops.into_iter().next().unwrap(),
+                    _ => return Err(user_error("Cannot abandon a merge operation")),
+                };
+                AbandonPlan::Range(parent_op, vec![op.clone()])
+            }
+            ops => {
+                if ops.iter().any(|op| op.parent_ids().is_empty()) {
+                    return Err(user_error("Cannot abandon the root operation"));
+                }
+                AbandonPlan::Set(ops.to_vec())
+            }
+        },
+    };
+    let abandon_head_ops = match &plan {
+        AbandonPlan::Range(_, head_ops) | AbandonPlan::Set(head_ops) => head_ops,
+    };
 
     if let Some(op) = abandon_head_ops
         .iter()
@@ -103,12 +146,26 @@ pub fn cmd_op_abandon(
     }
 
     // Reparent descendants, count the number of abandoned operations.
-    let stats = op_walk::reparent_range(
-        op_store.as_ref(),
-        &abandon_head_ops,
-        &current_head_ops,
-        &abandon_root_op,
-    )?;
+    let sign_settings = command.settings().sign_settings();
+    let signer = OperationSigner::new(repo_loader.store().signer(), &sign_settings);
+    let stats = match &plan {
+        AbandonPlan::Range(root_op, head_ops) => op_walk::reparent_range(
+            op_store.as_ref(),
+            head_ops,
+            &current_head_ops,
+            root_op,
+            signer.as_ref(),
+        )?,
+        AbandonPlan::Set(ops) => {
+            let ids: HashSet<_> = ops.iter().map(|op| op.id()).collect();
+            op_walk::retain_ancestors(
+                op_store.as_ref(),
+                &current_head_ops,
+                signer.as_ref(),
+                |op| Ok(!ids.contains(op.id())),
+            )?
+        }
+    };
     assert_eq!(
         current_head_ops.len(),
         stats.new_head_ids.len(),
@@ -125,6 +182,7 @@ pub fn cmd_op_abandon(
         stats.unreachable_count,
         stats.rewritten_count,
     )?;
+    print_unsigned_operations(ui, stats.unsigned_count)?;
     for (old, new_id) in reparented_head_ops().filter(|&(old, new_id)| old.id() != new_id) {
         op_heads_store.update_op_heads(slice::from_ref(old.id()), new_id)?;
     }
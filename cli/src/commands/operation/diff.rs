@@ -25,7 +25,7 @@ use jj_lib::commit::Commit;
 use jj_lib::dag_walk;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::TopoGroupedGraphIterator;
-use jj_lib::matchers::EverythingMatcher;
+use jj_lib::matchers::Matcher;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::RemoteRefState;
@@ -84,6 +84,9 @@ pub struct OperationDiffArgs {
     /// contaminated by unrelated changes.
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Restrict the diffs of modified changes to these paths
+    #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
@@ -109,6 +112,9 @@ pub fn cmd_op_diff(
     }
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
 
     let from_repo = repo_loader.load_at(&from_op)?;
     let to_repo = repo_loader.load_at(&to_op)?;
@@ -154,13 +160,15 @@ pub fn cmd_op_diff(
         (!args.no_graph).then_some(graph_style),
         &with_content_format,
         diff_renderer.as_ref(),
+        matcher.as_ref(),
     )
 }
 
 /// Computes and shows the differences between two operations, using the given
 /// `ReadonlyRepo`s for the operations.
 /// `current_repo` should contain a `Repo` with the indices of both repos merged
-/// into it.
+/// into it. Diffs of modified changes are restricted to the paths matched by
+/// `matcher`.
 #[allow(clippy::too_many_arguments)]
 pub fn show_op_diff(
     ui: &Ui,
@@ -172,6 +180,7 @@ pub fn show_op_diff(
     graph_style: Option<GraphStyle>,
     with_content_format: &LogContentFormat,
     diff_renderer: Option<&DiffRenderer>,
+    matcher: &dyn Matcher,
 ) -> Result<(), CommandError> {
     let changes = compute_operation_commits_diff(current_repo, from_repo, to_repo)?;
 
@@ -246,6 +255,7 @@ pub fn show_op_diff(
                         formatter.as_mut(),
                         diff_renderer,
                         modified_change,
+                        matcher,
                         within_graph.width(),
                     )?;
                 }
@@ -271,7 +281,14 @@ pub fn show_op_diff(
                 })?;
                 if let Some(diff_renderer) = &diff_renderer {
                     let width = with_content_format.width();
-                    show_change_diff(ui, formatter, diff_renderer, modified_change, width)?;
+                    show_change_diff(
+                        ui,
+                        formatter,
+                        diff_renderer,
+                        modified_change,
+                        matcher,
+                        width,
+                    )?;
                 }
             }
         }
@@ -550,24 +567,18 @@ fn show_change_diff(
     formatter: &mut dyn Formatter,
     diff_renderer: &DiffRenderer,
     change: &ModifiedChange,
+    matcher: &dyn Matcher,
     width: usize,
 ) -> Result<(), CommandError> {
     match (&*change.removed_commits, &*change.added_commits) {
         (predecessors @ ([] | [_]), [commit]) => {
             // New or modified change. If the modification involved a rebase,
             // show diffs from the rebased tree.
-            diff_renderer.show_inter_diff(
-                ui,
-                formatter,
-                predecessors,
-                commit,
-                &EverythingMatcher,
-                width,
-            )?;
+            diff_renderer.show_inter_diff(ui, formatter, predecessors, commit, matcher, width)?;
         }
         ([commit], []) => {
             // TODO: Should we show a reverse diff?
-            diff_renderer.show_patch(ui, formatter, commit, &EverythingMatcher, width)?;
+            diff_renderer.show_patch(ui, formatter, commit, matcher, width)?;
         }
         ([_, _, ..], _) | (_, [_, _, ..]) => {}
         ([], []) => panic!("ModifiedChange should have at least one entry"),
@@ -0,0 +1,95 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::slice;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::op_bundle;
+use jj_lib::op_walk;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Export operations and the objects they refer to as a bundle file
+///
+/// The bundle can be loaded into a scratch repo by `jj op import-bundle` to
+/// reproduce the operation log and the commit graph elsewhere, e.g. to debug
+/// a problem in someone else's repo.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationExportArgs {
+    /// The operations to export
+    ///
+    /// Use e.g. `..@` to export the whole operation log.
+    #[arg(
+        default_value = "@",
+        value_name = "OPSET",
+        add = ArgValueCandidates::new(complete::operations),
+    )]
+    operations: String,
+
+    /// The file to write the bundle to
+    #[arg(long, short, value_hint = clap::ValueHint::FilePath)]
+    output: PathBuf,
+
+    /// Also export the commits referenced by the operations, their ancestors,
+    /// and their trees and file contents
+    ///
+    /// Without this, the bundle can only be imported into a repo that already
+    /// contains the commits.
+    #[arg(long)]
+    with_objects: bool,
+}
+
+pub fn cmd_op_export(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationExportArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let ops = op_walk::resolve_opset_at(
+        repo.op_store(),
+        slice::from_ref(repo.operation()),
+        &args.operations,
+    )?;
+    let path = command.cwd().join(&args.output);
+    let mut file = BufWriter::new(File::create(&path).context(&path)?);
+    let stats = op_bundle::export_bundle(
+        repo.store(),
+        repo.op_store().as_ref(),
+        &ops,
+        args.with_objects,
+        &mut file,
+    )?;
+    file.flush().context(&path)?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Exported {} operations, {} commits, and {} other objects to {}",
+            stats.operation_count,
+            stats.commit_count,
+            stats.object_count,
+            path.display()
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::op_bundle;
+use jj_lib::repo::Repo as _;
+use jj_lib::signing::OperationSigner;
+
+use crate::cli_util::print_unsigned_operations;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Import operations from a bundle created by `jj op export`
+///
+/// The imported operations are merged into the operation log, and the repo is
+/// restored to the state of the imported head operation. The bundle should be
+/// imported into a scratch repo that uses the same backend as the exporting
+/// repo, e.g. one created by `jj git init`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationImportBundleArgs {
+    /// The bundle file to import
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    path: PathBuf,
+}
+
+pub fn cmd_op_import_bundle(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationImportBundleArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let path = command.cwd().join(&args.path);
+    let data = std::fs::read(&path).context(&path)?;
+    let repo = workspace_command.repo().clone();
+    let sign_settings = command.settings().sign_settings();
+    let signer = OperationSigner::new(repo.store().signer(), &sign_settings);
+    let imported = op_bundle::import_bundle(
+        repo.store(),
+        repo.op_store().as_ref(),
+        &data,
+        signer.as_ref(),
+    )?;
+    let repo_loader = repo.loader();
+    let head_ops: Vec<_> = imported
+        .head_ids
+        .iter()
+        .map(|id| repo_loader.load_operation(id))
+        .try_collect()?;
+
+    let mut tx = workspace_command.start_transaction();
+    for op in &head_ops {
+        tx.merge_operation(op.clone())?;
+    }
+    // Concurrent heads are left merged with the current state.
+    if let [head_op] = head_ops.as_slice() {
+        tx.repo_mut().set_view(head_op.view()?.store_view().clone());
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        let stats = &imported.stats;
+        writeln!(
+            formatter,
+            "Imported {} operations, {} commits, and {} other objects",
+            stats.operation_count, stats.commit_count, stats.object_count
+        )?;
+    }
+    print_unsigned_operations(ui, imported.unsigned_count)?;
+    tx.finish(ui, format!("import bundle {}", path.display()))?;
+    Ok(())
+}
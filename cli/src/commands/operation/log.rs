@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::slice;
 
 use clap_complete::ArgValueCandidates;
@@ -20,7 +22,10 @@ use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
+use jj_lib::graph::GraphNode;
+use jj_lib::matchers::EverythingMatcher;
 use jj_lib::op_store::OpStoreError;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
@@ -50,6 +55,15 @@ use crate::ui::Ui;
 /// to inspect the current state without mutation.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationLogArgs {
+    /// Which operations to show
+    ///
+    /// If no operations are specified, this defaults to all ancestors of the
+    /// current operation. See [operation sets] for the syntax.
+    ///
+    /// [operation sets]:
+    ///     https://jj-vcs.github.io/jj/latest/operation-log/#operation-sets
+    #[arg(long, short = 'r', value_name = "OPSET")]
+    operations: Option<String>,
     /// Limit number of operations to show
     ///
     /// Applied after operations are reordered topologically, but before being
@@ -197,6 +211,7 @@ fn do_op_log(
                 (!args.no_graph).then_some(graph_style),
                 with_content_format,
                 diff_renderer.as_ref(),
+                &EverythingMatcher,
             )
         };
         Some(show)
@@ -207,18 +222,28 @@ fn do_op_log(
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
-    let iter =
-        op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
+    let iter: Box<dyn Iterator<Item = Result<GraphNode<Operation, OperationId>, OpStoreError>>> =
+        if let Some(op_str) = &args.operations {
+            let ops = op_walk::resolve_opset_at(
+                &current_op.op_store(),
+                slice::from_ref(current_op),
+                op_str,
+            )?;
+            Box::new(build_filtered_graph(&ops)?.into_iter().map(Ok))
+        } else {
+            Box::new(
+                op_walk::walk_ancestors(slice::from_ref(current_op)).map_ok(|op| {
+                    let ids = op.parent_ids();
+                    let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
+                    (op, edges)
+                }),
+            )
+        };
+    let iter = iter.take(args.limit.unwrap_or(usize::MAX));
 
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        let iter = iter.map(|op| -> Result<_, OpStoreError> {
-            let op = op?;
-            let ids = op.parent_ids();
-            let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
-            Ok((op, edges))
-        });
         let iter_nodes: Box<dyn Iterator<Item = _>> = if args.reversed {
             Box::new(reverse_graph(iter, Operation::id)?.into_iter().map(Ok))
         } else {
@@ -252,8 +277,8 @@ fn do_op_log(
         } else {
             Box::new(iter)
         };
-        for op in iter {
-            let op = op?;
+        for node in iter {
+            let (op, _edges) = node?;
             with_content_format.write(formatter, |formatter| template.format(&op, formatter))?;
             if let Some(show) = &maybe_show_op_diff {
                 show(ui, formatter, &op, &with_content_format)?;
@@ -264,6 +289,49 @@ fn do_op_log(
     Ok(())
 }
 
+/// Builds graph nodes of the given operations sorted in reverse topological
+/// order. Edges to non-member ancestors are replaced with indirect edges to the
+/// nearest member ancestors.
+fn build_filtered_graph(
+    ops: &[Operation],
+) -> Result<Vec<GraphNode<Operation, OperationId>>, OpStoreError> {
+    let member_ids: HashSet<&OperationId> = ops.iter().map(|op| op.id()).collect();
+    // Nearest member ancestors of each operation, including itself.
+    let mut nearest_ids: HashMap<OperationId, Vec<OperationId>> = HashMap::new();
+    let ancestors: Vec<_> = op_walk::walk_ancestors(ops).try_collect()?;
+    for op in ancestors.iter().rev() {
+        let ids = if member_ids.contains(op.id()) {
+            vec![op.id().clone()]
+        } else {
+            op.parent_ids()
+                .iter()
+                .flat_map(|id| &nearest_ids[id])
+                .unique()
+                .cloned()
+                .collect()
+        };
+        nearest_ids.insert(op.id().clone(), ids);
+    }
+    let nodes = ops
+        .iter()
+        .map(|op| {
+            let mut edges = vec![];
+            for parent_id in op.parent_ids() {
+                if member_ids.contains(parent_id) {
+                    edges.push(GraphEdge::direct(parent_id.clone()));
+                } else if nearest_ids[parent_id].is_empty() {
+                    edges.push(GraphEdge::missing(parent_id.clone()));
+                } else {
+                    let ids = &nearest_ids[parent_id];
+                    edges.extend(ids.iter().cloned().map(GraphEdge::indirect));
+                }
+            }
+            (op.clone(), edges.into_iter().unique().collect())
+        })
+        .collect();
+    Ok(nodes)
+}
+
 fn get_node_template(style: GraphStyle, settings: &UserSettings) -> Result<String, ConfigGetError> {
     let symbol = settings.get_string("templates.op_log_node").optional()?;
     let default = if style.is_ascii() {
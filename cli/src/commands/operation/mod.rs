@@ -14,24 +14,36 @@
 
 mod abandon;
 mod diff;
+mod export;
+mod import_bundle;
 mod log;
 mod restore;
+mod revert;
 mod show;
 pub mod undo;
+mod verify;
 
 use abandon::cmd_op_abandon;
 use abandon::OperationAbandonArgs;
 use clap::Subcommand;
 use diff::cmd_op_diff;
 use diff::OperationDiffArgs;
+use export::cmd_op_export;
+use export::OperationExportArgs;
+use import_bundle::cmd_op_import_bundle;
+use import_bundle::OperationImportBundleArgs;
 use log::cmd_op_log;
 use log::OperationLogArgs;
 use restore::cmd_op_restore;
 use restore::OperationRestoreArgs;
+use revert::cmd_op_revert;
+use revert::OperationRevertArgs;
 use show::cmd_op_show;
 use show::OperationShowArgs;
 use undo::cmd_op_undo;
 use undo::OperationUndoArgs;
+use verify::cmd_op_verify;
+use verify::OperationVerifyArgs;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -47,10 +59,14 @@ use crate::ui::Ui;
 pub enum OperationCommand {
     Abandon(OperationAbandonArgs),
     Diff(OperationDiffArgs),
+    Export(OperationExportArgs),
+    ImportBundle(OperationImportBundleArgs),
     Log(OperationLogArgs),
     Restore(OperationRestoreArgs),
+    Revert(OperationRevertArgs),
     Show(OperationShowArgs),
     Undo(OperationUndoArgs),
+    Verify(OperationVerifyArgs),
 }
 
 pub fn cmd_operation(
@@ -61,10 +77,14 @@ pub fn cmd_operation(
     match subcommand {
         OperationCommand::Abandon(args) => cmd_op_abandon(ui, command, args),
         OperationCommand::Diff(args) => cmd_op_diff(ui, command, args),
+        OperationCommand::Export(args) => cmd_op_export(ui, command, args),
+        OperationCommand::ImportBundle(args) => cmd_op_import_bundle(ui, command, args),
         OperationCommand::Log(args) => cmd_op_log(ui, command, args),
         OperationCommand::Restore(args) => cmd_op_restore(ui, command, args),
+        OperationCommand::Revert(args) => cmd_op_revert(ui, command, args),
         OperationCommand::Show(args) => cmd_op_show(ui, command, args),
         OperationCommand::Undo(args) => cmd_op_undo(ui, command, args),
+        OperationCommand::Verify(args) => cmd_op_verify(ui, command, args),
     }
 }
 
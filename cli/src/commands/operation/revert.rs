@@ -0,0 +1,140 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::str_util::StringPattern;
+use jj_lib::view::ViewPortion;
+
+use super::view_with_desired_portions_restored;
+use super::UndoWhatToRestore;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Revert selected changes made by an earlier operation
+///
+/// Unlike `jj op undo`, the changes are reverted by three-way merge of the
+/// views, so that the later changes to the other parts of the repo are
+/// preserved. If the changes to revert conflict with the later changes, the
+/// affected bookmarks become conflicted.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationRevertArgs {
+    /// The operation to revert
+    ///
+    /// Use `jj op log` to find an operation to revert.
+    #[arg(default_value = "@", add = ArgValueCandidates::new(complete::operations))]
+    operation: String,
+
+    /// What portions of the operation to revert (can be repeated)
+    ///
+    /// * `repo`: The commits, local bookmarks, tags, and working-copy commits
+    ///
+    /// * `remote-tracking`: The remote-tracking bookmarks
+    ///
+    /// * `bookmarks:<pattern>`: The local bookmarks matching the [string
+    ///   pattern]
+    ///
+    /// * `working-copies`: The working-copy commits of all workspaces
+    ///
+    /// [string pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(
+        long,
+        value_name = "WHAT",
+        value_parser = parse_revert_what,
+        default_values = ["repo", "remote-tracking"],
+    )]
+    what: Vec<RevertWhat>,
+}
+
+/// Portion of the operation to be reverted.
+#[derive(Clone, Debug)]
+enum RevertWhat {
+    Repo,
+    Portion(ViewPortion),
+}
+
+fn parse_revert_what(src: &str) -> Result<RevertWhat, String> {
+    match src {
+        "repo" => Ok(RevertWhat::Repo),
+        "remote-tracking" => Ok(RevertWhat::Portion(ViewPortion::RemoteTracking)),
+        "working-copies" => Ok(RevertWhat::Portion(ViewPortion::WorkingCopies)),
+        _ => {
+            let Some(pattern) = src.strip_prefix("bookmarks:") else {
+                return Err(
+                    "expected `repo`, `remote-tracking`, `bookmarks:<pattern>`, or \
+                     `working-copies`"
+                        .to_owned(),
+                );
+            };
+            let pattern = StringPattern::parse(pattern).map_err(|err| err.to_string())?;
+            Ok(RevertWhat::Portion(ViewPortion::Bookmarks(pattern)))
+        }
+    }
+}
+
+pub fn cmd_op_revert(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationRevertArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let bad_op = workspace_command.resolve_single_op(&args.operation)?;
+    let mut parent_ops = bad_op.parents();
+    let Some(parent_op) = parent_ops.next().transpose()? else {
+        return Err(user_error("Cannot revert repo initialization"));
+    };
+    if parent_ops.next().is_some() {
+        return Err(user_error("Cannot revert a merge operation"));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let repo_loader = tx.base_repo().loader();
+    let bad_repo = repo_loader.load_at(&bad_op)?;
+    let parent_repo = repo_loader.load_at(&parent_op)?;
+    if args
+        .what
+        .iter()
+        .any(|what| matches!(what, RevertWhat::Repo))
+    {
+        tx.repo_mut().merge(&bad_repo, &parent_repo)?;
+        // Only the repo portion is merged here. The other portions are merged
+        // separately below.
+        let new_view = view_with_desired_portions_restored(
+            tx.repo().view().store_view(),
+            tx.base_repo().view().store_view(),
+            &[UndoWhatToRestore::Repo],
+        );
+        tx.repo_mut().set_view(new_view);
+    }
+    for what in &args.what {
+        if let RevertWhat::Portion(portion) = what {
+            tx.repo_mut()
+                .merge_view_portion(&bad_repo, &parent_repo, portion);
+        }
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Reverted operation: ")?;
+        let template = tx.base_workspace_helper().operation_summary_template();
+        template.format(&bad_op, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    tx.finish(ui, format!("revert operation {}", bad_op.id().hex()))?;
+
+    Ok(())
+}
@@ -12,10 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::iter;
+
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::op_store::ViewRefName;
+use jj_lib::operation::Operation;
+use jj_lib::repo::Repo;
 
 use super::diff::show_op_diff;
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::command_error::CommandError;
@@ -24,7 +32,9 @@ use crate::complete;
 use crate::diff_util::diff_formats_for_log;
 use crate::diff_util::DiffFormatArgs;
 use crate::diff_util::DiffRenderer;
+use crate::formatter::Formatter;
 use crate::graphlog::GraphStyle;
+use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// Show changes to the repository in an operation
@@ -43,6 +53,12 @@ pub struct OperationShowArgs {
     /// contaminated by unrelated changes.
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Restrict the diffs of modified changes to these paths
+    ///
+    /// The paths must be given after `--` so they aren't confused with the
+    /// operation, e.g. `jj op show @- -- src`.
+    #[arg(last = true, value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
@@ -58,7 +74,7 @@ pub fn cmd_op_show(
     let settings = workspace_command.settings();
     let op = workspace_command.resolve_single_op(&args.operation)?;
     let parents: Vec<_> = op.parents().try_collect()?;
-    let parent_op = repo_loader.merge_operations(parents, None)?;
+    let parent_op = repo_loader.merge_operations(parents.clone(), None)?;
     let parent_repo = repo_loader.load_at(&parent_op)?;
     let repo = repo_loader.load_at(&op)?;
 
@@ -71,6 +87,9 @@ pub fn cmd_op_show(
 
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
     let diff_renderer = {
         let formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
         let path_converter = workspace_env.path_converter();
@@ -96,6 +115,15 @@ pub fn cmd_op_show(
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     template.format(&op, formatter.as_mut())?;
+    if !op.metadata().conflicted_refs.is_empty() {
+        write_merge_conflicts(
+            formatter.as_mut(),
+            repo.as_ref(),
+            &commit_summary_template,
+            &op.metadata().conflicted_refs,
+            &parents,
+        )?;
+    }
 
     show_op_diff(
         ui,
@@ -107,5 +135,68 @@ pub fn cmd_op_show(
         (!args.no_graph).then_some(graph_style),
         &with_content_format,
         diff_renderer.as_ref(),
+        matcher.as_ref(),
     )
 }
+
+/// Explains the `conflicted_refs` recorded by the operation which merged the
+/// `parent_ops`.
+fn write_merge_conflicts(
+    formatter: &mut dyn Formatter,
+    repo: &dyn Repo,
+    commit_summary_template: &TemplateRenderer<Commit>,
+    conflicted_refs: &[ViewRefName],
+    parent_ops: &[Operation],
+) -> Result<(), CommandError> {
+    let parent_views: Vec<_> = parent_ops.iter().map(|op| op.view()).try_collect()?;
+    writeln!(formatter)?;
+    writeln!(
+        formatter,
+        "Conflicts introduced by merging concurrent operations:"
+    )?;
+    for name in conflicted_refs {
+        writeln!(formatter, "{name}:")?;
+        let target = repo.view().get_ref_target(name).as_merge();
+        for term in target.adds() {
+            write_conflict_term(formatter, repo, commit_summary_template, term, true)?;
+            // Tell which of the concurrent operations set the ref to this side.
+            let source_ops = iter::zip(parent_ops, &parent_views)
+                .filter(|(_, view)| view.get_ref_target(name).as_merge().adds().contains(term))
+                .map(|(op, _)| short_operation_hash(op.id()))
+                .collect_vec();
+            if !source_ops.is_empty() {
+                write!(formatter, " (from operation {})", source_ops.join(", "))?;
+            }
+            writeln!(formatter)?;
+        }
+        for term in target.removes() {
+            write_conflict_term(formatter, repo, commit_summary_template, term, false)?;
+            writeln!(formatter)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_conflict_term(
+    formatter: &mut dyn Formatter,
+    repo: &dyn Repo,
+    commit_summary_template: &TemplateRenderer<Commit>,
+    term: &Option<CommitId>,
+    added: bool,
+) -> Result<(), CommandError> {
+    formatter.with_label("diff", |formatter| {
+        write!(
+            formatter.labeled(if added { "added" } else { "removed" }),
+            "{}",
+            if added { "+" } else { "-" }
+        )
+    })?;
+    write!(formatter, " ")?;
+    if let Some(commit_id) = term {
+        let commit = repo.store().get_commit(commit_id)?;
+        commit_summary_template.format(&commit, formatter)?;
+    } else {
+        write!(formatter, "(absent)")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::slice;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::op_walk;
+use jj_lib::repo::Repo as _;
+use jj_lib::signing::SigStatus;
+use jj_lib::signing::SignError;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Verify the signatures of operations
+///
+/// Operations are signed by the configured signing backend if
+/// `signing.sign-operations` is enabled. This command reports the operations
+/// that don't have a good signature, and fails if there are any.
+///
+/// Since each operation refers to its parents by ID, a good signature on an
+/// operation also vouches for the chain of operations before it.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationVerifyArgs {
+    /// The operations to verify
+    ///
+    /// By default, all operations up to the current operation are verified.
+    #[arg(
+        default_value = "..@",
+        value_name = "OPSET",
+        add = ArgValueCandidates::new(complete::operations),
+    )]
+    operations: String,
+}
+
+pub fn cmd_op_verify(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationVerifyArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let signer = repo.store().signer();
+    let root_op_id = repo.op_store().root_operation_id();
+    let ops = op_walk::resolve_opset_at(
+        repo.op_store(),
+        slice::from_ref(repo.operation()),
+        &args.operations,
+    )?;
+    let template = workspace_command.operation_summary_template();
+
+    let mut good_count = 0;
+    let mut failed_count = 0;
+    let mut formatter = ui.stdout_formatter();
+    // The root operation can't be signed
+    for op in ops.iter().filter(|op| op.id() != root_op_id) {
+        let status = match op.verification(signer) {
+            Ok(Some(verification)) if verification.status == SigStatus::Good => {
+                good_count += 1;
+                continue;
+            }
+            Ok(Some(verification)) => verification.status.to_string(),
+            Ok(None) => "unsigned".to_owned(),
+            Err(SignError::InvalidSignatureFormat) => "invalid".to_owned(),
+            Err(err) => {
+                return Err(user_error_with_message(
+                    "Failed to verify operation signature",
+                    err,
+                ));
+            }
+        };
+        failed_count += 1;
+        formatter.with_label("signature status", |formatter| {
+            write!(formatter.labeled(&status), "{status}")
+        })?;
+        write!(formatter, ": ")?;
+        template.format(op, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    drop(formatter);
+
+    writeln!(
+        ui.status(),
+        "Verified {} operations: {good_count} with good signatures, {failed_count} without",
+        good_count + failed_count
+    )?;
+    if failed_count > 0 {
+        return Err(user_error("Some operations don't have a good signature"));
+    }
+    Ok(())
+}
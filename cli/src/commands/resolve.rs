@@ -16,27 +16,45 @@ use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools;
 use jj_lib::object_id::ObjectId;
+use jj_lib::recorded_resolutions::RecordedResolutions;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
+use crate::cli_util::record_conflict_resolutions;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
 
 /// Resolve conflicted files with an external merge tool
 ///
-/// Only conflicts that can be resolved with a 3-way merge are supported. See
-/// docs for merge tool configuration instructions. External merge tools will be
-/// invoked for each conflicted file one-by-one until all conflicts are
-/// resolved. To stop resolving conflicts, exit the merge tool without making
-/// any changes.
+/// External merge tools only support conflicts that can be resolved with a
+/// 3-way merge. See docs for merge tool configuration instructions. External
+/// merge tools will be invoked for each conflicted file one-by-one until all
+/// conflicts are resolved. To stop resolving conflicts, exit the merge tool
+/// without making any changes.
+///
+/// The built-in `:builtin` tool supports conflicts with any number of sides.
+/// It shows the lines of each side and base of every conflicting hunk, and
+/// the selected lines replace the hunk. Hunks without selected lines are left
+/// unresolved.
+///
+/// Conflicts which don't involve the contents of regular files, such as
+/// executable bit, symlink, and file/directory conflicts, are resolved by
+/// picking one of the sides at a prompt instead. The `:ours` and `:theirs`
+/// tools resolve any kind of conflict by picking the first or the second side
+/// without prompting. `:theirs` only supports 2-sided conflicts.
 ///
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
 /// editor.
+///
+/// If `rerere.enabled` is set, resolutions of conflicting hunks are recorded
+/// and reused when the same conflict appears again. Use `--list-reused` to see
+/// the reused resolutions, and `--forget` to remove recorded resolutions.
 //  TODOs:
 //   - `jj resolve --editor` to resolve a conflict in the default text editor. Should work for
 //     conflicts with 3+ adds. Useful to resolve conflicts in a commit other than the current one.
@@ -58,8 +76,21 @@ pub(crate) struct ResolveArgs {
     // `diff --summary`, but should be more verbose.
     #[arg(long, short)]
     list: bool,
-    /// Specify 3-way merge tool to be used
-    #[arg(long, conflicts_with = "list", value_name = "NAME")]
+    /// Instead of resolving conflicts, list the recorded resolutions which
+    /// were reused to resolve conflicts automatically, most recent first
+    #[arg(long, conflicts_with_all = ["list", "revision"])]
+    list_reused: bool,
+    /// Instead of resolving conflicts, forget the recorded resolutions of
+    /// conflicts in these paths
+    #[arg(long, conflicts_with_all = ["list", "list_reused", "revision"])]
+    forget: bool,
+    /// Specify 3-way merge tool to be used, or `:ours` or `:theirs` to pick
+    /// a side
+    #[arg(
+        long,
+        conflicts_with_all = ["list", "list_reused", "forget"],
+        value_name = "NAME"
+    )]
     tool: Option<String>,
     /// Only resolve conflicts in these paths. You can use the `--list` argument
     /// to find paths to use here.
@@ -81,6 +112,25 @@ pub(crate) fn cmd_resolve(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
+    if args.list_reused || args.forget {
+        let resolutions = RecordedResolutions::for_repo(workspace_command.repo_path());
+        if args.forget {
+            let count = resolutions.forget(matcher.as_ref()).map_err(user_error)?;
+            writeln!(ui.status(), "Forgot {count} recorded resolutions")?;
+            return Ok(());
+        }
+        let reused = resolutions.reused().map_err(user_error)?;
+        let mut formatter = ui.stdout_formatter();
+        for resolution in reused.iter().filter(|r| matcher.matches(&r.path)) {
+            write!(
+                formatter.labeled("path"),
+                "{}",
+                workspace_command.format_file_path(&resolution.path)
+            )?;
+            writeln!(formatter, " {}", &resolution.key[..12])?;
+        }
+        return Ok(());
+    }
     let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
     let tree = commit.tree()?;
     let conflicts = tree
@@ -111,6 +161,8 @@ pub(crate) fn cmd_resolve(
     let mut tx = workspace_command.start_transaction();
     let (new_tree_id, partial_resolution_error) =
         merge_editor.edit_files(ui, &tree, &repo_paths)?;
+    let new_tree = tree.store().get_root_tree(&new_tree_id)?;
+    record_conflict_resolutions(ui, &tree, &new_tree);
     let new_commit = tx
         .repo_mut()
         .rewrite_commit(&commit)
@@ -14,19 +14,63 @@
 
 //! This file contains the internal implementation of `run`.
 
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::io;
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::sync::Mutex;
+
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::fsmonitor::FsmonitorSettings;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::OperationId;
+use jj_lib::op_store::WorkspaceId;
+use jj_lib::repo::Repo as _;
+use jj_lib::store::Store;
+use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::WorkingCopy as _;
+use tracing::instrument;
 
+use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
-/// (**Stub**, does not work yet) Run a command across a set of revisions.
+/// Run a command across a set of revisions
+///
+/// Each revision is checked out into its own temporary working copy, and the
+/// command is run there by the system shell. The user's working copy is left
+/// untouched, so you can keep working while the command runs. Output of each
+/// run is prefixed with the change id of the revision it was run on.
 ///
+/// The temporary working copies are kept in the `.jj/run` directory and are
+/// reused between invocations, so ignored files such as build outputs survive
+/// from one run to the next. Use `jj run --clean` to start from scratch.
 ///
-/// All recorded state will be persisted in the `.jj` directory, so occasionally
-/// a `jj run --clean` is needed to clean up disk space.
+/// If `--rewrite` is given, files changed by the command are written back into
+/// the revision it was run on, and descendants are rebased on top of it.
 ///
 /// # Example
 ///
@@ -48,23 +92,427 @@ pub struct RunArgs {
     /// How many processes should run in parallel, uses by default all cores.
     #[arg(long, short)]
     jobs: Option<usize>,
+    /// Rewrite the revisions with the changes the command made to their files.
+    #[arg(long)]
+    rewrite: bool,
+    /// Remove the temporary working copies, including ignored files, before
+    /// running.
+    #[arg(long)]
+    clean: bool,
 }
 
+#[instrument(skip_all)]
 pub fn cmd_run(ui: &mut Ui, command: &CommandHelper, args: &RunArgs) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
-    let _resolved_commits: Vec<_> = workspace_command
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut commits: Vec<Commit> = workspace_command
         .parse_union_revsets(ui, &args.revisions)?
         .evaluate_to_commits()?
         .try_collect()?;
+    // Run ancestors first so the output reads in topological order.
+    commits.reverse();
+    if args.rewrite {
+        workspace_command.check_rewritable(commits.iter().ids())?;
+    }
     // Jobs are resolved in this order:
     // 1. Commandline argument iff > 0.
     // 2. the amount of cores available.
     // 3. a single job, if all of the above fails.
-    let _jobs = match args.jobs {
+    let jobs = match args.jobs {
         Some(0) | None => std::thread::available_parallelism().map(|t| t.into()).ok(),
         Some(jobs) => Some(jobs),
     }
     // Fallback to a single user-visible job.
-    .unwrap_or(1usize);
-    Err(user_error("This is a stub, do not use"))
+    .unwrap_or(1usize)
+    .min(commits.len().max(1));
+
+    let run_dir = workspace_command.workspace_root().join(".jj").join("run");
+    if args.clean && run_dir.exists() {
+        std::fs::remove_dir_all(&run_dir).map_err(|err| {
+            internal_error_with_message("Failed to remove temporary working copies", err)
+        })?;
+    }
+
+    let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
+    let snapshot_options = SnapshotOptions {
+        // The temporary working copies aren't watched by any fsmonitor.
+        fsmonitor_settings: FsmonitorSettings::None,
+        ..workspace_command.snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)?
+    };
+    let context = RunContext {
+        store: workspace_command.repo().store().clone(),
+        operation_id: workspace_command.repo().op_id().clone(),
+        shell_command: &args.shell_command,
+        checkout_options: workspace_command.checkout_options(),
+        snapshot_options,
+    };
+    let prefixes: HashMap<CommitId, String> = {
+        let template = workspace_command.short_change_id_template();
+        commits
+            .iter()
+            .map(|commit| (commit.id().clone(), format_template(ui, commit, &template)))
+            .collect()
+    };
+
+    let queue = Mutex::new(commits.iter().collect::<VecDeque<_>>());
+    let (event_tx, event_rx) = channel();
+    let results: HashMap<CommitId, RunResult> = std::thread::scope(|scope| {
+        for slot in 0..jobs {
+            let context = &context;
+            let queue = &queue;
+            let event_tx = event_tx.clone();
+            let slot_dir = run_dir.join(slot.to_string());
+            scope.spawn(move || {
+                let working_copy_path = slot_dir.join("working_copy");
+                let working_copy = match load_working_copy(context, &slot_dir, slot) {
+                    Ok(working_copy) => working_copy,
+                    Err(err) => {
+                        event_tx.send(RunEvent::SetUpFailed(err)).ok();
+                        return;
+                    }
+                };
+                loop {
+                    let Some(commit) = queue.lock().unwrap().pop_front() else {
+                        break;
+                    };
+                    let result = run_on_commit(
+                        context,
+                        &working_copy,
+                        &working_copy_path,
+                        commit,
+                        &event_tx,
+                    );
+                    event_tx
+                        .send(RunEvent::Finished(commit.id().clone(), result))
+                        .ok();
+                }
+            });
+        }
+        drop(event_tx);
+
+        let mut results = HashMap::new();
+        let mut handle_event = |event| -> Result<(), CommandError> {
+            match event {
+                RunEvent::Output {
+                    commit_id,
+                    stream,
+                    line,
+                } => {
+                    let prefix = &prefixes[&commit_id];
+                    match stream {
+                        OutputStream::Stdout => {
+                            let mut stdout = ui.stdout();
+                            write!(stdout, "{prefix}: ")?;
+                            stdout.write_all(&line)?;
+                        }
+                        OutputStream::Stderr => {
+                            let mut stderr = ui.stderr();
+                            write!(stderr, "{prefix}: ")?;
+                            stderr.write_all(&line)?;
+                        }
+                    }
+                }
+                RunEvent::Finished(commit_id, result) => {
+                    results.insert(commit_id, result?);
+                }
+                RunEvent::SetUpFailed(err) => return Err(err),
+            }
+            Ok(())
+        };
+        for event in event_rx {
+            if let Err(err) = handle_event(event) {
+                // Don't start any new runs. The ones in progress are waited for
+                // when the scope ends.
+                queue.lock().unwrap().clear();
+                return Err(err);
+            }
+        }
+        Ok(results)
+    })?;
+
+    let failed_commits = commits
+        .iter()
+        .filter(|commit| !results[commit.id()].status.success())
+        .collect_vec();
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Ran command on {} revisions: {} passed, {} failed",
+            commits.len(),
+            commits.len() - failed_commits.len(),
+            failed_commits.len()
+        )?;
+        for commit in &failed_commits {
+            write!(formatter, "  ")?;
+            formatter.with_label("error", |formatter| {
+                write!(
+                    formatter,
+                    "{}",
+                    describe_status(results[commit.id()].status)
+                )
+            })?;
+            write!(formatter, ": ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+
+    if args.rewrite {
+        rewrite_commits(
+            ui,
+            &mut workspace_command,
+            &args.shell_command,
+            &commits,
+            &results,
+        )?;
+    }
+
+    if failed_commits.is_empty() {
+        Ok(())
+    } else {
+        Err(user_error(format!(
+            "Command failed on {} of {} revisions",
+            failed_commits.len(),
+            commits.len()
+        )))
+    }
+}
+
+/// State shared by all the jobs of a `jj run` invocation.
+struct RunContext<'a> {
+    store: Arc<Store>,
+    operation_id: OperationId,
+    shell_command: &'a str,
+    checkout_options: CheckoutOptions,
+    snapshot_options: SnapshotOptions<'a>,
+}
+
+/// The outcome of running the command on a single revision.
+struct RunResult {
+    status: ExitStatus,
+    /// The working-copy tree after the command finished.
+    new_tree_id: MergedTreeId,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Messages sent from the jobs to the thread that owns the `Ui`.
+enum RunEvent {
+    Output {
+        commit_id: CommitId,
+        stream: OutputStream,
+        line: Vec<u8>,
+    },
+    Finished(CommitId, Result<RunResult, CommandError>),
+    SetUpFailed(CommandError),
+}
+
+/// Loads the temporary working copy of a job, creating it if it doesn't exist
+/// yet.
+fn load_working_copy(
+    context: &RunContext,
+    slot_dir: &Path,
+    slot: usize,
+) -> Result<LocalWorkingCopy, CommandError> {
+    let working_copy_path = slot_dir.join("working_copy");
+    let state_path = slot_dir.join("state");
+    if state_path.join("checkout").exists() {
+        return Ok(LocalWorkingCopy::load(
+            context.store.clone(),
+            working_copy_path,
+            state_path,
+        ));
+    }
+    for dir in [&working_copy_path, &state_path] {
+        std::fs::create_dir_all(dir).map_err(|err| {
+            internal_error_with_message(
+                format!(
+                    "Failed to create temporary working copy in {}",
+                    dir.display()
+                ),
+                err,
+            )
+        })?;
+    }
+    let working_copy = LocalWorkingCopy::init(
+        context.store.clone(),
+        working_copy_path,
+        state_path,
+        context.operation_id.clone(),
+        WorkspaceId::new(format!("run-{slot}")),
+    )?;
+    Ok(working_copy)
+}
+
+/// Checks out `commit` in the job's working copy, runs the command there, and
+/// snapshots whatever the command left behind.
+fn run_on_commit(
+    context: &RunContext,
+    working_copy: &LocalWorkingCopy,
+    working_copy_path: &Path,
+    commit: &Commit,
+    event_tx: &Sender<RunEvent>,
+) -> Result<RunResult, CommandError> {
+    let mut locked_wc = working_copy.start_mutation()?;
+    // Make the recorded state match the disk again in case an earlier run was
+    // interrupted before it could snapshot.
+    locked_wc.snapshot(&context.snapshot_options)?;
+    locked_wc
+        .check_out(commit, &context.checkout_options)
+        .map_err(|err| {
+            internal_error_with_message("Failed to check out commit in temporary working copy", err)
+        })?;
+    let status = run_shell_command(context.shell_command, working_copy_path, commit, event_tx)?;
+    let (new_tree_id, _stats) = locked_wc.snapshot(&context.snapshot_options)?;
+    locked_wc.finish(context.operation_id.clone())?;
+    Ok(RunResult {
+        status,
+        new_tree_id,
+    })
+}
+
+fn run_shell_command(
+    shell_command: &str,
+    cwd: &Path,
+    commit: &Commit,
+    event_tx: &Sender<RunEvent>,
+) -> Result<ExitStatus, CommandError> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command
+        .arg(shell_command)
+        .current_dir(cwd)
+        .env("JJ_CHANGE_ID", commit.change_id().reverse_hex())
+        .env("JJ_COMMIT_ID", commit.id().hex())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    tracing::debug!(?command, "spawning run command");
+    let mut child = command
+        .spawn()
+        .map_err(|err| user_error(format!("Failed to run '{shell_command}': {err}")))?;
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err(internal_error(format!(
+            "Failed to capture output of '{shell_command}'"
+        )));
+    };
+    let (stdout_result, stderr_result) = std::thread::scope(|scope| {
+        let stdout_handle =
+            scope.spawn(|| forward_lines(stdout, OutputStream::Stdout, commit.id(), event_tx));
+        let stderr_handle =
+            scope.spawn(|| forward_lines(stderr, OutputStream::Stderr, commit.id(), event_tx));
+        (stdout_handle.join().unwrap(), stderr_handle.join().unwrap())
+    });
+    // Reap the child before reporting read errors.
+    let status = child.wait()?;
+    stdout_result.and(stderr_result).map_err(|err| {
+        user_error_with_message(format!("Failed to read output of '{shell_command}'"), err)
+    })?;
+    Ok(status)
+}
+
+/// Sends the output of the child process line by line, so output of jobs
+/// running in parallel is interleaved only at line boundaries.
+fn forward_lines(
+    output: impl Read,
+    stream: OutputStream,
+    commit_id: &CommitId,
+    event_tx: &Sender<RunEvent>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(output);
+    loop {
+        let mut line = vec![];
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        if !line.ends_with(b"\n") {
+            line.push(b'\n');
+        }
+        let event = RunEvent::Output {
+            commit_id: commit_id.clone(),
+            stream,
+            line,
+        };
+        if event_tx.send(event).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+fn describe_status(status: ExitStatus) -> String {
+    if let Some(exit_code) = status.code() {
+        format!("exited with {exit_code}")
+    } else {
+        format!("terminated by {status}")
+    }
+}
+
+/// Writes the trees produced by successful runs back into their revisions.
+///
+/// Each revision is rebased onto its rewritten parents first, and the changes
+/// the command made on top of the original revision are then merged in. This
+/// way, changes made in ancestors are kept, whether or not they were also part
+/// of the run.
+fn rewrite_commits(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    shell_command: &str,
+    commits: &[Commit],
+    results: &HashMap<CommitId, RunResult>,
+) -> Result<(), CommandError> {
+    let store = workspace_command.repo().store().clone();
+    let mut new_trees = HashMap::new();
+    for commit in commits {
+        let result = &results[commit.id()];
+        if result.status.success() && result.new_tree_id != *commit.tree_id() {
+            new_trees.insert(
+                commit.id().clone(),
+                store.get_root_tree(&result.new_tree_id)?,
+            );
+        }
+    }
+    if new_trees.is_empty() {
+        writeln!(ui.status(), "No revisions were changed by the command.")?;
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let mut num_changed = 0;
+    let mut num_rebased = 0;
+    let roots = commits.iter().ids().cloned().collect_vec();
+    tx.repo_mut().transform_descendants(roots, |rewriter| {
+        let new_tree = new_trees.get(rewriter.old_commit().id());
+        if new_tree.is_none() && !rewriter.parents_changed() {
+            return Ok(());
+        }
+        let old_tree = rewriter.old_commit().tree()?;
+        let builder = rewriter.rebase()?;
+        let builder = if let Some(new_tree) = new_tree {
+            num_changed += 1;
+            let rebased_tree = store.get_root_tree(builder.tree_id())?;
+            let merged_tree = rebased_tree.merge(&old_tree, new_tree)?;
+            builder.set_tree_id(merged_tree.id())
+        } else {
+            num_rebased += 1;
+            builder
+        };
+        builder.write()?;
+        Ok(())
+    })?;
+    writeln!(
+        ui.status(),
+        "Rewrote {num_changed} revisions changed by the command and rebased {num_rebased} \
+         descendant revisions"
+    )?;
+    tx.finish(ui, format!("run '{shell_command}'"))
 }
@@ -19,6 +19,8 @@ use jj_lib::copies::CopyRecords;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::settings::HumanByteSize;
+use jj_lib::working_copy::UntrackedReason;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
@@ -76,8 +78,13 @@ pub(crate) fn cmd_status(
         let tree = wc_commit.tree()?;
 
         let wc_has_changes = tree.id() != parent_tree.id();
-        let wc_has_untracked = !snapshot_stats.untracked_paths.is_empty();
-        if !wc_has_changes && !wc_has_untracked {
+        let (untracked_paths, skipped_paths): (Vec<_>, Vec<_>) = snapshot_stats
+            .untracked_paths
+            .iter()
+            .partition(|(_, reason)| matches!(reason, UntrackedReason::FileNotAutoTracked));
+        let wc_has_untracked = !untracked_paths.is_empty();
+        let wc_has_skipped = !skipped_paths.is_empty();
+        if !wc_has_changes && !wc_has_untracked && !wc_has_skipped {
             writeln!(formatter, "The working copy has no changes.")?;
         } else {
             if wc_has_changes {
@@ -103,13 +110,25 @@ pub(crate) fn cmd_status(
             if wc_has_untracked {
                 writeln!(formatter, "Untracked paths:")?;
                 formatter.with_label("diff", |formatter| {
-                    for path in snapshot_stats.untracked_paths.keys() {
+                    for (path, _) in untracked_paths {
                         let ui_path = workspace_command.path_converter().format_file_path(path);
                         writeln!(formatter.labeled("untracked"), "? {ui_path}")?;
                     }
                     io::Result::Ok(())
                 })?;
             }
+
+            if wc_has_skipped {
+                writeln!(formatter, "Skipped paths:")?;
+                formatter.with_label("diff", |formatter| {
+                    for (path, reason) in skipped_paths {
+                        let ui_path = workspace_command.path_converter().format_file_path(path);
+                        write!(formatter.labeled("untracked"), "? {ui_path}")?;
+                        writeln!(formatter, " ({})", skipped_reason_message(reason))?;
+                    }
+                    io::Result::Ok(())
+                })?;
+            }
         }
 
         let template = workspace_command.commit_summary_template();
@@ -210,3 +229,21 @@ pub(crate) fn cmd_status(
 
     Ok(())
 }
+
+/// Describes why the untracked file was skipped by the snapshot.
+fn skipped_reason_message(reason: &UntrackedReason) -> String {
+    match reason {
+        UntrackedReason::FileTooLarge { size, max_size } => format!(
+            "{} is larger than snapshot.max-new-file-size {}",
+            HumanByteSize(*size),
+            HumanByteSize(*max_size)
+        ),
+        UntrackedReason::FileNotAutoTracked => "not matched by snapshot.auto-track".to_owned(),
+        UntrackedReason::FileSkippedByPolicy { policy } => {
+            format!("skipped by snapshot policy `{policy}`")
+        }
+        UntrackedReason::FileRequiresExplicitTracking { policy } => {
+            format!("snapshot policy `{policy}` requires `jj file track`")
+        }
+    }
+}
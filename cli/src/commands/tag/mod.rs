@@ -0,0 +1,146 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod create;
+mod delete;
+mod list;
+mod set;
+
+use jj_lib::backend::CommitId;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+
+use self::create::cmd_tag_create;
+use self::create::TagCreateArgs;
+use self::delete::cmd_tag_delete;
+use self::delete::TagDeleteArgs;
+use self::list::cmd_tag_list;
+use self::list::TagListArgs;
+use self::set::cmd_tag_set;
+use self::set::TagSetArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Manage tags.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum TagCommand {
+    #[command(visible_alias("c"))]
+    Create(TagCreateArgs),
+    #[command(visible_alias("d"))]
+    Delete(TagDeleteArgs),
+    #[command(visible_alias("l"))]
+    List(TagListArgs),
+    #[command(visible_alias("s"))]
+    Set(TagSetArgs),
+}
+
+pub fn cmd_tag(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &TagCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        TagCommand::Create(args) => cmd_tag_create(ui, command, args),
+        TagCommand::Delete(args) => cmd_tag_delete(ui, command, args),
+        TagCommand::List(args) => cmd_tag_list(ui, command, args),
+        TagCommand::Set(args) => cmd_tag_set(ui, command, args),
+    }
+}
+
+/// Options to create annotated tags.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagAnnotationArgs {
+    /// Create an annotated tag with the given message
+    ///
+    /// Annotated tags are only supported by the Git backend. Without this
+    /// option, a lightweight tag is created.
+    #[arg(long, short, value_name = "MESSAGE")]
+    message: Option<String>,
+    /// Sign the annotated tag
+    ///
+    /// This requires configuring a [commit signing] backend.
+    ///
+    /// [commit signing]:
+    ///     https://jj-vcs.github.io/jj/latest/config/#commit-signing
+    #[arg(long, requires = "message")]
+    sign: bool,
+}
+
+impl TagAnnotationArgs {
+    fn check_supported(
+        &self,
+        workspace_command: &WorkspaceCommandHelper,
+    ) -> Result<(), CommandError> {
+        let store = workspace_command.repo().store();
+        if self.message.is_some() && !is_git_backend(store) {
+            return Err(user_error(
+                "Annotated tags are only supported by the Git backend",
+            ));
+        }
+        if self.sign && !store.signer().can_sign() {
+            return Err(user_error_with_hint(
+                "No signing backend configured",
+                "For configuring a signing backend, see https://jj-vcs.github.io/jj/latest/config/#commit-signing",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "git")]
+fn is_git_backend(store: &jj_lib::store::Store) -> bool {
+    jj_lib::git::get_git_backend(store).is_ok()
+}
+
+#[cfg(not(feature = "git"))]
+fn is_git_backend(_store: &jj_lib::store::Store) -> bool {
+    false
+}
+
+/// Points the tag to the `target`. In Git-backed repos, the tag may be
+/// annotated. The Git ref is updated when the tag is exported.
+fn set_tag_target(
+    tx: &mut WorkspaceCommandTransaction,
+    name: &str,
+    target: &CommitId,
+    annotation: &TagAnnotationArgs,
+) -> Result<(), CommandError> {
+    #[cfg(feature = "git")]
+    if is_git_backend(tx.repo().store()) {
+        let annotation = annotation
+            .message
+            .as_ref()
+            .map(|message| jj_lib::git::GitTagAnnotation {
+                message: crate::text_util::complete_newline(message),
+                sign: annotation.sign,
+            });
+        jj_lib::git::set_tag(tx.repo_mut(), name, target, annotation.as_ref())?;
+        return Ok(());
+    }
+    assert!(annotation.message.is_none());
+    tx.repo_mut()
+        .set_tag_target(name, RefTarget::normal(target.clone()));
+    Ok(())
+}
+
+/// Deletes the tag. In Git-backed repos, the Git ref is deleted when the tag
+/// is exported.
+fn delete_tag(tx: &mut WorkspaceCommandTransaction, name: &str) {
+    tx.repo_mut().set_tag_target(name, RefTarget::absent());
+}
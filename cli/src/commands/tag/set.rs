@@ -0,0 +1,111 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::object_id::ObjectId as _;
+
+use super::set_tag_target;
+use super::TagAnnotationArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::revset_util;
+use crate::ui::Ui;
+
+/// Create or update tags
+///
+/// Unlike bookmarks, tags aren't supposed to move. Existing tags are only
+/// updated if `--allow-move` is specified.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagSetArgs {
+    /// The tag's target revision
+    #[arg(
+        long, short,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: RevisionArg,
+
+    /// Allow moving existing tags
+    #[arg(long)]
+    allow_move: bool,
+
+    #[command(flatten)]
+    annotation: TagAnnotationArgs,
+
+    /// The tags to update
+    #[arg(
+        required = true,
+        value_parser = revset_util::parse_tag_name,
+        add = ArgValueCandidates::new(complete::tags),
+    )]
+    names: Vec<String>,
+}
+
+pub fn cmd_tag_set(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagSetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    args.annotation.check_supported(&workspace_command)?;
+    let target_commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let view = workspace_command.repo().view();
+    let tag_names = &args.names;
+    let mut new_tag_count = 0;
+    let mut moved_tag_count = 0;
+    for name in tag_names {
+        let old_target = view.get_tag(name);
+        if old_target.is_absent() {
+            new_tag_count += 1;
+        } else if old_target.as_normal() != Some(target_commit.id()) {
+            if !args.allow_move {
+                return Err(user_error_with_hint(
+                    format!("Refusing to move tag: {name}"),
+                    "Use --allow-move to allow it.",
+                ));
+            }
+            moved_tag_count += 1;
+        }
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for name in tag_names {
+        set_tag_target(&mut tx, name, target_commit.id(), &args.annotation)?;
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        if new_tag_count > 0 {
+            write!(formatter, "Created {new_tag_count} tags pointing to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+        if moved_tag_count > 0 {
+            write!(formatter, "Moved {moved_tag_count} tags to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    tx.finish(
+        ui,
+        format!(
+            "point tag {names} to commit {id}",
+            names = tag_names.join(", "),
+            id = target_commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
@@ -12,12 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+use std::iter;
 use std::slice;
 use std::time::Duration;
 use std::time::SystemTime;
 
+use itertools::Itertools as _;
+use jj_lib::backend::Timestamp;
+use jj_lib::op_retention;
+use jj_lib::op_retention::OpRetentionPolicy;
+use jj_lib::op_walk;
+use jj_lib::pointer_file;
 use jj_lib::repo::Repo as _;
+use jj_lib::signing::OperationSigner;
 
+use crate::cli_util::print_unsigned_operations;
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
@@ -25,11 +36,16 @@ use crate::ui::Ui;
 
 /// Run backend-dependent garbage collection.
 ///
-/// To garbage-collect old operations and the commits/objects referenced by
-/// then, run `jj op abandon ..<some old operation>` before `jj util gc`.
+/// Old operations are pruned according to the retention policy configured in
+/// `operation.retention`. The retained operations are reparented, and the
+/// pruned operations and the commits/objects referenced only by them are
+/// garbage-collected. To discard operations manually, run
+/// `jj op abandon ..<some old operation>` before `jj util gc`. Operations are
+/// not pruned if `--ignore-working-copy` is specified.
 ///
 /// Previous versions of a change that are reachable via the evolution log are
-/// not garbage-collected.
+/// not garbage-collected. Contents of files stored as pointers are deleted
+/// once no commit refers to them.
 #[derive(clap::Args, Clone, Debug)]
 pub struct UtilGcArgs {
     /// Time threshold
@@ -42,6 +58,13 @@ pub struct UtilGcArgs {
     /// release.
     #[arg(long)]
     expire: Option<String>,
+    /// Run as the automatic garbage collection
+    ///
+    /// Operations are pruned even if `--ignore-working-copy` is specified. The
+    /// operation id of the current workspace is remapped, but the working copy
+    /// files are left untouched.
+    #[arg(long, hide = true)]
+    auto: bool,
 }
 
 pub fn cmd_util_gc(
@@ -61,9 +84,74 @@ pub fn cmd_util_gc(
     };
     let workspace_command = command.workspace_helper(ui)?;
 
-    let repo = workspace_command.repo();
-    repo.op_store()
-        .gc(slice::from_ref(repo.op_id()), keep_newer)?;
+    let repo_loader = workspace_command.repo().loader();
+    let op_store = repo_loader.op_store();
+    let op_heads_store = repo_loader.op_heads_store();
+    let mut policy = OpRetentionPolicy::from_settings(command.settings())?;
+    if command.global_args().ignore_working_copy && !args.auto && policy.is_enabled() {
+        // Reparented operations would make the working copy stale.
+        writeln!(
+            ui.warning_default(),
+            "Operations are not pruned because the working copy is ignored."
+        )?;
+        policy = OpRetentionPolicy::default();
+    }
+    let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
+    let now = command
+        .settings()
+        .operation_timestamp()
+        .unwrap_or_else(Timestamp::now);
+    let sign_settings = command.settings().sign_settings();
+    let signer = OperationSigner::new(repo_loader.store().signer(), &sign_settings);
+    let stats = op_retention::prune_operations(
+        op_store.as_ref(),
+        &current_head_ops,
+        &policy,
+        &now,
+        signer.as_ref(),
+    )?;
+    let reparented_head_ops = || iter::zip(&current_head_ops, &stats.new_head_ids);
+    if stats.unreachable_count > 0 {
+        writeln!(
+            ui.status(),
+            "Pruned {} operations and reparented {} descendant operations.",
+            stats.unreachable_count,
+            stats.rewritten_count,
+        )?;
+        print_unsigned_operations(ui, stats.unsigned_count)?;
+        for (old, new_id) in reparented_head_ops().filter(|&(old, new_id)| old.id() != new_id) {
+            op_heads_store.update_op_heads(slice::from_ref(old.id()), new_id)?;
+        }
+        // Remap the operation id of the current workspace as `jj op abandon`
+        // does.
+        let mut workspace = command.load_workspace()?;
+        let mut locked_ws = workspace.start_working_copy_mutation()?;
+        let old_op_id = locked_ws.locked_wc().old_operation_id();
+        if let Some((_, new_id)) = reparented_head_ops().find(|(old, _)| old.id() == old_op_id) {
+            locked_ws.finish(new_id.clone())?;
+        } else {
+            writeln!(
+                ui.warning_default(),
+                "The working copy operation {} is not updated because it differs from the repo \
+                 {}.",
+                short_operation_hash(old_op_id),
+                current_head_ops
+                    .iter()
+                    .map(|op| short_operation_hash(op.id()))
+                    .join(", "),
+            )?;
+        }
+    }
+
+    // Reload the repo so that commits referenced only by the pruned operations
+    // become unreachable.
+    let repo = if stats.unreachable_count > 0 {
+        repo_loader.load_at_head()?
+    } else {
+        workspace_command.repo().clone()
+    };
+    op_store.gc(&stats.new_head_ids, keep_newer)?;
     repo.store().gc(repo.index(), keep_newer)?;
+    pointer_file::gc(repo.store(), repo.index(), keep_newer)?;
     Ok(())
 }
@@ -24,10 +24,12 @@ use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::workspace::Workspace;
 use tracing::instrument;
 
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -46,10 +48,20 @@ enum SparseInheritance {
 ///
 /// By default, the new workspace inherits the sparse patterns of the current
 /// workspace. You can override this with the `--sparse-patterns` option.
+///
+/// Combined with `--at-op`, this creates a writable workspace at an earlier
+/// operation. Changes made in it are recorded as operations branching off from
+/// that operation, and are merged with the other operations like any concurrent
+/// operations. Use `--sandbox` to create a scratch workspace instead, which
+/// keeps its operations to itself until they're merged by `jj workspace
+/// merge-sandbox`.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceAddArgs {
     /// Where to create the new workspace
-    destination: String,
+    ///
+    /// May be omitted with `--sandbox`.
+    #[arg(required_unless_present = "sandbox")]
+    destination: Option<String>,
     /// A name for the workspace
     ///
     /// To override the default, which is the basename of the destination
@@ -73,6 +85,18 @@ pub struct WorkspaceAddArgs {
     /// How to handle sparse patterns when creating a new workspace.
     #[arg(long, value_enum, default_value_t = SparseInheritance::Copy)]
     sparse_patterns: SparseInheritance,
+    /// Create a scratch workspace at the operation the repo is loaded at
+    ///
+    /// Must be combined with `--at-op` pointing to an earlier operation. Unless
+    /// a destination is specified, the workspace is created in the
+    /// `.jj/sandboxes/` directory of the current workspace, and named after the
+    /// operation, e.g. `jj --at-op=<operation> workspace add --sandbox`.
+    ///
+    /// The sandbox has its own operation heads, so the operations made in it
+    /// are invisible to the other workspaces until `jj workspace
+    /// merge-sandbox` is run in the sandbox.
+    #[arg(long)]
+    sandbox: bool,
 }
 
 #[instrument(skip_all)]
@@ -82,7 +106,40 @@ pub fn cmd_workspace_add(
     args: &WorkspaceAddArgs,
 ) -> Result<(), CommandError> {
     let old_workspace_command = command.workspace_helper(ui)?;
-    let destination_path = command.cwd().join(&args.destination);
+    let repo = old_workspace_command.repo();
+    if args.sandbox {
+        let op_heads = repo.loader().op_heads_store().get_op_heads()?;
+        if op_heads.contains(repo.op_id()) {
+            return Err(user_error_with_hint(
+                "--sandbox requires the repo to be loaded at an earlier operation",
+                "Use --at-op to specify the operation to create the sandbox at.",
+            ));
+        }
+    }
+    let destination_path = if let Some(destination) = &args.destination {
+        command.cwd().join(destination)
+    } else {
+        let sandboxes_dir = old_workspace_command
+            .workspace_root()
+            .join(".jj")
+            .join("sandboxes");
+        fs::create_dir_all(&sandboxes_dir).context(&sandboxes_dir)?;
+        let base_name = format!("sandbox-{}", short_operation_hash(repo.op_id()));
+        (1..)
+            .map(|i| match i {
+                1 => sandboxes_dir.join(&base_name),
+                _ => sandboxes_dir.join(format!("{base_name}-{i}")),
+            })
+            .find(|path| {
+                let name = path.file_name().unwrap().to_str().unwrap();
+                !path.exists()
+                    && repo
+                        .view()
+                        .get_wc_commit_id(&WorkspaceId::new(name.to_owned()))
+                        .is_none()
+            })
+            .unwrap()
+    };
     if destination_path.exists() {
         return Err(user_error("Workspace already exists"));
     } else {
@@ -99,7 +156,6 @@ pub fn cmd_workspace_add(
             .to_string()
     };
     let workspace_id = WorkspaceId::new(name.clone());
-    let repo = old_workspace_command.repo();
     if repo.view().get_wc_commit_id(&workspace_id).is_some() {
         return Err(user_error(format!(
             "Workspace named '{name}' already exists"
@@ -110,13 +166,23 @@ pub fn cmd_workspace_add(
     let repo_path = old_workspace_command.repo_path();
     // If we add per-workspace configuration, we'll need to reload settings for
     // the new workspace.
-    let (new_workspace, repo) = Workspace::init_workspace_with_existing_repo(
-        &destination_path,
-        repo_path,
-        repo,
-        working_copy_factory,
-        workspace_id,
-    )?;
+    let (new_workspace, repo) = if args.sandbox {
+        Workspace::init_sandbox_with_existing_repo(
+            &destination_path,
+            repo_path,
+            repo,
+            working_copy_factory,
+            workspace_id,
+        )?
+    } else {
+        Workspace::init_workspace_with_existing_repo(
+            &destination_path,
+            repo_path,
+            repo,
+            working_copy_factory,
+            workspace_id,
+        )?
+    };
     writeln!(
         ui.status(),
         "Created workspace in \"{}\"",
@@ -124,12 +190,13 @@ pub fn cmd_workspace_add(
     )?;
     // Show a warning if the user passed a path without a separator, since they
     // may have intended the argument to only be the name for the workspace.
-    if !args.destination.contains(std::path::is_separator) {
-        writeln!(
-            ui.warning_default(),
-            r#"Workspace created inside current directory. If this was unintentional, delete the "{}" directory and run `jj workspace forget {name}` to remove it."#,
-            args.destination
-        )?;
+    if let Some(destination) = &args.destination {
+        if !destination.contains(std::path::is_separator) {
+            writeln!(
+                ui.warning_default(),
+                r#"Workspace created inside current directory. If this was unintentional, delete the "{destination}" directory and run `jj workspace forget {name}` to remove it."#,
+            )?;
+        }
     }
 
     let mut new_workspace_command = command.for_workable_repo(ui, new_workspace, repo)?;
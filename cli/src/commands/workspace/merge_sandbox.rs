@@ -0,0 +1,56 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use tracing::instrument;
+
+use crate::cli_util::short_operation_hash;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Merge the operations of the current sandbox workspace into the repo
+///
+/// Operations made in a workspace created by `jj workspace add --sandbox` are
+/// only visible in that workspace. This command makes them visible to the other
+/// workspaces, where they're merged like concurrent operations.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspaceMergeSandboxArgs {}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_merge_sandbox(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &WorkspaceMergeSandboxArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let workspace = workspace_command.workspace();
+    if !workspace.is_sandbox() {
+        return Err(user_error_with_hint(
+            "The current workspace is not a sandbox",
+            "Use `jj --at-op=<operation> workspace add --sandbox` to create one.",
+        ));
+    }
+    let repo_loader = command.load_repo_loader_at(workspace.repo_path())?;
+    for op_id in workspace.merge_sandbox_op_heads(&repo_loader)? {
+        writeln!(
+            ui.status(),
+            "Merged operation {} into the repo",
+            short_operation_hash(&op_id)
+        )?;
+    }
+    Ok(())
+}
@@ -15,6 +15,7 @@
 mod add;
 mod forget;
 mod list;
+mod merge_sandbox;
 mod rename;
 mod root;
 mod update_stale;
@@ -28,6 +29,8 @@ use self::forget::cmd_workspace_forget;
 use self::forget::WorkspaceForgetArgs;
 use self::list::cmd_workspace_list;
 use self::list::WorkspaceListArgs;
+use self::merge_sandbox::cmd_workspace_merge_sandbox;
+use self::merge_sandbox::WorkspaceMergeSandboxArgs;
 use self::rename::cmd_workspace_rename;
 use self::rename::WorkspaceRenameArgs;
 use self::root::cmd_workspace_root;
@@ -54,6 +57,7 @@ pub(crate) enum WorkspaceCommand {
     Add(WorkspaceAddArgs),
     Forget(WorkspaceForgetArgs),
     List(WorkspaceListArgs),
+    MergeSandbox(WorkspaceMergeSandboxArgs),
     Rename(WorkspaceRenameArgs),
     Root(WorkspaceRootArgs),
     UpdateStale(WorkspaceUpdateStaleArgs),
@@ -69,6 +73,7 @@ pub(crate) fn cmd_workspace(
         WorkspaceCommand::Add(args) => cmd_workspace_add(ui, command, args),
         WorkspaceCommand::Forget(args) => cmd_workspace_forget(ui, command, args),
         WorkspaceCommand::List(args) => cmd_workspace_list(ui, command, args),
+        WorkspaceCommand::MergeSandbox(args) => cmd_workspace_merge_sandbox(ui, command, args),
         WorkspaceCommand::Rename(args) => cmd_workspace_rename(ui, command, args),
         WorkspaceCommand::Root(args) => cmd_workspace_root(ui, command, args),
         WorkspaceCommand::UpdateStale(args) => cmd_workspace_update_stale(ui, command, args),
@@ -15,6 +15,7 @@
 use jj_lib::op_store::WorkspaceId;
 use tracing::instrument;
 
+use crate::cli_util::commit_transaction;
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
@@ -67,11 +68,15 @@ pub fn cmd_workspace_rename(
 
     tx.repo_mut()
         .rename_workspace(&old_workspace_id, new_workspace_id)?;
-    let repo = tx.commit(format!(
-        "Renamed workspace '{}' to '{}'",
-        old_workspace_id.as_str(),
-        args.new_workspace_name
-    ))?;
+    let repo = commit_transaction(
+        ui,
+        tx,
+        format!(
+            "Renamed workspace '{}' to '{}'",
+            old_workspace_id.as_str(),
+            args.new_workspace_name
+        ),
+    )?;
     locked_ws.finish(repo.op_id().clone())?;
 
     Ok(())
@@ -91,6 +91,7 @@ use crate::templater::TemplateFormatter;
 use crate::templater::TemplateProperty;
 use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
+use crate::templater::ToJson;
 use crate::text_util;
 
 pub trait CommitTemplateLanguageExtension {
@@ -588,6 +589,31 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
         }
     }
 
+    fn try_into_json(
+        self,
+    ) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'repo>> {
+        match self {
+            CommitTemplatePropertyKind::Core(property) => property.try_into_json(),
+            CommitTemplatePropertyKind::Commit(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::CommitOpt(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::CommitList(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RefName(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RefNameOpt(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RefNameList(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RepoPath(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::RepoPathOpt(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::CommitOrChangeId(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::ShortestIdPrefix(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeDiff(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeDiffEntry(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeDiffEntryList(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::TreeEntry(property) => Some(property.into_json()),
+            CommitTemplatePropertyKind::DiffStats(_) => None,
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => None,
+            CommitTemplatePropertyKind::AnnotationLine(_) => None,
+        }
+    }
+
     fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'repo>> {
         match (self, other) {
             (CommitTemplatePropertyKind::Core(lhs), CommitTemplatePropertyKind::Core(rhs)) => {
@@ -782,6 +808,19 @@ impl<'repo> CommitKeywordCache<'repo> {
     }
 }
 
+impl ToJson for Commit {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "commit_id": self.id().hex(),
+            "parents": self.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+            "change_id": self.change_id().reverse_hex(),
+            "description": self.description(),
+            "author": self.author().to_json()?,
+            "committer": self.committer().to_json()?,
+        }))
+    }
+}
+
 fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Commit> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1346,6 +1385,29 @@ impl Template for Vec<Rc<RefName>> {
     }
 }
 
+impl ToJson for Rc<RefName> {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        // Conflicted target is represented as [add, remove, add, ..], and
+        // absent terms are null.
+        let target_to_json = |target: &RefTarget| {
+            target
+                .as_merge()
+                .iter()
+                .map(|id| id.as_ref().map(|id| id.hex()))
+                .collect_vec()
+        };
+        Ok(serde_json::json!({
+            "name": self.name,
+            "remote": self.remote,
+            "target": target_to_json(&self.target),
+            "tracking_target": self
+                .tracking_ref
+                .as_ref()
+                .map(|tracking| target_to_json(&tracking.target)),
+        }))
+    }
+}
+
 fn builtin_ref_name_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Rc<RefName>> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1517,6 +1579,12 @@ impl Template for RepoPathBuf {
     }
 }
 
+impl ToJson for RepoPathBuf {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(self.as_internal_file_string().into())
+    }
+}
+
 fn builtin_repo_path_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, RepoPathBuf> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1587,6 +1655,12 @@ impl Template for CommitOrChangeId {
     }
 }
 
+impl ToJson for CommitOrChangeId {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(self.hex().into())
+    }
+}
+
 fn builtin_commit_or_change_id_methods<'repo>(
 ) -> CommitTemplateBuildMethodFnMap<'repo, CommitOrChangeId> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
@@ -1678,6 +1752,15 @@ impl Template for ShortestIdPrefix {
     }
 }
 
+impl ToJson for ShortestIdPrefix {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "prefix": self.prefix,
+            "rest": self.rest,
+        }))
+    }
+}
+
 impl ShortestIdPrefix {
     fn to_upper(&self) -> Self {
         Self {
@@ -1784,6 +1867,12 @@ impl TreeDiff {
     }
 }
 
+impl ToJson for TreeDiff {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.collect_entries().block_on()?.to_json()
+    }
+}
+
 /// Tree diff to be rendered by predefined function `F`.
 struct TreeDiffFormatted<F> {
     diff: TreeDiff,
@@ -1995,6 +2084,17 @@ impl TreeDiffEntry {
     }
 }
 
+impl ToJson for TreeDiffEntry {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "path": self.path.target.to_json()?,
+            "status": self.status_label(),
+            "source": self.clone().into_source_entry().to_json()?,
+            "target": self.clone().into_target_entry().to_json()?,
+        }))
+    }
+}
+
 fn builtin_tree_diff_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeDiffEntry>
 {
     type L<'repo> = CommitTemplateLanguage<'repo>;
@@ -2044,6 +2144,17 @@ pub struct TreeEntry {
     pub value: MergedTreeValue,
 }
 
+impl ToJson for TreeEntry {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "path": self.path.to_json()?,
+            "conflict": !self.value.is_resolved(),
+            "file_type": describe_file_type(&self.value),
+            "executable": is_executable_file(&self.value).unwrap_or_default(),
+        }))
+    }
+}
+
 fn builtin_tree_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeEntry> {
     type L<'repo> = CommitTemplateLanguage<'repo>;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -183,6 +183,24 @@ pub fn bookmarks() -> Vec<CompletionCandidate> {
     })
 }
 
+pub fn tags() -> Vec<CompletionCandidate> {
+    with_jj(|jj, _| {
+        let output = jj
+            .build()
+            .arg("tag")
+            .arg("list")
+            .arg("--template")
+            .arg(r#"name ++ "\n""#)
+            .output()
+            .map_err(user_error)?;
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(CompletionCandidate::new)
+            .collect())
+    })
+}
+
 pub fn git_remotes() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
         let output = jj
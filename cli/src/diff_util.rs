@@ -1212,10 +1212,13 @@ fn git_diff_part(
             };
         }
         MaterializedTreeValue::GitSubmodule(id) => {
-            // TODO: What should we actually do here?
-            mode = "040000";
+            // Like Git, show the submodule commit as the content of a gitlink.
+            mode = "160000";
             hash = id.hex();
-            content = FileContent::empty();
+            content = FileContent {
+                is_binary: false,
+                contents: format!("Subproject commit {}\n", id.hex()).into_bytes(),
+            };
         }
         MaterializedTreeValue::FileConflict {
             id: _,
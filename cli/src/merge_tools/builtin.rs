@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use bstr::BString;
 use futures::StreamExt;
 use futures::TryFutureExt;
 use futures::TryStreamExt;
@@ -49,6 +51,8 @@ pub enum BuiltinToolError {
     },
     #[error("Rendering {item} {id} is unimplemented for the builtin difftool/mergetool")]
     Unimplemented { item: &'static str, id: String },
+    #[error("Unexpected sections in the merge result of {path:?} (this should not happen)")]
+    UnexpectedMergeSections { path: PathBuf },
     #[error("Backend error")]
     BackendError(#[from] jj_lib::backend::BackendError),
 }
@@ -553,6 +557,9 @@ pub fn edit_diff_builtin(
     Ok(tree_id)
 }
 
+/// Line shown for an empty term of a conflicting hunk.
+const EMPTY_TERM_PLACEHOLDER: &str = "(empty)\n";
+
 fn make_merge_sections(
     merge_result: MergeResult,
 ) -> Result<Vec<scm_record::Section<'static>>, BuiltinToolError> {
@@ -584,7 +591,7 @@ fn make_merge_sections(
         }
         MergeResult::Conflict(hunks) => {
             for hunk in hunks {
-                let section = match hunk.into_resolved() {
+                match hunk.into_resolved() {
                     Ok(contents) => {
                         let contents = std::str::from_utf8(&contents).map_err(|err| {
                             BuiltinToolError::DecodeUtf8 {
@@ -592,41 +599,46 @@ fn make_merge_sections(
                                 item: "unchanged hunk",
                             }
                         })?;
-                        scm_record::Section::Unchanged {
+                        sections.push(scm_record::Section::Unchanged {
                             lines: contents
                                 .split_inclusive('\n')
                                 .map(|line| Cow::Owned(line.to_owned()))
                                 .collect(),
-                        }
+                        });
                     }
                     Err(merge) => {
-                        let lines: Vec<scm_record::SectionChangedLine> = merge
-                            .iter()
-                            .zip(
-                                [
-                                    scm_record::ChangeType::Added,
-                                    scm_record::ChangeType::Removed,
-                                ]
-                                .into_iter()
-                                .cycle(),
-                            )
-                            .map(|(contents, change_type)| -> Result<_, BuiltinToolError> {
+                        // Each term gets its own section so that a whole side
+                        // can be picked at once. Sides are shown as added lines
+                        // and bases as removed lines. Empty terms are shown as
+                        // a placeholder line, which resolves the hunk to
+                        // nothing if picked.
+                        for (contents, change_type) in merge.iter().zip(
+                            [
+                                scm_record::ChangeType::Added,
+                                scm_record::ChangeType::Removed,
+                            ]
+                            .into_iter()
+                            .cycle(),
+                        ) {
+                            let lines = if contents.is_empty() {
+                                vec![scm_record::SectionChangedLine {
+                                    is_checked: false,
+                                    change_type,
+                                    line: Cow::Borrowed(EMPTY_TERM_PLACEHOLDER),
+                                }]
+                            } else {
                                 let contents = std::str::from_utf8(contents).map_err(|err| {
                                     BuiltinToolError::DecodeUtf8 {
                                         source: err,
                                         item: "conflicting hunk",
                                     }
                                 })?;
-                                let changed_lines =
-                                    make_section_changed_lines(contents, change_type);
-                                Ok(changed_lines)
-                            })
-                            .flatten_ok()
-                            .try_collect()?;
-                        scm_record::Section::Changed { lines }
+                                make_section_changed_lines(contents, change_type)
+                            };
+                            sections.push(scm_record::Section::Changed { lines });
+                        }
                     }
-                };
-                sections.push(section);
+                }
             }
         }
     }
@@ -651,6 +663,130 @@ fn make_merge_file(
     })
 }
 
+/// Applies the lines selected in the merge `file` to the conflict hunks.
+/// Returns the contents of each term of the simplified conflict. Hunks
+/// without any selected lines are left unresolved.
+fn apply_merge_sections(
+    content: &Merge<BString>,
+    file: &scm_record::File,
+) -> Result<Merge<BString>, BuiltinToolError> {
+    let hunks = match files::merge(content) {
+        MergeResult::Resolved(content) => return Ok(Merge::resolved(content)),
+        MergeResult::Conflict(hunks) => hunks,
+    };
+    // The sections were created from the same hunks by make_merge_sections()
+    let mut sections = file.sections.iter();
+    let mut contents = content.map(|_| BString::default());
+    for hunk in hunks {
+        if let Some(slice) = hunk.as_resolved() {
+            sections.next();
+            for content in contents.iter_mut() {
+                content.extend_from_slice(slice);
+            }
+            continue;
+        }
+        let mut selected: Option<BString> = None;
+        for term in hunk.iter() {
+            let Some(scm_record::Section::Changed { lines }) = sections.next() else {
+                return Err(BuiltinToolError::UnexpectedMergeSections {
+                    path: file.path.to_path_buf(),
+                });
+            };
+            let mut checked_lines = lines.iter().filter(|line| line.is_checked).peekable();
+            if checked_lines.peek().is_none() {
+                continue;
+            }
+            let selected = selected.get_or_insert_with(BString::default);
+            // The placeholder of an empty term adds nothing
+            if !term.is_empty() {
+                for line in checked_lines {
+                    selected.extend_from_slice(line.line.as_bytes());
+                }
+            }
+        }
+        if let Some(selected) = selected {
+            for content in contents.iter_mut() {
+                content.extend_from_slice(&selected);
+            }
+        } else {
+            for (content, term) in contents.iter_mut().zip(hunk.iter()) {
+                content.extend_from_slice(term);
+            }
+        }
+    }
+    if contents.iter().all_equal() {
+        Ok(Merge::resolved(contents.first().clone()))
+    } else {
+        Ok(contents)
+    }
+}
+
+fn apply_merge_builtin(
+    store: &Arc<Store>,
+    tree: &MergedTree,
+    merge_tool_files: &[MergeToolFile],
+    files: &[scm_record::File],
+) -> Result<MergedTreeId, BuiltinToolError> {
+    let mut tree_builder = MergedTreeBuilder::new(tree.id());
+    assert_eq!(
+        merge_tool_files.len(),
+        files.len(),
+        "result had a different number of files"
+    );
+    for (merge_tool_file, file) in merge_tool_files.iter().zip(files) {
+        let MergeToolFile {
+            repo_path,
+            conflict,
+            file_merge,
+            content,
+        } = merge_tool_file;
+        let new_content = apply_merge_sections(content, file)?;
+        if new_content == *content {
+            continue;
+        }
+        let new_tree_value = match new_content.into_resolved() {
+            Ok(new_content) => {
+                let id = store
+                    .write_file(repo_path, &mut new_content.as_slice())
+                    .block_on()?;
+                Merge::normal(TreeValue::File {
+                    id,
+                    executable: conflict
+                        .to_executable_merge()
+                        .as_ref()
+                        .and_then(Merge::resolve_trivial)
+                        .copied()
+                        .unwrap_or_default(),
+                })
+            }
+            Err(new_content) => {
+                let simplified_file_merge = file_merge.clone().simplify();
+                let new_file_ids: Vec<_> = simplified_file_merge
+                    .iter()
+                    .zip(new_content.iter())
+                    .map(|(file_id, new_content)| match file_id {
+                        Some(_) => store
+                            .write_file(repo_path, &mut new_content.as_slice())
+                            .block_on()
+                            .map(Some),
+                        // Absent terms are materialized as empty content
+                        None => Ok(None),
+                    })
+                    .try_collect()?;
+                let new_file_ids = file_merge
+                    .clone()
+                    .update_from_simplified(Merge::from_vec(new_file_ids));
+                conflict.with_new_file_ids(&new_file_ids)
+            }
+        };
+        tree_builder.set_or_remove(repo_path.clone(), new_tree_value);
+    }
+    Ok(tree_builder.write_tree(store)?)
+}
+
+/// Lets the user resolve the conflicts by picking the lines to keep from the
+/// sides and bases of each conflicting hunk. Conflicts with any number of
+/// sides are supported.
 pub fn edit_merge_builtin(
     tree: &MergedTree,
     merge_tool_files: &[MergeToolFile],
@@ -665,22 +801,12 @@ pub fn edit_merge_builtin(
         &mut input,
     );
     let state = recorder.run()?;
-
-    apply_diff_builtin(
-        tree.store(),
-        tree,
-        tree,
-        merge_tool_files
-            .iter()
-            .map(|file| file.repo_path.clone())
-            .collect_vec(),
-        &state.files,
-    )
-    .map_err(BuiltinToolError::BackendError)
+    apply_merge_builtin(tree.store(), tree, merge_tool_files, &state.files)
 }
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use jj_lib::conflicts::extract_as_single_hunk;
     use jj_lib::merge::MergedTreeValue;
     use jj_lib::repo::Repo;
@@ -1098,7 +1224,7 @@ mod tests {
             .unwrap();
         let merge_result = files::merge(&content);
         let sections = make_merge_sections(merge_result).unwrap();
-        insta::assert_debug_snapshot!(sections, @r###"
+        insta::assert_debug_snapshot!(sections, @r#"
         [
             Changed {
                 lines: [
@@ -1107,11 +1233,19 @@ mod tests {
                         change_type: Added,
                         line: "left 1\n",
                     },
+                ],
+            },
+            Changed {
+                lines: [
                     SectionChangedLine {
                         is_checked: false,
                         change_type: Removed,
                         line: "base 1\n",
                     },
+                ],
+            },
+            Changed {
+                lines: [
                     SectionChangedLine {
                         is_checked: false,
                         change_type: Added,
@@ -1133,11 +1267,19 @@ mod tests {
                         change_type: Added,
                         line: "left 5\n",
                     },
+                ],
+            },
+            Changed {
+                lines: [
                     SectionChangedLine {
                         is_checked: false,
                         change_type: Removed,
                         line: "base 5\n",
                     },
+                ],
+            },
+            Changed {
+                lines: [
                     SectionChangedLine {
                         is_checked: false,
                         change_type: Added,
@@ -1146,6 +1288,115 @@ mod tests {
                 ],
             },
         ]
-        "###);
+        "#);
+    }
+
+    #[test]
+    fn test_apply_merge_sections() {
+        let content = Merge::from_vec(
+            [
+                "left 1\nsame\nleft 2\n",
+                "base 1\nsame\nbase 2\n",
+                "mid 1\nsame\nmid 2\n",
+                "base 1\nsame\nbase 2\n",
+                "right 1\nsame\nright 2\n",
+            ]
+            .map(BString::from)
+            .to_vec(),
+        );
+        let make_file = |checked: &[(usize, usize)]| {
+            let mut sections = make_merge_sections(files::merge(&content)).unwrap();
+            for &(section_index, line_index) in checked {
+                let scm_record::Section::Changed { lines } = &mut sections[section_index] else {
+                    panic!("section {section_index} should be changed");
+                };
+                lines[line_index].is_checked = true;
+            }
+            scm_record::File {
+                old_path: None,
+                path: Cow::Borrowed(Path::new("file")),
+                file_mode: None,
+                sections,
+            }
+        };
+        // A section for each term of the 2 conflicting hunks, and the
+        // unchanged line between them
+        assert_eq!(make_file(&[]).sections.len(), 11);
+
+        // Nothing selected
+        assert_eq!(
+            apply_merge_sections(&content, &make_file(&[])).unwrap(),
+            content
+        );
+
+        // Lines from multiple sides of the first hunk are selected
+        let merged = apply_merge_sections(&content, &make_file(&[(0, 0), (4, 0)])).unwrap();
+        assert_eq!(
+            merged,
+            Merge::from_vec(
+                [
+                    "left 1\nright 1\nsame\nleft 2\n",
+                    "left 1\nright 1\nsame\nbase 2\n",
+                    "left 1\nright 1\nsame\nmid 2\n",
+                    "left 1\nright 1\nsame\nbase 2\n",
+                    "left 1\nright 1\nsame\nright 2\n",
+                ]
+                .map(BString::from)
+                .to_vec()
+            )
+        );
+
+        // All hunks resolved, including by picking the base
+        let merged = apply_merge_sections(&content, &make_file(&[(2, 0), (7, 0)])).unwrap();
+        assert_eq!(
+            merged,
+            Merge::resolved(BString::from("mid 1\nsame\nbase 2\n"))
+        );
+
+        // Sections not matching the conflict
+        let mut file = make_file(&[]);
+        file.sections.truncate(1);
+        assert_matches!(
+            apply_merge_sections(&content, &file),
+            Err(BuiltinToolError::UnexpectedMergeSections { .. })
+        );
+    }
+
+    #[test]
+    fn test_apply_merge_sections_empty_term() {
+        // The left side deleted the line the right side modified
+        let content = Merge::from_vec(
+            ["same\n", "base\nsame\n", "right\nsame\n"]
+                .map(BString::from)
+                .to_vec(),
+        );
+        let mut sections = make_merge_sections(files::merge(&content)).unwrap();
+        insta::assert_debug_snapshot!(sections[0], @r#"
+        Changed {
+            lines: [
+                SectionChangedLine {
+                    is_checked: false,
+                    change_type: Added,
+                    line: "(empty)\n",
+                },
+            ],
+        }
+        "#);
+        let scm_record::Section::Changed { lines } = &mut sections[0] else {
+            panic!("section should be changed");
+        };
+        lines[0].is_checked = true;
+        let file = scm_record::File {
+            old_path: None,
+            path: Cow::Borrowed(Path::new("file")),
+            file_mode: None,
+            sections,
+        };
+
+        // Picking the empty side resolves the hunk to nothing
+        assert_eq!(
+            apply_merge_sections(&content, &file).unwrap(),
+            Merge::resolved(BString::from("same\n"))
+        );
     }
 }
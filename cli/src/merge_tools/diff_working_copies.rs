@@ -299,6 +299,9 @@ diff editing in mind and be a little inaccurate.
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
             conflict_marker_style,
+            conflict_marker_overrides: vec![],
+            submodule_store: None,
+            file_policies: vec![],
         })?;
         Ok(output_tree_state.current_tree_id().clone())
     }
@@ -15,6 +15,7 @@ use jj_lib::conflicts::choose_materialized_conflict_marker_len;
 use jj_lib::conflicts::materialize_merge_result_to_bytes_with_marker_len;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Merge;
@@ -379,6 +380,9 @@ pub fn edit_diff_external(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        conflict_marker_overrides: vec![],
+        submodule_store: None,
+        pointer_paths: FilesetExpression::none(),
     };
 
     let got_output_field = find_all_variables(&editor.edit_args).contains(&"output");
@@ -427,6 +431,9 @@ pub fn generate_diff(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        conflict_marker_overrides: vec![],
+        submodule_store: None,
+        pointer_paths: FilesetExpression::none(),
     };
     let store = left_tree.store();
     let diff_wc = check_out_trees(store, left_tree, right_tree, matcher, None, &options)?;
@@ -15,6 +15,7 @@
 mod builtin;
 mod diff_working_copies;
 mod external;
+mod side_picker;
 
 use std::sync::Arc;
 
@@ -29,9 +30,11 @@ use jj_lib::conflicts::extract_as_single_hunk;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
+use jj_lib::merge::ConflictKind;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::repo_path::InvalidRepoPathError;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
@@ -52,10 +55,14 @@ pub use self::external::invoke_external_diff;
 pub use self::external::DiffToolMode;
 pub use self::external::ExternalMergeTool;
 use self::external::ExternalToolError;
+use self::side_picker::choose_side;
+use self::side_picker::pick_side;
 use crate::config::CommandNameAndArgs;
 use crate::ui::Ui;
 
 const BUILTIN_EDITOR_NAME: &str = ":builtin";
+const OURS_TOOL_NAME: &str = ":ours";
+const THEIRS_TOOL_NAME: &str = ":theirs";
 
 #[derive(Debug, Error)]
 pub enum DiffEditError {
@@ -91,12 +98,7 @@ pub enum ConflictResolveError {
     PathNotFound(RepoPathBuf),
     #[error("Couldn't find any conflicts at {0:?} in this revision")]
     NotAConflict(RepoPathBuf),
-    #[error(
-        "Only conflicts that involve normal files (not symlinks, not executable, etc.) are \
-         supported. Conflict summary for {0:?}:\n{1}"
-    )]
-    NotNormalFiles(RepoPathBuf, String),
-    #[error("The conflict at {path:?} has {sides} sides. At most 2 sides are supported.")]
+    #[error("The conflict at {path:?} has {sides} sides. At most 2 sides are supported by this merge tool.")]
     ConflictTooComplicated { path: RepoPathBuf, sides: usize },
     #[error(
         "The output file is either unchanged or empty after the editor quit (run with --debug to \
@@ -122,11 +124,17 @@ pub enum MergeToolConfigError {
     Config(#[from] ConfigGetError),
     #[error("The tool `{tool_name}` cannot be used as a merge tool with `jj resolve`")]
     MergeArgsNotConfigured { tool_name: String },
+    #[error("The tool `{tool_name}` can only be used as a merge tool with `jj resolve`")]
+    MergeOnly { tool_name: String },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum MergeTool {
     Builtin,
+    /// Resolves conflicts by picking the first side. Only for merges.
+    Ours,
+    /// Resolves conflicts by picking the second side. Only for merges.
+    Theirs,
     // Boxed because ExternalMergeTool is big compared to the Builtin variant.
     External(Box<ExternalMergeTool>),
 }
@@ -167,6 +175,10 @@ fn get_tool_config(
 ) -> Result<Option<MergeTool>, ConfigGetError> {
     if name == BUILTIN_EDITOR_NAME {
         Ok(Some(MergeTool::Builtin))
+    } else if name == OURS_TOOL_NAME {
+        Ok(Some(MergeTool::Ours))
+    } else if name == THEIRS_TOOL_NAME {
+        Ok(Some(MergeTool::Theirs))
     } else {
         Ok(get_external_tool_config(settings, name)?.map(MergeTool::external))
     }
@@ -207,7 +219,7 @@ impl DiffEditor {
     ) -> Result<Self, MergeToolConfigError> {
         let tool = get_tool_config(settings, name)?
             .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_program(name)));
-        Self::new_inner(tool, settings, base_ignores, conflict_marker_style)
+        Self::new_inner(name, tool, settings, base_ignores, conflict_marker_style)
     }
 
     /// Loads the default diff editor from the settings.
@@ -224,15 +236,21 @@ impl DiffEditor {
             None
         }
         .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_edit_args(&args)));
-        Self::new_inner(tool, settings, base_ignores, conflict_marker_style)
+        Self::new_inner(&args, tool, settings, base_ignores, conflict_marker_style)
     }
 
     fn new_inner(
+        name: impl ToString,
         tool: MergeTool,
         settings: &UserSettings,
         base_ignores: Arc<GitIgnoreFile>,
         conflict_marker_style: ConflictMarkerStyle,
     ) -> Result<Self, MergeToolConfigError> {
+        if matches!(tool, MergeTool::Ours | MergeTool::Theirs) {
+            return Err(MergeToolConfigError::MergeOnly {
+                tool_name: name.to_string(),
+            });
+        }
         Ok(DiffEditor {
             tool,
             base_ignores,
@@ -263,6 +281,9 @@ impl DiffEditor {
                         .map_err(Box::new)?,
                 )
             }
+            MergeTool::Ours | MergeTool::Theirs => {
+                unreachable!("merge-only tools are rejected by DiffEditor::new_inner()")
+            }
             MergeTool::External(editor) => {
                 let instructions = self.use_instructions.then(format_instructions);
                 edit_diff_external(
@@ -288,31 +309,21 @@ struct MergeToolFile {
 }
 
 impl MergeToolFile {
-    fn from_tree_and_path(
+    /// Loads the file contents of a conflict of
+    /// [`ConflictKind::Content`] kind.
+    fn from_conflict(
         tree: &MergedTree,
-        repo_path: &RepoPath,
+        repo_path: RepoPathBuf,
+        conflict: MergedTreeValue,
     ) -> Result<Self, ConflictResolveError> {
-        let conflict = match tree.path_value(repo_path)?.into_resolved() {
-            Err(conflict) => conflict,
-            Ok(Some(_)) => return Err(ConflictResolveError::NotAConflict(repo_path.to_owned())),
-            Ok(None) => return Err(ConflictResolveError::PathNotFound(repo_path.to_owned())),
-        };
-        let file_merge = conflict.to_file_merge().ok_or_else(|| {
-            let summary = conflict.describe();
-            ConflictResolveError::NotNormalFiles(repo_path.to_owned(), summary)
-        })?;
+        let file_merge = conflict
+            .to_file_merge()
+            .expect("content conflicts should only involve files");
         let simplified_file_merge = file_merge.clone().simplify();
-        // We only support conflicts with 2 sides (3-way conflicts)
-        if simplified_file_merge.num_sides() > 2 {
-            return Err(ConflictResolveError::ConflictTooComplicated {
-                path: repo_path.to_owned(),
-                sides: simplified_file_merge.num_sides(),
-            });
-        };
         let content =
-            extract_as_single_hunk(&simplified_file_merge, tree.store(), repo_path).block_on()?;
+            extract_as_single_hunk(&simplified_file_merge, tree.store(), &repo_path).block_on()?;
         Ok(MergeToolFile {
-            repo_path: repo_path.to_owned(),
+            repo_path,
             conflict,
             file_merge,
             content,
@@ -384,24 +395,98 @@ impl MergeEditor {
         tree: &MergedTree,
         repo_paths: &[&RepoPath],
     ) -> Result<(MergedTreeId, Option<MergeToolPartialResolutionError>), ConflictResolveError> {
-        let merge_tool_files: Vec<MergeToolFile> = repo_paths
+        let store = tree.store();
+        let conflicts: Vec<(RepoPathBuf, MergedTreeValue)> = repo_paths
             .iter()
-            .map(|&repo_path| MergeToolFile::from_tree_and_path(tree, repo_path))
+            .map(
+                |&repo_path| match tree.path_value(repo_path)?.into_resolved() {
+                    Err(conflict) => Ok((repo_path.to_owned(), conflict)),
+                    Ok(Some(_)) => Err(ConflictResolveError::NotAConflict(repo_path.to_owned())),
+                    Ok(None) => Err(ConflictResolveError::PathNotFound(repo_path.to_owned())),
+                },
+            )
+            .try_collect()?;
+
+        let picked_side = match &self.tool {
+            MergeTool::Ours => Some(0),
+            MergeTool::Theirs => Some(1),
+            MergeTool::Builtin | MergeTool::External(_) => None,
+        };
+        if let Some(index) = picked_side {
+            let mut tree_builder = MergedTreeBuilder::new(tree.id());
+            for (repo_path, conflict) in conflicts {
+                let conflict = conflict.simplify();
+                // The first side exists in any conflict, but "theirs" is only
+                // well-defined if there are 2 sides.
+                if index > 0 && conflict.num_sides() > 2 {
+                    return Err(ConflictResolveError::ConflictTooComplicated {
+                        path: repo_path,
+                        sides: conflict.num_sides(),
+                    });
+                }
+                tree_builder.set_or_remove(repo_path, pick_side(&conflict, index));
+            }
+            return Ok((tree_builder.write_tree(store)?, None));
+        }
+
+        // Conflicts which aren't about the file contents can't be merged by
+        // the tools, so a side is picked instead.
+        let (content_conflicts, other_conflicts): (Vec<_>, Vec<_>) = conflicts
+            .into_iter()
+            .partition(|(_, conflict)| conflict.conflict_kind() == ConflictKind::Content);
+        let mut tree = tree.clone();
+        if !other_conflicts.is_empty() {
+            let mut tree_builder = MergedTreeBuilder::new(tree.id());
+            for (repo_path, conflict) in other_conflicts {
+                writeln!(
+                    ui.status(),
+                    "Resolving conflicts in: {}",
+                    self.path_converter.format_file_path(&repo_path)
+                )?;
+                let conflict = conflict.simplify();
+                if let Some(index) = choose_side(ui, store, &repo_path, &conflict)? {
+                    tree_builder.set_or_remove(repo_path, pick_side(&conflict, index));
+                }
+            }
+            tree = store.get_root_tree(&tree_builder.write_tree(store)?)?;
+        }
+        if content_conflicts.is_empty() {
+            return Ok((tree.id(), None));
+        }
+        let merge_tool_files: Vec<MergeToolFile> = content_conflicts
+            .into_iter()
+            .map(|(repo_path, conflict)| MergeToolFile::from_conflict(&tree, repo_path, conflict))
             .try_collect()?;
 
         match &self.tool {
             MergeTool::Builtin => {
-                let tree_id = edit_merge_builtin(tree, &merge_tool_files).map_err(Box::new)?;
+                let tree_id = edit_merge_builtin(&tree, &merge_tool_files).map_err(Box::new)?;
                 Ok((tree_id, None))
             }
-            MergeTool::External(editor) => external::run_mergetool_external(
-                ui,
-                &self.path_converter,
-                editor,
-                tree,
-                &merge_tool_files,
-                self.conflict_marker_style,
-            ),
+            MergeTool::Ours | MergeTool::Theirs => {
+                unreachable!("side-picking tools are handled above")
+            }
+            MergeTool::External(editor) => {
+                // External tools only support conflicts with 2 sides (3-way
+                // conflicts)
+                if let Some(file) = merge_tool_files
+                    .iter()
+                    .find(|file| file.content.num_sides() > 2)
+                {
+                    return Err(ConflictResolveError::ConflictTooComplicated {
+                        path: file.repo_path.clone(),
+                        sides: file.content.num_sides(),
+                    });
+                }
+                external::run_mergetool_external(
+                    ui,
+                    &self.path_converter,
+                    editor,
+                    &tree,
+                    &merge_tool_files,
+                    self.conflict_marker_style,
+                )
+            }
         }
     }
 }
@@ -437,6 +522,11 @@ mod tests {
         };
 
         insta::assert_debug_snapshot!(get(":builtin", "").unwrap(), @"Builtin");
+        insta::assert_debug_snapshot!(get(":ours", "").unwrap_err(), @r#"
+        MergeOnly {
+            tool_name: ":ours",
+        }
+        "#);
 
         // Just program name, edit_args are filled by default
         insta::assert_debug_snapshot!(get("my diff", "").unwrap(), @r#"
@@ -701,6 +791,8 @@ mod tests {
         };
 
         insta::assert_debug_snapshot!(get(":builtin", "").unwrap(), @"Builtin");
+        insta::assert_debug_snapshot!(get(":ours", "").unwrap(), @"Ours");
+        insta::assert_debug_snapshot!(get(":theirs", "").unwrap(), @"Theirs");
 
         // Just program name
         insta::assert_debug_snapshot!(get("my diff", "").unwrap_err(), @r###"
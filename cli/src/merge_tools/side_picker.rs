@@ -0,0 +1,106 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resolves conflicts by picking one of the sides as a whole. This works for
+//! any kind of conflict, including symlink, executable bit, and
+//! file/directory conflicts.
+
+use std::io::Write as _;
+
+use jj_lib::backend::TreeValue;
+use jj_lib::merge::ConflictKind;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::store::Store;
+
+use super::ConflictResolveError;
+use crate::ui::Ui;
+
+/// Returns the value of the side at `index` of the simplified `conflict`.
+///
+/// If only the executable bits conflict, the merged file content is kept and
+/// only the executable bit is taken from the side.
+pub fn pick_side(conflict: &MergedTreeValue, index: usize) -> MergedTreeValue {
+    let side = conflict
+        .get_add(index)
+        .expect("side index should be in range");
+    if conflict.conflict_kind() == ConflictKind::ExecutableBit {
+        let file_ids = conflict.to_file_merge().unwrap();
+        if let (Some(TreeValue::File { executable, .. }), Some(Some(id))) =
+            (side, file_ids.resolve_trivial())
+        {
+            return Merge::normal(TreeValue::File {
+                id: id.clone(),
+                executable: *executable,
+            });
+        }
+    }
+    Merge::resolved(side.clone())
+}
+
+fn describe_side(
+    store: &Store,
+    path: &RepoPath,
+    value: Option<&TreeValue>,
+) -> Result<String, ConflictResolveError> {
+    let description = match value {
+        None => "deleted".to_owned(),
+        Some(TreeValue::File {
+            executable: false, ..
+        }) => "regular file".to_owned(),
+        Some(TreeValue::File {
+            executable: true, ..
+        }) => "executable file".to_owned(),
+        Some(TreeValue::Symlink(id)) => {
+            format!("symlink to {}", store.read_symlink(path, id)?)
+        }
+        Some(TreeValue::Tree(_)) => "directory".to_owned(),
+        Some(TreeValue::GitSubmodule(id)) => format!("Git submodule at commit {}", id.hex()),
+        Some(TreeValue::Conflict(id)) => format!("conflict with id {}", id.hex()),
+    };
+    Ok(description)
+}
+
+/// Describes the sides of the simplified `conflict` and prompts for the side
+/// to keep. Returns `None` if the conflict should be skipped.
+pub fn choose_side(
+    ui: &Ui,
+    store: &Store,
+    path: &RepoPath,
+    conflict: &MergedTreeValue,
+) -> Result<Option<usize>, ConflictResolveError> {
+    let kind = match conflict.conflict_kind() {
+        ConflictKind::Content => "content",
+        ConflictKind::ExecutableBit => "executable bit",
+        ConflictKind::Symlink => "symlink",
+        ConflictKind::FileDirectory => "file/directory",
+        ConflictKind::GitSubmodule => "Git submodule",
+    };
+    let mut choices = vec![];
+    writeln!(ui.stderr(), "The {kind} conflict has these sides:")?;
+    for (i, side) in conflict.adds().enumerate() {
+        let description = describe_side(store, path, side.as_ref())?;
+        writeln!(ui.stderr(), "{}: {description}", i + 1)?;
+        choices.push((i + 1).to_string());
+    }
+    writeln!(ui.stderr(), "s: skip this conflict")?;
+    choices.push("s".to_owned());
+    let choice = ui.prompt_choice("enter the index of the side to keep", &choices, None)?;
+    if choice == "s" {
+        return Ok(None);
+    }
+    Ok(Some(choice.parse::<usize>().unwrap() - 1))
+}
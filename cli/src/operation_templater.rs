@@ -14,6 +14,7 @@
 
 use std::any::Any;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::io;
 
@@ -24,6 +25,7 @@ use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
 use jj_lib::settings::UserSettings;
+use jj_lib::signing::SignError;
 
 use crate::template_builder;
 use crate::template_builder::merge_fn_map;
@@ -41,8 +43,10 @@ use crate::templater::PlainTextFormattedProperty;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
 use crate::templater::TemplateProperty;
+use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
 use crate::templater::TimestampRange;
+use crate::templater::ToJson;
 
 pub trait OperationTemplateLanguageExtension {
     fn build_fn_table(&self) -> OperationTemplateBuildFnTable;
@@ -197,6 +201,14 @@ impl IntoTemplateProperty<'static> for OperationTemplatePropertyKind {
         }
     }
 
+    fn try_into_json(self) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value>>> {
+        match self {
+            OperationTemplatePropertyKind::Core(property) => property.try_into_json(),
+            OperationTemplatePropertyKind::Operation(property) => Some(property.into_json()),
+            OperationTemplatePropertyKind::OperationId(property) => Some(property.into_json()),
+        }
+    }
+
     fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool>>> {
         match (self, other) {
             (
@@ -317,6 +329,29 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
             Ok(L::wrap_boolean(out_property))
         },
     );
+    map.insert(
+        "merge",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|op| op.parent_ids().len() > 1);
+            Ok(L::wrap_boolean(out_property))
+        },
+    );
+    map.insert(
+        "signature_status",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let store = language.repo_loader.store().clone();
+            let out_property =
+                self_property.and_then(move |op| match op.verification(store.signer()) {
+                    Ok(Some(verification)) => Ok(verification.status.to_string()),
+                    Ok(None) => Ok("".to_string()),
+                    Err(SignError::InvalidSignatureFormat) => Ok("invalid".to_string()),
+                    Err(err) => Err(err.into()),
+                });
+            Ok(L::wrap_string(out_property))
+        },
+    );
     map.insert(
         "time",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -351,12 +386,38 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
     map
 }
 
+impl ToJson for Operation {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        let metadata = self.metadata();
+        let time = TimestampRange {
+            start: metadata.start_time,
+            end: metadata.end_time,
+        };
+        Ok(serde_json::json!({
+            "id": self.id().to_json()?,
+            "parents": self.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+            "time": time.to_json()?,
+            "description": metadata.description,
+            "hostname": metadata.hostname,
+            "username": metadata.username,
+            "is_snapshot": metadata.is_snapshot,
+            "tags": metadata.tags.iter().collect::<BTreeMap<_, _>>(),
+        }))
+    }
+}
+
 impl Template for OperationId {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         write!(formatter, "{}", self.hex())
     }
 }
 
+impl ToJson for OperationId {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(self.hex().into())
+    }
+}
+
 fn builtin_operation_id_methods() -> OperationTemplateBuildMethodFnMap<OperationId> {
     type L = OperationTemplateLanguage;
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -15,11 +15,14 @@
 //! Utility for parsing and evaluating user-provided revset expressions.
 
 use std::io;
+use std::path::Path;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectionState;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigNamePathBuf;
@@ -27,8 +30,10 @@ use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset;
 use jj_lib::revset::DefaultSymbolResolver;
+use jj_lib::revset::FunctionCallNode;
 use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetAliasesMap;
@@ -202,6 +207,35 @@ pub fn load_revset_aliases(
     Ok(aliases_map)
 }
 
+/// Returns path to the state file of the ongoing bisection in the workspace.
+pub(crate) fn bisection_state_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".jj").join("bisect")
+}
+
+/// Implements `bisect_candidates()` revset function, which resolves to the
+/// candidates of the ongoing bisection in the workspace.
+///
+/// The bisection state is loaded only when the function is used.
+pub(crate) fn bisect_candidates(
+    _diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    context: &RevsetParseContext,
+) -> Result<Rc<UserRevsetExpression>, RevsetParseError> {
+    function.expect_no_arguments()?;
+    let workspace_root = match context.workspace().map(|ctx| ctx.path_converter) {
+        Some(RepoPathUiConverter::Fs { base, .. }) => base,
+        None => return Ok(RevsetExpression::none()),
+    };
+    match BisectionState::load(&bisection_state_path(workspace_root)) {
+        Ok(Some(state)) => Ok(state.candidates_expression()),
+        Ok(None) => Ok(RevsetExpression::none()),
+        Err(err) => Err(RevsetParseError::expression(
+            format!("Failed to load bisection state: {err}"),
+            function.name_span,
+        )),
+    }
+}
+
 /// Wraps the given `IdPrefixContext` in `SymbolResolver` to be passed in to
 /// `evaluate()`.
 pub fn default_symbol_resolver<'a>(
@@ -367,3 +401,18 @@ pub fn parse_bookmark_name(text: &str) -> Result<String, BookmarkNameParseError>
         source,
     })
 }
+
+#[derive(Debug, Error)]
+#[error("Failed to parse tag name: {}", source.kind())]
+pub struct TagNameParseError {
+    pub input: String,
+    pub source: RevsetParseError,
+}
+
+/// Parses tag name specified in revset syntax.
+pub fn parse_tag_name(text: &str) -> Result<String, TagNameParseError> {
+    revset::parse_symbol(text).map_err(|source| TagNameParseError {
+        input: text.to_owned(),
+        source,
+    })
+}
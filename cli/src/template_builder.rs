@@ -25,6 +25,8 @@ use jj_lib::config::ConfigValue;
 use jj_lib::dsl_util::AliasExpandError as _;
 use jj_lib::settings::UserSettings;
 use jj_lib::time_util::DatePattern;
+use jj_lib::trailer;
+use jj_lib::trailer::Trailer;
 use serde::de::IntoDeserializer as _;
 use serde::Deserialize;
 
@@ -88,6 +90,10 @@ pub trait TemplateLanguage<'a> {
     fn wrap_timestamp_range(
         property: impl TemplateProperty<Output = TimestampRange> + 'a,
     ) -> Self::Property;
+    fn wrap_trailer(property: impl TemplateProperty<Output = Trailer> + 'a) -> Self::Property;
+    fn wrap_trailer_list(
+        property: impl TemplateProperty<Output = Vec<Trailer>> + 'a,
+    ) -> Self::Property;
 
     fn wrap_template(template: Box<dyn Template + 'a>) -> Self::Property;
     fn wrap_list_template(template: Box<dyn ListTemplate + 'a>) -> Self::Property;
@@ -136,6 +142,8 @@ macro_rules! impl_core_wrap_property_fns {
                 wrap_size_hint($crate::templater::SizeHint) => SizeHint,
                 wrap_timestamp(jj_lib::backend::Timestamp) => Timestamp,
                 wrap_timestamp_range($crate::templater::TimestampRange) => TimestampRange,
+                wrap_trailer(jj_lib::trailer::Trailer) => Trailer,
+                wrap_trailer_list(Vec<jj_lib::trailer::Trailer>) => TrailerList,
             }
         );
         fn wrap_template(
@@ -180,6 +188,9 @@ pub trait IntoTemplateProperty<'a> {
     fn try_into_plain_text(self) -> Option<Box<dyn TemplateProperty<Output = String> + 'a>>;
     fn try_into_template(self) -> Option<Box<dyn Template + 'a>>;
 
+    /// Transforms into a property that will evaluate to a JSON value.
+    fn try_into_json(self) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>>;
+
     /// Transforms into a property that will evaluate to `self == other`.
     fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'a>>;
 
@@ -200,6 +211,8 @@ pub enum CoreTemplatePropertyKind<'a> {
     SizeHint(Box<dyn TemplateProperty<Output = SizeHint> + 'a>),
     Timestamp(Box<dyn TemplateProperty<Output = Timestamp> + 'a>),
     TimestampRange(Box<dyn TemplateProperty<Output = TimestampRange> + 'a>),
+    Trailer(Box<dyn TemplateProperty<Output = Trailer> + 'a>),
+    TrailerList(Box<dyn TemplateProperty<Output = Vec<Trailer>> + 'a>),
 
     // Both TemplateProperty and Template can represent a value to be evaluated
     // dynamically, which suggests that `Box<dyn Template + 'a>` could be
@@ -229,6 +242,8 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::SizeHint(_) => "SizeHint",
             CoreTemplatePropertyKind::Timestamp(_) => "Timestamp",
             CoreTemplatePropertyKind::TimestampRange(_) => "TimestampRange",
+            CoreTemplatePropertyKind::Trailer(_) => "Trailer",
+            CoreTemplatePropertyKind::TrailerList(_) => "List<Trailer>",
             CoreTemplatePropertyKind::Template(_) => "Template",
             CoreTemplatePropertyKind::ListTemplate(_) => "ListTemplate",
         }
@@ -255,6 +270,10 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::SizeHint(_) => None,
             CoreTemplatePropertyKind::Timestamp(_) => None,
             CoreTemplatePropertyKind::TimestampRange(_) => None,
+            CoreTemplatePropertyKind::Trailer(_) => None,
+            CoreTemplatePropertyKind::TrailerList(property) => {
+                Some(Box::new(property.map(|l| !l.is_empty())))
+            }
             // Template types could also be evaluated to boolean, but it's less likely
             // to apply label() or .map() and use the result as conditional. It's also
             // unclear whether ListTemplate should behave as a "list" or a "template".
@@ -296,11 +315,33 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::SizeHint(_) => None,
             CoreTemplatePropertyKind::Timestamp(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::TimestampRange(property) => Some(property.into_template()),
+            CoreTemplatePropertyKind::Trailer(property) => Some(property.into_template()),
+            CoreTemplatePropertyKind::TrailerList(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::Template(template) => Some(template),
             CoreTemplatePropertyKind::ListTemplate(template) => Some(template.into_template()),
         }
     }
 
+    fn try_into_json(self) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>> {
+        match self {
+            CoreTemplatePropertyKind::String(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::StringList(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Boolean(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Integer(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::IntegerOpt(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::ConfigValue(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Signature(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Email(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::SizeHint(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Timestamp(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::TimestampRange(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Trailer(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::TrailerList(property) => Some(property.into_json()),
+            CoreTemplatePropertyKind::Template(_) => None,
+            CoreTemplatePropertyKind::ListTemplate(_) => None,
+        }
+    }
+
     fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'a>> {
         match (self, other) {
             (CoreTemplatePropertyKind::String(lhs), CoreTemplatePropertyKind::String(rhs)) => {
@@ -332,6 +373,8 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             (CoreTemplatePropertyKind::SizeHint(_), _) => None,
             (CoreTemplatePropertyKind::Timestamp(_), _) => None,
             (CoreTemplatePropertyKind::TimestampRange(_), _) => None,
+            (CoreTemplatePropertyKind::Trailer(_), _) => None,
+            (CoreTemplatePropertyKind::TrailerList(_), _) => None,
             (CoreTemplatePropertyKind::Template(_), _) => None,
             (CoreTemplatePropertyKind::ListTemplate(_), _) => None,
         }
@@ -356,6 +399,8 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             (CoreTemplatePropertyKind::SizeHint(_), _) => None,
             (CoreTemplatePropertyKind::Timestamp(_), _) => None,
             (CoreTemplatePropertyKind::TimestampRange(_), _) => None,
+            (CoreTemplatePropertyKind::Trailer(_), _) => None,
+            (CoreTemplatePropertyKind::TrailerList(_), _) => None,
             (CoreTemplatePropertyKind::Template(_), _) => None,
             (CoreTemplatePropertyKind::ListTemplate(_), _) => None,
         }
@@ -405,6 +450,7 @@ pub struct CoreTemplateBuildFnTable<'a, L: TemplateLanguage<'a> + ?Sized> {
     pub size_hint_methods: TemplateBuildMethodFnMap<'a, L, SizeHint>,
     pub timestamp_methods: TemplateBuildMethodFnMap<'a, L, Timestamp>,
     pub timestamp_range_methods: TemplateBuildMethodFnMap<'a, L, TimestampRange>,
+    pub trailer_methods: TemplateBuildMethodFnMap<'a, L, Trailer>,
 }
 
 pub fn merge_fn_map<'s, F>(base: &mut HashMap<&'s str, F>, extension: HashMap<&'s str, F>) {
@@ -429,6 +475,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods: builtin_size_hint_methods(),
             timestamp_methods: builtin_timestamp_methods(),
             timestamp_range_methods: builtin_timestamp_range_methods(),
+            trailer_methods: builtin_trailer_methods(),
         }
     }
 
@@ -444,6 +491,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods: HashMap::new(),
             timestamp_methods: HashMap::new(),
             timestamp_range_methods: HashMap::new(),
+            trailer_methods: HashMap::new(),
         }
     }
 
@@ -459,6 +507,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods,
             timestamp_methods,
             timestamp_range_methods,
+            trailer_methods,
         } = extension;
 
         merge_fn_map(&mut self.functions, functions);
@@ -471,6 +520,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
         merge_fn_map(&mut self.size_hint_methods, size_hint_methods);
         merge_fn_map(&mut self.timestamp_methods, timestamp_methods);
         merge_fn_map(&mut self.timestamp_range_methods, timestamp_range_methods);
+        merge_fn_map(&mut self.trailer_methods, trailer_methods);
     }
 
     /// Translates the function call node `function` by using this symbol table.
@@ -568,6 +618,23 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::Trailer(property) => {
+                let table = &self.trailer_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
+            CoreTemplatePropertyKind::TrailerList(property) => {
+                // TODO: migrate to table?
+                build_formattable_list_method(
+                    language,
+                    diagnostics,
+                    build_ctx,
+                    property,
+                    function,
+                    L::wrap_trailer,
+                    L::wrap_trailer_list,
+                )
+            }
             CoreTemplatePropertyKind::Template(_) => {
                 // TODO: migrate to table?
                 Err(TemplateParseError::no_such_method(type_name, function))
@@ -624,6 +691,12 @@ impl<'a, P: IntoTemplateProperty<'a>> Expression<P> {
         }
     }
 
+    pub fn try_into_json(
+        self,
+    ) -> Option<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>> {
+        self.property.try_into_json()
+    }
+
     pub fn try_into_eq(self, other: Self) -> Option<Box<dyn TemplateProperty<Output = bool> + 'a>> {
         self.property.try_into_eq(other.property)
     }
@@ -903,6 +976,14 @@ fn builtin_string_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
             Ok(L::wrap_string_list(out_property))
         },
     );
+    map.insert(
+        "trailers",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|s| trailer::parse_description_trailers(&s));
+            Ok(L::wrap_trailer_list(out_property))
+        },
+    );
     map.insert(
         "upper",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -1236,6 +1317,30 @@ fn builtin_timestamp_range_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
     map
 }
 
+fn builtin_trailer_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, Trailer> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, Trailer>::new();
+    map.insert(
+        "key",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|trailer| trailer.key);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "value",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|trailer| trailer.value);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map
+}
+
 fn build_list_template_method<'a, L: TemplateLanguage<'a> + ?Sized>(
     language: &L,
     diagnostics: &mut TemplateDiagnostics,
@@ -1582,6 +1687,12 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
         let content = expect_plain_text_expression(language, diagnostics, build_ctx, content_node)?;
         Ok(L::wrap_string(content))
     });
+    map.insert("json", |language, diagnostics, build_ctx, function| {
+        let [value_node] = function.expect_exact_arguments()?;
+        let value = expect_json_expression(language, diagnostics, build_ctx, value_node)?;
+        let out_property = value.map(|value| value.to_string());
+        Ok(L::wrap_string(out_property))
+    });
     map.insert("if", |language, diagnostics, build_ctx, function| {
         let ([condition_node, true_node], [false_node]) = function.expect_arguments()?;
         let condition =
@@ -1937,6 +2048,22 @@ pub fn expect_template_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
     )
 }
 
+pub fn expect_json_expression<'a, L: TemplateLanguage<'a> + ?Sized>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    node: &ExpressionNode,
+) -> TemplateParseResult<Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>> {
+    expect_expression_of_type(
+        language,
+        diagnostics,
+        build_ctx,
+        node,
+        "Serializable",
+        |expression| expression.try_into_json(),
+    )
+}
+
 fn expect_expression_of_type<'a, L: TemplateLanguage<'a> + ?Sized, T>(
     language: &L,
     diagnostics: &mut TemplateDiagnostics,
@@ -2710,6 +2837,34 @@ mod tests {
         insta::assert_snapshot!(env.render_ok(r#""he \n ll \n \" o".escape_json()"#), @r#""he \n ll \n \" o""#);
     }
 
+    #[test]
+    fn test_trailer_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("description", || {
+            L::wrap_string(Literal(
+                "subject\n\nbody\n\nSigned-off-by: Alice <alice@example.com>\nChange-Id: I1234\n"
+                    .to_owned(),
+            ))
+        });
+
+        insta::assert_snapshot!(env.render_ok(r#""subject".trailers()"#), @"");
+        insta::assert_snapshot!(env.render_ok(r#"if("subject".trailers(), "yes", "no")"#), @"no");
+        insta::assert_snapshot!(env.render_ok("description.trailers()"), @r"
+        Signed-off-by: Alice <alice@example.com>
+        Change-Id: I1234
+        ");
+        insta::assert_snapshot!(env.render_ok("description.trailers().len()"), @"2");
+        insta::assert_snapshot!(
+            env.render_ok(r#"description.trailers().map(|t| t.key() ++ "=" ++ t.value())"#),
+            @"Signed-off-by=Alice <alice@example.com> Change-Id=I1234");
+        insta::assert_snapshot!(
+            env.render_ok(r#"description.trailers().filter(|t| t.key() == "Change-Id")"#),
+            @"Change-Id: I1234");
+        insta::assert_snapshot!(
+            env.render_ok("json(description.trailers())"),
+            @r#"[{"key":"Signed-off-by","value":"Alice <alice@example.com>"},{"key":"Change-Id","value":"I1234"}]"#);
+    }
+
     #[test]
     fn test_config_value_method() {
         let mut env = TestTemplateEnv::new();
@@ -3181,6 +3336,38 @@ mod tests {
         insta::assert_snapshot!(env.render_ok("stringify(label('error', 'text'))"), @"text");
     }
 
+    #[test]
+    fn test_json_function() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("author", || {
+            L::wrap_signature(Literal(new_signature("Test User", "test.user@example.com")))
+        });
+        env.add_keyword("none_i64", || L::wrap_integer_opt(Literal(None)));
+        env.add_keyword("size_hint", || L::wrap_size_hint(Literal((5, None))));
+        env.add_keyword("t0", || L::wrap_timestamp(Literal(new_timestamp(0, 0))));
+        env.add_keyword("bad_string", || L::wrap_string(new_error_property("Bad")));
+
+        insta::assert_snapshot!(env.render_ok(r#"json("a\"b\n")"#), @r#""a\"b\n""#);
+        insta::assert_snapshot!(env.render_ok(r#"json("a\nb".lines())"#), @r#"["a","b"]"#);
+        insta::assert_snapshot!(env.render_ok(r#"json(42)"#), @"42");
+        insta::assert_snapshot!(env.render_ok(r#"json(none_i64)"#), @"null");
+        insta::assert_snapshot!(env.render_ok(r#"json(true)"#), @"true");
+        insta::assert_snapshot!(env.render_ok(r#"json(size_hint)"#), @"[5,null]");
+        insta::assert_snapshot!(env.render_ok(r#"json(author)"#), @r#"{"email":"test.user@example.com","name":"Test User","timestamp":"1970-01-01T00:00:00+00:00"}"#);
+        insta::assert_snapshot!(env.render_ok(r#"json(author.email())"#), @r#""test.user@example.com""#);
+        insta::assert_snapshot!(env.render_ok(r#"json(t0)"#), @r#""1970-01-01T00:00:00+00:00""#);
+        insta::assert_snapshot!(env.render_ok(r#"json(bad_string)"#), @"<Error: Bad>");
+
+        insta::assert_snapshot!(env.parse_err(r#"json(label("x", "y"))"#), @r#"
+         --> 1:6
+          |
+        1 | json(label("x", "y"))
+          |      ^-------------^
+          |
+          = Expected expression of type `Serializable`, but actual type is `Template`
+        "#);
+    }
+
     #[test]
     fn test_coalesce_function() {
         let mut env = TestTemplateEnv::new();
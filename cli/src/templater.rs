@@ -25,6 +25,9 @@ use bstr::BString;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::config::ConfigValue;
+use jj_lib::trailer::Trailer;
+use serde::de::IntoDeserializer as _;
+use serde::Deserialize as _;
 
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
@@ -182,12 +185,26 @@ impl Template for TimestampRange {
     }
 }
 
+impl Template for Trailer {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        write!(formatter.labeled("key"), "{}", self.key)?;
+        write!(formatter, ": ")?;
+        write!(formatter.labeled("value"), "{}", self.value)
+    }
+}
+
 impl Template for Vec<String> {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         format_joined(formatter, self, " ")
     }
 }
 
+impl Template for Vec<Trailer> {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        format_joined(formatter, self, "\n")
+    }
+}
+
 impl Template for bool {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         let repr = if *self { "true" } else { "false" };
@@ -201,6 +218,105 @@ impl Template for i64 {
     }
 }
 
+/// Value that can be serialized by the `json()` template function.
+///
+/// The output should be a stable representation of the value, which doesn't
+/// depend on the user settings or the current working directory.
+pub trait ToJson {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError>;
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.as_ref()
+            .map_or(Ok(serde_json::Value::Null), |t| t.to_json())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.iter().map(|t| t.to_json()).collect()
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok((*self).into())
+    }
+}
+
+impl ToJson for i64 {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok((*self).into())
+    }
+}
+
+impl ToJson for usize {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok((*self).into())
+    }
+}
+
+impl ToJson for ConfigValue {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        let value = serde_json::Value::deserialize(self.clone().into_deserializer())?;
+        Ok(value)
+    }
+}
+
+impl ToJson for Signature {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "name": self.name,
+            "email": self.email,
+            "timestamp": self.timestamp.to_json()?,
+        }))
+    }
+}
+
+impl ToJson for Trailer {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "key": self.key,
+            "value": self.value,
+        }))
+    }
+}
+
+impl ToJson for Email {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        self.0.to_json()
+    }
+}
+
+impl ToJson for SizeHint {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        let (lower, upper) = self;
+        Ok(serde_json::json!([lower, upper]))
+    }
+}
+
+impl ToJson for Timestamp {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(time_util::format_rfc3339_timestamp(self)?.into())
+    }
+}
+
+impl ToJson for TimestampRange {
+    fn to_json(&self) -> Result<serde_json::Value, TemplatePropertyError> {
+        Ok(serde_json::json!({
+            "start": self.start.to_json()?,
+            "end": self.end.to_json()?,
+        }))
+    }
+}
+
 pub struct LabelTemplate<T, L> {
     content: T,
     labels: L,
@@ -435,6 +551,15 @@ pub trait TemplatePropertyExt: TemplateProperty {
     {
         Box::new(FormattablePropertyTemplate::new(self))
     }
+
+    /// Converts this property into JSON value property.
+    fn into_json<'a>(self) -> Box<dyn TemplateProperty<Output = serde_json::Value> + 'a>
+    where
+        Self: Sized + 'a,
+        Self::Output: ToJson,
+    {
+        Box::new(self.and_then(|value| value.to_json()))
+    }
 }
 
 impl<P: TemplateProperty + ?Sized> TemplatePropertyExt for P {}
@@ -17,6 +17,8 @@ use std::cmp;
 use std::io;
 
 use bstr::ByteSlice as _;
+use jj_lib::trailer;
+use jj_lib::trailer::Trailer;
 use unicode_width::UnicodeWidthChar as _;
 use unicode_width::UnicodeWidthStr as _;
 
@@ -547,6 +549,13 @@ pub fn parse_author(author: &str) -> Result<(String, String), &'static str> {
     Ok((captures["name"].to_string(), captures["email"].to_string()))
 }
 
+pub fn parse_trailer(text: &str) -> Result<Trailer, &'static str> {
+    match trailer::parse_trailers(text).as_deref() {
+        Ok([trailer]) => Ok(trailer.clone()),
+        _ => Err("Invalid trailer string, expected `Key: value`"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Write as _;
@@ -1567,4 +1576,14 @@ mod tests {
         let parsed = parse_author(&format!("<{expected_email}>")).unwrap();
         assert_eq!(("".to_string(), expected_email.to_string()), parsed);
     }
+
+    #[test]
+    fn test_parse_trailer() {
+        let parsed = parse_trailer("Signed-off-by: Example <example@example.com>").unwrap();
+        assert_eq!(parsed.key, "Signed-off-by");
+        assert_eq!(parsed.value, "Example <example@example.com>");
+        assert!(parse_trailer("Signed-off-by").is_err());
+        assert!(parse_trailer("Not a key: value").is_err());
+        assert!(parse_trailer("A: 1\nB: 2").is_err());
+    }
 }
@@ -0,0 +1,265 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+
+/// Creates linear history "a" to "f", where "bug" file is added by "d".
+fn create_history(test_env: &TestEnvironment, repo_path: &Path) {
+    for name in ["a", "b", "c", "d", "e", "f"] {
+        std::fs::write(repo_path.join(name), "").unwrap();
+        if name == "d" {
+            std::fs::write(repo_path.join("bug"), "").unwrap();
+        }
+        test_env
+            .run_jj_in(repo_path, ["commit", "-m", name])
+            .success();
+        test_env
+            .run_jj_in(repo_path, ["bookmark", "create", "-r@-", name])
+            .success();
+    }
+}
+
+fn get_candidates(test_env: &TestEnvironment, repo_path: &Path) -> CommandOutput {
+    test_env.run_jj_in(
+        repo_path,
+        [
+            "log",
+            "--no-graph",
+            "-r",
+            "bisect_candidates()",
+            "-T",
+            r#"bookmarks ++ " ""#,
+        ],
+    )
+}
+
+#[test]
+fn test_bisect_manual() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_history(&test_env, &repo_path);
+
+    // No candidates outside of bisection
+    let output = get_candidates(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @"");
+
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "start", "--bad=f", "--good=a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bisecting: 4 untested revisions left. Testing zsuskuln 563a5bdc c | c
+    Working copy now at: kxryzmor 779ac646 (empty) (no description set)
+    Parent commit      : zsuskuln 563a5bdc c | c
+    Added 0 files, modified 0 files, removed 4 files
+    [EOF]
+    ");
+    let output = get_candidates(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @"f e d c b [EOF]");
+
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "good"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Marked 1 revisions as good.
+    Bisecting: 2 untested revisions left. Testing royxmykx 113ea150 d | d
+    Working copy now at: uyznsvlq ee98eeeb (empty) (no description set)
+    Parent commit      : royxmykx 113ea150 d | d
+    Added 2 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    let output = get_candidates(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @"f e d [EOF]");
+
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "bad"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Marked 1 revisions as bad.
+    The first bad revision is:
+      royxmykx 113ea150 d | d
+    [EOF]
+    ");
+
+    // The state isn't affected by undo
+    test_env.run_jj_in(&repo_path, ["undo"]).success();
+    let output = get_candidates(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @"d [EOF]");
+
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "skip"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No revision is being tested
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "bad", "d"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Marked 1 revisions as bad.
+    The first bad revision is:
+      royxmykx 113ea150 d | d
+    [EOF]
+    ");
+
+    // Another bisection can't be started
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "start"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: A bisection is already in progress
+    Hint: Use `jj bisect reset` to end it first.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "reset"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Ended the bisection.
+    [EOF]
+    ");
+    let output = get_candidates(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @"");
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "good"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start bisecting.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_bisect_skip_all() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_history(&test_env, &repo_path);
+
+    test_env
+        .run_jj_in(&repo_path, ["bisect", "start", "--bad=e", "--good=b"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "skip", "c|d"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Marked 2 revisions as skipped.
+    Some revisions were skipped. The first bad revision is one of:
+      vruxwmqv f4fae8f5 e | e
+      royxmykx 113ea150 d | d
+      zsuskuln 563a5bdc c | c
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_bisect_inconsistent() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_history(&test_env, &repo_path);
+
+    test_env
+        .run_jj_in(&repo_path, ["bisect", "start", "--bad=e", "--good=d"])
+        .success();
+    // A bad revision which is an ancestor of a good revision leaves no candidates
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "bad", "c"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Marked 1 revisions as bad.
+    Error: No revision can be the first bad revision: every common ancestor of the bad revisions is an ancestor of a good revision
+    Hint: Mark the revisions again with `jj bisect good` or `jj bisect bad`, or use `jj bisect reset` to start over.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = get_candidates(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @"");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bisect_run() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_history(&test_env, &repo_path);
+
+    test_env
+        .run_jj_in(&repo_path, ["bisect", "start", "--bad=f"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "run", "test ! -f bug"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bisecting: 5 untested revisions left. Testing zsuskuln 563a5bdc c | c
+    Working copy now at: kxryzmor 779ac646 (empty) (no description set)
+    Parent commit      : zsuskuln 563a5bdc c | c
+    Marked the revision as good.
+    Bisecting: 2 untested revisions left. Testing royxmykx 113ea150 d | d
+    Working copy now at: nwtnpptq b3e8b9e3 (empty) (no description set)
+    Parent commit      : royxmykx 113ea150 d | d
+    Added 2 files, modified 0 files, removed 0 files
+    Marked the revision as bad.
+    The first bad revision is:
+      royxmykx 113ea150 d | d
+    [EOF]
+    ");
+
+    // Exit status 125 skips the revision, and 128 or above aborts
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "reset"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Ended the bisection.
+    [EOF]
+    ");
+    test_env
+        .run_jj_in(&repo_path, ["bisect", "start", "--bad=f", "--good=c"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "run", "exit 125"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bisecting: 2 untested revisions left. Testing royxmykx 113ea150 d | d
+    Working copy now at: xznxytkn fefb945c (empty) (no description set)
+    Parent commit      : royxmykx 113ea150 d | d
+    Marked the revision as skipped.
+    Bisecting: 1 untested revisions left. Testing vruxwmqv f4fae8f5 e | e
+    Working copy now at: smwtzssm 66ccba2f (empty) (no description set)
+    Parent commit      : vruxwmqv f4fae8f5 e | e
+    Added 1 files, modified 0 files, removed 0 files
+    Marked the revision as skipped.
+    Some revisions were skipped. The first bad revision is one of:
+      znkkpsqq 0c65ea9a f | f
+      vruxwmqv f4fae8f5 e | e
+      royxmykx 113ea150 d | d
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "reset"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Ended the bisection.
+    [EOF]
+    ");
+    test_env
+        .run_jj_in(&repo_path, ["bisect", "start", "--bad=f", "--good=c"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["bisect", "run", "exit 255"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Bisecting: 2 untested revisions left. Testing royxmykx 113ea150 d | d
+    Working copy now at: tlkvzzqu 5d563806 (empty) (no description set)
+    Parent commit      : royxmykx 113ea150 d | d
+    Error: Command failed with exit status: 255; aborting the bisection
+    [EOF]
+    [exit status: 1]
+    ");
+}
@@ -1822,6 +1822,53 @@ fn test_bookmark_list() {
     ");
 }
 
+#[test]
+fn test_bookmark_list_json() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+
+    // Initialize remote refs
+    test_env.run_jj_in(".", ["git", "init", "remote"]).success();
+    let remote_path = test_env.env_root().join("remote");
+    test_env
+        .run_jj_in(&remote_path, ["bookmark", "create", "-r@", "remote-sync"])
+        .success();
+    test_env
+        .run_jj_in(&remote_path, ["git", "export"])
+        .success();
+
+    // Initialize local refs
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env
+        .run_jj_in(
+            ".",
+            ["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+        )
+        .success();
+    let local_path = test_env.env_root().join("local");
+    test_env
+        .run_jj_in(&local_path, ["bookmark", "create", "-r@", "local-only"])
+        .success();
+
+    let output = test_env.run_jj_in(
+        &local_path,
+        [
+            "bookmark",
+            "list",
+            "--all-remotes",
+            "-T",
+            r#"json(self) ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(output, @r#"
+    {"name":"local-only","remote":null,"target":["f652c32197cfd6978501fab60d52a8299175ffa4"],"tracking_target":null}
+    {"name":"remote-sync","remote":null,"target":["230dd059e1b059aefc0da06a2e5a7dbf22362f22"],"tracking_target":null}
+    {"name":"remote-sync","remote":"origin","target":["230dd059e1b059aefc0da06a2e5a7dbf22362f22"],"tracking_target":["230dd059e1b059aefc0da06a2e5a7dbf22362f22"]}
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_bookmark_list_filtered() {
     let test_env = TestEnvironment::default();
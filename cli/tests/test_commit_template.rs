@@ -1639,6 +1639,91 @@ fn test_repo_path() {
     ");
 }
 
+#[test]
+fn test_log_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "first \"line\"\n\nbody"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["bookmark", "create", "-r@-", "main"])
+        .success();
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::remove_file(repo_path.join("file2")).unwrap();
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["log", "--no-graph", "-r@-", "-T", r#"json(self) ++ "\n""#],
+    );
+    insta::assert_snapshot!(output, @r#"
+    {"author":{"email":"test.user@example.com","name":"Test User","timestamp":"2001-02-03T04:05:08+07:00"},"change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","commit_id":"275b50ffbb934e64075af4716ebb50d2a5e2bd80","committer":{"email":"test.user@example.com","name":"Test User","timestamp":"2001-02-03T04:05:08+07:00"},"description":"first \"line\"\n\nbody\n","parents":["0000000000000000000000000000000000000000"]}
+    [EOF]
+    "#);
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["log", "--no-graph", "-r@", "-T", r#"json(diff) ++ "\n""#],
+    );
+    insta::assert_snapshot!(output, @r#"
+    [{"path":"file1","source":{"conflict":false,"executable":false,"file_type":"file","path":"file1"},"status":"modified","target":{"conflict":false,"executable":false,"file_type":"file","path":"file1"}},{"path":"file2","source":{"conflict":false,"executable":false,"file_type":"file","path":"file2"},"status":"removed","target":{"conflict":false,"executable":false,"file_type":"","path":"file2"}}]
+    [EOF]
+    "#);
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "show",
+            "--summary",
+            "@-",
+            "-T",
+            r#"json(bookmarks) ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(output, @r#"
+    [{"name":"main","remote":null,"target":["275b50ffbb934e64075af4716ebb50d2a5e2bd80"],"tracking_target":null}]
+    A file1
+    A file2
+    [EOF]
+    "#);
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "evolog",
+            "--no-graph",
+            "-r@-",
+            "-T",
+            r#"json(commit_id) ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(output, @r#"
+    "275b50ffbb934e64075af4716ebb50d2a5e2bd80"
+    "fc687cb88a82400b4ab22ce860f3c31560f892d7"
+    "230dd059e1b059aefc0da06a2e5a7dbf22362f22"
+    [EOF]
+    "#);
+
+    // Not all types are serializable
+    let output = test_env.run_jj_in(&repo_path, ["log", "-T", "json(diff.stat())"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse template: Expected expression of type `Serializable`, but actual type is `DiffStats`
+    Caused by:  --> 1:6
+      |
+    1 | json(diff.stat())
+      |      ^---------^
+      |
+      = Expected expression of type `Serializable`, but actual type is `DiffStats`
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_signature_templates() {
     let test_env = TestEnvironment::default();
@@ -709,6 +709,7 @@ fn test_config() {
     ui.conflict-marker-style=diff
     ui.conflict-marker-style=snapshot
     ui.conflict-marker-style=git
+    ui.conflict-marker-style=zdiff3
     [EOF]
     ");
     let output = test_env.run_jj_in(
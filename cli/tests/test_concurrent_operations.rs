@@ -68,6 +68,85 @@ fn test_concurrent_operation_divergence() {
     ");
 }
 
+#[test]
+fn test_concurrent_operations_conflicted_bookmark() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    for description in ["A", "B", "C"] {
+        test_env
+            .run_jj_in(&repo_path, ["new", "root()", "-m", description])
+            .success();
+    }
+    test_env
+        .run_jj_in(&repo_path, ["bookmark", "create", "-r@", "main", "other"])
+        .success();
+    let op_id = test_env.current_operation_id(&repo_path);
+    test_env
+        .run_jj_in(
+            &repo_path,
+            [
+                "bookmark",
+                "set",
+                "-rdescription(A)",
+                "main",
+                "--allow-backwards",
+            ],
+        )
+        .success();
+    test_env
+        .run_jj_in(
+            &repo_path,
+            [
+                "bookmark",
+                "set",
+                "--at-op",
+                &op_id,
+                "-rdescription(B)",
+                "main",
+                "--allow-backwards",
+            ],
+        )
+        .success();
+
+    // The merge operation is marked in the graph
+    test_env.run_jj_in(&repo_path, ["new", "-m", "D"]).success();
+    let output = test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription", "--limit=5"]);
+    insta::assert_snapshot!(output, @r"
+    @  new empty commit
+    ◇    reconcile divergent operations
+    ├─╮
+    ○ │  point bookmark main to commit a7e4cec4256b7995129b9d1e1bda7e1df6e60678
+    │ ○  point bookmark main to commit 66f4d1806ae41bd604f69155dece64062a0056cf
+    ├─╯
+    ○  create bookmark main, other pointing to commit d97df99f2501bc5399c6f6a8c605bac6db5a0631
+    [EOF]
+    ");
+
+    // The conflict is explained by "op show"
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "op",
+            "show",
+            "@-",
+            "--no-graph",
+            r#"--config=templates.op_log='description ++ "\n"'"#,
+        ],
+    );
+    insta::assert_snapshot!(output, @r#"
+    reconcile divergent operations
+
+    Conflicts introduced by merging concurrent operations:
+    bookmark main:
+    + rlvkpnrz a7e4cec4 main?? | (empty) A (from operation 200c8ae04f30)
+    + kkmpptxz 66f4d180 main?? | (empty) B (from operation 20acb449a0ea)
+    - zsuskuln d97df99f other | (empty) C
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_concurrent_operations_auto_rebase() {
     let test_env = TestEnvironment::default();
@@ -173,7 +252,7 @@ fn test_concurrent_operations_wc_modified() {
     let output = test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription"]);
     insta::assert_snapshot!(output, @r"
     @  snapshot working copy
-    ○    reconcile divergent operations
+    ◇    reconcile divergent operations
     ├─╮
     ○ │  new empty commit
     │ ○  new empty commit
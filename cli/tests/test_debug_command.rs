@@ -153,6 +153,77 @@ fn test_debug_reindex() {
     ");
 }
 
+#[test]
+fn test_debug_reindex_changed_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(workspace_path.join("file1"), "").unwrap();
+    test_env
+        .run_jj_in(&workspace_path, ["commit", "-m1"])
+        .success();
+    std::fs::write(workspace_path.join("file2"), "").unwrap();
+    test_env
+        .run_jj_in(&workspace_path, ["commit", "-m2"])
+        .success();
+
+    let output = test_env.run_jj_in(&workspace_path, ["debug", "reindex", "--changed-paths"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Finished indexing 8 commits.
+    Indexed paths changed by the commits.
+    [EOF]
+    ");
+    let changed_paths_dir = workspace_path.join(".jj/repo/index/changed_paths");
+    assert!(changed_paths_dir.is_dir());
+
+    // Newly-created commits aren't indexed, but can still be queried
+    std::fs::write(workspace_path.join("file1"), "modified").unwrap();
+    test_env
+        .run_jj_in(&workspace_path, ["describe", "-m3"])
+        .success();
+    let output = test_env.run_jj_in(
+        &workspace_path,
+        [
+            "log",
+            "--no-graph",
+            "-r",
+            "files(file1)",
+            "-T",
+            "description",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    3
+    1
+    [EOF]
+    ");
+
+    let output = test_env.run_jj_in(&workspace_path, ["debug", "reindex", "--no-changed-paths"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Finished indexing 10 commits.
+    [EOF]
+    ");
+    assert!(!changed_paths_dir.exists());
+    let output = test_env.run_jj_in(
+        &workspace_path,
+        [
+            "log",
+            "--no-graph",
+            "-r",
+            "files(file1)",
+            "-T",
+            "description",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    3
+    1
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_debug_tree() {
     let test_env = TestEnvironment::default();
@@ -252,6 +323,77 @@ fn test_debug_operation_id() {
     ");
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_debug_fsmonitor() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let workspace_path = test_env.env_root().join("repo");
+    let pid_regex = Regex::new(r"pid \d+").unwrap();
+    let status = || {
+        test_env
+            .run_jj_in(&workspace_path, ["debug", "fsmonitor", "status"])
+            .normalize_stdout_with(|text| pid_regex.replace_all(&text, "pid [pid]").into_owned())
+    };
+
+    let output = status();
+    assert_snapshot!(output, @r#"
+    The inotify filesystem monitor is disabled. Set `core.fsmonitor="inotify"` to enable.
+    The daemon is not running.
+    [EOF]
+    "#);
+
+    let output = test_env.run_jj_in(&workspace_path, ["debug", "fsmonitor", "start"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    Started the daemon.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&workspace_path, ["debug", "fsmonitor", "start"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    The daemon is already running.
+    [EOF]
+    ");
+    let output = status();
+    assert_snapshot!(output, @r#"
+    The inotify filesystem monitor is disabled. Set `core.fsmonitor="inotify"` to enable.
+    The daemon is running (pid [pid]).
+    The next snapshot will scan the whole working copy.
+    [EOF]
+    "#);
+
+    // The clock is recorded by the next snapshot
+    test_env.add_config(r#"core.fsmonitor = "inotify""#);
+    test_env.run_jj_in(&workspace_path, ["status"]).success();
+    std::fs::write(workspace_path.join("file"), "contents").unwrap();
+    let output = status();
+    assert_snapshot!(output, @r"
+    The inotify filesystem monitor is enabled via `core.fsmonitor`.
+    The daemon is running (pid [pid]).
+    Changed paths since the last snapshot: 1
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&workspace_path, ["diff", "--summary"]);
+    assert_snapshot!(output, @r"
+    A file
+    [EOF]
+    ");
+
+    let output = test_env.run_jj_in(&workspace_path, ["debug", "fsmonitor", "stop"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    Stopped the daemon.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&workspace_path, ["debug", "fsmonitor", "stop"]);
+    assert_snapshot!(output, @r"
+    ------- stderr -------
+    The daemon is not running.
+    [EOF]
+    ");
+}
+
 fn filter_index_stats(output: CommandOutput) -> CommandOutput {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
     output.normalize_stdout_with(|text| regex.replace_all(&text, "    Name: [hash]").into_owned())
@@ -840,6 +840,107 @@ fn test_describe_author() {
     "#);
 }
 
+#[test]
+fn test_describe_add_trailer() {
+    let mut test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    let edit_script = test_env.set_up_fake_editor();
+    let get_description = || {
+        test_env.run_jj_in(
+            &repo_path,
+            ["log", "--no-graph", "-r@", "-T", "description"],
+        )
+    };
+
+    // Trailers are added in a new paragraph
+    test_env
+        .run_jj_in(
+            &repo_path,
+            [
+                "describe",
+                "-m",
+                "subject",
+                "--add-trailer",
+                "Reviewed-by: Alice <alice@example.com>",
+                "--add-trailer=Change-Id: I1234",
+            ],
+        )
+        .success();
+    insta::assert_snapshot!(get_description(), @r"
+    subject
+
+    Reviewed-by: Alice <alice@example.com>
+    Change-Id: I1234
+    [EOF]
+    ");
+
+    // Adding the same trailer again is a no-op, whereas new trailers are
+    // appended to the existing trailer block
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "describe",
+            "--no-edit",
+            "--add-trailer",
+            "reviewed-by: Alice <alice@example.com>",
+            "--add-trailer",
+            "Reviewed-by: Bob <bob@example.com>",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy now at: qpvuntsm 1efda8e1 (empty) subject
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_description(), @r"
+    subject
+
+    Reviewed-by: Alice <alice@example.com>
+    Change-Id: I1234
+    Reviewed-by: Bob <bob@example.com>
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["describe", "--no-edit", "--add-trailer", "Change-Id: I1234"],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Trailers are added to the edited description
+    std::fs::write(edit_script, ["dump editor"].join("\0")).unwrap();
+    test_env.run_jj_in(&repo_path, ["new"]).success();
+    test_env
+        .run_jj_in(&repo_path, ["describe", "--add-trailer", "Tested: yes"])
+        .success();
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    insta::assert_snapshot!(get_description(), @r"
+
+    Tested: yes
+    [EOF]
+    ");
+
+    // Invalid trailer
+    let output = test_env.run_jj_in(&repo_path, ["describe", "--add-trailer", "Not a trailer"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value 'Not a trailer' for '--add-trailer <TRAILER>': Invalid trailer string, expected `Key: value`
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_describe_avoids_unc() {
     let mut test_env = TestEnvironment::default();
@@ -40,7 +40,7 @@ fn test_track_untrack() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Error: This command must be able to update the working copy.
-    Hint: Don't use --at-op.
+    Hint: Don't use --at-op, or use `jj workspace add --sandbox` to create a writable workspace at the operation.
     [EOF]
     [exit status: 1]
     ");
@@ -993,6 +993,93 @@ fn test_git_clone_with_depth_subprocess() {
     ");
 }
 
+#[test]
+fn test_git_clone_with_filter_git2() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.subprocess = false");
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    set_up_non_empty_git_repo(&git_repo);
+
+    let output = test_env.run_jj_in(
+        ".",
+        ["git", "clone", "--filter", "blob:none", "source", "clone"],
+    );
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone"
+    Error: Partial clones are only supported when fetching through a git subprocess
+    Hint: Set `git.subprocess = true` to fetch through a git subprocess.
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
+#[test]
+fn test_git_clone_with_filter_subprocess() {
+    let test_env = TestEnvironment::default();
+    let clone_path = test_env.env_root().join("clone");
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    set_up_non_empty_git_repo(&git_repo);
+    git_repo
+        .config()
+        .unwrap()
+        .set_bool("uploadpack.allowFilter", true)
+        .unwrap();
+    // Add a file on another branch, which won't be checked out
+    let signature =
+        git2::Signature::new("Some One", "some.one@example.com", &git2::Time::new(0, 0)).unwrap();
+    let parent = git_repo.head().unwrap().peel_to_commit().unwrap();
+    let other_blob_oid = git_repo.blob(b"other content").unwrap();
+    let mut tree_builder = git_repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+    tree_builder
+        .insert("other", other_blob_oid, git2::FileMode::Blob.into())
+        .unwrap();
+    let tree = git_repo.find_tree(tree_builder.write().unwrap()).unwrap();
+    git_repo
+        .commit(
+            Some("refs/heads/feature"),
+            &signature,
+            &signature,
+            "feature",
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+
+    let output = test_env.run_jj_in(
+        ".",
+        ["git", "clone", "--filter", "blob:none", "source", "clone"],
+    );
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: feature@origin [new] untracked
+    bookmark: main@origin    [new] untracked
+    Setting the revset alias `trunk()` to `main@origin`
+    Working copy now at: sqpuoqvx cad212e1 (empty) (no description set)
+    Parent commit      : mzyxwzks 9f01a0e0 main | message
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+    // The file in the working copy has been fetched on demand
+    assert_eq!(
+        std::fs::read_to_string(clone_path.join("file")).unwrap(),
+        "content"
+    );
+
+    // The blob which isn't checked out is still missing
+    let clone_git_repo = git2::Repository::open(clone_path.join(".jj/repo/store/git")).unwrap();
+    assert!(!clone_git_repo.odb().unwrap().exists(other_blob_oid));
+    let output = test_env.run_jj_in(
+        &clone_path,
+        ["file", "show", "-r", "feature@origin", "other"],
+    );
+    insta::assert_snapshot!(output, @"other content[EOF]");
+    assert!(clone_git_repo.odb().unwrap().exists(other_blob_oid));
+}
+
 #[test_case(false; "use git2 for remote calls")]
 #[test_case(true; "spawn a git subprocess for remote calls")]
 fn test_git_clone_invalid_immutable_heads(subprocess: bool) {
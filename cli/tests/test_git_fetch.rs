@@ -1416,6 +1416,79 @@ fn test_git_fetch_bookmarks_missing_with_subprocess_localized_message() {
 
 // See `test_undo_restore_commands.rs` for fetch-undo-push and fetch-undo-fetch
 // of the same bookmarks for various kinds of undo.
+#[test_case(false; "use git2 for remote calls")]
+#[test_case(true; "spawn a git subprocess for remote calls")]
+fn test_git_fetch_tags(subprocess: bool) {
+    let test_env = TestEnvironment::default();
+    if !subprocess {
+        test_env.add_config("git.subprocess = false");
+    }
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = add_git_remote(&test_env, &repo_path, "origin");
+    let origin_oid = git_repo.refname_to_id("refs/heads/origin").unwrap();
+    let other_oid = add_commit_to_branch(&git_repo, "other");
+    git_repo
+        .reference("refs/tags/v1", other_oid, false, "")
+        .unwrap();
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["git", "fetch", "--branch=origin", "--tag=glob:v*"],
+    );
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: origin@origin [new] untracked
+    tag:    v1            [new] 
+    [EOF]
+    ");
+    }
+    let output = test_env.run_jj_in(&repo_path, ["tag", "list"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    v1: qztxwoly 102a0b1f message
+    [EOF]
+    ");
+    }
+
+    // Moved tags are updated only if specified
+    git_repo
+        .reference("refs/tags/v1", origin_oid, true, "")
+        .unwrap();
+    test_env
+        .run_jj_in(&repo_path, ["git", "fetch", "--branch=origin"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["tag", "list"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    v1: qztxwoly 102a0b1f message
+    [EOF]
+    ");
+    }
+    test_env
+        .run_jj_in(&repo_path, ["git", "fetch", "--branch=origin", "--tag=v1"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["tag", "list"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    v1: oputwtnw ffecd2d6 message
+    [EOF]
+    ");
+    }
+
+    // Invalid patterns are rejected
+    let output = test_env.run_jj_in(&repo_path, ["git", "fetch", "--tag=glob:v?"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Invalid tag pattern provided. When fetching, tag names and globs may not contain the characters `:`, `^`, `?`, `[`, `]`
+    [EOF]
+    [exit status: 1]
+    ");
+    }
+}
+
 #[test_case(false; "use git2 for remote calls")]
 #[test_case(true; "spawn a git subprocess for remote calls")]
 fn test_git_fetch_undo(subprocess: bool) {
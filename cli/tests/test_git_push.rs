@@ -16,6 +16,7 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use test_case::test_case;
+use testutils::git;
 
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
@@ -917,6 +918,232 @@ fn test_git_push_multiple(subprocess: bool) {
     }
 }
 
+#[test_case(false; "use git2 for remote calls")]
+#[test_case(true; "spawn a git subprocess for remote calls")]
+fn test_git_push_tag(subprocess: bool) {
+    let (test_env, workspace_root) = set_up();
+    if !subprocess {
+        test_env.add_config("git.subprocess = false");
+    }
+    let origin_git_repo = git::open(
+        test_env
+            .env_root()
+            .join("origin")
+            .join(".jj")
+            .join("repo")
+            .join("store")
+            .join("git"),
+    );
+    test_env
+        .run_jj_in(&workspace_root, ["tag", "create", "-rbookmark1", "v1"])
+        .success();
+    test_env
+        .run_jj_in(
+            &workspace_root,
+            ["tag", "create", "-rbookmark2", "-mRelease 2", "v2"],
+        )
+        .success();
+
+    let output = test_env.run_jj_in(&workspace_root, ["git", "push", "--tag=v3"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such tag: v3
+    [EOF]
+    [exit status: 1]
+    ");
+    }
+
+    let output = test_env.run_jj_in(&workspace_root, ["git", "push", "--tag=glob:v*"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Add tag v1 to d13ecdbda2a2
+      Add tag v2 to 8476341eb395
+    [EOF]
+    ");
+    }
+    let v1_ref = origin_git_repo.find_reference("refs/tags/v1").unwrap();
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(v1_ref.target().id(), @"d13ecdbda2a2e7471a29db2bed6f08ca28f91264");
+    }
+    let v2_tag = origin_git_repo
+        .find_reference("refs/tags/v2")
+        .unwrap()
+        .peel_to_kind(gix::object::Kind::Tag)
+        .unwrap()
+        .into_tag();
+    assert_eq!(v2_tag.decode().unwrap().message, "Release 2\n");
+
+    // Tags which already exist on the remote aren't overwritten
+    test_env
+        .run_jj_in(
+            &workspace_root,
+            ["tag", "set", "--allow-move", "-rbookmark2", "v1"],
+        )
+        .success();
+    let output = test_env.run_jj_in(&workspace_root, ["git", "push", "--tag=v1"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Add tag v1 to 8476341eb395
+    Error: Refusing to overwrite tags which already exist on the remote: v1
+    Hint: Delete the tags on the remote first, or push them under different names.
+    [EOF]
+    [exit status: 1]
+    ");
+    }
+    let v1_ref = origin_git_repo.find_reference("refs/tags/v1").unwrap();
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(v1_ref.target().id(), @"d13ecdbda2a2e7471a29db2bed6f08ca28f91264");
+    }
+
+    // Bookmarks and tags are pushed together
+    test_env
+        .run_jj_in(&workspace_root, ["tag", "create", "-rbookmark2", "v3"])
+        .success();
+    test_env
+        .run_jj_in(
+            &workspace_root,
+            [
+                "bookmark",
+                "set",
+                "--allow-backwards",
+                "-rbookmark2",
+                "bookmark1",
+            ],
+        )
+        .success();
+    let output = test_env.run_jj_in(
+        &workspace_root,
+        ["git", "push", "--bookmark=bookmark1", "--tag=v3"],
+    );
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Move sideways bookmark bookmark1 from d13ecdbda2a2 to 8476341eb395
+      Add tag v3 to 8476341eb395
+    [EOF]
+    ");
+    }
+    let output = test_env.run_jj_in(&workspace_root, ["bookmark", "list", "--all", "bookmark1"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    bookmark1: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    [EOF]
+    ");
+    }
+    let v3_ref = origin_git_repo.find_reference("refs/tags/v3").unwrap();
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(v3_ref.target().id(), @r"8476341eb3955478919c198e0c04587b6b8ea071");
+    }
+}
+
+#[cfg(unix)]
+fn set_up_rejecting_hook(test_env: &TestEnvironment, bookmark: &str) {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let hooks_path = test_env
+        .env_root()
+        .join("origin")
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git")
+        .join("hooks");
+    std::fs::create_dir_all(&hooks_path).unwrap();
+    let hook_path = hooks_path.join("update");
+    std::fs::write(
+        &hook_path,
+        format!("#!/bin/sh\ntest \"$1\" != refs/heads/{bookmark}\n"),
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+// libgit2 doesn't run hooks when pushing to a local repo, so this is only
+// tested with a git subprocess.
+#[cfg(unix)]
+#[test]
+fn test_git_push_rejected_by_remote() {
+    let (test_env, workspace_root) = set_up();
+    set_up_rejecting_hook(&test_env, "bookmark1");
+    test_env
+        .run_jj_in(&workspace_root, ["describe", "bookmark1", "-m=modified"])
+        .success();
+
+    // Remove the padding added to the remote message to clear the line
+    let output = test_env
+        .run_jj_in(&workspace_root, ["git", "push", "-bbookmark1"])
+        .normalize_stderr_with(|stderr| stderr.replace("        \n", "\n"));
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Move sideways bookmark bookmark1 from d13ecdbda2a2 to 362f96033795
+    remote: error: hook declined to update refs/heads/bookmark1
+    Error: Remote rejected the update of some refs: refs/heads/bookmark1 (hook declined)
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[cfg(unix)]
+#[test_case(false; "use git2 for remote calls")]
+#[test_case(true; "spawn a git subprocess for remote calls")]
+fn test_git_push_atomic(subprocess: bool) {
+    let (test_env, workspace_root) = set_up();
+    if !subprocess {
+        test_env.add_config("git.subprocess = false");
+    }
+    set_up_rejecting_hook(&test_env, "bookmark1");
+    test_env
+        .run_jj_in(&workspace_root, ["describe", "bookmark1", "-m=modified 1"])
+        .success();
+    test_env
+        .run_jj_in(&workspace_root, ["describe", "bookmark2", "-m=modified 2"])
+        .success();
+
+    let output = test_env
+        .run_jj_in(&workspace_root, ["git", "push", "--all", "--atomic"])
+        .normalize_stderr_with(|stderr| stderr.replace("        \n", "\n"));
+    if subprocess {
+        insta::assert_snapshot!(output, @r"
+        ------- stderr -------
+        Changes to push to origin:
+          Move sideways bookmark bookmark1 from d13ecdbda2a2 to bdd854bcde79
+          Move sideways bookmark bookmark2 from 8476341eb395 to 6b1c424bf0e1
+        remote: error: hook declined to update refs/heads/bookmark1
+        Error: Remote rejected the update of some refs: refs/heads/bookmark1 (hook declined)
+        [EOF]
+        [exit status: 1]
+        ");
+    } else {
+        insta::assert_snapshot!(output, @r"
+        ------- stderr -------
+        Changes to push to origin:
+          Move sideways bookmark bookmark1 from d13ecdbda2a2 to bdd854bcde79
+          Move sideways bookmark bookmark2 from 8476341eb395 to 6b1c424bf0e1
+        Error: Atomic pushes are only supported when pushing through a git subprocess
+        Hint: Set `git.subprocess = true` to push through a git subprocess.
+        [EOF]
+        [exit status: 1]
+        ");
+    }
+    // Neither bookmark was updated on the remote
+    let output = test_env.run_jj_in(&workspace_root, ["git", "fetch"]);
+    insta::allow_duplicates! {
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    }
+}
+
 #[test_case(false; "use git2 for remote calls")]
 #[test_case(true; "spawn a git subprocess for remote calls")]
 fn test_git_push_changes(subprocess: bool) {
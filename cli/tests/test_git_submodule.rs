@@ -70,3 +70,90 @@ fn test_gitsubmodule_print_gitmodules() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_git_submodule_update_and_snapshot() {
+    let test_env = TestEnvironment::default();
+
+    // Set up a submodule repo and a superproject which records it at "sub"
+    // with a URL relative to the superproject.
+    let sub_repo = git::init(test_env.env_root().join("sub-origin"));
+    let sub_commit =
+        git::add_commit(&sub_repo, "refs/heads/main", "file", b"v1\n", "sub v1", &[]).commit_id;
+    let super_repo = git::init(test_env.env_root().join("super-origin"));
+    let gitmodules_blob = super_repo
+        .write_blob("[submodule \"sub\"]\n\tpath = sub\n\turl = ../sub-origin\n")
+        .unwrap()
+        .detach();
+    let mut tree_editor = super_repo
+        .edit_tree(gix::ObjectId::empty_tree(gix::hash::Kind::Sha1))
+        .unwrap();
+    tree_editor
+        .upsert(
+            ".gitmodules",
+            gix::object::tree::EntryKind::Blob,
+            gitmodules_blob,
+        )
+        .unwrap();
+    tree_editor
+        .upsert("sub", gix::object::tree::EntryKind::Commit, sub_commit)
+        .unwrap();
+    let tree_id = tree_editor.write().unwrap().detach();
+    git::write_commit(&super_repo, "refs/heads/main", tree_id, "super", &[]);
+    git::set_symbolic_reference(&super_repo, "HEAD", "refs/heads/main");
+
+    test_env
+        .run_jj_in(".", ["git", "clone", "super-origin", "repo"])
+        .success();
+    let workspace_root = test_env.env_root().join("repo");
+    let sub_file = workspace_root.join("sub").join("file");
+    assert!(!sub_file.exists());
+
+    let output = test_env.run_jj_in(&workspace_root, ["git", "submodule", "status"]);
+    insta::assert_snapshot!(output, @r"
+    sub b29b9cbefd7e (not initialized)
+    [EOF]
+    ");
+
+    let output = test_env.run_jj_in(&workspace_root, ["git", "submodule", "update"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Fetching submodule sub from $TEST_ENV/sub-origin
+    Checked out submodule sub at b29b9cbefd7e
+    [EOF]
+    ");
+    assert_eq!(std::fs::read_to_string(&sub_file).unwrap(), "v1\n");
+
+    let output = test_env.run_jj_in(&workspace_root, ["git", "submodule", "status"]);
+    insta::assert_snapshot!(output, @r"
+    sub b29b9cbefd7e (checked out)
+    [EOF]
+    ");
+
+    // Changes in the submodule are recorded as a new submodule commit
+    std::fs::write(&sub_file, "v2\n").unwrap();
+    let output = test_env.run_jj_in(&workspace_root, ["diff", "--git"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/sub b/sub
+    index b29b9cbefd..c043aebc31 160000
+    --- a/sub
+    +++ b/sub
+    @@ -1,1 +1,1 @@
+    -Subproject commit b29b9cbefd7ef4e094bced33a5b32de2642100a7
+    +Subproject commit c043aebc31934b23c034f8462d433d58a0d6a933
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&workspace_root, ["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    M sub
+    [EOF]
+    ");
+
+    // Checking out the old commit restores the submodule files
+    test_env
+        .run_jj_in(&workspace_root, ["new", "main@origin"])
+        .success();
+    assert_eq!(std::fs::read_to_string(&sub_file).unwrap(), "v1\n");
+    let output = test_env.run_jj_in(&workspace_root, ["diff", "--summary"]);
+    insta::assert_snapshot!(output, @"");
+}
@@ -0,0 +1,140 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::formatdoc;
+
+use crate::common::to_toml_value;
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_rebase_with_merge_drivers() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    let formatter_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    let formatter = to_toml_value(formatter_path.to_str().unwrap());
+    test_env.add_config(formatdoc! {"
+        [merge-drivers.changelog]
+        paths = 'glob:*.md'
+        builtin = 'union'
+
+        [merge-drivers.lock]
+        paths = 'file.lock'
+        command = [{formatter}, '--stdout', 'merged by $path', '--tee', '$output']
+
+        [merge-drivers.failing]
+        paths = 'failing | clean'
+        command = [{formatter}, '--fail']
+
+        [merge-drivers.missing]
+        paths = 'missing'
+        command = ['jj-nonexistent-driver']
+    "});
+
+    let file_names = ["CHANGELOG.md", "file.lock", "failing", "missing", "other"];
+    for name in file_names {
+        std::fs::write(repo_path.join(name), "a\nb\n").unwrap();
+    }
+    std::fs::write(repo_path.join("clean"), "a\nb\nc\n").unwrap();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "base"])
+        .success();
+    for name in file_names {
+        std::fs::write(repo_path.join(name), "a\nleft\nb\n").unwrap();
+    }
+    std::fs::write(repo_path.join("clean"), "left\nb\nc\n").unwrap();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "left"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["new", "description(base)", "-m", "right"])
+        .success();
+    for name in file_names {
+        std::fs::write(repo_path.join(name), "a\nright\nb\n").unwrap();
+    }
+    std::fs::write(repo_path.join("clean"), "a\nb\nright\n").unwrap();
+
+    // Files with a failing driver are merged by the built-in merge, so only
+    // the conflicts it can't resolve remain
+    let output = test_env.run_jj_in(&repo_path, ["rebase", "-d", "description(left)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commits onto destination
+    Warning: Merge driver missing failed on missing: Failed to run merge driver command jj-nonexistent-driver: No such file or directory (os error 2)
+    Hint: The files were merged by the built-in merge instead.
+    Working copy now at: zsuskuln 71a0f316 (conflict) right
+    Parent commit      : rlvkpnrz bb343a45 left
+    Added 0 files, modified 6 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    failing    2-sided conflict
+    missing    2-sided conflict
+    other      2-sided conflict
+    New conflicts appeared in these commits:
+      zsuskuln 71a0f316 (conflict) right
+    Hint: To resolve the conflicts, start by updating to it:
+      jj new zsuskuln
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--list"]);
+    insta::assert_snapshot!(output, @r"
+    failing    2-sided conflict
+    missing    2-sided conflict
+    other      2-sided conflict
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "clean"]);
+    insta::assert_snapshot!(output, @r"
+    left
+    b
+    right
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "CHANGELOG.md"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    left
+    right
+    b
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "file.lock"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    left
+    b
+    merged by file.lock[EOF]
+    ");
+}
+
+#[test]
+fn test_merge_drivers_invalid_config() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.add_config("merge-drivers.bad = { paths = 'all()', builtin = 'unknown' }");
+    let output = test_env.run_jj_in(&repo_path, ["log"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Config error: Invalid type or value for merge-drivers.bad.builtin
+    Caused by: Unknown built-in merge driver: unknown
+    Hint: Check the config file: $TEST_ENV/config/config0002.toml
+    For help, see https://jj-vcs.github.io/jj/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
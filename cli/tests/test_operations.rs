@@ -195,6 +195,114 @@ fn test_op_log_limit() {
     ");
 }
 
+#[test]
+fn test_op_log_opset() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .run_jj_in(&repo_path, ["describe", "-m", "description 0"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["bookmark", "create", "-r@", "main"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["describe", "-m", "description 1"])
+        .success();
+
+    let run_op_log = |opset: &str| {
+        test_env.run_jj_in(
+            &repo_path,
+            [
+                "op",
+                "log",
+                "-Tdescription",
+                "--ignore-working-copy",
+                "-r",
+                opset,
+            ],
+        )
+    };
+    insta::assert_snapshot!(run_op_log("@---::@-"), @r"
+    ○  create bookmark main pointing to commit 19611c995a342c01f525583e5fcafdd211f6d009
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ○  add workspace 'default'
+    │
+    ~
+    [EOF]
+    ");
+    insta::assert_snapshot!(run_op_log(r#"command("describe")"#), @r"
+    @  describe commit 19611c995a342c01f525583e5fcafdd211f6d009
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    │
+    ~
+    [EOF]
+    ");
+    insta::assert_snapshot!(run_op_log("touched_bookmark(main) | root()"), @r"
+    @  describe commit 19611c995a342c01f525583e5fcafdd211f6d009
+    ○  create bookmark main pointing to commit 19611c995a342c01f525583e5fcafdd211f6d009
+    ○
+    [EOF]
+    ");
+    insta::assert_snapshot!(run_op_log("workspace(default)"), @r"
+    @  describe commit 19611c995a342c01f525583e5fcafdd211f6d009
+    ○  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ○  add workspace 'default'
+    │
+    ~
+    [EOF]
+    ");
+    insta::assert_snapshot!(run_op_log("none()"), @"");
+
+    // Errors
+    insta::assert_snapshot!(run_op_log("foo"), @r#"
+    ------- stderr -------
+    Error: Operation ID "foo" is not a valid hexadecimal prefix
+    [EOF]
+    [exit status: 1]
+    "#);
+    insta::assert_snapshot!(run_op_log("command(x"), @r"
+    ------- stderr -------
+    Error: Failed to parse operation set: Syntax error
+    Caused by:  --> 1:10
+      |
+    1 | command(x
+      |          ^---
+      |
+      = expected `:`, `-`, `+`, `::`, `..`, `|`, `&`, or `~`
+    Hint: See https://jj-vcs.github.io/jj/latest/operation-log/ for operation set syntax.
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(run_op_log("comand(x)"), @r"
+    ------- stderr -------
+    Error: Failed to parse operation set: Function `comand` doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | comand(x)
+      | ^----^
+      |
+      = Function `comand` doesn't exist
+    Hint: Did you mean `command`?
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(run_op_log("time(x)"), @r"
+    ------- stderr -------
+    Error: Failed to parse operation set: Invalid date pattern
+    Caused by:
+    1:  --> 1:6
+      |
+    1 | time(x)
+      |      ^
+      |
+      = Invalid date pattern
+    2: Date pattern must specify 'after' or 'before'
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -392,6 +500,23 @@ fn test_op_log_template() {
     ");
 }
 
+#[test]
+fn test_op_log_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["op", "log", "--no-graph", "-T", r#"json(self) ++ "\n""#],
+    );
+    insta::assert_snapshot!(output, @r#"
+    {"description":"add workspace 'default'","hostname":"host.example.com","id":"eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1","is_snapshot":false,"parents":["00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],"tags":{},"time":{"end":"2001-02-03T04:05:07+07:00","start":"2001-02-03T04:05:07+07:00"},"username":"test-username"}
+    {"description":"","hostname":"","id":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","is_snapshot":false,"parents":[],"tags":{},"time":{"end":"1970-01-01T00:00:00+00:00","start":"1970-01-01T00:00:00+00:00"},"username":""}
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_op_log_builtin_templates() {
     let test_env = TestEnvironment::default();
@@ -719,6 +844,242 @@ fn test_op_abandon_ancestors() {
     ");
 }
 
+#[test]
+fn test_op_abandon_opset() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    for i in 1..=3 {
+        std::fs::write(repo_path.join("file"), format!("{i}\n")).unwrap();
+        test_env
+            .run_jj_in(&repo_path, ["describe", "-m", &format!("commit {i}")])
+            .success();
+    }
+    insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription"]), @r"
+    @  describe commit c45e0af78639bd97c2990cc2631e93af1e79cd77
+    ○  snapshot working copy
+    ○  describe commit 879954c810f292973ce314d00549adf9d05369f2
+    ○  snapshot working copy
+    ○  describe commit 8e8c57d9f0e05c214cefd1dd55ee01b5194bf5ed
+    ○  snapshot working copy
+    ○  add workspace 'default'
+    ○
+    [EOF]
+    ");
+
+    // Can't abandon the root operation.
+    let output = test_env.run_jj_in(&repo_path, ["op", "abandon", "::@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot abandon the root operation
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Abandon non-contiguous operations.
+    let output = test_env.run_jj_in(&repo_path, ["op", "abandon", "snapshot() & ..@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 3 operations and reparented 3 descendant operations.
+    [EOF]
+    ");
+    insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription"]), @r"
+    @  describe commit c45e0af78639bd97c2990cc2631e93af1e79cd77
+    ○  describe commit 879954c810f292973ce314d00549adf9d05369f2
+    ○  describe commit 8e8c57d9f0e05c214cefd1dd55ee01b5194bf5ed
+    ○  add workspace 'default'
+    ○
+    [EOF]
+    ");
+
+    // Nothing to abandon.
+    let output = test_env.run_jj_in(&repo_path, ["op", "abandon", "snapshot() & ..@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_op_export_import_bundle() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file"), "contents\n").unwrap();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "first"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["bookmark", "create", "-r@-", "main"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["describe", "-m", "second"])
+        .success();
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["op", "export", "..@", "--with-objects", "-o", "../bundle"],
+    );
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    ------- stderr -------
+    Exported 5 operations, 5 commits, and 4 other objects to $TEST_ENV/repo/../bundle
+    [EOF]
+    ");
+
+    // Import into a scratch repo
+    test_env
+        .run_jj_in(".", ["git", "init", "scratch"])
+        .success();
+    let scratch_path = test_env.env_root().join("scratch");
+    let output = test_env.run_jj_in(&scratch_path, ["op", "import-bundle", "../bundle"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Imported 5 operations, 5 commits, and 4 other objects
+    Working copy now at: rlvkpnrz d403f79a (empty) second
+    Parent commit      : qpvuntsm b7348de1 main | first
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&scratch_path, ["log", "-Tbuiltin_log_oneline"]);
+    insta::assert_snapshot!(output, @r"
+    @  rlvkpnrz test.user 2001-02-03 08:05:10 d403f79a (empty) second
+    ○  qpvuntsm test.user 2001-02-03 08:05:08 main b7348de1 first
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&scratch_path, ["file", "show", "-rmain", "dir/file"]);
+    insta::assert_snapshot!(output, @r"
+    contents
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&scratch_path, ["op", "log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @    import bundle $TEST_ENV/scratch/../bundle
+    ├─╮
+    ○ │  add workspace 'default'
+    │ ○  describe commit 83204ca7702bd7755dfacea78b21f11139c44806
+    │ ○  create bookmark main pointing to commit b7348de19baeb4fe418f992331bdc6439dff2e76
+    │ ○  commit 41791f7f82c5f86754c5a3a92c60ed184c771f83
+    │ ○  snapshot working copy
+    │ ○  add workspace 'default'
+    ├─╯
+    ○
+    [EOF]
+    ");
+
+    // Without objects, the commits can't be found in the scratch repo
+    test_env
+        .run_jj_in(&repo_path, ["op", "export", "-o", "../bundle-no-objects"])
+        .success();
+    test_env
+        .run_jj_in(".", ["git", "init", "scratch2"])
+        .success();
+    let scratch_path = test_env.env_root().join("scratch2");
+    let output = test_env.run_jj_in(
+        &scratch_path,
+        ["op", "import-bundle", "../bundle-no-objects"],
+    );
+    // The missing commit is reported in arbitrary order
+    insta::assert_snapshot!(output.strip_stderr_last_line().strip_stderr_last_line(), @r"
+    ------- stderr -------
+    Internal error: Failed to load the repo
+    Caused by:
+    1: Failed to index commits at operation 6bbfac67aad656b411db184aed18ad89c0e6ce690caab3d86518177a5046d4e7df67765285af6e690950cd6e2b6a8cec026c8b8ce990fd8f7dcc40367f42958c
+    [EOF]
+    [exit status: 255]
+    ");
+
+    // Invalid bundle
+    std::fs::write(test_env.env_root().join("garbage"), "garbage").unwrap();
+    let output = test_env.run_jj_in(&scratch_path, ["op", "import-bundle", "../garbage"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to decode operation bundle
+    Caused by: failed to decode Protobuf message: invalid wire type value: 7
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_op_verify() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        ui.show-cryptographic-signatures = true
+        signing.backend = "test"
+        signing.sign-operations = true
+        "#,
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "signed"])
+        .success();
+    test_env
+        .run_jj_in(
+            &repo_path,
+            [
+                "new",
+                "-m",
+                "unsigned",
+                "--config=signing.sign-operations=false",
+            ],
+        )
+        .success();
+
+    let output = test_env.run_jj_in(&repo_path, ["op", "log"]);
+    insta::assert_snapshot!(output, @r"
+    @  53fa532a4fde test-username@host.example.com 2001-02-03 04:05:09.000 +07:00 - 2001-02-03 04:05:09.000 +07:00 [-]
+    │  new empty commit
+    │  args: jj new -m unsigned '--config=signing.sign-operations=false'
+    ○  509291e5018c test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00 [✓︎]
+    │  new empty commit
+    │  args: jj new -m signed
+    ○  f7e2c4c8d13f test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00 [✓︎]
+    │  add workspace 'default'
+    ○  000000000000 root()
+    [EOF]
+    ");
+
+    let output = test_env.run_jj_in(&repo_path, ["op", "verify"]);
+    insta::assert_snapshot!(output, @r"
+    unsigned: 53fa532a4fde (2001-02-03 08:05:09) new empty commit
+    [EOF]
+    ------- stderr -------
+    Verified 3 operations: 2 with good signatures, 1 without
+    Error: Some operations don't have a good signature
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["op", "verify", "..@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Verified 2 operations: 2 with good signatures, 0 without
+    [EOF]
+    ");
+
+    // Signed operations are signed again when reparented
+    let output = test_env.run_jj_in(&repo_path, ["op", "abandon", "..@--"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 1 operations and reparented 2 descendant operations.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["op", "verify"]);
+    insta::assert_snapshot!(output, @r"
+    unsigned: bbf3bcb92e74 (2001-02-03 08:05:09) new empty commit
+    [EOF]
+    ------- stderr -------
+    Verified 2 operations: 1 with good signatures, 1 without
+    Error: Some operations don't have a good signature
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_op_abandon_without_updating_working_copy() {
     let test_env = TestEnvironment::default();
@@ -1225,7 +1586,7 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "log"]);
     insta::assert_snapshot!(output, @r"
-    @    7f6887c5ae04 test-username@host.example.com 2001-02-03 04:05:16.000 +07:00 - 2001-02-03 04:05:16.000 +07:00
+    @    1c2df5afb103 test-username@host.example.com 2001-02-03 04:05:16.000 +07:00 - 2001-02-03 04:05:16.000 +07:00
     ├─╮  reconcile divergent operations
     │ │  args: jj log
     ○ │  364d0a677b0c test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00
@@ -1254,7 +1615,7 @@ fn test_op_diff() {
     );
     insta::assert_snapshot!(output, @r"
     From operation: 364d0a677b0c (2001-02-03 08:05:07) check out git remote's default branch
-      To operation: 7f6887c5ae04 (2001-02-03 08:05:16) reconcile divergent operations
+      To operation: 1c2df5afb103 (2001-02-03 08:05:16) reconcile divergent operations
 
     Changed local bookmarks:
     bookmark-1:
@@ -1272,7 +1633,7 @@ fn test_op_diff() {
     );
     insta::assert_snapshot!(output, @r"
     From operation: ee092a3adf88 (2001-02-03 08:05:15) point bookmark bookmark-1 to commit 4ff6253913375c6ebdddd8423c11df3b3f17e331
-      To operation: 7f6887c5ae04 (2001-02-03 08:05:16) reconcile divergent operations
+      To operation: 1c2df5afb103 (2001-02-03 08:05:16) reconcile divergent operations
 
     Changed commits:
     ○  + sqpuoqvx c7b48fea (empty) (no description set)
@@ -1304,8 +1665,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 7f6887c5ae04 (2001-02-03 08:05:16) reconcile divergent operations
-      To operation: 0fda9dbbc995 (2001-02-03 08:05:20) fetch from git remote(s) origin
+    From operation: 1c2df5afb103 (2001-02-03 08:05:16) reconcile divergent operations
+      To operation: 479e4422ac92 (2001-02-03 08:05:20) fetch from git remote(s) origin
 
     Changed commits:
     ○  + kulxwnxm e1a239a5 bookmark-2@origin | Commit 5
@@ -1350,8 +1711,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 0fda9dbbc995 (2001-02-03 08:05:20) fetch from git remote(s) origin
-      To operation: 505a09f5c0f0 (2001-02-03 08:05:22) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
+    From operation: 479e4422ac92 (2001-02-03 08:05:20) fetch from git remote(s) origin
+      To operation: 6b9726229a4c (2001-02-03 08:05:22) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
 
     Changed local bookmarks:
     bookmark-2:
@@ -1369,8 +1730,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 505a09f5c0f0 (2001-02-03 08:05:22) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
-      To operation: e7d3f25689e1 (2001-02-03 08:05:24) track remote bookmark bookmark-2@origin
+    From operation: 6b9726229a4c (2001-02-03 08:05:22) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
+      To operation: a3e79a639bb2 (2001-02-03 08:05:24) track remote bookmark bookmark-2@origin
 
     Changed remote bookmarks:
     bookmark-2@origin:
@@ -1390,8 +1751,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 505a09f5c0f0 (2001-02-03 08:05:22) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
-      To operation: e7d3f25689e1 (2001-02-03 08:05:24) track remote bookmark bookmark-2@origin
+    From operation: 6b9726229a4c (2001-02-03 08:05:22) create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
+      To operation: a3e79a639bb2 (2001-02-03 08:05:24) track remote bookmark bookmark-2@origin
 
     Changed remote bookmarks:
     bookmark-2@origin:
@@ -1411,8 +1772,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: e7d3f25689e1 (2001-02-03 08:05:24) track remote bookmark bookmark-2@origin
-      To operation: b94b5ef70c8a (2001-02-03 08:05:28) new empty commit
+    From operation: a3e79a639bb2 (2001-02-03 08:05:24) track remote bookmark bookmark-2@origin
+      To operation: 6840c0ed9ea1 (2001-02-03 08:05:28) new empty commit
 
     Changed commits:
     ○  + wvuyspvk fefb1e17 (empty) new commit
@@ -1429,8 +1790,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: b94b5ef70c8a (2001-02-03 08:05:28) new empty commit
-      To operation: 26918495eee5 (2001-02-03 08:05:30) point bookmark bookmark-1 to commit fefb1e17c85328767a596c6dc3d9d604c024a02c
+    From operation: 6840c0ed9ea1 (2001-02-03 08:05:28) new empty commit
+      To operation: 80202c1379cd (2001-02-03 08:05:30) point bookmark bookmark-1 to commit fefb1e17c85328767a596c6dc3d9d604c024a02c
 
     Changed local bookmarks:
     bookmark-1:
@@ -1449,8 +1810,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 26918495eee5 (2001-02-03 08:05:30) point bookmark bookmark-1 to commit fefb1e17c85328767a596c6dc3d9d604c024a02c
-      To operation: 9969a6088fd3 (2001-02-03 08:05:32) delete bookmark bookmark-2
+    From operation: 80202c1379cd (2001-02-03 08:05:30) point bookmark bookmark-1 to commit fefb1e17c85328767a596c6dc3d9d604c024a02c
+      To operation: 7e979953aedd (2001-02-03 08:05:32) delete bookmark bookmark-2
 
     Changed local bookmarks:
     bookmark-2:
@@ -1473,8 +1834,8 @@ fn test_op_diff() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "diff"]);
     insta::assert_snapshot!(output, @r"
-    From operation: 9969a6088fd3 (2001-02-03 08:05:32) delete bookmark bookmark-2
-      To operation: ff305cfe0aca (2001-02-03 08:05:34) push all tracked bookmarks to git remote origin
+    From operation: 7e979953aedd (2001-02-03 08:05:32) delete bookmark bookmark-2
+      To operation: 1a9a1aeae9cf (2001-02-03 08:05:34) push all tracked bookmarks to git remote origin
 
     Changed commits:
     ○  + oupztwtk fe3ad088 (empty) (no description set)
@@ -1589,6 +1950,100 @@ fn test_op_diff_patch() {
     ");
 }
 
+#[test]
+fn test_op_diff_patch_with_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+    test_env.run_jj_in(&repo_path, ["new"]).success();
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "d\n").unwrap();
+    test_env.run_jj_in(&repo_path, ["squash"]).success();
+
+    // Only the diffs of the matching files are shown
+    let output = test_env.run_jj_in(&repo_path, ["op", "diff", "-p", "--git", "file1"]);
+    insta::assert_snapshot!(output, @r"
+    From operation: d2797769cf69 (2001-02-03 08:05:09) snapshot working copy
+      To operation: 8f18e85612e9 (2001-02-03 08:05:09) squash commits into 933277788aad3742ebd0800497408da537a0501b
+
+    Changed commits:
+    ○  + kkmpptxz 8bbbf3fc (empty) (no description set)
+    │ ○  - rlvkpnrz hidden 102c3cf4 (no description set)
+    ├─╯  diff --git a/file1 b/file1
+    │    index 7898192261..f2ad6c76f0 100644
+    │    --- a/file1
+    │    +++ b/file1
+    │    @@ -1,1 +1,1 @@
+    │    -a
+    │    +c
+    ○  + qpvuntsm 5d941d74 (no description set)
+       - qpvuntsm hidden 93327778 (no description set)
+       diff --git a/file1 b/file1
+       index 7898192261..f2ad6c76f0 100644
+       --- a/file1
+       +++ b/file1
+       @@ -1,1 +1,1 @@
+       -a
+       +c
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["op", "diff", "--no-graph", "--stat", "glob:file[2]"],
+    );
+    insta::assert_snapshot!(output, @r"
+    From operation: d2797769cf69 (2001-02-03 08:05:09) snapshot working copy
+      To operation: 8f18e85612e9 (2001-02-03 08:05:09) squash commits into 933277788aad3742ebd0800497408da537a0501b
+
+    Changed commits:
+    + kkmpptxz 8bbbf3fc (empty) (no description set)
+    0 files changed, 0 insertions(+), 0 deletions(-)
+    - rlvkpnrz hidden 102c3cf4 (no description set)
+    file2 | 2 +-
+    1 file changed, 1 insertion(+), 1 deletion(-)
+    + qpvuntsm 5d941d74 (no description set)
+    - qpvuntsm hidden 93327778 (no description set)
+    file2 | 2 +-
+    1 file changed, 1 insertion(+), 1 deletion(-)
+    [EOF]
+    ");
+
+    // Commit summaries are still shown if no files match
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["op", "show", "-p", "--git", "@", "--", "nonexistent"],
+    );
+    insta::assert_snapshot!(output, @r"
+    8f18e85612e9 test-username@host.example.com 2001-02-03 04:05:09.000 +07:00 - 2001-02-03 04:05:09.000 +07:00
+    squash commits into 933277788aad3742ebd0800497408da537a0501b
+    args: jj squash
+
+    Changed commits:
+    ○  + kkmpptxz 8bbbf3fc (empty) (no description set)
+    │ ○  - rlvkpnrz hidden 102c3cf4 (no description set)
+    ├─╯
+    ○  + qpvuntsm 5d941d74 (no description set)
+       - qpvuntsm hidden 93327778 (no description set)
+    [EOF]
+    ");
+
+    // The paths must be separated from the operation
+    let output = test_env.run_jj_in(&repo_path, ["op", "show", "@", "file1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: unexpected argument 'file1' found
+
+    Usage: jj operation show [OPTIONS] [OPERATION] [-- <FILESETS>...]
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_op_diff_sibling() {
     let test_env = TestEnvironment::default();
@@ -1976,9 +2431,15 @@ fn test_op_show() {
     // Showing a merge operation is empty.
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    774687cc6e4e test-username@host.example.com 2001-02-03 04:05:14.000 +07:00 - 2001-02-03 04:05:14.000 +07:00
+    585e7032ad23 test-username@host.example.com 2001-02-03 04:05:14.000 +07:00 - 2001-02-03 04:05:14.000 +07:00
     reconcile divergent operations
     args: jj log
+
+    Conflicts introduced by merging concurrent operations:
+    bookmark bookmark-1:
+    + pukowqtp 0cb7e07e bookmark-1?? bookmark-1@origin | Commit 1 (from operation 364d0a677b0c)
+    + rnnslrkn 4ff62539 bookmark-1?? bookmark-2@origin | Commit 2 (from operation 4fe4acac8c5f)
+    - (absent)
     [EOF]
     ");
 
@@ -1995,7 +2456,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    fed3d4f59819 test-username@host.example.com 2001-02-03 04:05:16.000 +07:00 - 2001-02-03 04:05:16.000 +07:00
+    54f3ce241fee test-username@host.example.com 2001-02-03 04:05:16.000 +07:00 - 2001-02-03 04:05:16.000 +07:00
     fetch from git remote(s) origin
     args: jj git fetch
 
@@ -2042,7 +2503,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    77ed6ae71f3f test-username@host.example.com 2001-02-03 04:05:18.000 +07:00 - 2001-02-03 04:05:18.000 +07:00
+    7af6a549f109 test-username@host.example.com 2001-02-03 04:05:18.000 +07:00 - 2001-02-03 04:05:18.000 +07:00
     create bookmark bookmark-2 pointing to commit e1a239a57eb15cefc5910198befbbbe2b43c47af
     args: jj bookmark create bookmark-2 -r bookmark-2@origin
 
@@ -2062,7 +2523,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    f4e770fd7370 test-username@host.example.com 2001-02-03 04:05:20.000 +07:00 - 2001-02-03 04:05:20.000 +07:00
+    da8b16fd7115 test-username@host.example.com 2001-02-03 04:05:20.000 +07:00 - 2001-02-03 04:05:20.000 +07:00
     track remote bookmark bookmark-2@origin
     args: jj bookmark track bookmark-2@origin
 
@@ -2083,7 +2544,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    f4e770fd7370 test-username@host.example.com 2001-02-03 04:05:20.000 +07:00 - 2001-02-03 04:05:20.000 +07:00
+    da8b16fd7115 test-username@host.example.com 2001-02-03 04:05:20.000 +07:00 - 2001-02-03 04:05:20.000 +07:00
     track remote bookmark bookmark-2@origin
     args: jj bookmark track bookmark-2@origin
 
@@ -2105,7 +2566,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    e54bb5012457 test-username@host.example.com 2001-02-03 04:05:24.000 +07:00 - 2001-02-03 04:05:24.000 +07:00
+    b31a7235bf82 test-username@host.example.com 2001-02-03 04:05:24.000 +07:00 - 2001-02-03 04:05:24.000 +07:00
     new empty commit
     args: jj new bookmark-1@origin -m 'new commit'
 
@@ -2124,7 +2585,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    55527022d8b9 test-username@host.example.com 2001-02-03 04:05:26.000 +07:00 - 2001-02-03 04:05:26.000 +07:00
+    d2e3f6a8d098 test-username@host.example.com 2001-02-03 04:05:26.000 +07:00 - 2001-02-03 04:05:26.000 +07:00
     point bookmark bookmark-1 to commit 560df364f0a09fe29f6a4fca8bd07c4464c7feee
     args: jj bookmark set bookmark-1 -r @
 
@@ -2145,7 +2606,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    a6838035030b test-username@host.example.com 2001-02-03 04:05:28.000 +07:00 - 2001-02-03 04:05:28.000 +07:00
+    fc6515d9f260 test-username@host.example.com 2001-02-03 04:05:28.000 +07:00 - 2001-02-03 04:05:28.000 +07:00
     delete bookmark bookmark-2
     args: jj bookmark delete bookmark-2
 
@@ -2170,7 +2631,7 @@ fn test_op_show() {
     ");
     let output = test_env.run_jj_in(&repo_path, ["op", "show"]);
     insta::assert_snapshot!(output, @r"
-    2a7821341a99 test-username@host.example.com 2001-02-03 04:05:30.000 +07:00 - 2001-02-03 04:05:30.000 +07:00
+    89f0fcad3b74 test-username@host.example.com 2001-02-03 04:05:30.000 +07:00 - 2001-02-03 04:05:30.000 +07:00
     push all tracked bookmarks to git remote origin
     args: jj git push --tracked
 
@@ -16,6 +16,7 @@ use std::path::Path;
 
 use indoc::indoc;
 
+use crate::common::force_interactive;
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 
@@ -672,7 +673,7 @@ fn test_baseless_conflict_input_files() {
 
 #[test]
 fn test_too_many_parents() {
-    let test_env = TestEnvironment::default();
+    let mut test_env = TestEnvironment::default();
     test_env.run_jj_in(".", ["git", "init", "repo"]).success();
     let repo_path = test_env.env_root().join("repo");
 
@@ -692,15 +693,43 @@ fn test_too_many_parents() {
     [EOF]
     ");
 
+    // External merge tools only support 2-sided conflicts
+    test_env.set_up_fake_editor();
     let output = test_env.run_jj_in(&repo_path, ["resolve"]);
     insta::assert_snapshot!(output, @r#"
     ------- stderr -------
-    Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
     Error: Failed to resolve conflicts
-    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported.
+    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported by this merge tool.
+    Hint: Use `:builtin` to pick lines from any side, or `:ours` to pick the first side.
     [EOF]
     [exit status: 1]
     "#);
+
+    // So does `:theirs`, but `:ours` picks the first side
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--tool=:theirs"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to resolve conflicts
+    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported by this merge tool.
+    Hint: Use `:builtin` to pick lines from any side, or `:ours` to pick the first side.
+    [EOF]
+    [exit status: 1]
+    "#);
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--tool=:ours"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy now at: znkkpsqq ebb2addd conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Parent commit      : vruxwmqv a425542c c | c
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "file"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    [EOF]
+    ");
 }
 
 #[test]
@@ -902,23 +931,186 @@ fn test_file_vs_dir() {
     ");
 
     insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["resolve", "--list"]), @r"
-    file    2-sided conflict including a directory
+    file    2-sided file/directory conflict including a directory
     [EOF]
     ");
     let output = test_env.run_jj_in(&repo_path, ["resolve"]);
-    insta::assert_snapshot!(output, @r#"
+    insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
-    Error: Failed to resolve conflicts
-    Caused by: Only conflicts that involve normal files (not symlinks, not executable, etc.) are supported. Conflict summary for "file":
-    Conflict:
-      Removing file with id df967b96a579e45a18b8251732d16804b2e56a55
-      Adding file with id 78981922613b2afb6025042ff6bd878ac1994e85
-      Adding tree with id 133bb38fc4e4bf6b551f1f04db7e48f04cac2877
-
+    Resolving conflicts in: file
+    The file/directory conflict has these sides:
+    1: regular file
+    2: directory
+    s: skip this conflict
+    Error: Cannot prompt for input since the output is not connected to a terminal
     [EOF]
     [exit status: 1]
-    "#);
+    ");
+
+    // Pick the directory side interactively
+    let output = test_env.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .current_dir(&repo_path)
+            .args(["resolve", "--tool=:builtin"])
+            .write_stdin("x\n2\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Resolving conflicts in: file
+    The file/directory conflict has these sides:
+    1: regular file
+    2: directory
+    s: skip this conflict
+    enter the index of the side to keep: unrecognized response
+    enter the index of the side to keep: Working copy now at: vruxwmqv 0a1dc4b2 conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx 8602fc65 b | b
+    Added 1 files, modified 0 files, removed 1 files
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    file/placeholder
+    [EOF]
+    ");
+
+    // Pick the file side without prompting
+    test_env.run_jj_in(&repo_path, ["undo"]).success();
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--tool=:ours"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy now at: vruxwmqv ab528e0f conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx 8602fc65 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "file"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    [EOF]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_symlink_and_executable_bit_conflicts() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file", "a\n"), ("exec", "same\n")],
+    );
+    std::os::unix::fs::symlink("target", repo_path.join("link")).unwrap();
+    test_env
+        .run_jj_in(&repo_path, ["file", "chmod", "x", "exec"])
+        .success();
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file", "b\n"), ("exec", "same\n"), ("link", "file\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    let output = test_env.run_jj_in(&repo_path, ["status"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy : yostqsxw 2e25765f conflict | (conflict) (empty) conflict
+    Parent commit: zsuskuln 09d3f705 a | a
+    Parent commit: yqosqzyt 59408069 b | b
+    Warning: There are unresolved conflicts at these paths:
+    exec    2-sided executable bit conflict including an executable
+    file    2-sided conflict
+    link    2-sided symlink conflict including a symlink
+    [EOF]
+    ");
+
+    // The side-picking tools work for any kind of conflict
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--tool=:theirs", "exec"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy now at: yostqsxw 646a76c4 conflict | (conflict) conflict
+    Parent commit      : zsuskuln 09d3f705 a | a
+    Parent commit      : yqosqzyt 59408069 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    link    2-sided symlink conflict including a symlink
+    New conflicts appeared in these commits:
+      yostqsxw 646a76c4 conflict | (conflict) conflict
+    Hint: To resolve the conflicts, start by updating to it:
+      jj new yostqsxw
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    let template = r#"separate(" ", path, "executable=" ++ executable) ++ "\n""#;
+    let output = test_env.run_jj_in(&repo_path, ["file", "list", "-T", template, "exec"]);
+    insta::assert_snapshot!(output, @r"
+    exec executable=false
+    [EOF]
+    ");
+
+    // Symlink conflicts are resolved by picking a side, and content conflicts
+    // are left to the merge tool
+    let output = test_env.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .current_dir(&repo_path)
+            .args(["resolve", "--tool=:builtin", "link"])
+            .write_stdin("1\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Resolving conflicts in: link
+    The symlink conflict has these sides:
+    1: symlink to target
+    2: regular file
+    s: skip this conflict
+    enter the index of the side to keep: Working copy now at: yostqsxw 43bbf00c conflict | (conflict) conflict
+    Parent commit      : zsuskuln 09d3f705 a | a
+    Parent commit      : yqosqzyt 59408069 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    [EOF]
+    ");
+    assert_eq!(
+        std::fs::read_link(repo_path.join("link")).unwrap(),
+        Path::new("target")
+    );
+
+    // Skipped conflicts are left as is
+    test_env.run_jj_in(&repo_path, ["undo"]).success();
+    let output = test_env.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .current_dir(&repo_path)
+            .args(["resolve", "--tool=:builtin", "link"])
+            .write_stdin("s\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Resolving conflicts in: link
+    The symlink conflict has these sides:
+    1: symlink to target
+    2: regular file
+    s: skip this conflict
+    enter the index of the side to keep: Working copy now at: yostqsxw daf185bf conflict | (conflict) conflict
+    Parent commit      : zsuskuln 09d3f705 a | a
+    Parent commit      : yqosqzyt 59408069 b | b
+    Warning: There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    link    2-sided symlink conflict including a symlink
+    [EOF]
+    ");
 }
 
 #[test]
@@ -957,30 +1149,29 @@ fn test_description_with_dir_and_deletion() {
     ");
 
     insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["resolve", "--list"]), @r"
-    file    3-sided conflict including 1 deletion and a directory
+    file    3-sided file/directory conflict including 1 deletion and a directory
     [EOF]
     ");
     // Test warning color. The deletion is fine, so it's not highlighted
     insta::assert_snapshot!(
         test_env.run_jj_in(&repo_path, ["resolve", "--list", "--color=always"]), @r"
-    file    [38;5;1m3-sided[38;5;3m conflict including 1 deletion and [38;5;1ma directory[39m
+    file    [38;5;1m3-sided[38;5;3m file/directory conflict including 1 deletion and [38;5;1ma directory[39m
     [EOF]
     ");
     let output = test_env.run_jj_in(&repo_path, ["resolve"]);
-    insta::assert_snapshot!(output, @r#"
+    insta::assert_snapshot!(output, @r"
     ------- stderr -------
     Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
-    Error: Failed to resolve conflicts
-    Caused by: Only conflicts that involve normal files (not symlinks, not executable, etc.) are supported. Conflict summary for "file":
-    Conflict:
-      Removing file with id df967b96a579e45a18b8251732d16804b2e56a55
-      Removing file with id df967b96a579e45a18b8251732d16804b2e56a55
-      Adding file with id 61780798228d17af2d34fce4cfbdf35556832472
-      Adding tree with id 133bb38fc4e4bf6b551f1f04db7e48f04cac2877
-
+    Resolving conflicts in: file
+    The file/directory conflict has these sides:
+    1: regular file
+    2: directory
+    3: deleted
+    s: skip this conflict
+    Error: Cannot prompt for input since the output is not connected to a terminal
     [EOF]
     [exit status: 1]
-    "#);
+    ");
 }
 
 #[test]
@@ -1788,3 +1979,94 @@ fn test_multiple_conflicts_with_error() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_resolve_with_recorded_resolutions() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("rerere.enabled = true");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    test_env
+        .run_jj_in(&repo_path, ["rebase", "-r", "b", "-d", "a"])
+        .success();
+    insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["resolve", "--list"]), @r"
+    file    2-sided conflict
+    [EOF]
+    ");
+
+    // Resolving the conflict in the working copy records the resolution
+    std::fs::write(repo_path.join("file"), "resolved\n").unwrap();
+    test_env.run_jj_in(&repo_path, ["status"]).success();
+
+    // Merges computed by other commands don't reuse the recorded resolution
+    create_commit(&test_env, &repo_path, "c", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "merge", &["a", "c"], &[]);
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--list"]);
+    insta::assert_snapshot!(output, @r"
+    file    2-sided conflict
+    [EOF]
+    ");
+    test_env.run_jj_in(&repo_path, ["diff"]).success();
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--list-reused"]);
+    insta::assert_snapshot!(output, @"");
+
+    // The same conflict is resolved by the recorded resolution when rebasing
+    let output = test_env.run_jj_in(&repo_path, ["rebase", "-r", "c", "-d", "a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commits onto destination
+    Rebased 1 descendant commits
+    Resolved conflicts using recorded resolutions in these files:
+      file
+    Working copy now at: wqnwkozp e1e86d8e merge | (empty) merge
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : rlvkpnrz 4b3c028c base | base
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "-r", "c", "file"]);
+    insta::assert_snapshot!(output, @r"
+    resolved
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--list-reused"]);
+    insta::assert_snapshot!(output, @r"
+    file 80d70f26c6b1
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--list-reused", "other"]);
+    insta::assert_snapshot!(output, @"");
+
+    // Forgotten resolutions aren't reused
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--forget", "file"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Forgot 1 recorded resolutions
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["resolve", "--list-reused"]);
+    insta::assert_snapshot!(output, @"");
+    create_commit(&test_env, &repo_path, "d", &["base"], &[("file", "b\n")]);
+    let output = test_env.run_jj_in(&repo_path, ["rebase", "-r", "d", "-d", "a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Rebased 1 commits onto destination
+    Working copy now at: pzsxstzt 72e05de0 d | (conflict) d
+    Parent commit      : zsuskuln aa493daf a | a
+    Added 0 files, modified 1 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    New conflicts appeared in these commits:
+      pzsxstzt 72e05de0 d | (conflict) d
+    Hint: To resolve the conflicts, start by updating to it:
+      jj new pzsxstzt
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+}
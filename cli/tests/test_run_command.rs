@@ -0,0 +1,234 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The commands below are run by `sh`.
+#![cfg(unix)]
+
+use std::path::Path;
+
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+
+fn create_commit(test_env: &TestEnvironment, repo_path: &Path, name: &str, parents: &[&str]) {
+    if parents.is_empty() {
+        test_env
+            .run_jj_in(repo_path, ["new", "root()", "-m", name])
+            .success();
+    } else {
+        let mut args = vec!["new", "-m", name];
+        args.extend(parents);
+        test_env.run_jj_in(repo_path, &args).success();
+    }
+    std::fs::write(repo_path.join(name), format!("{name}\n")).unwrap();
+    test_env
+        .run_jj_in(repo_path, ["bookmark", "create", "-r@", name])
+        .success();
+}
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> CommandOutput {
+    let template =
+        r#"separate(" ", description.first_line(), bookmarks, diff.files().map(|f| f.path()))"#;
+    test_env.run_jj_in(repo_path, ["log", "-T", template])
+}
+
+#[test]
+fn test_run_on_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+
+    // Output is labeled with the change id, and the command runs in a checkout
+    // of each revision
+    let output = test_env.run_jj_in(&repo_path, ["run", "-j1", "-r", "a::c", "ls"]);
+    insta::assert_snapshot!(output, @r"
+    rlvkpnrz: a
+    zsuskuln: a
+    zsuskuln: b
+    royxmykx: a
+    royxmykx: b
+    royxmykx: c
+    [EOF]
+    ------- stderr -------
+    Ran command on 3 revisions: 3 passed, 0 failed
+    [EOF]
+    ");
+
+    // The working copy is left untouched
+    let output = test_env.run_jj_in(&repo_path, ["status"]);
+    insta::assert_snapshot!(output, @r"
+    Working copy changes:
+    A c
+    Working copy : royxmykx 7e4fbf4f c | c
+    Parent commit: zsuskuln 1394f625 b | b
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_run_failure() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+
+    // Revisions where the command fails are listed, and the exit status is
+    // non-zero
+    let output = test_env.run_jj_in(&repo_path, ["run", "-j1", "-r", "a::b", "test ! -f b"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Ran command on 2 revisions: 1 passed, 1 failed
+      exited with 1: zsuskuln 1394f625 b | b
+    Error: Command failed on 1 of 2 revisions
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_run_parallel() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &["c"]);
+
+    let output = test_env.run_jj_in(&repo_path, ["run", "-j4", "-r", "a::d", "cat a"]);
+    let output = output.normalize_stdout_with(|stdout| {
+        let mut lines: Vec<_> = stdout.lines().collect();
+        lines.sort_unstable();
+        lines.join("\n") + "\n"
+    });
+    insta::assert_snapshot!(output, @r"
+    rlvkpnrz: a
+    royxmykx: a
+    vruxwmqv: a
+    zsuskuln: a
+    [EOF]
+    ------- stderr -------
+    Ran command on 4 revisions: 4 passed, 0 failed
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_run_reuses_working_copies() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", &[]);
+    std::fs::write(repo_path.join(".gitignore"), "out\n").unwrap();
+
+    // Ignored files are kept between runs
+    test_env
+        .run_jj_in(&repo_path, ["run", "-r", "a", "echo built > out"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["run", "-r", "a", "cat out"]);
+    insta::assert_snapshot!(output, @r"
+    rlvkpnrz: built
+    [EOF]
+    ------- stderr -------
+    Ran command on 1 revisions: 1 passed, 0 failed
+    [EOF]
+    ");
+
+    // Unless --clean is passed
+    let output = test_env.run_jj_in(&repo_path, ["run", "--clean", "-r", "a", "ls"]);
+    insta::assert_snapshot!(output, @r"
+    rlvkpnrz: a
+    [EOF]
+    ------- stderr -------
+    Ran command on 1 revisions: 1 passed, 0 failed
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_run_rewrite() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+
+    // Without --rewrite, changes made by the command are discarded
+    let output = test_env.run_jj_in(&repo_path, ["run", "-r", "a", "echo changed > a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Ran command on 1 revisions: 1 passed, 0 failed
+    [EOF]
+    ");
+    let output = get_log_output(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @r"
+    @  c c c
+    ○  b b b
+    ○  a a a
+    ◆
+    [EOF]
+    ");
+
+    // With --rewrite, the changes are written back and descendants are rebased
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "run",
+            "--rewrite",
+            "-j1",
+            "-r",
+            "a|b",
+            "for f in *; do echo fixed >> $f; done",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Ran command on 2 revisions: 2 passed, 0 failed
+    Rewrote 2 revisions changed by the command and rebased 1 descendant revisions
+    Working copy now at: royxmykx e92b8081 c | c
+    Parent commit      : zsuskuln 54c8e2ca b | b
+    Added 0 files, modified 2 files, removed 0 files
+    [EOF]
+    ");
+    let output = get_log_output(&test_env, &repo_path);
+    insta::assert_snapshot!(output, @r"
+    @  c c c
+    ○  b b b
+    ○  a a a
+    ◆
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "-r", "c", "a", "b", "c"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    fixed
+    b
+    fixed
+    c
+    [EOF]
+    ");
+
+    // Immutable revisions can't be rewritten
+    let output = test_env.run_jj_in(&repo_path, ["run", "--rewrite", "-r", "root()", "true"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The root commit 000000000000 is immutable
+    [EOF]
+    [exit status: 1]
+    ");
+}
@@ -477,3 +477,80 @@ backend = "test"
     [EOF]
     ");
 }
+
+#[test]
+fn test_signed_revsets() {
+    let test_env = TestEnvironment::default();
+
+    test_env.add_config(
+        r#"
+[signing]
+behavior = "keep"
+backend = "test"
+"#,
+    );
+
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "one"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "two"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "three"])
+        .success();
+    test_env
+        .run_jj_in(
+            &repo_path,
+            ["sign", "-r", "description(one)", "--key", "alice"],
+        )
+        .success();
+    test_env
+        .run_jj_in(
+            &repo_path,
+            ["sign", "-r", "description(two)", "--key", "bob"],
+        )
+        .success();
+
+    let template = r#"description.first_line() ++ "\n""#;
+    let output = test_env.run_jj_in(&repo_path, ["log", "-r", "signed()", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    ○  two
+    ○  one
+    │
+    ~
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["log", "-r", "..@- & ~signed(good)", "-T", template],
+    );
+    insta::assert_snapshot!(output, @r"
+    ○  three
+    │
+    ~
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["log", "-r", "signed_by(bob)", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    ○  two
+    │
+    ~
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["log", "-r", "signed(fine)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse revset: Expected signature status: good, bad, unknown, or invalid
+    Caused by:  --> 1:8
+      |
+    1 | signed(fine)
+      |        ^--^
+      |
+      = Expected signature status: good, bad, unknown, or invalid
+    [EOF]
+    [exit status: 1]
+    ");
+}
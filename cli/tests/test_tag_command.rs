@@ -143,3 +143,157 @@ fn test_tag_list() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_tag_create_set_delete() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git::open(&repo_path);
+
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-mcommit1"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-mcommit2"])
+        .success();
+
+    let output = test_env.run_jj_in(&repo_path, ["tag", "create", "-r@--", "v1", "v1.0"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Created 2 tags pointing to qpvuntsm caf975d0 (empty) commit1
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["tag", "create", "-r@-", "v1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Tag already exists: v1
+    Hint: Use `jj tag set --allow-move` to update it.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Tags are exported to the colocated Git repo when the command finishes
+    let git_ref = git_repo.find_reference("refs/tags/v1").unwrap();
+    insta::assert_snapshot!(git_ref.target().id(), @"caf975d0989a4e84d6d2fd1047ea03663244969c");
+
+    let output = test_env.run_jj_in(&repo_path, ["tag", "set", "-r@-", "v1", "v2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Refusing to move tag: v1
+    Hint: Use --allow-move to allow it.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["tag", "set", "-r@-", "--allow-move", "v1", "v2"],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Created 1 tags pointing to rlvkpnrz d0a19ea4 (empty) commit2
+    Moved 1 tags to rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+    insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["tag", "list"]), @r"
+    v1: rlvkpnrz d0a19ea4 (empty) commit2
+    v1.0: qpvuntsm caf975d0 (empty) commit1
+    v2: rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+
+    // Annotated tags point to tag objects in the Git repo
+    let output = test_env.run_jj_in(&repo_path, ["tag", "create", "-r@-", "-mRelease", "v3"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Created 1 tags pointing to rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+    let tag = git_repo
+        .find_reference("refs/tags/v3")
+        .unwrap()
+        .peel_to_kind(gix::object::Kind::Tag)
+        .unwrap()
+        .into_tag();
+    let tag = tag.decode().unwrap();
+    assert_eq!(tag.name, "v3");
+    assert_eq!(tag.message, "Release\n");
+    assert!(tag.pgp_signature.is_none());
+
+    let output = test_env.run_jj_in(&repo_path, ["tag", "delete", "v1.0", "glob:v[23]"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Deleted 3 tags.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["tag", "delete", "v4"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such tag: v4
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["tag", "list"]), @r"
+    v1: rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+    assert!(git_repo
+        .try_find_reference("refs/tags/v3")
+        .unwrap()
+        .is_none());
+
+    // Tags deleted by jj aren't resurrected by import
+    test_env.run_jj_in(&repo_path, ["git", "import"]).success();
+    insta::assert_snapshot!(test_env.run_jj_in(&repo_path, ["tag", "list"]), @r"
+    v1: rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_tag_export_non_colocated() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = {
+        let mut git_repo_path = repo_path.clone();
+        git_repo_path.extend([".jj", "repo", "store", "git"]);
+        git::open(git_repo_path)
+    };
+
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-mcommit1"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["tag", "create", "-r@-", "-mRelease", "v1"])
+        .success();
+
+    // Like bookmarks, tags aren't written to the Git repo until exported
+    assert!(git_repo
+        .try_find_reference("refs/tags/v1")
+        .unwrap()
+        .is_none());
+    test_env.run_jj_in(&repo_path, ["git", "export"]).success();
+    let tag = git_repo
+        .find_reference("refs/tags/v1")
+        .unwrap()
+        .peel_to_kind(gix::object::Kind::Tag)
+        .unwrap()
+        .into_tag();
+    assert_eq!(tag.decode().unwrap().message, "Release\n");
+
+    test_env
+        .run_jj_in(&repo_path, ["tag", "delete", "v1"])
+        .success();
+    assert!(git_repo
+        .try_find_reference("refs/tags/v1")
+        .unwrap()
+        .is_some());
+    test_env.run_jj_in(&repo_path, ["git", "export"]).success();
+    assert!(git_repo
+        .try_find_reference("refs/tags/v1")
+        .unwrap()
+        .is_none());
+}
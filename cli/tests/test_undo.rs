@@ -547,6 +547,102 @@ fn test_shows_no_warning_when_undoing_a_specific_undo_change() {
     ");
 }
 
+#[test]
+fn test_op_revert() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env
+        .run_jj_in(&repo_path, ["new", "root()", "-m", "A"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["bookmark", "create", "-r@", "a1", "a2"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["new", "root()", "-m", "B"])
+        .success();
+    test_env
+        .run_jj_in(
+            &repo_path,
+            ["bookmark", "set", "--allow-backwards", "-r@", "a1", "a2"],
+        )
+        .success();
+    let bad_op_id = test_env.current_operation_id(&repo_path);
+    test_env
+        .run_jj_in(&repo_path, ["bookmark", "create", "-r@", "b"])
+        .success();
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    a1: zsuskuln eccedddf (empty) B
+    a2: zsuskuln eccedddf (empty) B
+    b: zsuskuln eccedddf (empty) B
+    [EOF]
+    ");
+
+    // Revert the move of "a1" only. The later changes are preserved.
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["op", "revert", &bad_op_id, "--what=bookmarks:a1"],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Reverted operation: 914e8b3365f5 (2001-02-03 08:05:11) point bookmark a1, a2 to commit eccedddfa5152d99fc8ddd1081b375387a8a382a
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    a1: rlvkpnrz a7e4cec4 (empty) A
+    a2: zsuskuln eccedddf (empty) B
+    b: zsuskuln eccedddf (empty) B
+    [EOF]
+    ");
+
+    // Reverting the move of "a2" conflicts with the later change
+    test_env
+        .run_jj_in(
+            &repo_path,
+            ["bookmark", "set", "-r@-", "a2", "--allow-backwards"],
+        )
+        .success();
+    let output = test_env.run_jj_in(
+        &repo_path,
+        ["op", "revert", &bad_op_id, "--what=bookmarks:glob:a*"],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Reverted operation: 914e8b3365f5 (2001-02-03 08:05:11) point bookmark a1, a2 to commit eccedddfa5152d99fc8ddd1081b375387a8a382a
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    a1: rlvkpnrz a7e4cec4 (empty) A
+    a2 (conflicted):
+      - zsuskuln eccedddf (empty) B
+      + zzzzzzzz 00000000 (empty) (no description set)
+      + rlvkpnrz a7e4cec4 (empty) A
+    b: zsuskuln eccedddf (empty) B
+    [EOF]
+    ");
+
+    // Invalid portion
+    let output = test_env.run_jj_in(&repo_path, ["op", "revert", "--what=foo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value 'foo' for '--what <WHAT>': expected `repo`, `remote-tracking`, `bookmarks:<pattern>`, or `working-copies`
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Root operation can't be reverted
+    let output = test_env.run_jj_in(&repo_path, ["op", "revert", "000000000000"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot revert repo initialization
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[must_use]
 fn get_bookmark_output(test_env: &TestEnvironment, repo_path: &Path) -> CommandOutput {
     // --quiet to suppress deleted bookmarks hint
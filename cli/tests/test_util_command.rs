@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use indoc::indoc;
 use insta::assert_snapshot;
 
 use crate::common::TestEnvironment;
@@ -112,6 +113,258 @@ fn test_gc_operation_log() {
     "#);
 }
 
+#[test]
+fn test_gc_pointer_store() {
+    let test_env = TestEnvironment::default();
+    // Use the local backend because GitBackend::gc() depends on the git CLI.
+    test_env
+        .run_jj_in(".", ["init", "repo", "--config=ui.allow-init-native=true"])
+        .success();
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(indoc! {r#"
+        [snapshot.policies.media]
+        paths = "glob:*.bin"
+        action = "pointer"
+    "#});
+    let pointers_dir = repo_path.join(".jj").join("repo").join("pointers");
+    let count_pointers = || std::fs::read_dir(&pointers_dir).unwrap().count();
+
+    // Store a pointer in a commit, and another one in an abandoned commit
+
+    std::fs::write(repo_path.join("kept.bin"), "kept").unwrap();
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "kept"])
+        .success();
+    std::fs::write(repo_path.join("dropped.bin"), "dropped").unwrap();
+    test_env.run_jj_in(&repo_path, ["abandon"]).success();
+    assert_eq!(count_pointers(), 2);
+
+    // The abandoned content is still referenced by the operation log
+    test_env
+        .run_jj_in(&repo_path, ["util", "gc", "--expire=now"])
+        .success();
+    assert_eq!(count_pointers(), 2);
+
+    test_env
+        .run_jj_in(&repo_path, ["operation", "abandon", "..@-"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["debug", "reindex"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["util", "gc", "--expire=now"])
+        .success();
+    assert_eq!(count_pointers(), 1);
+}
+
+#[test]
+fn test_gc_operation_log_retention() {
+    let test_env = TestEnvironment::default();
+    // Use the local backend because GitBackend::gc() depends on the git CLI.
+    test_env
+        .run_jj_in(".", ["init", "repo", "--config=ui.allow-init-native=true"])
+        .success();
+    let repo_path = test_env.env_root().join("repo");
+
+    // Create some operations, one of which sets a bookmark.
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "first"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["bookmark", "create", "-r@", "main"])
+        .success();
+    let op_to_remove = test_env.current_operation_id(&repo_path);
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "second"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "third"])
+        .success();
+
+    // No operations are pruned by default.
+    let output = test_env.run_jj_in(&repo_path, ["util", "gc", "--expire=now"]);
+    insta::assert_snapshot!(output, @"");
+
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "util",
+            "gc",
+            "--expire=now",
+            "--config=operation.retention.keep-count=2",
+            "--config=operation.retention.keep-bookmark-ops=true",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Pruned 2 operations and reparented 3 descendant operations.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  new empty commit
+    ○  new empty commit
+    ○  create bookmark main pointing to commit cbb7ed30b7bbf18c444afc49b6ba98bd844aaa1f4bf6d11d4252ce28df1f360604613d6ef293fde4c15fe82ee3115dc7e29d1c4e9d37fd547d121dd120974b69
+    ○
+    [EOF]
+    ");
+
+    // The working copy is still usable.
+    let output = test_env.run_jj_in(&repo_path, ["log", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  third
+    │
+    ~
+    [EOF]
+    ");
+
+    // Without the bookmark operation, only the latest operations are kept.
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "util",
+            "gc",
+            "--expire=now",
+            "--config=operation.retention.keep-count=2",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Pruned 1 operations and reparented 2 descendant operations.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  new empty commit
+    ○  new empty commit
+    ○
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["debug", "operation", &op_to_remove]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: No operation ID matching "d6a92815e1a20f0007c96225322716271c0ce0c047d4819d8689f4e78c671747f5e7c78b2b5d78b80674600b1be168d837a33eeeb6c546b62c8bec0c9b00a005"
+    [EOF]
+    [exit status: 1]
+    "#);
+}
+
+#[test]
+fn test_gc_operation_log_retention_ignore_working_copy() {
+    let test_env = TestEnvironment::default();
+    // Use the local backend because GitBackend::gc() depends on the git CLI.
+    test_env
+        .run_jj_in(".", ["init", "repo", "--config=ui.allow-init-native=true"])
+        .success();
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "first"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "second"])
+        .success();
+
+    // Operations aren't reparented since the working copy couldn't be updated.
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "util",
+            "gc",
+            "--expire=now",
+            "--ignore-working-copy",
+            "--config=operation.retention.keep-count=1",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Operations are not pruned because the working copy is ignored.
+    [EOF]
+    ");
+
+    // The working copy isn't stale.
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "third"])
+        .success();
+    let output = test_env.run_jj_in(&repo_path, ["log", "-r@-::", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  third
+    ○  second
+    │
+    ~
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  new empty commit
+    ○  new empty commit
+    ○  new empty commit
+    ○  add workspace 'default'
+    ○
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_gc_auto_prunes_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "first"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["new", "-m", "second"])
+        .success();
+
+    // The automatic gc prunes operations without touching the working copy.
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "util",
+            "gc",
+            "--auto",
+            "--expire=now",
+            "--ignore-working-copy",
+            "--config=operation.retention.keep-count=1",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Pruned 2 operations and reparented 1 descendant operations.
+    [EOF]
+    ");
+
+    // The working copy isn't stale.
+    let output = test_env.run_jj_in(&repo_path, ["log", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  second
+    │
+    ~
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["op", "log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  new empty commit
+    ○
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_gc_auto_invalid_config() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    // The command succeeds even though automatic gc can't be configured.
+    let output = test_env.run_jj_in(&repo_path, ["new", "--quiet", "--config=gc.auto=1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Skipped automatic garbage collection: Invalid type or value for gc.auto
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_shell_completions() {
     #[track_caller]
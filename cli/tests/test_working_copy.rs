@@ -127,6 +127,115 @@ fn test_snapshot_large_file() {
     ");
 }
 
+#[test]
+fn test_snapshot_file_policies() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(indoc! {r#"
+        [snapshot.policies.objects]
+        paths = "glob:*.o"
+        action = "skip"
+
+        [snapshot.policies.datasets]
+        paths = "data"
+        action = "manual"
+
+        [snapshot.policies.media]
+        paths = "glob:*.bin"
+        min-size = 4
+        action = "pointer"
+    "#});
+    std::fs::write(repo_path.join("file"), "").unwrap();
+    std::fs::write(repo_path.join("main.o"), "object").unwrap();
+    std::fs::create_dir(repo_path.join("data")).unwrap();
+    std::fs::write(repo_path.join("data").join("set"), "set").unwrap();
+    std::fs::write(repo_path.join("movie.bin"), "movie").unwrap();
+    std::fs::write(repo_path.join("tiny.bin"), "bin").unwrap();
+
+    // Skipped files are warned about, and listed by `jj status`
+    let output = test_env.run_jj_in(&repo_path, ["status"]);
+    insta::assert_snapshot!(output, @r"
+    Working copy changes:
+    A file
+    A movie.bin
+    A tiny.bin
+    Skipped paths:
+    ? data/set (snapshot policy `datasets` requires `jj file track`)
+    ? main.o (skipped by snapshot policy `objects`)
+    Working copy : qpvuntsm 15da008f (no description set)
+    Parent commit: zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ------- stderr -------
+    Warning: Refused to snapshot some files:
+      main.o: skipped by snapshot policy `objects`
+    [EOF]
+    ");
+
+    // The pointer is recorded instead of the content
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "movie.bin", "tiny.bin"]);
+    insta::assert_snapshot!(output, @r"
+    version jj-pointer/v1
+    oid blake2b:3240fdd55ae44c7018324378b41bcb0e9b85256a50d97e28be926563e37178b21371100292058a398f45a34a1cfd200a16ce9eaacfdfe3bde5b449a84ae5bca9
+    size 5
+    bin[EOF]
+    ------- stderr -------
+    Warning: Refused to snapshot some files:
+      main.o: skipped by snapshot policy `objects`
+    [EOF]
+    ");
+
+    // The content is restored when the pointer is checked out
+    test_env
+        .run_jj_in(&repo_path, ["describe", "-m", "policies"])
+        .success();
+    test_env.run_jj_in(&repo_path, ["new", "root()"]).success();
+    assert!(!repo_path.join("movie.bin").exists());
+    test_env
+        .run_jj_in(&repo_path, ["edit", "description(policies)"])
+        .success();
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("movie.bin")).unwrap(), @r"
+    movie
+    ");
+
+    // Explicitly tracked files override the "manual" and "skip" policies
+    let output = test_env.run_jj_in(&repo_path, ["file", "track", "data", "main.o"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Refused to snapshot some files:
+      main.o: skipped by snapshot policy `objects`
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&repo_path, ["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    data/set
+    file
+    main.o
+    movie.bin
+    tiny.bin
+    [EOF]
+    ");
+
+    // test invalid configuration
+    let output = test_env.run_jj_in(
+        &repo_path,
+        [
+            "file",
+            "list",
+            "--config=snapshot.policies.objects.action='drop'",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Config error: Invalid type or value for snapshot.policies.objects.action
+    Caused by: unknown variant `drop`, expected one of `skip`, `manual`, `pointer`
+
+    For help, see https://jj-vcs.github.io/jj/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_snapshot_large_file_restore() {
     let test_env = TestEnvironment::default();
@@ -288,6 +397,103 @@ fn test_materialize_and_snapshot_different_conflict_markers() {
     ");
 }
 
+#[test]
+fn test_materialize_and_snapshot_per_file_conflict_markers() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.add_config(r#"ui.conflict-marker-style = "zdiff3""#);
+    test_env.add_config(indoc! {r#"
+        [ui.conflict-marker-overrides]
+        "glob:*.yaml" = { style = "snapshot" }
+        "glob:*.md" = { length = 10 }
+    "#});
+
+    // Create a conflict in several files
+    let file_names = ["file.txt", "file.yaml", "file.md"];
+    for name in file_names {
+        std::fs::write(repo_path.join(name), "line 1\nline 2\nline 3\n").unwrap();
+    }
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "base"])
+        .success();
+    for name in file_names {
+        std::fs::write(repo_path.join(name), "line 1\nline 2 - a\nline 3\n").unwrap();
+    }
+    test_env
+        .run_jj_in(&repo_path, ["commit", "-m", "side-a"])
+        .success();
+    test_env
+        .run_jj_in(&repo_path, ["new", "description(base)", "-m", "side-b"])
+        .success();
+    for name in file_names {
+        std::fs::write(repo_path.join(name), "line 1\nline 2 - b\nline 3\n").unwrap();
+    }
+    test_env
+        .run_jj_in(
+            &repo_path,
+            ["new", "description(side-a)", "description(side-b)"],
+        )
+        .success();
+
+    // The default style is used unless overridden for the file
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file.txt")).unwrap(), @r"
+    line 1
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    line 2 - a
+    ||||||| Base
+    line 2
+    =======
+    line 2 - b
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file.yaml")).unwrap(), @r"
+    line 1
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    line 2 - a
+    ------- Contents of base
+    line 2
+    +++++++ Contents of side #2
+    line 2 - b
+    >>>>>>> Conflict 1 of 1 ends
+    line 3
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file.md")).unwrap(), @r"
+    line 1
+    <<<<<<<<<< Side #1 (Conflict 1 of 1)
+    line 2 - a
+    |||||||||| Base
+    line 2
+    ==========
+    line 2 - b
+    >>>>>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    ");
+
+    // All materialized conflicts should be parsed back unchanged
+    let output = test_env.run_jj_in(&repo_path, ["diff", "--summary"]);
+    insta::assert_snapshot!(output, @"");
+
+    // `jj file show` uses the same per-file markers
+    let output = test_env.run_jj_in(&repo_path, ["file", "show", "file.yaml"]);
+    insta::assert_snapshot!(output, @r"
+    line 1
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    line 2 - a
+    ------- Contents of base
+    line 2
+    +++++++ Contents of side #2
+    line 2 - b
+    >>>>>>> Conflict 1 of 1 ends
+    line 3
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_snapshot_invalid_ignore_pattern() {
     let test_env = TestEnvironment::default();
@@ -274,7 +274,7 @@ fn test_workspaces_add_at_operation() {
     let output = test_env.run_jj_in(&secondary_path, ["op", "log", "-Tdescription"]);
     insta::assert_snapshot!(output, @r"
     @  snapshot working copy
-    ○    reconcile divergent operations
+    ◇    reconcile divergent operations
     ├─╮
     ○ │  commit cd06097124e3e5860867e35c2bb105902c28ea38
     │ ○  create initial working-copy commit in workspace secondary
@@ -289,6 +289,146 @@ fn test_workspaces_add_at_operation() {
     ");
 }
 
+#[test]
+fn test_workspaces_add_sandbox() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_path = test_env.env_root().join("main");
+
+    std::fs::write(main_path.join("file1"), "1").unwrap();
+    test_env.run_jj_in(&main_path, ["commit", "-m1"]).success();
+    let op_id = test_env.current_operation_id(&main_path);
+    std::fs::write(main_path.join("file1"), "2").unwrap();
+    test_env.run_jj_in(&main_path, ["commit", "-m2"]).success();
+
+    // Create a scratch workspace at the operation before the second commit
+    let output = test_env.run_jj_in(
+        &main_path,
+        ["workspace", "add", "--at-op", &op_id, "--sandbox"],
+    );
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    ------- stderr -------
+    Created workspace in ".jj/sandboxes/sandbox-b0918bd0cf7c"
+    Working copy now at: zxsnswpr dfa7e31d (empty) (no description set)
+    Parent commit      : qpvuntsm 40bbcc7f 1
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+    // The sandbox isn't visible at the head operation
+    let output = test_env.run_jj_in(&main_path, ["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: zsuskuln cd46fdf6 (empty) (no description set)
+    [EOF]
+    ");
+    let sandbox_path = main_path
+        .join(".jj")
+        .join("sandboxes")
+        .join(format!("sandbox-{}", &op_id[..12]));
+    assert_eq!(
+        std::fs::read_to_string(sandbox_path.join("file1")).unwrap(),
+        "1"
+    );
+
+    // Changes made in the sandbox don't appear at the head operation until
+    // they're merged
+    std::fs::write(sandbox_path.join("file2"), "sandbox").unwrap();
+    test_env
+        .run_jj_in(&sandbox_path, ["describe", "-m", "experiment"])
+        .success();
+    let output = test_env.run_jj_in(&sandbox_path, ["log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  experiment
+    │ ○
+    ├─╯
+    ○  1
+    ◆
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&main_path, ["log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @
+    ○  2
+    ○  1
+    ◆
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&main_path, ["workspace", "merge-sandbox"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The current workspace is not a sandbox
+    Hint: Use `jj --at-op=<operation> workspace add --sandbox` to create one.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = test_env.run_jj_in(&sandbox_path, ["workspace", "merge-sandbox"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Merged operation 4b1d37acab27 into the repo
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(&main_path, ["log", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @
+    ○  2
+    │ ○  experiment
+    ├─╯
+    ○  1
+    ◆
+    [EOF]
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    [EOF]
+    ");
+    let output = test_env.run_jj_in(
+        &main_path,
+        ["file", "show", "-r", "description(experiment)", "file2"],
+    );
+    insta::assert_snapshot!(output, @"sandbox[EOF]");
+
+    // Sandbox can't be created at the head operation
+    let output = test_env.run_jj_in(&main_path, ["workspace", "add", "--sandbox"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --sandbox requires the repo to be loaded at an earlier operation
+    Hint: Use --at-op to specify the operation to create the sandbox at.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = test_env.run_jj_in(
+        &main_path,
+        [
+            "workspace",
+            "add",
+            "--at-op",
+            "@",
+            "--sandbox",
+            "../sandbox",
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --sandbox requires the repo to be loaded at an earlier operation
+    Hint: Use --at-op to specify the operation to create the sandbox at.
+    [EOF]
+    [exit status: 1]
+    ");
+    assert!(!test_env.env_root().join("sandbox").exists());
+
+    // Another sandbox at the same operation gets a different name
+    let output = test_env.run_jj_in(
+        &main_path,
+        ["workspace", "add", "--at-op", &op_id, "--sandbox"],
+    );
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    ------- stderr -------
+    Created workspace in ".jj/sandboxes/sandbox-b0918bd0cf7c-2"
+    Working copy now at: ruktrxxu ad815fa8 (empty) (no description set)
+    Parent commit      : qpvuntsm 40bbcc7f 1
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+}
+
 /// Test adding a workspace, but at a specific revision using '-r'
 #[test]
 fn test_workspaces_add_workspace_at_revision() {
@@ -19,6 +19,7 @@ fn main() -> Result<()> {
     let input = [
         "git_store.proto",
         "local_store.proto",
+        "op_bundle.proto",
         "op_store.proto",
         "working_copy.proto",
     ];
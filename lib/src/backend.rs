@@ -421,6 +421,16 @@ pub trait Backend: Send + Sync + Debug {
     /// sent.
     fn concurrency(&self) -> usize;
 
+    /// Loads the objects referenced by the given `values` in one batch if the
+    /// backend would otherwise load them lazily one by one, as a Git partial
+    /// clone does.
+    ///
+    /// This is only an optimization. Objects that couldn't be prefetched are
+    /// still loaded by the individual read methods.
+    async fn prefetch_objects(&self, _values: &[TreeValue]) -> BackendResult<()> {
+        Ok(())
+    }
+
     async fn read_file(&self, path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>>;
 
     async fn write_file(
@@ -0,0 +1,292 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Binary search for the first bad commit in a range of commits.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::rc::Rc;
+
+use itertools::Itertools as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::CommitId;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::object_id::ObjectId as _;
+use crate::repo::Repo;
+use crate::revset::ExpressionState;
+use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+
+/// Result of testing a commit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Evaluation {
+    /// The commit doesn't have the problem.
+    Good,
+    /// The commit has the problem.
+    Bad,
+    /// The commit can't be tested.
+    Skip,
+}
+
+impl Evaluation {
+    fn keyword(self) -> &'static str {
+        match self {
+            Evaluation::Good => "good",
+            Evaluation::Bad => "bad",
+            Evaluation::Skip => "skip",
+        }
+    }
+}
+
+/// Error that may occur while loading bisection state.
+#[derive(Debug, Error)]
+pub enum BisectionStateError {
+    /// Failed to read or write the state file.
+    #[error(transparent)]
+    Path(#[from] PathError),
+    /// The state file is malformed.
+    #[error("Invalid bisection state at line {line_number}: {line}")]
+    InvalidLine {
+        /// 1-based line number.
+        line_number: usize,
+        /// Content of the line.
+        line: String,
+    },
+}
+
+/// Error that may occur while determining the next step of a bisection.
+#[derive(Debug, Error)]
+pub enum BisectionError {
+    /// No commit can be the first bad commit.
+    #[error(
+        "No revision can be the first bad revision: every common ancestor of the bad revisions is \
+         an ancestor of a good revision"
+    )]
+    InconsistentEvaluations,
+    /// Failed to evaluate the candidate revisions.
+    #[error(transparent)]
+    Evaluation(#[from] RevsetEvaluationError),
+}
+
+/// Commits evaluated so far in a bisection.
+///
+/// The state is stored as a list of `<evaluation> <commit id>` lines in the
+/// order the commits were marked.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BisectionState {
+    evaluations: Vec<(Evaluation, CommitId)>,
+}
+
+impl BisectionState {
+    /// Creates state with no commits evaluated.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads state from the `path`. Returns `None` if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Option<Self>, BisectionStateError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).context(path).map_err(Into::into),
+        };
+        let mut state = Self::new();
+        for (i, line) in content.lines().enumerate() {
+            let evaluation_and_id = line.split_once(' ').and_then(|(keyword, hex)| {
+                let evaluation = match keyword {
+                    "good" => Evaluation::Good,
+                    "bad" => Evaluation::Bad,
+                    "skip" => Evaluation::Skip,
+                    _ => return None,
+                };
+                Some((evaluation, CommitId::try_from_hex(hex).ok()?))
+            });
+            let Some((evaluation, id)) = evaluation_and_id else {
+                return Err(BisectionStateError::InvalidLine {
+                    line_number: i + 1,
+                    line: line.to_owned(),
+                });
+            };
+            state.mark(id, evaluation);
+        }
+        Ok(Some(state))
+    }
+
+    /// Saves state to the `path`.
+    pub fn save(&self, path: &Path) -> Result<(), PathError> {
+        let dir = path.parent().unwrap();
+        let mut temp_file = NamedTempFile::new_in(dir).context(dir)?;
+        for (evaluation, id) in &self.evaluations {
+            writeln!(temp_file, "{} {}", evaluation.keyword(), id.hex()).context(path)?;
+        }
+        temp_file
+            .persist(path)
+            .map_err(|err| err.error)
+            .context(path)?;
+        Ok(())
+    }
+
+    /// Records the evaluation of the commit. The previous evaluation of the
+    /// same commit is overridden.
+    pub fn mark(&mut self, id: CommitId, evaluation: Evaluation) {
+        self.evaluations.retain(|(_, old_id)| *old_id != id);
+        self.evaluations.push((evaluation, id));
+    }
+
+    /// Iterates commits marked as `evaluation`.
+    pub fn commits_marked(&self, evaluation: Evaluation) -> impl Iterator<Item = &CommitId> {
+        self.evaluations
+            .iter()
+            .filter(move |(e, _)| *e == evaluation)
+            .map(|(_, id)| id)
+    }
+
+    fn commits_expression<St: ExpressionState>(
+        &self,
+        evaluation: Evaluation,
+    ) -> Rc<RevsetExpression<St>> {
+        RevsetExpression::commits(self.commits_marked(evaluation).cloned().collect())
+    }
+
+    /// Returns expression of the commits which may be the first bad commit.
+    ///
+    /// These are the common ancestors of the bad commits which aren't
+    /// ancestors of the good commits. The root commit is assumed to be good.
+    /// If no commit is marked as bad, the set is empty.
+    pub fn candidates_expression<St: ExpressionState>(&self) -> Rc<RevsetExpression<St>> {
+        let bad_ancestors = self
+            .commits_marked(Evaluation::Bad)
+            .map(|id| RevsetExpression::commit(id.clone()).ancestors())
+            .reduce(|acc, ancestors| acc.intersection(&ancestors));
+        let Some(bad_ancestors) = bad_ancestors else {
+            return RevsetExpression::none();
+        };
+        let good_heads = self
+            .commits_expression(Evaluation::Good)
+            .union(&RevsetExpression::root());
+        bad_ancestors.minus(&good_heads.ancestors())
+    }
+
+    /// Determines what to do next.
+    ///
+    /// Returns an error if commits are marked as bad but none of them can be
+    /// the first bad commit, e.g. because a bad commit is an ancestor of a
+    /// good commit.
+    pub fn next_step(&self, repo: &dyn Repo) -> Result<BisectionStep, BisectionError> {
+        let candidates_expression: Rc<ResolvedRevsetExpression> = self.candidates_expression();
+        let untested_expression = candidates_expression
+            .minus(&self.commits_expression(Evaluation::Bad))
+            .minus(&self.commits_expression(Evaluation::Skip));
+        let untested: Vec<_> = untested_expression.evaluate(repo)?.iter().try_collect()?;
+        if !untested.is_empty() {
+            let commit_id = select_midpoint(repo, candidates_expression.clone(), &untested)?;
+            return Ok(BisectionStep::Evaluate {
+                commit_id,
+                num_untested: untested.len(),
+            });
+        }
+        let candidates: Vec<_> = candidates_expression
+            .clone()
+            .evaluate(repo)?
+            .iter()
+            .try_collect()?;
+        if candidates.is_empty() && self.commits_marked(Evaluation::Bad).next().is_some() {
+            return Err(BisectionError::InconsistentEvaluations);
+        }
+        let is_skipped = |id: &CommitId| self.commits_marked(Evaluation::Skip).contains(id);
+        if candidates.iter().any(is_skipped) {
+            Ok(BisectionStep::Indeterminate(candidates))
+        } else {
+            let first_bad = candidates_expression
+                .roots()
+                .evaluate(repo)?
+                .iter()
+                .try_collect()?;
+            Ok(BisectionStep::Found(first_bad))
+        }
+    }
+}
+
+/// Picks the `untested` commit which splits the candidates most evenly, i.e.
+/// the one whose ancestors within the candidates come closest to half of them.
+/// Ties are broken in favor of the commit which comes last in the topological
+/// order.
+fn select_midpoint(
+    repo: &dyn Repo,
+    candidates_expression: Rc<ResolvedRevsetExpression>,
+    untested: &[CommitId],
+) -> Result<CommitId, BisectionError> {
+    let nodes: Vec<_> = candidates_expression
+        .evaluate(repo)?
+        .iter_graph()
+        .try_collect()?;
+    let positions: HashMap<&CommitId, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(pos, (id, _))| (id, pos))
+        .collect();
+    // Nodes are sorted topologically with children first, so the ancestors of
+    // the parents are known when visiting the nodes in reverse.
+    let num_words = nodes.len().div_ceil(64);
+    let mut ancestors: Vec<Vec<u64>> = vec![vec![]; nodes.len()];
+    for (pos, (_, edges)) in nodes.iter().enumerate().rev() {
+        let mut bits = vec![0; num_words];
+        bits[pos / 64] |= 1 << (pos % 64);
+        // Edges to commits outside of the candidates are missing edges
+        for parent_pos in edges.iter().filter_map(|edge| positions.get(&edge.target)) {
+            for (word, parent_word) in bits.iter_mut().zip(&ancestors[*parent_pos]) {
+                *word |= parent_word;
+            }
+        }
+        ancestors[pos] = bits;
+    }
+    let num_candidates = nodes.len();
+    // max_by_key() returns the last maximum.
+    let commit_id = untested
+        .iter()
+        .max_by_key(|id| {
+            let bits = &ancestors[positions[id]];
+            let num_ancestors = bits
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>();
+            num_ancestors.min(num_candidates - num_ancestors)
+        })
+        .unwrap();
+    Ok(commit_id.clone())
+}
+
+/// Next step of a bisection.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BisectionStep {
+    /// The commit should be tested next.
+    Evaluate {
+        /// Commit to test.
+        commit_id: CommitId,
+        /// Number of candidate commits which haven't been tested yet.
+        num_untested: usize,
+    },
+    /// The first bad commits were found. Empty if no commit is bad.
+    Found(Vec<CommitId>),
+    /// Any of the commits (some of which were skipped) may be the first bad
+    /// commit.
+    Indeterminate(Vec<CommitId>),
+}
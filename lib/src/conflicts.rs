@@ -21,6 +21,7 @@ use std::iter::zip;
 
 use bstr::BString;
 use bstr::ByteSlice;
+use futures::stream;
 use futures::stream::BoxStream;
 use futures::try_join;
 use futures::Stream;
@@ -43,6 +44,8 @@ use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::files;
 use crate::files::MergeResult;
+use crate::fileset::FilesetExpression;
+use crate::matchers::Matcher;
 use crate::merge::Merge;
 use crate::merge::MergeBuilder;
 use crate::merge::MergedTreeValue;
@@ -237,6 +240,64 @@ pub enum ConflictMarkerStyle {
     Snapshot,
     /// Style which replicates Git's "diff3" style to support external tools.
     Git,
+    /// Like the "git" style, but lines common to both sides at the start and
+    /// end of a conflict are moved out of it, like Git's "zdiff3" style.
+    Zdiff3,
+}
+
+/// Conflict marker style and length to use for the files matching `paths`
+/// instead of the defaults.
+#[derive(Clone, Debug)]
+pub struct ConflictMarkerOverride {
+    /// Files the override applies to.
+    pub paths: FilesetExpression,
+    /// Conflict marker style to use, if overridden.
+    pub style: Option<ConflictMarkerStyle>,
+    /// Minimum length of the conflict markers, if overridden.
+    pub min_len: Option<usize>,
+}
+
+/// Chooses the conflict marker style and length of each file from the default
+/// style and the overrides.
+#[derive(Debug)]
+pub struct ConflictMarkerOverrides {
+    default_style: ConflictMarkerStyle,
+    overrides: Vec<(Box<dyn Matcher>, ConflictMarkerOverride)>,
+}
+
+impl ConflictMarkerOverrides {
+    /// Creates an instance. The first override matching a file takes
+    /// precedence.
+    pub fn new(default_style: ConflictMarkerStyle, overrides: &[ConflictMarkerOverride]) -> Self {
+        let overrides = overrides
+            .iter()
+            .map(|o| (o.paths.to_matcher(), o.clone()))
+            .collect();
+        ConflictMarkerOverrides {
+            default_style,
+            overrides,
+        }
+    }
+
+    /// Conflict marker style to use for the `path`.
+    pub fn style(&self, path: &RepoPath) -> ConflictMarkerStyle {
+        self.overrides
+            .iter()
+            .filter(|(matcher, _)| matcher.matches(path))
+            .find_map(|(_, o)| o.style)
+            .unwrap_or(self.default_style)
+    }
+
+    /// Minimum length of the conflict markers to use for the `path`.
+    pub fn min_len(&self, path: &RepoPath) -> usize {
+        self.overrides
+            .iter()
+            .filter(|(matcher, _)| matcher.matches(path))
+            .find_map(|(_, o)| o.min_len)
+            .map_or(MIN_CONFLICT_MARKER_LEN, |len| {
+                len.max(MIN_CONFLICT_MARKER_LEN)
+            })
+    }
 }
 
 /// Characters which can be repeated to form a conflict marker line when
@@ -434,6 +495,17 @@ fn materialize_conflict_hunks(
                 // 2-sided conflicts can use Git-style conflict markers
                 (ConflictMarkerStyle::Git, [left, base, right]) => {
                     materialize_git_style_conflict(
+                        left,
+                        base,
+                        right,
+                        &conflict_info,
+                        &format!("{conflict_info} ends"),
+                        conflict_marker_len,
+                        output,
+                    )?;
+                }
+                (ConflictMarkerStyle::Zdiff3, [left, base, right]) => {
+                    materialize_zdiff3_style_conflict(
                         left,
                         base,
                         right,
@@ -461,7 +533,8 @@ fn materialize_git_style_conflict(
     left: &[u8],
     base: &[u8],
     right: &[u8],
-    conflict_info: &str,
+    start_info: &str,
+    end_info: &str,
     conflict_marker_len: usize,
     output: &mut dyn Write,
 ) -> io::Result<()> {
@@ -469,7 +542,7 @@ fn materialize_git_style_conflict(
         output,
         ConflictMarkerLineChar::ConflictStart,
         conflict_marker_len,
-        &format!("Side #1 ({conflict_info})"),
+        &format!("Side #1 ({start_info})"),
     )?;
     write_and_ensure_newline(output, left)?;
 
@@ -494,12 +567,86 @@ fn materialize_git_style_conflict(
         output,
         ConflictMarkerLineChar::ConflictEnd,
         conflict_marker_len,
-        &format!("Side #2 ({conflict_info} ends)"),
+        &format!("Side #2 ({end_info})"),
     )?;
 
     Ok(())
 }
 
+/// Writes a Git-style conflict with the lines common to both sides at the
+/// start and end moved out of it. The number of moved lines is recorded in the
+/// conflict markers so the sides can be restored by `parse_conflict()`.
+fn materialize_zdiff3_style_conflict(
+    left: &[u8],
+    base: &[u8],
+    right: &[u8],
+    conflict_info: &str,
+    conflict_marker_len: usize,
+    output: &mut dyn Write,
+) -> io::Result<()> {
+    let left_lines = left.lines_with_terminator().collect_vec();
+    let right_lines = right.lines_with_terminator().collect_vec();
+    let num_above = zip(&left_lines, &right_lines)
+        .take_while(|(l, r)| l == r)
+        .count();
+    let num_below = zip(
+        left_lines[num_above..].iter().rev(),
+        right_lines[num_above..].iter().rev(),
+    )
+    .take_while(|(l, r)| l == r)
+    .count();
+    let above: BString = left_lines[..num_above].concat().into();
+    let below: BString = left_lines[left_lines.len() - num_below..].concat().into();
+    let left_middle = &left[above.len()..left.len() - below.len()];
+    let right_middle = &right[above.len()..right.len() - below.len()];
+
+    let mut start_info = conflict_info.to_owned();
+    if num_above > 0 {
+        start_info += &format!(", {}", common_lines_text(num_above, "above"));
+    }
+    let mut end_info = format!("{conflict_info} ends");
+    if num_below > 0 {
+        end_info += &format!(", {}", common_lines_text(num_below, "below"));
+    }
+    output.write_all(&above)?;
+    materialize_git_style_conflict(
+        left_middle,
+        base,
+        right_middle,
+        &start_info,
+        &end_info,
+        conflict_marker_len,
+        output,
+    )?;
+    output.write_all(&below)
+}
+
+fn common_lines_text(num_lines: usize, position: &str) -> String {
+    if num_lines == 1 {
+        format!("1 common line {position}")
+    } else {
+        format!("{num_lines} common lines {position}")
+    }
+}
+
+/// Parses the number of common lines moved out of a "zdiff3"-style conflict
+/// from the text of its conflict marker.
+fn parse_common_lines_text(marker_line: &[u8], position: &str) -> usize {
+    let Some(text) = marker_line
+        .trim_end()
+        .strip_suffix(format!(" {position})").as_bytes())
+    else {
+        return 0;
+    };
+    let text = text
+        .strip_suffix(b" common lines")
+        .or_else(|| text.strip_suffix(b" common line"));
+    text.and_then(|text| text.rsplit_str(", ").next())
+        .and_then(|num| num.to_str().ok())
+        .and_then(|num| num.parse().ok())
+        .unwrap_or(0)
+}
+
 fn materialize_jj_style_conflict(
     hunk: &Merge<BString>,
     conflict_info: &str,
@@ -662,11 +809,31 @@ pub struct MaterializedTreeDiffEntry {
     pub values: BackendResult<(MaterializedTreeValue, MaterializedTreeValue)>,
 }
 
+/// Number of diff entries whose objects are prefetched at once.
+const PREFETCH_CHUNK_SIZE: usize = 100;
+
 pub fn materialized_diff_stream<'a>(
     store: &'a Store,
     tree_diff: BoxStream<'a, CopiesTreeDiffEntry>,
 ) -> impl Stream<Item = MaterializedTreeDiffEntry> + 'a {
-    tree_diff
+    // The prefetching future makes the stream !Unpin
+    let stream = tree_diff
+        .chunks(PREFETCH_CHUNK_SIZE)
+        .then(move |entries| async move {
+            let values = entries
+                .iter()
+                .filter_map(|entry| entry.values.as_ref().ok())
+                .flat_map(|(before, after)| before.iter().chain(after.iter()).flatten())
+                .cloned()
+                .collect_vec();
+            // Objects that couldn't be prefetched are loaded individually, which
+            // will report the error if any.
+            if let Err(err) = store.prefetch_objects(&values).await {
+                tracing::warn!(?err, "failed to prefetch objects of diff");
+            }
+            stream::iter(entries)
+        })
+        .flatten()
         .map(|CopiesTreeDiffEntry { path, values }| async {
             match values {
                 Err(err) => MaterializedTreeDiffEntry {
@@ -681,7 +848,8 @@ pub fn materialized_diff_stream<'a>(
                 }
             }
         })
-        .buffered((store.concurrency() / 2).max(1))
+        .buffered((store.concurrency() / 2).max(1));
+    Box::pin(stream)
 }
 
 /// Parses conflict markers from a slice.
@@ -693,11 +861,15 @@ pub fn materialized_diff_stream<'a>(
 ///
 /// All conflict markers in the file must be at least as long as the expected
 /// length. Any shorter conflict markers will be ignored.
+///
+/// If the `conflict_marker_style` is "zdiff3", the common lines moved out of
+/// 2-sided conflicts are restored to the sides.
 // TODO: "parse" is not usually the opposite of "materialize", so maybe we
 // should rename them to "serialize" and "deserialize"?
 pub fn parse_conflict(
     input: &[u8],
     num_sides: usize,
+    conflict_marker_style: ConflictMarkerStyle,
     expected_marker_len: usize,
 ) -> Option<Vec<Merge<BString>>> {
     if input.is_empty() {
@@ -707,24 +879,58 @@ pub fn parse_conflict(
     let mut pos = 0;
     let mut resolved_start = 0;
     let mut conflict_start = None;
-    let mut conflict_start_len = 0;
+    let mut conflict_start_line: &[u8] = &[];
     for line in input.lines_with_terminator() {
+        if pos < resolved_start {
+            // Skip common lines which were moved back into the previous conflict
+            pos += line.len();
+            continue;
+        }
         match parse_conflict_marker(line, expected_marker_len) {
             Some(ConflictMarkerLineChar::ConflictStart) => {
                 conflict_start = Some(pos);
-                conflict_start_len = line.len();
+                conflict_start_line = line;
             }
             Some(ConflictMarkerLineChar::ConflictEnd) => {
                 if let Some(conflict_start_index) = conflict_start.take() {
-                    let conflict_body = &input[conflict_start_index + conflict_start_len..pos];
-                    let hunk = parse_conflict_hunk(conflict_body, expected_marker_len);
+                    let conflict_body =
+                        &input[conflict_start_index + conflict_start_line.len()..pos];
+                    let mut hunk = parse_conflict_hunk(conflict_body, expected_marker_len);
                     if hunk.num_sides() == num_sides {
-                        let resolved_slice = &input[resolved_start..conflict_start_index];
+                        let mut resolved_slice = &input[resolved_start..conflict_start_index];
+                        let mut conflict_end = pos + line.len();
+                        if conflict_marker_style == ConflictMarkerStyle::Zdiff3
+                            && hunk.num_sides() == 2
+                        {
+                            // Restore the common lines of "zdiff3"-style conflicts
+                            let num_above = parse_common_lines_text(conflict_start_line, "above");
+                            let num_below = parse_common_lines_text(line, "below");
+                            let above_len = resolved_slice
+                                .lines_with_terminator()
+                                .rev()
+                                .take(num_above)
+                                .map(|line| line.len())
+                                .sum::<usize>();
+                            let below_len = input[conflict_end..]
+                                .lines_with_terminator()
+                                .take(num_below)
+                                .map(|line| line.len())
+                                .sum::<usize>();
+                            let (resolved, above) =
+                                resolved_slice.split_at(resolved_slice.len() - above_len);
+                            let below = &input[conflict_end..conflict_end + below_len];
+                            for side in hunk.iter_mut().step_by(2) {
+                                side.splice(0..0, above.iter().copied());
+                                side.extend_from_slice(below);
+                            }
+                            resolved_slice = resolved;
+                            conflict_end += below_len;
+                        }
                         if !resolved_slice.is_empty() {
                             hunks.push(Merge::resolved(BString::from(resolved_slice)));
                         }
                         hunks.push(hunk);
-                        resolved_start = pos + line.len();
+                        resolved_start = conflict_end;
                     }
                 }
             }
@@ -927,6 +1133,7 @@ pub async fn update_from_content(
     let Some(mut hunks) = parse_conflict(
         content,
         simplified_file_ids.num_sides(),
+        conflict_marker_style,
         conflict_marker_len,
     ) else {
         // Either there are no markers or they don't have the expected arity
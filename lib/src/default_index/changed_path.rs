@@ -0,0 +1,407 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Index of paths changed by commits.
+//!
+//! The changed paths are stored per commit index segment, in a file named
+//! after the segment. Since a segment file is immutable, the changed-path
+//! file can be reused by all operations sharing the segment. When segments
+//! are squashed, the changed paths of the indexed commits are carried forward
+//! to the new segment, and the other commits are recorded as not indexed.
+
+use std::fs;
+use std::io;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::iter;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use tempfile::NamedTempFile;
+
+use super::composite::CompositeIndex;
+use super::composite::IndexSegment;
+use super::entry::IndexPosition;
+use super::readonly::ReadonlyIndexSegment;
+use crate::backend::BackendResult;
+use crate::commit::Commit;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::matchers::EverythingMatcher;
+use crate::merged_tree::resolve_file_values;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::rewrite;
+
+pub(super) const CHANGED_PATH_FILE_FORMAT_VERSION: u32 = 2;
+
+/// Paths changed by the commits of a commit index segment.
+///
+/// File format:
+/// ```text
+/// u32: file format version
+/// u32: number of local commit entries
+/// u32: number of commits not indexed
+/// u32: number of path references
+/// u32: number of unique paths
+/// for each commit, in index position order:
+///   u32: end position in the path references table
+/// for each commit not indexed, in index position order:
+///   u32: local position
+/// for each path reference:
+///   u32: position in the sorted paths table
+/// for each path, sorted:
+///   u32: end offset in the path bytes
+/// <path bytes>
+/// ```
+#[derive(Debug)]
+pub(super) struct ChangedPathSegment {
+    commit_ends: Vec<u32>,
+    unindexed: Vec<u32>,
+    path_refs: Vec<u32>,
+    paths: Vec<RepoPathBuf>,
+}
+
+impl ChangedPathSegment {
+    /// Builds segment from the changed paths of the local commits. `None`
+    /// entry means the commit isn't indexed.
+    pub(super) fn from_entries(entries: &[Option<Vec<RepoPathBuf>>]) -> Self {
+        let paths = entries
+            .iter()
+            .flatten()
+            .flatten()
+            .sorted_unstable()
+            .dedup()
+            .cloned()
+            .collect_vec();
+        let mut commit_ends = Vec::with_capacity(entries.len());
+        let mut unindexed = Vec::new();
+        let mut path_refs = Vec::new();
+        for (local_pos, changed_paths) in entries.iter().enumerate() {
+            if let Some(changed_paths) = changed_paths {
+                for path in changed_paths {
+                    let pos = paths.binary_search(path).unwrap();
+                    path_refs.push(u32::try_from(pos).unwrap());
+                }
+            } else {
+                unindexed.push(u32::try_from(local_pos).unwrap());
+            }
+            commit_ends.push(u32::try_from(path_refs.len()).unwrap());
+        }
+        ChangedPathSegment {
+            commit_ends,
+            unindexed,
+            path_refs,
+            paths,
+        }
+    }
+
+    pub(super) fn load(file_path: &Path) -> io::Result<Self> {
+        let data = fs::read(file_path)?;
+        Self::load_from(&data).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid changed-path file {}", file_path.display()),
+            )
+        })
+    }
+
+    fn load_from(mut data: &[u8]) -> Option<Self> {
+        let mut read_u32 = || {
+            let mut buf = [0; 4];
+            data.read_exact(&mut buf).ok()?;
+            Some(u32::from_le_bytes(buf))
+        };
+        if read_u32()? != CHANGED_PATH_FILE_FORMAT_VERSION {
+            return None;
+        }
+        let num_commits = read_u32()?;
+        let num_unindexed = read_u32()?;
+        let num_path_refs = read_u32()?;
+        let num_paths = read_u32()?;
+        let commit_ends: Vec<_> = (0..num_commits)
+            .map(|_| read_u32())
+            .collect::<Option<_>>()?;
+        let unindexed: Vec<_> = (0..num_unindexed)
+            .map(|_| read_u32())
+            .collect::<Option<_>>()?;
+        let path_refs: Vec<_> = (0..num_path_refs)
+            .map(|_| read_u32())
+            .collect::<Option<_>>()?;
+        let path_ends: Vec<_> = (0..num_paths).map(|_| read_u32()).collect::<Option<_>>()?;
+        let path_bytes = str::from_utf8(data).ok()?;
+        let mut start = 0;
+        let mut paths = Vec::with_capacity(path_ends.len());
+        for end in path_ends {
+            let path = path_bytes.get(start..usize::try_from(end).ok()?)?;
+            paths.push(RepoPathBuf::from_internal_string(path));
+            start = end as usize;
+        }
+        // Make sure lookups won't panic
+        if commit_ends
+            .iter()
+            .tuple_windows()
+            .any(|(start, end)| start > end)
+            || commit_ends.last().is_some_and(|&end| end != num_path_refs)
+            || unindexed
+                .iter()
+                .tuple_windows()
+                .any(|(prev, next)| prev >= next)
+            || unindexed.last().is_some_and(|&pos| pos >= num_commits)
+            || path_refs.iter().any(|&pos| pos >= num_paths)
+        {
+            return None;
+        }
+        Some(ChangedPathSegment {
+            commit_ends,
+            unindexed,
+            path_refs,
+            paths,
+        })
+    }
+
+    pub(super) fn save_in(&self, dir: &Path, name: &str) -> io::Result<()> {
+        let mut buf = Vec::new();
+        buf.extend(CHANGED_PATH_FILE_FORMAT_VERSION.to_le_bytes());
+        buf.extend(u32::try_from(self.commit_ends.len()).unwrap().to_le_bytes());
+        buf.extend(u32::try_from(self.unindexed.len()).unwrap().to_le_bytes());
+        buf.extend(u32::try_from(self.path_refs.len()).unwrap().to_le_bytes());
+        buf.extend(u32::try_from(self.paths.len()).unwrap().to_le_bytes());
+        for end in &self.commit_ends {
+            buf.extend(end.to_le_bytes());
+        }
+        for pos in &self.unindexed {
+            buf.extend(pos.to_le_bytes());
+        }
+        for pos in &self.path_refs {
+            buf.extend(pos.to_le_bytes());
+        }
+        let mut path_end = 0;
+        for path in &self.paths {
+            path_end += path.as_internal_file_string().len();
+            buf.extend(u32::try_from(path_end).unwrap().to_le_bytes());
+        }
+        for path in &self.paths {
+            buf.extend(path.as_internal_file_string().as_bytes());
+        }
+
+        let mut temp_file = NamedTempFile::new_in(dir)?;
+        temp_file.as_file_mut().write_all(&buf)?;
+        persist_content_addressed_temp_file(temp_file, dir.join(name))?;
+        Ok(())
+    }
+
+    pub(super) fn num_local_commits(&self) -> u32 {
+        self.commit_ends.len().try_into().unwrap()
+    }
+
+    /// Returns true if all commits are indexed.
+    pub(super) fn is_complete(&self) -> bool {
+        self.unindexed.is_empty()
+    }
+
+    /// Returns paths changed by the commit at `local_pos`, or `None` if the
+    /// commit isn't indexed.
+    pub(super) fn changed_paths(&self, local_pos: u32) -> Option<impl Iterator<Item = &RepoPath>> {
+        if self.unindexed.binary_search(&local_pos).is_ok() {
+            return None;
+        }
+        let local_pos = local_pos as usize;
+        let start = local_pos
+            .checked_sub(1)
+            .map_or(0, |prev| self.commit_ends[prev] as usize);
+        let end = self.commit_ends[local_pos] as usize;
+        let paths = self.path_refs[start..end]
+            .iter()
+            .map(|&pos| self.paths[pos as usize].as_ref());
+        Some(paths)
+    }
+}
+
+/// Changed paths of a commit index segment and its ancestors.
+///
+/// Segments without changed-path file, and commits recorded as not indexed,
+/// aren't covered. The files are loaded on first access.
+#[derive(Debug)]
+pub(super) struct ChangedPathIndex {
+    /// Covered segments in descending position order.
+    segments: Vec<LazyChangedPathSegment>,
+}
+
+#[derive(Debug)]
+struct LazyChangedPathSegment {
+    start: IndexPosition,
+    num_local_commits: u32,
+    file_path: PathBuf,
+    data: OnceLock<Option<ChangedPathSegment>>,
+}
+
+impl ChangedPathIndex {
+    /// Looks up changed-path files of the `index_segment` and its ancestors.
+    pub(super) fn load(dir: &Path, index_segment: &Arc<ReadonlyIndexSegment>) -> Self {
+        let segments = readonly_segments(index_segment)
+            .filter_map(|segment| {
+                let file_path = dir.join(segment.name());
+                file_path.is_file().then(|| LazyChangedPathSegment {
+                    start: IndexPosition(segment.num_parent_commits()),
+                    num_local_commits: segment.num_local_commits(),
+                    file_path,
+                    data: OnceLock::new(),
+                })
+            })
+            .collect();
+        ChangedPathIndex { segments }
+    }
+
+    /// Returns paths changed by the commit at `pos`, or `None` if the commit
+    /// isn't covered by the index.
+    pub(super) fn changed_paths(
+        &self,
+        pos: IndexPosition,
+    ) -> Option<impl Iterator<Item = &RepoPath>> {
+        let segment = self.segments.iter().find(|segment| segment.start <= pos)?;
+        let local_pos = pos.0 - segment.start.0;
+        if local_pos >= segment.num_local_commits {
+            return None;
+        }
+        let data = segment.data.get_or_init(|| {
+            // Corrupted file will be ignored. The caller will fall back to
+            // tree diffs.
+            ChangedPathSegment::load(&segment.file_path)
+                .ok()
+                .filter(|data| data.num_local_commits() == segment.num_local_commits)
+        });
+        data.as_ref()?.changed_paths(local_pos)
+    }
+}
+
+/// Iterates `index_segment` and its ancestor segments.
+pub(super) fn readonly_segments(
+    index_segment: &Arc<ReadonlyIndexSegment>,
+) -> impl Iterator<Item = &Arc<ReadonlyIndexSegment>> {
+    iter::once(index_segment).chain(index_segment.as_composite().ancestor_files_without_local())
+}
+
+/// Collects paths changed by the `commit` compared to its parents.
+///
+/// The parent commits must exist in the `index`.
+pub(super) fn collect_changed_paths(
+    index: &CompositeIndex,
+    commit: &Commit,
+) -> BackendResult<Vec<RepoPathBuf>> {
+    let store = commit.store();
+    let parents: Vec<_> = commit.parents().try_collect()?;
+    if let [parent] = parents.as_slice() {
+        if commit.tree_id() == parent.tree_id() {
+            return Ok(vec![]);
+        }
+    }
+    let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
+    let to_tree = commit.tree()?;
+    let mut tree_diff = from_tree.diff_stream(&to_tree, &EverythingMatcher);
+    async {
+        let mut paths = vec![];
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value).await?;
+            if from_value != to_value {
+                paths.push(entry.path);
+            }
+        }
+        Ok(paths)
+    }
+    .block_on()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_temp_dir;
+
+    fn repo_path_buf(value: &str) -> RepoPathBuf {
+        RepoPathBuf::from_internal_string(value)
+    }
+
+    fn collect_paths(segment: &ChangedPathSegment, local_pos: u32) -> Option<Vec<&str>> {
+        let paths = segment.changed_paths(local_pos)?;
+        Some(paths.map(|path| path.as_internal_file_string()).collect())
+    }
+
+    #[test]
+    fn test_changed_path_segment_save_load() {
+        let temp_dir = new_temp_dir();
+        let entries = vec![
+            Some(vec![repo_path_buf("b"), repo_path_buf("dir/a")]),
+            Some(vec![]),
+            Some(vec![repo_path_buf("b")]),
+            Some(vec![
+                repo_path_buf("a"),
+                repo_path_buf("b"),
+                repo_path_buf("dir/c"),
+            ]),
+        ];
+        let segment = ChangedPathSegment::from_entries(&entries);
+        segment.save_in(temp_dir.path(), "segment").unwrap();
+
+        let segment = ChangedPathSegment::load(&temp_dir.path().join("segment")).unwrap();
+        assert_eq!(segment.num_local_commits(), 4);
+        assert!(segment.is_complete());
+        assert_eq!(collect_paths(&segment, 0).unwrap(), ["b", "dir/a"]);
+        assert!(collect_paths(&segment, 1).unwrap().is_empty());
+        assert_eq!(collect_paths(&segment, 2).unwrap(), ["b"]);
+        assert_eq!(collect_paths(&segment, 3).unwrap(), ["a", "b", "dir/c"]);
+    }
+
+    #[test]
+    fn test_changed_path_segment_save_load_partial() {
+        let temp_dir = new_temp_dir();
+        let entries = vec![
+            None,
+            Some(vec![repo_path_buf("a")]),
+            None,
+            None,
+            Some(vec![repo_path_buf("b")]),
+        ];
+        let segment = ChangedPathSegment::from_entries(&entries);
+        segment.save_in(temp_dir.path(), "segment").unwrap();
+
+        let segment = ChangedPathSegment::load(&temp_dir.path().join("segment")).unwrap();
+        assert_eq!(segment.num_local_commits(), 5);
+        assert!(!segment.is_complete());
+        assert_eq!(collect_paths(&segment, 0), None);
+        assert_eq!(collect_paths(&segment, 1).unwrap(), ["a"]);
+        assert_eq!(collect_paths(&segment, 2), None);
+        assert_eq!(collect_paths(&segment, 3), None);
+        assert_eq!(collect_paths(&segment, 4).unwrap(), ["b"]);
+    }
+
+    #[test]
+    fn test_changed_path_segment_load_corrupt() {
+        let temp_dir = new_temp_dir();
+        let file_path = temp_dir.path().join("segment");
+        let segment = ChangedPathSegment::from_entries(&[Some(vec![repo_path_buf("a")])]);
+        segment.save_in(temp_dir.path(), "segment").unwrap();
+        let data = fs::read(&file_path).unwrap();
+
+        fs::write(&file_path, &data[..data.len() - 5]).unwrap();
+        assert!(ChangedPathSegment::load(&file_path).is_err());
+        fs::write(&file_path, b"\0".repeat(16)).unwrap();
+        assert!(ChangedPathSegment::load(&file_path).is_err());
+    }
+}
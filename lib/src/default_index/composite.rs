@@ -28,6 +28,7 @@ use itertools::Itertools;
 use ref_cast::ref_cast_custom;
 use ref_cast::RefCastCustom;
 
+use super::changed_path::ChangedPathIndex;
 use super::entry::IndexEntry;
 use super::entry::IndexPosition;
 use super::entry::IndexPositionByGeneration;
@@ -417,8 +418,10 @@ impl CompositeIndex {
         &self,
         expression: &ResolvedExpression,
         store: &Arc<Store>,
+        changed_paths: Option<&Arc<ChangedPathIndex>>,
     ) -> Result<Box<dyn Revset + '_>, RevsetEvaluationError> {
-        let revset_impl = revset_engine::evaluate(expression, store, self)?;
+        let revset_impl =
+            revset_engine::evaluate_with_changed_paths(expression, store, self, changed_paths)?;
         Ok(Box::new(revset_impl))
     }
 }
@@ -508,7 +511,7 @@ impl Index for &CompositeIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        CompositeIndex::evaluate_revset(self, expression, store)
+        CompositeIndex::evaluate_revset(self, expression, store, None)
     }
 }
 
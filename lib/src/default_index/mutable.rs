@@ -31,6 +31,7 @@ use smallvec::smallvec;
 use smallvec::SmallVec;
 use tempfile::NamedTempFile;
 
+use super::changed_path::ChangedPathIndex;
 use super::composite::AsCompositeIndex;
 use super::composite::ChangeIdIndexImpl;
 use super::composite::CompositeIndex;
@@ -456,17 +457,34 @@ impl IndexSegment for MutableIndexSegment {
 }
 
 /// In-memory mutable records for the on-disk commit index backend.
-pub struct DefaultMutableIndex(MutableIndexSegment);
+pub struct DefaultMutableIndex {
+    segment: MutableIndexSegment,
+    /// Changed paths of the parent commits. The commits added to this index
+    /// aren't covered.
+    changed_paths: Option<Arc<ChangedPathIndex>>,
+}
 
 impl DefaultMutableIndex {
     pub(crate) fn full(commit_id_length: usize, change_id_length: usize) -> Self {
         let mutable_segment = MutableIndexSegment::full(commit_id_length, change_id_length);
-        DefaultMutableIndex(mutable_segment)
+        DefaultMutableIndex {
+            segment: mutable_segment,
+            changed_paths: None,
+        }
     }
 
     pub(super) fn incremental(parent_file: Arc<ReadonlyIndexSegment>) -> Self {
         let mutable_segment = MutableIndexSegment::incremental(parent_file);
-        DefaultMutableIndex(mutable_segment)
+        DefaultMutableIndex {
+            segment: mutable_segment,
+            changed_paths: None,
+        }
+    }
+
+    /// Sets changed-path index of the parent commits, which will be used to
+    /// evaluate revsets.
+    pub(super) fn set_changed_paths(&mut self, changed_paths: Arc<ChangedPathIndex>) {
+        self.changed_paths = Some(changed_paths);
     }
 
     #[cfg(test)]
@@ -476,17 +494,23 @@ impl DefaultMutableIndex {
         change_id: ChangeId,
         parent_ids: &[CommitId],
     ) {
-        self.0.add_commit_data(commit_id, change_id, parent_ids);
+        self.segment
+            .add_commit_data(commit_id, change_id, parent_ids);
+    }
+
+    /// Returns the parent segment which this index was started from.
+    pub(super) fn parent_file(&self) -> Option<&Arc<ReadonlyIndexSegment>> {
+        self.segment.parent_file()
     }
 
     pub(super) fn squash_and_save_in(self, dir: &Path) -> io::Result<Arc<ReadonlyIndexSegment>> {
-        self.0.maybe_squash_with_ancestors().save_in(dir)
+        self.segment.maybe_squash_with_ancestors().save_in(dir)
     }
 }
 
 impl AsCompositeIndex for DefaultMutableIndex {
     fn as_composite(&self) -> &CompositeIndex {
-        self.0.as_composite()
+        self.segment.as_composite()
     }
 }
 
@@ -530,7 +554,8 @@ impl Index for DefaultMutableIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        self.as_composite().evaluate_revset(expression, store)
+        self.as_composite()
+            .evaluate_revset(expression, store, self.changed_paths.as_ref())
     }
 }
 
@@ -555,7 +580,7 @@ impl MutableIndex for DefaultMutableIndex {
     }
 
     fn add_commit(&mut self, commit: &Commit) {
-        self.0.add_commit(commit);
+        self.segment.add_commit(commit);
     }
 
     fn merge_in(&mut self, other: &dyn ReadonlyIndex) {
@@ -563,7 +588,7 @@ impl MutableIndex for DefaultMutableIndex {
             .as_any()
             .downcast_ref::<DefaultReadonlyIndex>()
             .expect("index to merge in must be a DefaultReadonlyIndex");
-        self.0.merge_in(other.as_segment().clone());
+        self.segment.merge_in(other.as_segment().clone());
     }
 }
 
@@ -27,6 +27,7 @@ use std::sync::Arc;
 use smallvec::smallvec;
 use thiserror::Error;
 
+use super::changed_path::ChangedPathIndex;
 use super::composite::AsCompositeIndex;
 use super::composite::ChangeIdIndexImpl;
 use super::composite::CompositeIndex;
@@ -588,21 +589,30 @@ impl IndexSegment for ReadonlyIndexSegment {
 
 /// Commit index backend which stores data on local disk.
 #[derive(Clone, Debug)]
-pub struct DefaultReadonlyIndex(Arc<ReadonlyIndexSegment>);
+pub struct DefaultReadonlyIndex {
+    segment: Arc<ReadonlyIndexSegment>,
+    changed_paths: Option<Arc<ChangedPathIndex>>,
+}
 
 impl DefaultReadonlyIndex {
-    pub(super) fn from_segment(segment: Arc<ReadonlyIndexSegment>) -> Self {
-        DefaultReadonlyIndex(segment)
+    pub(super) fn from_segment(
+        segment: Arc<ReadonlyIndexSegment>,
+        changed_paths: Option<Arc<ChangedPathIndex>>,
+    ) -> Self {
+        DefaultReadonlyIndex {
+            segment,
+            changed_paths,
+        }
     }
 
     pub(super) fn as_segment(&self) -> &Arc<ReadonlyIndexSegment> {
-        &self.0
+        &self.segment
     }
 }
 
 impl AsCompositeIndex for DefaultReadonlyIndex {
     fn as_composite(&self) -> &CompositeIndex {
-        self.0.as_composite()
+        self.segment.as_composite()
     }
 }
 
@@ -646,7 +656,8 @@ impl Index for DefaultReadonlyIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        self.as_composite().evaluate_revset(expression, store)
+        self.as_composite()
+            .evaluate_revset(expression, store, self.changed_paths.as_ref())
     }
 }
 
@@ -667,7 +678,11 @@ impl ReadonlyIndex for DefaultReadonlyIndex {
     }
 
     fn start_modification(&self) -> Box<dyn MutableIndex> {
-        Box::new(DefaultMutableIndex::incremental(self.0.clone()))
+        let mut mutable_index = DefaultMutableIndex::incremental(self.segment.clone());
+        if let Some(changed_paths) = &self.changed_paths {
+            mutable_index.set_changed_paths(changed_paths.clone());
+        }
+        Box::new(mutable_index)
     }
 }
 
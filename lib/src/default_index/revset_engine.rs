@@ -31,6 +31,7 @@ use futures::StreamExt as _;
 use itertools::Itertools;
 use pollster::FutureExt as _;
 
+use super::changed_path::ChangedPathIndex;
 use super::rev_walk::EagerRevWalk;
 use super::rev_walk::PeekableRevWalk;
 use super::rev_walk::RevWalk;
@@ -61,10 +62,14 @@ use crate::revset::Revset;
 use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetFilterPredicate;
+use crate::revset::SignatureFilter;
 use crate::revset::GENERATION_RANGE_FULL;
 use crate::rewrite;
+use crate::signing::SigStatus;
+use crate::signing::SignError;
 use crate::store::Store;
 use crate::str_util::StringPattern;
+use crate::trailer::parse_description_trailers;
 use crate::union_find;
 
 type BoxedPredicateFn<'a> =
@@ -765,10 +770,22 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
     expression: &ResolvedExpression,
     store: &Arc<Store>,
     index: I,
+) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
+    evaluate_with_changed_paths(expression, store, index, None)
+}
+
+/// Like [`evaluate()`], but uses the changed-path index to filter commits by
+/// files.
+pub(super) fn evaluate_with_changed_paths<I: AsCompositeIndex + Clone>(
+    expression: &ResolvedExpression,
+    store: &Arc<Store>,
+    index: I,
+    changed_paths: Option<&Arc<ChangedPathIndex>>,
 ) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
     let context = EvaluationContext {
         store: store.clone(),
         index: index.as_composite(),
+        changed_paths: changed_paths.cloned(),
     };
     let internal_revset = context.evaluate(expression)?;
     Ok(RevsetImpl::new(internal_revset, index))
@@ -777,6 +794,7 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
 struct EvaluationContext<'index> {
     store: Arc<Store>,
     index: &'index CompositeIndex,
+    changed_paths: Option<Arc<ChangedPathIndex>>,
 }
 
 fn to_u32_generation_range(range: &Range<u64>) -> Result<Range<u32>, RevsetEvaluationError> {
@@ -1012,9 +1030,11 @@ impl EvaluationContext<'_> {
         expression: &ResolvedPredicateExpression,
     ) -> Result<Box<dyn ToPredicateFn>, RevsetEvaluationError> {
         match expression {
-            ResolvedPredicateExpression::Filter(predicate) => {
-                Ok(build_predicate_fn(self.store.clone(), predicate))
-            }
+            ResolvedPredicateExpression::Filter(predicate) => Ok(build_predicate_fn(
+                self.store.clone(),
+                self.changed_paths.clone(),
+                predicate,
+            )),
             ResolvedPredicateExpression::Set(expression) => {
                 Ok(self.evaluate(expression)?.into_predicate())
             }
@@ -1143,6 +1163,7 @@ fn box_pure_predicate_fn<'a>(
 
 fn build_predicate_fn(
     store: Arc<Store>,
+    changed_paths: Option<Arc<ChangedPathIndex>>,
     predicate: &RevsetFilterPredicate,
 ) -> Box<dyn ToPredicateFn> {
     match predicate {
@@ -1169,6 +1190,18 @@ fn build_predicate_fn(
                 Ok(pattern.matches(commit.description().lines().next().unwrap_or_default()))
             })
         }
+        RevsetFilterPredicate::Trailer { key, value } => {
+            let key = key.clone();
+            let value = value.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let trailers = parse_description_trailers(commit.description());
+                Ok(trailers
+                    .iter()
+                    .any(|trailer| trailer.has_key(&key) && value.matches(&trailer.value)))
+            })
+        }
         RevsetFilterPredicate::AuthorName(pattern) => {
             let pattern = pattern.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1222,6 +1255,9 @@ fn build_predicate_fn(
         RevsetFilterPredicate::File(expr) => {
             let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
             box_pure_predicate_fn(move |index, pos| {
+                if let Some(mut paths) = lookup_changed_paths(&changed_paths, pos) {
+                    return Ok(paths.any(|path| matcher.matches(path)));
+                }
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(has_diff_from_parent(&store, index, &commit, &*matcher)?)
@@ -1231,6 +1267,12 @@ fn build_predicate_fn(
             let text_pattern = text.clone();
             let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
             box_pure_predicate_fn(move |index, pos| {
+                // Skip commits which don't touch the files at all
+                if let Some(mut paths) = lookup_changed_paths(&changed_paths, pos) {
+                    if !paths.any(|path| files_matcher.matches(path)) {
+                        return Ok(false);
+                    }
+                }
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(matches_diff_from_parent(
@@ -1247,6 +1289,14 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.has_conflict()?)
         }),
+        RevsetFilterPredicate::Signed(filter) => {
+            let filter = filter.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                matches_signature(&commit, &filter)
+            })
+        }
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1258,6 +1308,54 @@ fn build_predicate_fn(
     }
 }
 
+fn lookup_changed_paths(
+    changed_paths: &Option<Arc<ChangedPathIndex>>,
+    pos: IndexPosition,
+) -> Option<impl Iterator<Item = &RepoPath>> {
+    changed_paths.as_ref()?.changed_paths(pos)
+}
+
+/// Checks if the `commit` signature matches the `filter`. The signature is
+/// verified only if needed, and the result is cached by the signer.
+///
+/// Only signatures rejected by the signing backend are considered invalid.
+/// Other verification errors are propagated.
+fn matches_signature(
+    commit: &Commit,
+    filter: &SignatureFilter,
+) -> Result<bool, RevsetEvaluationError> {
+    if !commit.is_signed() {
+        return Ok(false);
+    }
+    if let SignatureFilter::Any = filter {
+        return Ok(true);
+    }
+    let verification = match commit.verification() {
+        Ok(verification) => verification,
+        Err(SignError::InvalidSignatureFormat) => {
+            return Ok(matches!(filter, SignatureFilter::Invalid));
+        }
+        Err(err) => return Err(RevsetEvaluationError::Other(err.into())),
+    };
+    let Some(verification) = verification else {
+        return Ok(false);
+    };
+    let matches = match filter {
+        SignatureFilter::Any => true,
+        SignatureFilter::Status(status) => verification.status == *status,
+        SignatureFilter::Invalid => false,
+        // Bad signatures may claim any key or signer
+        SignatureFilter::Key(pattern) => {
+            verification.status == SigStatus::Good
+                && [verification.key, verification.display]
+                    .iter()
+                    .flatten()
+                    .any(|text| pattern.matches(text))
+        }
+    };
+    Ok(matches)
+}
+
 fn has_diff_from_parent(
     store: &Arc<Store>,
     index: &CompositeIndex,
@@ -27,6 +27,12 @@ use itertools::Itertools;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+use super::changed_path;
+use super::changed_path::collect_changed_paths;
+use super::changed_path::ChangedPathIndex;
+use super::changed_path::ChangedPathSegment;
+use super::composite::IndexSegment as _;
+use super::entry::LocalPosition;
 use super::mutable::DefaultMutableIndex;
 use super::readonly::DefaultReadonlyIndex;
 use super::readonly::ReadonlyIndexLoadError;
@@ -84,6 +90,10 @@ pub enum DefaultIndexStoreError {
         op_id: OperationId,
         source: BackendError,
     },
+    #[error("Failed to index changed paths")]
+    IndexChangedPaths(#[source] BackendError),
+    #[error("Failed to write changed-path index file")]
+    SaveChangedPaths(#[source] io::Error),
     #[error(transparent)]
     OpStore(#[from] OpStoreError),
 }
@@ -120,6 +130,9 @@ impl DefaultIndexStore {
         // Remove index segments to save disk space. If raced, new segment file
         // will be created by the other process.
         file_util::remove_dir_contents(&self.segments_dir())?;
+        if self.is_changed_path_index_enabled() {
+            file_util::remove_dir_contents(&self.changed_paths_dir())?;
+        }
         // jj <= 0.14 created segment files in the top directory
         for entry in self.dir.read_dir().context(&self.dir)? {
             let entry = entry.context(&self.dir)?;
@@ -148,6 +161,130 @@ impl DefaultIndexStore {
         self.dir.join("segments")
     }
 
+    fn changed_paths_dir(&self) -> PathBuf {
+        self.dir.join("changed_paths")
+    }
+
+    /// Returns true if the paths changed by commits are indexed.
+    pub fn is_changed_path_index_enabled(&self) -> bool {
+        self.changed_paths_dir().is_dir()
+    }
+
+    /// Enables indexing of the paths changed by commits.
+    ///
+    /// The changed paths of the existing commits will be indexed by
+    /// `build_index_at_operation()`. Commits indexed later aren't covered
+    /// until the index is rebuilt, but the indexed commits stay covered when
+    /// the index segments are squashed.
+    pub fn enable_changed_path_index(&self) -> Result<(), DefaultIndexStoreInitError> {
+        let dir = self.changed_paths_dir();
+        file_util::create_or_reuse_dir(&dir).context(&dir)?;
+        Ok(())
+    }
+
+    /// Disables indexing of the paths changed by commits, and removes the
+    /// existing changed-path index files.
+    pub fn disable_changed_path_index(&self) -> Result<(), DefaultIndexStoreInitError> {
+        let dir = self.changed_paths_dir();
+        if dir.is_dir() {
+            fs::remove_dir_all(&dir).context(&dir)?;
+        }
+        Ok(())
+    }
+
+    /// Loads changed-path index for the `index_segment`. Segments not yet
+    /// indexed aren't covered.
+    fn load_changed_path_index(
+        &self,
+        index_segment: &Arc<ReadonlyIndexSegment>,
+    ) -> Option<Arc<ChangedPathIndex>> {
+        self.is_changed_path_index_enabled().then(|| {
+            Arc::new(ChangedPathIndex::load(
+                &self.changed_paths_dir(),
+                index_segment,
+            ))
+        })
+    }
+
+    /// Computes and saves the changed paths of the commits not yet indexed.
+    ///
+    /// This diffs the trees of all commits not yet indexed, so it shouldn't be
+    /// called when the index is loaded for ordinary commands.
+    fn build_changed_path_index(
+        &self,
+        index_segment: &Arc<ReadonlyIndexSegment>,
+        store: &Arc<Store>,
+    ) -> Result<(), DefaultIndexStoreError> {
+        let dir = self.changed_paths_dir();
+        for segment in changed_path::readonly_segments(index_segment) {
+            let file_path = dir.join(segment.name());
+            // Corrupted file will be rebuilt.
+            let existing = file_path
+                .is_file()
+                .then(|| ChangedPathSegment::load(&file_path).ok())
+                .flatten()
+                .filter(|data| data.num_local_commits() == segment.num_local_commits());
+            if existing.as_ref().is_some_and(|data| data.is_complete()) {
+                continue;
+            }
+            let entries: Vec<_> = (0..segment.num_local_commits())
+                .map(|local_pos| {
+                    if let Some(paths) = existing
+                        .as_ref()
+                        .and_then(|data| data.changed_paths(local_pos))
+                    {
+                        return Ok(Some(paths.map(ToOwned::to_owned).collect()));
+                    }
+                    let commit = store.get_commit(&segment.commit_id(LocalPosition(local_pos)))?;
+                    collect_changed_paths(segment.as_composite(), &commit).map(Some)
+                })
+                .try_collect()
+                .map_err(DefaultIndexStoreError::IndexChangedPaths)?;
+            tracing::info!(
+                segment = segment.name(),
+                commits_count = entries.len(),
+                "indexed changed paths"
+            );
+            ChangedPathSegment::from_entries(&entries)
+                .save_in(&dir, segment.name())
+                .map_err(DefaultIndexStoreError::SaveChangedPaths)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the changed paths of the new `index_segment` which are known to
+    /// the changed-path index of the `old_parent_file` segment.
+    ///
+    /// If the new segment was squashed with its ancestors, the changed paths
+    /// of the squashed commits are carried forward. The other commits are
+    /// recorded as not indexed.
+    fn carry_forward_changed_paths(
+        &self,
+        index_segment: &Arc<ReadonlyIndexSegment>,
+        old_parent_file: &Arc<ReadonlyIndexSegment>,
+    ) -> Result<(), DefaultIndexStoreError> {
+        let dir = self.changed_paths_dir();
+        if dir.join(index_segment.name()).is_file() {
+            return Ok(());
+        }
+        let old_changed_paths = ChangedPathIndex::load(&dir, old_parent_file);
+        let old_index = old_parent_file.as_composite();
+        let entries = (0..index_segment.num_local_commits())
+            .map(|local_pos| {
+                let commit_id = index_segment.commit_id(LocalPosition(local_pos));
+                let pos = old_index.commit_id_to_pos(&commit_id)?;
+                let paths = old_changed_paths.changed_paths(pos)?;
+                Some(paths.map(ToOwned::to_owned).collect_vec())
+            })
+            .collect_vec();
+        if entries.iter().all(Option::is_none) {
+            return Ok(());
+        }
+        ChangedPathSegment::from_entries(&entries)
+            .save_in(&dir, index_segment.name())
+            .map_err(DefaultIndexStoreError::SaveChangedPaths)
+    }
+
     fn load_index_segments_at_operation(
         &self,
         op_id: &OperationId,
@@ -170,13 +307,23 @@ impl DefaultIndexStore {
     ///
     /// The index to be built will be calculated from one of the ancestor
     /// operations if exists. Use `reinit()` to rebuild index from scratch.
+    ///
+    /// If the changed-path index is enabled, the changed paths of the commits
+    /// not yet indexed are also computed.
     pub fn build_index_at_operation(
         &self,
         operation: &Operation,
         store: &Arc<Store>,
     ) -> Result<DefaultReadonlyIndex, DefaultIndexStoreError> {
         let index_segment = self.build_index_segments_at_operation(operation, store)?;
-        Ok(DefaultReadonlyIndex::from_segment(index_segment))
+        if self.is_changed_path_index_enabled() {
+            self.build_changed_path_index(&index_segment, store)?;
+        }
+        let changed_paths = self.load_changed_path_index(&index_segment);
+        Ok(DefaultReadonlyIndex::from_segment(
+            index_segment,
+            changed_paths,
+        ))
     }
 
     #[tracing::instrument(skip(self, store))]
@@ -288,9 +435,15 @@ impl DefaultIndexStore {
         mutable_index: DefaultMutableIndex,
         op_id: &OperationId,
     ) -> Result<Arc<ReadonlyIndexSegment>, DefaultIndexStoreError> {
+        let old_parent_file = mutable_index.parent_file().cloned();
         let index_segment = mutable_index
             .squash_and_save_in(&self.segments_dir())
             .map_err(DefaultIndexStoreError::SaveIndex)?;
+        if let Some(old_parent_file) = &old_parent_file {
+            if self.is_changed_path_index_enabled() {
+                self.carry_forward_changed_paths(&index_segment, old_parent_file)?;
+            }
+        }
         self.associate_file_with_operation(&index_segment, op_id)
             .map_err(|source| DefaultIndexStoreError::AssociateIndex {
                 op_id: op_id.to_owned(),
@@ -361,7 +514,11 @@ impl IndexStore for DefaultIndexStore {
             result => result,
         }
         .map_err(|err| IndexReadError(err.into()))?;
-        Ok(Box::new(DefaultReadonlyIndex::from_segment(index_segment)))
+        let changed_paths = self.load_changed_path_index(&index_segment);
+        Ok(Box::new(DefaultReadonlyIndex::from_segment(
+            index_segment,
+            changed_paths,
+        )))
     }
 
     fn write_index(
@@ -376,6 +533,10 @@ impl IndexStore for DefaultIndexStore {
         let index_segment = self
             .save_mutable_index(*index, op.id())
             .map_err(|err| IndexWriteError(err.into()))?;
-        Ok(Box::new(DefaultReadonlyIndex::from_segment(index_segment)))
+        let changed_paths = self.load_changed_path_index(&index_segment);
+        Ok(Box::new(DefaultReadonlyIndex::from_segment(
+            index_segment,
+            changed_paths,
+        )))
     }
 }
@@ -14,38 +14,204 @@
 
 #![allow(missing_docs)]
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
 
+use pollster::FutureExt as _;
+
+use crate::backend;
+use crate::backend::CommitId;
+use crate::backend::MergedTreeId;
+use crate::backend::TreeId;
+#[cfg(feature = "git")]
+use crate::git_backend::GitBackend;
+#[cfg(feature = "git")]
+use crate::merge_driver::MergeDrivers;
+use crate::pointer_file::PointerStore;
+use crate::settings::UserSettings;
+#[cfg(feature = "git")]
+use crate::signing::Signer;
+use crate::store::Store;
 use crate::submodule_store::SubmoduleStore;
+use crate::submodule_store::SubmoduleStoreError;
+use crate::submodule_store::SubmoduleStoreResult;
 
+/// Stores each submodule as a Git-backed store in a subdirectory named after
+/// the hex-encoded submodule name.
 #[derive(Debug)]
 pub struct DefaultSubmoduleStore {
-    #[allow(dead_code)]
     path: PathBuf,
+    settings: UserSettings,
+    stores: Mutex<HashMap<String, Arc<Store>>>,
 }
 
 impl DefaultSubmoduleStore {
     /// Load an existing SubmoduleStore
-    pub fn load(store_path: &Path) -> Self {
+    pub fn load(settings: &UserSettings, store_path: &Path) -> Self {
         DefaultSubmoduleStore {
             path: store_path.to_path_buf(),
+            settings: settings.clone(),
+            stores: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn init(store_path: &Path) -> Self {
+    pub fn init(settings: &UserSettings, store_path: &Path) -> Self {
         DefaultSubmoduleStore {
             path: store_path.to_path_buf(),
+            settings: settings.clone(),
+            stores: Mutex::new(HashMap::new()),
         }
     }
 
     pub fn name() -> &'static str {
         "default"
     }
+
+    fn submodule_path(&self, name: &str) -> PathBuf {
+        self.path.join(hex::encode(name))
+    }
+
+    #[cfg(feature = "git")]
+    fn load_store(&self, name: &str, init: bool) -> SubmoduleStoreResult<Option<Arc<Store>>> {
+        let mut stores = self.stores.lock().unwrap();
+        if let Some(store) = stores.get(name) {
+            return Ok(Some(store.clone()));
+        }
+        let store_path = self.submodule_path(name);
+        let backend = if store_path.exists() {
+            GitBackend::load(&self.settings, &store_path).map_err(|err| {
+                SubmoduleStoreError::Other {
+                    message: format!("Failed to load submodule {name}"),
+                    source: err,
+                }
+            })?
+        } else if init {
+            std::fs::create_dir_all(&store_path).map_err(|err| SubmoduleStoreError::Other {
+                message: format!("Failed to create directory {}", store_path.display()),
+                source: err.into(),
+            })?;
+            GitBackend::init_internal(&self.settings, &store_path).map_err(|err| {
+                SubmoduleStoreError::Other {
+                    message: format!("Failed to initialize submodule {name}"),
+                    source: err,
+                }
+            })?
+        } else {
+            return Ok(None);
+        };
+        let signer =
+            Signer::from_settings(&self.settings).map_err(|err| SubmoduleStoreError::Other {
+                message: "Failed to initialize signing".to_owned(),
+                source: err.into(),
+            })?;
+        let merge_drivers = MergeDrivers::from_settings(&self.settings).map_err(|err| {
+            SubmoduleStoreError::Other {
+                message: "Failed to load merge drivers".to_owned(),
+                source: err.into(),
+            }
+        })?;
+        let pointer_store = PointerStore::new(store_path.join("pointers"));
+        let store = Store::new(
+            Box::new(backend),
+            signer,
+            merge_drivers,
+            None,
+            pointer_store,
+        );
+        stores.insert(name.to_owned(), store.clone());
+        Ok(Some(store))
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn load_store(&self, _name: &str, init: bool) -> SubmoduleStoreResult<Option<Arc<Store>>> {
+        if init {
+            Err(SubmoduleStoreError::Other {
+                message: "Failed to initialize submodule".to_owned(),
+                source: "Git submodules require jj to be compiled with the `git` feature".into(),
+            })
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 impl SubmoduleStore for DefaultSubmoduleStore {
     fn name(&self) -> &str {
         Self::name()
     }
+
+    fn get_submodule(&self, name: &str) -> SubmoduleStoreResult<Option<Arc<Store>>> {
+        self.load_store(name, false)
+    }
+
+    fn init_submodule(&self, name: &str) -> SubmoduleStoreResult<Arc<Store>> {
+        Ok(self.load_store(name, true)?.unwrap())
+    }
+
+    #[cfg(feature = "git")]
+    fn fetch_submodule(&self, name: &str, url: &str) -> SubmoduleStoreResult<()> {
+        let store =
+            self.get_submodule(name)?
+                .ok_or_else(|| SubmoduleStoreError::NotInitialized {
+                    name: name.to_owned(),
+                })?;
+        let fetch_error = |err: git2::Error| SubmoduleStoreError::Fetch {
+            name: name.to_owned(),
+            url: url.to_owned(),
+            source: err.into(),
+        };
+        let git_backend = crate::git::get_git_backend(&store).unwrap();
+        let git_repo = git_backend.open_git_repo().map_err(fetch_error)?;
+        let mut remote = git_repo.remote_anonymous(url).map_err(fetch_error)?;
+        remote
+            .fetch(
+                &[
+                    "+refs/heads/*:refs/remotes/origin/*",
+                    "+refs/tags/*:refs/tags/*",
+                ],
+                None,
+                None,
+            )
+            .map_err(fetch_error)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn fetch_submodule(&self, name: &str, _url: &str) -> SubmoduleStoreResult<()> {
+        Err(SubmoduleStoreError::NotInitialized {
+            name: name.to_owned(),
+        })
+    }
+
+    fn write_submodule_commit(
+        &self,
+        name: &str,
+        parent_id: &CommitId,
+        tree_id: &TreeId,
+    ) -> SubmoduleStoreResult<CommitId> {
+        let store =
+            self.get_submodule(name)?
+                .ok_or_else(|| SubmoduleStoreError::NotInitialized {
+                    name: name.to_owned(),
+                })?;
+        let signature = self.settings.signature();
+        let commit = backend::Commit {
+            parents: vec![parent_id.clone()],
+            predecessors: vec![],
+            root_tree: MergedTreeId::resolved(tree_id.clone()),
+            change_id: self
+                .settings
+                .get_rng()
+                .new_change_id(store.change_id_length()),
+            description: String::new(),
+            author: signature.clone(),
+            committer: signature,
+            secure_sig: None,
+        };
+        let commit = store.write_commit(commit, None).block_on()?;
+        Ok(commit.id().clone())
+    }
 }
@@ -40,6 +40,10 @@ pub enum FsmonitorSettings {
     /// The Watchman filesystem monitor (<https://facebook.github.io/watchman/>).
     Watchman(WatchmanConfig),
 
+    /// The built-in filesystem monitor based on Linux inotify. Changes are
+    /// recorded by a background daemon.
+    Inotify,
+
     /// Only used in tests.
     Test {
         /// The set of changed files to pretend that the filesystem monitor is
@@ -63,6 +67,7 @@ impl FsmonitorSettings {
                 // TODO: rename to "register-snapshot-trigger" for consistency?
                 register_trigger: settings.get_bool("core.watchman.register_snapshot_trigger")?,
             })),
+            "inotify" => Ok(Self::Inotify),
             "test" => Err(ConfigGetError::Type {
                 name: name.to_owned(),
                 error: "Cannot use test fsmonitor in real repository".into(),
@@ -334,3 +339,575 @@ pub mod watchman {
         }
     }
 }
+
+/// Built-in filesystem monitor based on Linux inotify.
+///
+/// A daemon process watches every directory in the working copy and appends
+/// the changed paths to a journal file in the working-copy state directory.
+/// Clients synchronize with the daemon by creating a cookie file, and read the
+/// paths recorded between their previous clock and the cookie.
+#[cfg(target_os = "linux")]
+pub mod inotify {
+    use std::collections::HashMap;
+    use std::ffi::OsStr;
+    use std::fs;
+    use std::fs::File;
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::io::BufRead as _;
+    use std::io::BufReader;
+    use std::io::BufWriter;
+    use std::io::Read as _;
+    use std::io::Seek as _;
+    use std::io::SeekFrom;
+    use std::io::Write as _;
+    use std::mem::MaybeUninit;
+    use std::os::fd::AsFd as _;
+    use std::os::fd::BorrowedFd;
+    use std::os::unix::ffi::OsStrExt as _;
+    use std::os::unix::process::CommandExt as _;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::process::Command;
+    use std::process::Stdio;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+    use std::time::SystemTime;
+
+    use itertools::Itertools as _;
+    use rustix::fs::inotify::CreateFlags;
+    use rustix::fs::inotify::ReadFlags;
+    use rustix::fs::inotify::WatchFlags;
+    use rustix::fs::FlockOperation;
+    use rustix::io::Errno;
+    use thiserror::Error;
+    use tracing::info;
+    use tracing::instrument;
+
+    const JOURNAL_MAGIC: &[u8] = b"jj-inotify-journal ";
+    /// The journal is restarted as a new instance once it grows beyond this
+    /// size. Clients will then crawl the working copy once.
+    const MAX_JOURNAL_SIZE: u64 = 16 << 20;
+    const SYNC_TIMEOUT: Duration = Duration::from_secs(5);
+    const STOP_COOKIE: &str = "stop";
+    const EXCLUDED_ROOT_DIRS: [&str; 2] = [".git", ".jj"];
+
+    /// Represents an instance in time from the perspective of the filesystem
+    /// monitor.
+    ///
+    /// This is a position in the journal of a particular daemon instance. If
+    /// the daemon has been restarted since, the clock can't be used for
+    /// incremental queries.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Clock {
+        instance: String,
+        offset: u64,
+    }
+
+    impl From<crate::protos::working_copy::InotifyClock> for Clock {
+        fn from(clock: crate::protos::working_copy::InotifyClock) -> Self {
+            Self {
+                instance: clock.instance,
+                offset: clock.offset,
+            }
+        }
+    }
+
+    impl From<Clock> for crate::protos::working_copy::InotifyClock {
+        fn from(clock: Clock) -> Self {
+            Self {
+                instance: clock.instance,
+                offset: clock.offset,
+            }
+        }
+    }
+
+    #[allow(missing_docs)]
+    #[derive(Debug, Error)]
+    pub enum Error {
+        #[error("Failed to access filesystem monitor state {0}")]
+        StateError(PathBuf, #[source] io::Error),
+
+        #[error("Corrupt filesystem monitor journal {0}")]
+        CorruptJournal(PathBuf),
+
+        #[error("The filesystem monitor daemon is already running")]
+        AlreadyRunning,
+
+        #[error("Failed to set up inotify")]
+        InitError(#[source] io::Error),
+
+        #[error(
+            "Failed to watch directory {0} (consider raising the `fs.inotify.max_user_watches` \
+             sysctl)"
+        )]
+        WatchError(PathBuf, #[source] io::Error),
+
+        #[error("Failed to read filesystem events")]
+        ReadEventsError(#[source] io::Error),
+
+        #[error("Timed out waiting for the filesystem monitor daemon")]
+        SyncTimeout,
+
+        #[error("Failed to start the filesystem monitor daemon")]
+        SpawnError(#[source] io::Error),
+    }
+
+    type ClockAndChangedFiles = (Clock, Option<Vec<PathBuf>>);
+
+    /// Handle to the inotify filesystem monitor of a working copy.
+    pub struct Fsmonitor {
+        working_copy_path: PathBuf,
+        state_dir: PathBuf,
+    }
+
+    impl Fsmonitor {
+        /// Creates a handle for the working copy at `working_copy_path`. The
+        /// daemon keeps its lock, log, and journal in `state_dir`.
+        pub fn new(working_copy_path: &Path, state_dir: &Path) -> Self {
+            Fsmonitor {
+                working_copy_path: working_copy_path.to_owned(),
+                state_dir: state_dir.to_owned(),
+            }
+        }
+
+        /// The directory where the daemon keeps its state.
+        pub fn state_dir(&self) -> &Path {
+            &self.state_dir
+        }
+
+        /// The file the daemon's output is redirected to when started by
+        /// [`Fsmonitor::start_daemon()`].
+        pub fn log_path(&self) -> PathBuf {
+            self.state_dir.join("daemon.log")
+        }
+
+        fn lock_path(&self) -> PathBuf {
+            self.state_dir.join("daemon.lock")
+        }
+
+        fn journal_path(&self) -> PathBuf {
+            self.state_dir.join("journal")
+        }
+
+        fn cookies_dir(&self) -> PathBuf {
+            self.state_dir.join("cookies")
+        }
+
+        fn state_error(&self, path: &Path) -> impl FnOnce(io::Error) -> Error {
+            let path = path.to_owned();
+            move |err| Error::StateError(path, err)
+        }
+
+        /// Returns whether the daemon is running for this working copy.
+        pub fn is_daemon_running(&self) -> Result<bool, Error> {
+            let lock_path = self.lock_path();
+            let file = match File::open(&lock_path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+                Err(err) => return Err(Error::StateError(lock_path, err)),
+            };
+            match rustix::fs::flock(&file, FlockOperation::NonBlockingLockShared) {
+                Ok(()) => Ok(false),
+                Err(Errno::WOULDBLOCK) => Ok(true),
+                Err(err) => Err(Error::StateError(lock_path, err.into())),
+            }
+        }
+
+        /// Returns the process ID of the running daemon, if any.
+        pub fn daemon_pid(&self) -> Result<Option<u32>, Error> {
+            if !self.is_daemon_running()? {
+                return Ok(None);
+            }
+            let lock_path = self.lock_path();
+            let content = fs::read_to_string(&lock_path).map_err(self.state_error(&lock_path))?;
+            Ok(content.trim().parse().ok())
+        }
+
+        /// Spawns `command` as a detached process that is expected to run the
+        /// daemon by calling [`Fsmonitor::run_daemon()`]. Doesn't wait for
+        /// the daemon to become ready.
+        #[instrument(skip(self))]
+        pub fn start_daemon(&self, mut command: Command) -> Result<(), Error> {
+            info!("Starting inotify filesystem monitor daemon...");
+            fs::create_dir_all(&self.state_dir).map_err(self.state_error(&self.state_dir))?;
+            let log_path = self.log_path();
+            let log_file = File::create(&log_path).map_err(self.state_error(&log_path))?;
+            let log_file_clone = log_file.try_clone().map_err(self.state_error(&log_path))?;
+            command
+                .stdin(Stdio::null())
+                .stdout(log_file)
+                .stderr(log_file_clone)
+                // Don't receive signals sent to the terminal's process group.
+                .process_group(0);
+            // The daemon outlives us, so there's no point in waiting for it.
+            #[allow(clippy::zombie_processes)]
+            command.spawn().map_err(Error::SpawnError)?;
+            Ok(())
+        }
+
+        /// Asks the running daemon to exit, and waits for it. Returns whether
+        /// the daemon was running.
+        #[instrument(skip(self))]
+        pub fn stop_daemon(&self) -> Result<bool, Error> {
+            if !self.is_daemon_running()? {
+                return Ok(false);
+            }
+            let stop_path = self.cookies_dir().join(STOP_COOKIE);
+            File::create(&stop_path).map_err(self.state_error(&stop_path))?;
+            let deadline = Instant::now() + SYNC_TIMEOUT;
+            while self.is_daemon_running()? {
+                if Instant::now() >= deadline {
+                    return Err(Error::SyncTimeout);
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            Ok(true)
+        }
+
+        /// Query for changed files since the previous point in time.
+        ///
+        /// The returned list of paths is relative to the `working_copy_path`.
+        /// A path may refer to a directory, in which case anything below it
+        /// may have changed. If the list is `None`, then the caller must crawl
+        /// the entire working copy themselves. The returned clock is `None` if
+        /// the daemon isn't running.
+        #[instrument(skip(self))]
+        pub fn query_changed_files(
+            &self,
+            previous_clock: Option<Clock>,
+        ) -> Result<(Option<Clock>, Option<Vec<PathBuf>>), Error> {
+            if !self.is_daemon_running()? {
+                info!("The inotify filesystem monitor daemon isn't running");
+                return Ok((None, None));
+            }
+            info!("Querying inotify filesystem monitor for changed files...");
+            let cookie = format!("{}-{}", std::process::id(), timestamp_nanos());
+            let cookie_path = self.cookies_dir().join(&cookie);
+            File::create(&cookie_path).map_err(self.state_error(&cookie_path))?;
+            let deadline = Instant::now() + SYNC_TIMEOUT;
+            let mut delay = Duration::from_millis(1);
+            let result = loop {
+                match self.read_journal(previous_clock.as_ref(), cookie.as_bytes()) {
+                    Ok(Some((clock, paths))) => break Ok((Some(clock), paths)),
+                    Ok(None) if Instant::now() < deadline => {}
+                    Ok(None) => break Err(Error::SyncTimeout),
+                    Err(err) => break Err(err),
+                }
+                thread::sleep(delay);
+                delay = (delay * 2).min(Duration::from_millis(20));
+            };
+            // The daemon deletes the cookies it has seen, but not if it has
+            // died in the meantime.
+            fs::remove_file(&cookie_path).ok();
+            result
+        }
+
+        /// Reads the journal up to the given `cookie`. Returns `None` if the
+        /// daemon hasn't recorded the cookie yet.
+        fn read_journal(
+            &self,
+            previous_clock: Option<&Clock>,
+            cookie: &[u8],
+        ) -> Result<Option<ClockAndChangedFiles>, Error> {
+            let journal_path = self.journal_path();
+            let file = match File::open(&journal_path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(Error::StateError(journal_path, err)),
+            };
+            let mut reader = BufReader::new(file);
+            let mut header = vec![];
+            reader
+                .read_until(0, &mut header)
+                .map_err(self.state_error(&journal_path))?;
+            let Some(instance) = header
+                .strip_prefix(JOURNAL_MAGIC)
+                .and_then(|rest| rest.strip_suffix(b"\0"))
+                .and_then(|instance| String::from_utf8(instance.to_vec()).ok())
+            else {
+                return Err(Error::CorruptJournal(journal_path));
+            };
+            let header_len = header.len() as u64;
+            let incremental_offset = previous_clock
+                .filter(|clock| clock.instance == instance && clock.offset >= header_len)
+                .map(|clock| clock.offset);
+            let mut offset = incremental_offset.unwrap_or(header_len);
+            let mut data = vec![];
+            reader
+                .seek(SeekFrom::Start(offset))
+                .and_then(|_| reader.read_to_end(&mut data))
+                .map_err(self.state_error(&journal_path))?;
+
+            let mut paths = vec![];
+            // An incomplete record at the end is still being written.
+            for record in data.split_inclusive(|&b| b == 0) {
+                let Some(record) = record.strip_suffix(b"\0") else {
+                    break;
+                };
+                offset += record.len() as u64 + 1;
+                if let Some(name) = record.strip_prefix(b"/") {
+                    if name == cookie {
+                        let clock = Clock { instance, offset };
+                        let paths =
+                            incremental_offset.map(|_| paths.into_iter().unique().collect());
+                        return Ok(Some((clock, paths)));
+                    }
+                } else if incremental_offset.is_some() {
+                    paths.push(PathBuf::from(OsStr::from_bytes(record)));
+                }
+            }
+            Ok(None)
+        }
+
+        /// Runs the daemon in the current process until it's asked to stop,
+        /// or until the working copy is deleted.
+        #[instrument(skip(self))]
+        pub fn run_daemon(&self) -> Result<(), Error> {
+            let cookies_dir = self.cookies_dir();
+            fs::create_dir_all(&cookies_dir).map_err(self.state_error(&cookies_dir))?;
+            let lock_path = self.lock_path();
+            let mut lock_file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&lock_path)
+                .map_err(self.state_error(&lock_path))?;
+            // Clients briefly take shared locks to check if we're running.
+            let mut attempts = 0;
+            loop {
+                match rustix::fs::flock(&lock_file, FlockOperation::NonBlockingLockExclusive) {
+                    Ok(()) => break,
+                    Err(Errno::WOULDBLOCK) if attempts < 10 => {
+                        attempts += 1;
+                        thread::sleep(Duration::from_millis(10));
+                    }
+                    Err(Errno::WOULDBLOCK) => return Err(Error::AlreadyRunning),
+                    Err(err) => return Err(Error::StateError(lock_path, err.into())),
+                }
+            }
+            lock_file
+                .set_len(0)
+                .and_then(|()| writeln!(lock_file, "{}", std::process::id()))
+                .map_err(self.state_error(&lock_path))?;
+            // Stale cookies would otherwise be taken as new requests.
+            for entry in fs::read_dir(&cookies_dir).map_err(self.state_error(&cookies_dir))? {
+                let entry = entry.map_err(self.state_error(&cookies_dir))?;
+                fs::remove_file(entry.path()).ok();
+            }
+
+            let inotify_fd = rustix::fs::inotify::init(CreateFlags::CLOEXEC)
+                .map_err(|err| Error::InitError(err.into()))?;
+            let cookies_wd = rustix::fs::inotify::add_watch(
+                &inotify_fd,
+                &cookies_dir,
+                WatchFlags::CREATE | WatchFlags::ONLYDIR,
+            )
+            .map_err(|err| Error::WatchError(cookies_dir.clone(), err.into()))?;
+            let mut daemon = Daemon {
+                fsmonitor: self,
+                inotify_fd: inotify_fd.as_fd(),
+                watches: HashMap::new(),
+                journal: None,
+                journal_size: 0,
+            };
+            info!("Watching {}", self.working_copy_path.display());
+            let root_wd = daemon.watch_recursive(Path::new(""))?.ok_or_else(|| {
+                let err = io::Error::from(io::ErrorKind::NotFound);
+                Error::WatchError(self.working_copy_path.clone(), err)
+            })?;
+            daemon.start_journal()?;
+            daemon.process_events(root_wd, cookies_wd)
+        }
+    }
+
+    struct Daemon<'a> {
+        fsmonitor: &'a Fsmonitor,
+        inotify_fd: BorrowedFd<'a>,
+        /// Directories being watched, relative to the working copy root.
+        watches: HashMap<i32, PathBuf>,
+        journal: Option<BufWriter<File>>,
+        journal_size: u64,
+    }
+
+    impl Daemon<'_> {
+        /// Watches `dir` and all directories below it. Returns the watch
+        /// descriptor of `dir`, or `None` if it no longer exists.
+        fn watch_recursive(&mut self, dir: &Path) -> Result<Option<i32>, Error> {
+            let flags = WatchFlags::MODIFY
+                | WatchFlags::ATTRIB
+                | WatchFlags::CREATE
+                | WatchFlags::DELETE
+                | WatchFlags::MOVED_FROM
+                | WatchFlags::MOVED_TO
+                | WatchFlags::DELETE_SELF
+                | WatchFlags::MOVE_SELF
+                | WatchFlags::DONT_FOLLOW
+                | WatchFlags::EXCL_UNLINK
+                | WatchFlags::ONLYDIR;
+            let mut top_wd = None;
+            let mut dirs_to_visit = vec![dir.to_owned()];
+            while let Some(dir) = dirs_to_visit.pop() {
+                let disk_dir = self.fsmonitor.working_copy_path.join(&dir);
+                let wd = match rustix::fs::inotify::add_watch(self.inotify_fd, &disk_dir, flags) {
+                    Ok(wd) => wd,
+                    // The directory was removed or replaced in the meantime
+                    Err(Errno::NOENT | Errno::NOTDIR) => continue,
+                    Err(err) => return Err(Error::WatchError(disk_dir, err.into())),
+                };
+                if top_wd.is_none() {
+                    top_wd = Some(wd);
+                }
+                let Ok(entries) = fs::read_dir(&disk_dir) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let name = entry.file_name();
+                    if dir.as_os_str().is_empty() && EXCLUDED_ROOT_DIRS.iter().any(|n| name == *n) {
+                        continue;
+                    }
+                    if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                        dirs_to_visit.push(dir.join(name));
+                    }
+                }
+                self.watches.insert(wd, dir);
+            }
+            Ok(top_wd)
+        }
+
+        /// Stops watching `dir` and all directories below it.
+        fn unwatch_recursive(&mut self, dir: &Path) {
+            let wds = self
+                .watches
+                .iter()
+                .filter(|(_, path)| path.starts_with(dir))
+                .map(|(&wd, _)| wd)
+                .collect_vec();
+            for wd in wds {
+                // Fails if the kernel has already removed the watch
+                rustix::fs::inotify::remove_watch(self.inotify_fd, wd).ok();
+                self.watches.remove(&wd);
+            }
+        }
+
+        /// Replaces the journal with an empty one of a new instance.
+        fn start_journal(&mut self) -> Result<(), Error> {
+            let journal_path = self.fsmonitor.journal_path();
+            let state_error = self.fsmonitor.state_error(&journal_path);
+            let instance = format!("{}-{}", std::process::id(), timestamp_nanos());
+            let header = [JOURNAL_MAGIC, instance.as_bytes(), b"\0"].concat();
+            let temp_path = journal_path.with_extension("tmp");
+            let file = fs::write(&temp_path, &header)
+                .and_then(|()| fs::rename(&temp_path, &journal_path))
+                .and_then(|()| OpenOptions::new().append(true).open(&journal_path))
+                .map_err(state_error)?;
+            info!(instance, "Started a new journal");
+            self.journal = Some(BufWriter::new(file));
+            self.journal_size = header.len() as u64;
+            Ok(())
+        }
+
+        fn append(&mut self, record: &[u8]) -> Result<(), Error> {
+            let journal_path = self.fsmonitor.journal_path();
+            let journal = self.journal.as_mut().unwrap();
+            journal
+                .write_all(record)
+                .and_then(|()| journal.write_all(b"\0"))
+                .map_err(self.fsmonitor.state_error(&journal_path))?;
+            self.journal_size += record.len() as u64 + 1;
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            let journal_path = self.fsmonitor.journal_path();
+            let journal = self.journal.as_mut().unwrap();
+            journal
+                .flush()
+                .map_err(self.fsmonitor.state_error(&journal_path))?;
+            Ok(())
+        }
+
+        fn process_events(&mut self, root_wd: i32, cookies_wd: i32) -> Result<(), Error> {
+            let mut buf = [MaybeUninit::uninit(); 16384];
+            let mut reader = rustix::fs::inotify::Reader::new(self.inotify_fd, &mut buf);
+            loop {
+                if reader.is_buffer_empty() {
+                    // About to block for more events
+                    self.flush()?;
+                }
+                let event = match reader.next() {
+                    Ok(event) => event,
+                    Err(Errno::INTR) => continue,
+                    Err(err) => return Err(Error::ReadEventsError(err.into())),
+                };
+                let wd = event.wd();
+                let flags = event.events();
+                let name = event
+                    .file_name()
+                    .map(|name| PathBuf::from(OsStr::from_bytes(name.to_bytes())));
+
+                if flags.contains(ReadFlags::QUEUE_OVERFLOW) {
+                    // Events were lost, so clients have to crawl again.
+                    tracing::warn!("The inotify event queue overflowed");
+                    self.start_journal()?;
+                } else if wd == cookies_wd {
+                    if flags.contains(ReadFlags::IGNORED) {
+                        info!("The state directory was removed; exiting");
+                        return Ok(());
+                    }
+                    let Some(name) = name else {
+                        continue;
+                    };
+                    fs::remove_file(self.fsmonitor.cookies_dir().join(&name)).ok();
+                    if name.as_os_str() == STOP_COOKIE {
+                        info!("Asked to stop; exiting");
+                        return self.flush();
+                    }
+                    // Rotate before recording the cookie, not after, so the
+                    // client finds the cookie in the journal it will read.
+                    if self.journal_size > MAX_JOURNAL_SIZE {
+                        self.start_journal()?;
+                    }
+                    self.append(&[b"/", name.as_os_str().as_bytes()].concat())?;
+                    self.flush()?;
+                } else if flags.contains(ReadFlags::IGNORED) {
+                    self.watches.remove(&wd);
+                    if wd == root_wd {
+                        info!("The working copy was removed; exiting");
+                        return self.flush();
+                    }
+                } else if wd == root_wd && flags.contains(ReadFlags::MOVE_SELF) {
+                    info!("The working copy was moved; exiting");
+                    return self.flush();
+                } else if let (Some(dir), Some(name)) = (self.watches.get(&wd), name) {
+                    if dir.as_os_str().is_empty()
+                        && EXCLUDED_ROOT_DIRS.iter().any(|n| name.as_os_str() == *n)
+                    {
+                        continue;
+                    }
+                    let path = dir.join(name);
+                    if flags.contains(ReadFlags::ISDIR) {
+                        if flags.intersects(ReadFlags::DELETE | ReadFlags::MOVED_FROM) {
+                            self.unwatch_recursive(&path);
+                        }
+                        // Recording the directory after watching it covers
+                        // whatever was created before the watches were added.
+                        if flags.intersects(ReadFlags::CREATE | ReadFlags::MOVED_TO) {
+                            self.watch_recursive(&path)?;
+                        }
+                    }
+                    self.append(path.as_os_str().as_bytes())?;
+                }
+            }
+        }
+    }
+
+    fn timestamp_nanos() -> u128 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    }
+}
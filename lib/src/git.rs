@@ -21,6 +21,7 @@ use std::collections::HashSet;
 use std::default::Default;
 use std::fmt;
 use std::io::Read;
+use std::iter;
 use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::str;
@@ -526,11 +527,12 @@ fn diff_refs_to_import(
             let RemoteRef { target, state } = remote_ref;
             (ref_name, (target, *state))
         }),
-        // TODO: compare to tags stored in the "git" remote view. Since tags should never
-        // be moved locally in jj, we can consider local tags as merge base.
-        view.tags().iter().map(|(name, target)| {
+        // TODO: compare to tags stored in the "git" remote view. Since tags moved
+        // locally in jj are exported, the last known Git refs are the merge base.
+        view.git_refs().iter().filter_map(|(full_name, target)| {
+            let name = full_name.strip_prefix("refs/tags/")?;
             let ref_name = RefName::Tag(name.to_owned());
-            (ref_name, (target, RemoteRefState::Tracking))
+            Some((ref_name, (target, RemoteRefState::Tracking)))
         }),
     )
     .filter(|(ref_name, _)| git_ref_filter(ref_name))
@@ -751,17 +753,16 @@ struct RefsToExport {
     failed_branches: HashMap<RefName, FailedRefExportReason>,
 }
 
-/// Export changes to branches made in the Jujutsu repo compared to our last
-/// seen view of the Git repo in `mut_repo.view().git_refs()`. Returns a list of
-/// refs that failed to export.
+/// Export changes to branches and tags made in the Jujutsu repo compared to
+/// our last seen view of the Git repo in `mut_repo.view().git_refs()`. Returns
+/// a list of refs that failed to export.
 ///
-/// We ignore changed branches that are conflicted (were also changed in the Git
-/// repo compared to our last remembered view of the Git repo). These will be
-/// marked conflicted by the next `jj git import`.
+/// We ignore changed branches and tags that are conflicted (were also changed
+/// in the Git repo compared to our last remembered view of the Git repo).
+/// These will be marked conflicted by the next `jj git import`.
 ///
-/// We do not export tags and other refs at the moment, since these aren't
-/// supposed to be modified by JJ. For them, the Git state is considered
-/// authoritative.
+/// We do not export other refs at the moment, since these aren't supposed to
+/// be modified by JJ. For them, the Git state is considered authoritative.
 pub fn export_refs(mut_repo: &mut MutableRepo) -> Result<Vec<FailedRefExport>, GitExportError> {
     export_some_refs(mut_repo, |_| true)
 }
@@ -843,6 +844,8 @@ pub fn export_some_refs(
         }
     }
 
+    export_tags(mut_repo, &git_repo, &git_ref_filter, &mut failed_branches);
+
     copy_exportable_local_branches_to_remote_view(
         mut_repo,
         REMOTE_NAME_FOR_LOCAL_GIT_REPO,
@@ -970,6 +973,208 @@ fn diff_refs_to_export(
     }
 }
 
+/// Annotation of a tag created by [`set_tag()`].
+#[derive(Clone, Debug)]
+pub struct GitTagAnnotation {
+    pub message: String,
+    /// Whether to sign the tag object with the configured signing backend.
+    pub sign: bool,
+}
+
+/// Points the tag `name` to the `target` commit in the jj view. If
+/// `annotation` is specified, an annotated tag object is written. Otherwise,
+/// the tag will be a lightweight tag.
+///
+/// Like bookmarks, the Git ref is updated when the tag is exported by
+/// [`export_refs()`]. Until then, the annotated tag object is kept reachable
+/// by a jj-internal ref, which is also used when the tag is pushed.
+pub fn set_tag(
+    mut_repo: &mut MutableRepo,
+    name: &str,
+    target: &CommitId,
+    annotation: Option<&GitTagAnnotation>,
+) -> Result<(), GitExportError> {
+    let git_backend = get_git_backend(mut_repo.store())?;
+    let git_repo = git_backend.git_repo();
+    let annotated_ref_name = annotated_tag_ref_name(name);
+    if let Some(annotation) = annotation {
+        let settings = mut_repo.base_repo().settings();
+        let store = mut_repo.store();
+        let sign_key = settings.sign_settings().key;
+        let sign_fn = |data: &[u8]| store.signer().sign(data, sign_key.as_deref());
+        let oid = git_backend.write_tag(
+            name,
+            target,
+            &settings.signature(),
+            &annotation.message,
+            annotation.sign.then_some(&mut &sign_fn),
+        )?;
+        git_repo
+            .reference(
+                annotated_ref_name.as_str(),
+                oid,
+                gix::refs::transaction::PreviousValue::Any,
+                "tag from jj",
+            )
+            .map_err(GitExportError::from_git)?;
+    } else if let Some(git_ref) = git_repo
+        .try_find_reference(annotated_ref_name.as_str())
+        .map_err(GitExportError::from_git)?
+    {
+        // Don't export the annotated tag object written previously
+        git_ref.delete().map_err(GitExportError::from_git)?;
+    }
+    mut_repo.set_tag_target(name, RefTarget::normal(target.clone()));
+    Ok(())
+}
+
+/// Name of the jj-internal ref keeping the annotated tag object written by
+/// [`set_tag()`].
+fn annotated_tag_ref_name(name: &str) -> String {
+    format!("refs/jj/tags/{name}")
+}
+
+/// Returns the annotated tag object written for the tag `name` by
+/// [`set_tag()`] if it points to the `target` commit.
+fn find_annotated_tag(
+    git_repo: &gix::Repository,
+    name: &str,
+    target: &gix::oid,
+) -> Option<gix::ObjectId> {
+    let git_ref = git_repo
+        .find_reference(annotated_tag_ref_name(name).as_str())
+        .ok()?;
+    let oid = git_ref.try_id()?.detach();
+    let target_oid = git_ref.into_fully_peeled_id().ok()?;
+    (target_oid.detach() == target).then_some(oid)
+}
+
+/// Returns the commit the Git ref points to, peeling annotated tags.
+fn peel_git_ref(git_ref: &gix::Reference) -> Option<gix::ObjectId> {
+    let id = git_ref.clone().into_fully_peeled_id().ok()?;
+    Some(id.detach())
+}
+
+/// Exports changes to tags made in the jj repo compared to our last seen view
+/// of the Git repo. Tags are compared by the commits they point to, so an
+/// annotated tag in the Git repo matches its target commit in the jj view.
+fn export_tags(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+    git_ref_filter: impl Fn(&RefName) -> bool,
+    failed_refs: &mut HashMap<RefName, FailedRefExportReason>,
+) {
+    let view = mut_repo.view();
+    let mut all_tag_targets: BTreeMap<String, (RefTarget, RefTarget)> = view
+        .tags()
+        .iter()
+        .map(|(name, target)| (name.clone(), (RefTarget::absent(), target.clone())))
+        .collect();
+    for (full_name, target) in view.git_refs() {
+        let Some(name) = full_name.strip_prefix("refs/tags/") else {
+            continue;
+        };
+        all_tag_targets
+            .entry(name.to_owned())
+            .and_modify(|(old_target, _)| *old_target = target.clone())
+            .or_insert_with(|| (target.clone(), RefTarget::absent()));
+    }
+
+    let root_commit_target = RefTarget::normal(mut_repo.store().root_commit_id().clone());
+    for (name, (old_target, new_target)) in all_tag_targets {
+        let ref_name = RefName::Tag(name.clone());
+        if new_target == old_target || !git_ref_filter(&ref_name) {
+            continue;
+        }
+        if new_target == root_commit_target {
+            // Git doesn't have a root commit
+            failed_refs.insert(ref_name, FailedRefExportReason::OnRootCommit);
+            continue;
+        }
+        let old_oid = if let Some(id) = old_target.as_normal() {
+            Some(gix::ObjectId::from_bytes_or_panic(id.as_bytes()))
+        } else if old_target.has_conflict() {
+            failed_refs.insert(ref_name, FailedRefExportReason::ConflictedOldState);
+            continue;
+        } else {
+            None
+        };
+        let Some(git_ref_name) = to_git_ref_name(&ref_name) else {
+            failed_refs.insert(ref_name, FailedRefExportReason::InvalidGitName);
+            continue;
+        };
+        let result = if let Some(id) = new_target.as_normal() {
+            let new_oid = gix::ObjectId::from_bytes_or_panic(id.as_bytes());
+            let object_oid = find_annotated_tag(git_repo, &name, &new_oid).unwrap_or(new_oid);
+            update_git_tag_ref(git_repo, &git_ref_name, old_oid, new_oid, object_oid)
+        } else if new_target.has_conflict() {
+            // Skip conflicts and leave the old value in git_refs
+            continue;
+        } else {
+            delete_git_tag_ref(git_repo, &git_ref_name, old_oid.unwrap())
+        };
+        match result {
+            Ok(()) => mut_repo.set_git_ref_target(&git_ref_name, new_target),
+            Err(reason) => {
+                failed_refs.insert(ref_name, reason);
+            }
+        }
+    }
+}
+
+/// Like [`delete_git_ref()`], but the tag may be an annotated tag pointing to
+/// the `old_oid` commit.
+fn delete_git_tag_ref(
+    git_repo: &gix::Repository,
+    git_ref_name: &str,
+    old_oid: gix::ObjectId,
+) -> Result<(), FailedRefExportReason> {
+    if let Ok(git_ref) = git_repo.find_reference(git_ref_name) {
+        if peel_git_ref(&git_ref) != Some(old_oid) {
+            // The tag was updated by git
+            return Err(FailedRefExportReason::DeletedInJjModifiedInGit);
+        }
+        git_ref
+            .delete()
+            .map_err(|err| FailedRefExportReason::FailedToDelete(err.into()))?;
+    }
+    Ok(())
+}
+
+/// Like [`update_git_ref()`], but the tags may be annotated tags. The tag
+/// pointing to the `old_oid` commit is updated to the `object_oid`, which is
+/// either the `new_oid` commit or an annotated tag object pointing to it.
+fn update_git_tag_ref(
+    git_repo: &gix::Repository,
+    git_ref_name: &str,
+    old_oid: Option<gix::ObjectId>,
+    new_oid: gix::ObjectId,
+    object_oid: gix::ObjectId,
+) -> Result<(), FailedRefExportReason> {
+    let current = git_repo
+        .find_reference(git_ref_name)
+        .ok()
+        .map(|git_ref| (git_ref.inner.target.clone(), peel_git_ref(&git_ref)));
+    let expected = match (current, old_oid) {
+        (None, None) => gix::refs::transaction::PreviousValue::MustNotExist,
+        (Some((raw_target, current_oid)), Some(old_oid)) if current_oid == Some(old_oid) => {
+            gix::refs::transaction::PreviousValue::MustExistAndMatch(raw_target)
+        }
+        // We're good if and only if git pointed it to our desired target
+        (Some((_, current_oid)), _) if current_oid == Some(new_oid) => return Ok(()),
+        (Some(_), None) => return Err(FailedRefExportReason::AddedInJjAddedInGit),
+        (None, Some(_)) => return Err(FailedRefExportReason::ModifiedInJjDeletedInGit),
+        // The tag was moved in git, so this fails like a branch update
+        (Some(_), Some(old_oid)) => {
+            gix::refs::transaction::PreviousValue::MustExistAndMatch(old_oid.into())
+        }
+    };
+    git_repo
+        .reference(git_ref_name, object_oid, expected, "export from jj")
+        .map_err(|err| FailedRefExportReason::FailedToSet(err.into()))?;
+    Ok(())
+}
+
 fn delete_git_ref(
     git_repo: &gix::Repository,
     git_ref_name: &str,
@@ -1501,8 +1706,15 @@ pub enum GitFetchError {
         chars = INVALID_REFSPEC_CHARS.iter().join("`, `")
     )]
     InvalidBranchPattern(StringPattern),
+    #[error(
+        "Invalid tag pattern provided. When fetching, tag names and globs may not contain the characters `{chars}`",
+        chars = INVALID_REFSPEC_CHARS.iter().join("`, `")
+    )]
+    InvalidTagPattern(StringPattern),
     #[error("Git remotes with slashes are incompatible with jj: {0}")]
     RemoteWithSlash(String),
+    #[error("Partial clones are only supported when fetching through a git subprocess")]
+    PartialCloneUnsupported,
     // TODO: I'm sure there are other errors possible, such as transport-level errors.
     #[error("Unexpected git error when fetching")]
     InternalGitError(#[from] git2::Error),
@@ -1575,6 +1787,11 @@ impl<'a> GitFetch<'a> {
     ///
     /// Keeps track of the {branch_names, remote_name} pair the refs can be
     /// subsequently imported into the `jj` repo by calling `import_refs()`.
+    ///
+    /// If `filter` is specified (e.g. `blob:none`), the fetch is partial, and
+    /// the remote is registered as a promisor remote from which the objects
+    /// left out will be fetched on demand. Partial fetches are only supported
+    /// when fetching through a git subprocess.
     #[tracing::instrument(skip(self, callbacks))]
     pub fn fetch(
         &mut self,
@@ -1582,12 +1799,13 @@ impl<'a> GitFetch<'a> {
         branch_names: &[StringPattern],
         callbacks: RemoteCallbacks<'_>,
         depth: Option<NonZeroU32>,
+        filter: Option<&str>,
     ) -> Result<(), GitFetchError> {
         if remote_name.contains("/") {
             return Err(GitFetchError::RemoteWithSlash(remote_name.to_owned()));
         }
         self.fetch_impl
-            .fetch(remote_name, branch_names, callbacks, depth)?;
+            .fetch(remote_name, branch_names, callbacks, depth, filter)?;
         self.fetched.push(FetchedBranches {
             remote: remote_name.to_string(),
             branches: branch_names.to_vec(),
@@ -1595,6 +1813,25 @@ impl<'a> GitFetch<'a> {
         Ok(())
     }
 
+    /// Perform a `git fetch` of the tags matching `tag_names`, overwriting the
+    /// local tags of the same names in the git repo.
+    ///
+    /// Unlike `fetch()`, local tags which don't exist on the remote are never
+    /// pruned. The fetched tags will be imported by `import_refs()`.
+    #[tracing::instrument(skip(self, callbacks))]
+    pub fn fetch_tags(
+        &mut self,
+        remote_name: &str,
+        tag_names: &[StringPattern],
+        callbacks: RemoteCallbacks<'_>,
+    ) -> Result<(), GitFetchError> {
+        if remote_name.contains("/") {
+            return Err(GitFetchError::RemoteWithSlash(remote_name.to_owned()));
+        }
+        self.fetch_impl
+            .fetch_tags(remote_name, tag_names, callbacks)
+    }
+
     /// Queries remote for the default branch name.
     #[tracing::instrument(skip(self, callbacks))]
     pub fn get_default_branch(
@@ -1666,6 +1903,21 @@ fn expand_fetch_refspecs(
         .collect()
 }
 
+fn expand_tag_fetch_refspecs(tag_names: &[StringPattern]) -> Result<Vec<RefSpec>, GitFetchError> {
+    tag_names
+        .iter()
+        .map(|pattern| {
+            pattern
+                .to_glob()
+                .filter(|glob| !glob.contains(INVALID_REFSPEC_CHARS))
+                .map(|glob| {
+                    RefSpec::forced(format!("refs/tags/{glob}"), format!("refs/tags/{glob}"))
+                })
+                .ok_or_else(|| GitFetchError::InvalidTagPattern(pattern.clone()))
+        })
+        .collect()
+}
+
 enum GitFetchImpl<'a> {
     Git2 {
         git_repo: git2::Repository,
@@ -1696,9 +1948,13 @@ impl<'a> GitFetchImpl<'a> {
         branch_names: &[StringPattern],
         callbacks: RemoteCallbacks<'_>,
         depth: Option<NonZeroU32>,
+        filter: Option<&str>,
     ) -> Result<(), GitFetchError> {
         match self {
             GitFetchImpl::Git2 { git_repo } => {
+                if filter.is_some() {
+                    return Err(GitFetchError::PartialCloneUnsupported);
+                }
                 git2_fetch(git_repo, remote_name, branch_names, callbacks, depth)
             }
             GitFetchImpl::Subprocess { git_repo, git_ctx } => subprocess_fetch(
@@ -1708,10 +1964,27 @@ impl<'a> GitFetchImpl<'a> {
                 branch_names,
                 callbacks,
                 depth,
+                filter,
             ),
         }
     }
 
+    fn fetch_tags(
+        &self,
+        remote_name: &str,
+        tag_names: &[StringPattern],
+        callbacks: RemoteCallbacks<'_>,
+    ) -> Result<(), GitFetchError> {
+        match self {
+            GitFetchImpl::Git2 { git_repo } => {
+                git2_fetch_tags(git_repo, remote_name, tag_names, callbacks)
+            }
+            GitFetchImpl::Subprocess { git_repo, git_ctx } => {
+                subprocess_fetch_tags(git_repo, git_ctx, remote_name, tag_names, callbacks)
+            }
+        }
+    }
+
     fn get_default_branch(
         &self,
         remote_name: &str,
@@ -1770,6 +2043,41 @@ fn git2_fetch(
     Ok(())
 }
 
+fn git2_fetch_tags(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    tag_names: &[StringPattern],
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitFetchError> {
+    let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitFetchError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitFetchError::InternalGitError(err)
+        }
+    })?;
+    let refspecs: Vec<String> = expand_tag_fetch_refspecs(tag_names)?
+        .iter()
+        .map(|refspec| refspec.to_git_format())
+        .collect();
+    if refspecs.is_empty() {
+        return Ok(());
+    }
+
+    tracing::debug!("remote.download");
+    remote.download(&refspecs, Some(&mut git2_fetch_options(callbacks, None)))?;
+    tracing::debug!("remote.update_tips");
+    remote.update_tips(
+        None,
+        git2::RemoteUpdateFlags::empty(),
+        git2::AutotagOption::None,
+        None,
+    )?;
+    tracing::debug!("remote.disconnect");
+    remote.disconnect()?;
+    Ok(())
+}
+
 fn git2_get_default_branch(
     git_repo: &git2::Repository,
     remote_name: &str,
@@ -1815,6 +2123,7 @@ fn subprocess_fetch(
     branch_names: &[StringPattern],
     mut callbacks: RemoteCallbacks<'_>,
     depth: Option<NonZeroU32>,
+    filter: Option<&str>,
 ) -> Result<(), GitFetchError> {
     // check the remote exists
     if git_repo.try_find_remote(remote_name).is_none() {
@@ -1836,9 +2145,14 @@ fn subprocess_fetch(
     //
     // even more unfortunately, git errors out one refspec at a time,
     // meaning that the below cycle runs in O(#failed refspecs)
-    while let Some(failing_refspec) =
-        git_ctx.spawn_fetch(remote_name, &remaining_refspecs, &mut callbacks, depth)?
-    {
+    while let Some(failing_refspec) = git_ctx.spawn_fetch(
+        remote_name,
+        &remaining_refspecs,
+        &mut callbacks,
+        depth,
+        filter,
+        true,
+    )? {
         remaining_refspecs.retain(|r| r.source.as_ref() != Some(&failing_refspec));
 
         if let Some(branch_name) = failing_refspec.strip_prefix("refs/heads/") {
@@ -1852,6 +2166,32 @@ fn subprocess_fetch(
     Ok(())
 }
 
+fn subprocess_fetch_tags(
+    git_repo: &gix::Repository,
+    git_ctx: &GitSubprocessContext,
+    remote_name: &str,
+    tag_names: &[StringPattern],
+    mut callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitFetchError> {
+    if git_repo.try_find_remote(remote_name).is_none() {
+        return Err(GitFetchError::NoSuchRemote(remote_name.to_owned()));
+    }
+    let mut remaining_refspecs = expand_tag_fetch_refspecs(tag_names)?;
+    // Tags not found on the remote are filtered out one by one as in
+    // subprocess_fetch().
+    while let Some(failing_refspec) = git_ctx.spawn_fetch(
+        remote_name,
+        &remaining_refspecs,
+        &mut callbacks,
+        None,
+        None,
+        false,
+    )? {
+        remaining_refspecs.retain(|r| r.source.as_ref() != Some(&failing_refspec));
+    }
+    Ok(())
+}
+
 fn subprocess_get_default_branch(
     git_repo: &gix::Repository,
     git_ctx: &GitSubprocessContext,
@@ -1881,6 +2221,14 @@ pub enum GitPushError {
     RefInUnexpectedLocation(Vec<String>),
     #[error("Remote rejected the update of some refs (do you have permission to push to {0:?}?)")]
     RefUpdateRejected(Vec<String>),
+    /// The remote rejected the update of some refs with the given reasons.
+    #[error(
+        "Remote rejected the update of some refs: {}",
+        .0.iter().map(|(name, reason)| format!("{name} ({reason})")).join(", ")
+    )]
+    RefUpdateRejectedByRemote(Vec<(String, String)>),
+    #[error("Atomic pushes are only supported when pushing through a git subprocess")]
+    AtomicPushUnsupported,
     // TODO: I'm sure there are other errors possible, such as transport-level errors,
     // and errors caused by the remote rejecting the push.
     #[error("Unexpected git error when pushing")]
@@ -1914,6 +2262,27 @@ pub fn push_branches(
     targets: &GitBranchPushTargets,
     callbacks: RemoteCallbacks<'_>,
 ) -> Result<(), GitPushError> {
+    push_branches_and_tags(mut_repo, git_settings, remote, targets, &[], callbacks)
+}
+
+/// Pushes the specified branches and tags in a single push, and updates the
+/// repo view of the branches accordingly.
+///
+/// The tags must not exist on the remote yet. If the tag ref in the underlying
+/// Git repo, or the tag object written by [`set_tag()`], is an annotated tag
+/// pointing to the tag target, the tag object is pushed. Otherwise, a
+/// lightweight tag is created on the remote.
+pub fn push_branches_and_tags(
+    mut_repo: &mut MutableRepo,
+    git_settings: &GitSettings,
+    remote: &str,
+    targets: &GitBranchPushTargets,
+    tags: &[(String, CommitId)],
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
+    if remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        return Err(GitPushError::RemoteReservedForLocalGitRepo);
+    }
     if remote.contains("/") {
         return Err(GitPushError::RemoteWithSlash(remote.to_owned()));
     }
@@ -1927,7 +2296,30 @@ pub fn push_branches(
             new_target: update.new_target.clone(),
         })
         .collect_vec();
-    push_updates(mut_repo, git_settings, remote, &ref_updates, callbacks)?;
+    let qualified_tag_names = tags
+        .iter()
+        .map(|(name, _)| format!("refs/tags/{name}"))
+        .collect_vec();
+    let mut qualified_remote_refs_expected_locations = HashMap::new();
+    let mut refspecs =
+        ref_update_refspecs(&ref_updates, &mut qualified_remote_refs_expected_locations);
+    if !tags.is_empty() {
+        let git_repo = get_git_repo(mut_repo.store())?;
+        refspecs.extend(tag_refspecs(
+            &git_repo,
+            tags,
+            &qualified_tag_names,
+            &mut qualified_remote_refs_expected_locations,
+        ));
+    }
+    push_refspecs(
+        mut_repo,
+        git_settings,
+        remote,
+        &qualified_remote_refs_expected_locations,
+        &refspecs,
+        callbacks,
+    )?;
 
     // TODO: add support for partially pushed refs? we could update the view
     // excluding rejected refs, but the transaction would be aborted anyway
@@ -1959,6 +2351,21 @@ pub fn push_updates(
     }
 
     let mut qualified_remote_refs_expected_locations = HashMap::new();
+    let refspecs = ref_update_refspecs(updates, &mut qualified_remote_refs_expected_locations);
+    push_refspecs(
+        repo,
+        git_settings,
+        remote_name,
+        &qualified_remote_refs_expected_locations,
+        &refspecs,
+        callbacks,
+    )
+}
+
+fn ref_update_refspecs<'a>(
+    updates: &'a [GitRefUpdate],
+    qualified_remote_refs_expected_locations: &mut HashMap<&'a str, Option<&'a CommitId>>,
+) -> Vec<RefSpec> {
     let mut refspecs = vec![];
     for update in updates {
         qualified_remote_refs_expected_locations.insert(
@@ -1977,6 +2384,45 @@ pub fn push_updates(
             refspecs.push(RefSpec::delete(&update.qualified_name));
         }
     }
+    refspecs
+}
+
+fn tag_refspecs<'a>(
+    git_repo: &gix::Repository,
+    tags: &[(String, CommitId)],
+    qualified_names: &'a [String],
+    qualified_remote_refs_expected_locations: &mut HashMap<&'a str, Option<&'a CommitId>>,
+) -> Vec<RefSpec> {
+    iter::zip(tags, qualified_names)
+        .map(|((name, target), qualified_name)| {
+            // The remote ref is expected to be absent, which is checked by the
+            // push negotiation.
+            qualified_remote_refs_expected_locations.insert(qualified_name.as_str(), None);
+            let target_oid = gix::ObjectId::from_bytes_or_panic(target.as_bytes());
+            let source = git_repo
+                .find_reference(qualified_name.as_str())
+                .ok()
+                .filter(|git_ref| {
+                    let known_target = RefTarget::normal(target.clone());
+                    resolve_git_ref_to_commit_id(git_ref, &known_target).as_ref() == Some(target)
+                })
+                .and_then(|git_ref| git_ref.try_id().map(|id| id.detach()))
+                .or_else(|| find_annotated_tag(git_repo, name, &target_oid))
+                .unwrap_or(target_oid)
+                .to_string();
+            RefSpec::forced(source, qualified_name)
+        })
+        .collect()
+}
+
+fn push_refspecs(
+    repo: &dyn Repo,
+    git_settings: &GitSettings,
+    remote_name: &str,
+    qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
+    refspecs: &[RefSpec],
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
     // TODO(ilyagr): `push_refs`, or parts of it, should probably be inlined. This
     // requires adjusting some tests.
 
@@ -1989,18 +2435,22 @@ pub fn push_updates(
             &git_repo,
             &git_ctx,
             remote_name,
-            &qualified_remote_refs_expected_locations,
-            &refspecs,
+            qualified_remote_refs_expected_locations,
+            refspecs,
             callbacks,
+            git_settings.push_atomic,
         )
     } else {
+        if git_settings.push_atomic {
+            return Err(GitPushError::AtomicPushUnsupported);
+        }
         let git_repo = git_backend.open_git_repo()?;
         let refspecs: Vec<String> = refspecs.iter().map(RefSpec::to_git_format).collect();
         git2_push_refs(
             repo,
             &git_repo,
             remote_name,
-            &qualified_remote_refs_expected_locations,
+            qualified_remote_refs_expected_locations,
             &refspecs,
             callbacks,
         )
@@ -2027,6 +2477,7 @@ fn git2_push_refs(
         .copied()
         .collect();
     let mut failed_push_negotiations = vec![];
+    let mut rejected_updates = vec![];
     let push_result = {
         let mut push_options = git2::PushOptions::new();
         let mut proxy_options = git2::ProxyOptions::new();
@@ -2094,7 +2545,9 @@ fn git2_push_refs(
         });
         callbacks.push_update_reference(|refname, status| {
             // The status is Some if the ref update was rejected
-            if status.is_none() {
+            if let Some(reason) = status {
+                rejected_updates.push((refname.to_owned(), reason.to_owned()));
+            } else {
                 remaining_remote_refs.remove(refname);
             }
             Ok(())
@@ -2118,6 +2571,9 @@ fn git2_push_refs(
         push_result?;
         if remaining_remote_refs.is_empty() {
             Ok(())
+        } else if !rejected_updates.is_empty() {
+            rejected_updates.sort();
+            Err(GitPushError::RefUpdateRejectedByRemote(rejected_updates))
         } else {
             // TODO: this is probably dead code right now
             // The only way this would happen is if a push fails from some
@@ -2140,6 +2596,7 @@ fn subprocess_push_refs(
     qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
     refspecs: &[RefSpec],
     mut callbacks: RemoteCallbacks<'_>,
+    atomic: bool,
 ) -> Result<(), GitPushError> {
     // check the remote exists
     if git_repo.try_find_remote(remote_name).is_none() {
@@ -2156,19 +2613,44 @@ fn subprocess_push_refs(
         .map(|full_refspec| RefToPush::new(full_refspec, qualified_remote_refs_expected_locations))
         .collect();
 
-    let (failed_ref_matches, successful_pushes) =
-        git_ctx.spawn_push(remote_name, &refs_to_push, &mut callbacks)?;
+    let push_stats = git_ctx.spawn_push(remote_name, &refs_to_push, &mut callbacks, atomic)?;
 
-    for remote_ref in successful_pushes {
+    for remote_ref in &push_stats.pushed {
         remaining_remote_refs.remove(remote_ref.as_str());
     }
 
-    if !failed_ref_matches.is_empty() {
-        let mut refs_in_unexpected_locations = failed_ref_matches;
-        refs_in_unexpected_locations.sort();
+    // In an atomic push, the refs which would have been updated are rejected
+    // because of the failure of the other refs.
+    let is_atomic_push_failure = |reason: &Option<String>| {
+        reason
+            .as_deref()
+            .is_some_and(|reason| reason.starts_with("atomic push fail"))
+    };
+    // Since all pushes are forced, the refs are rejected locally only if the
+    // lease failed.
+    let refs_in_unexpected_locations = push_stats
+        .rejected
+        .iter()
+        .filter(|(_, reason)| !is_atomic_push_failure(reason))
+        .map(|(name, _)| name.clone())
+        .sorted()
+        .collect_vec();
+    let remote_rejected_updates = push_stats
+        .remote_rejected
+        .into_iter()
+        .filter(|(_, reason)| !is_atomic_push_failure(reason))
+        .map(|(name, reason)| (name, reason.unwrap_or_default()))
+        .sorted()
+        .collect_vec();
+
+    if !refs_in_unexpected_locations.is_empty() {
         Err(GitPushError::RefInUnexpectedLocation(
             refs_in_unexpected_locations,
         ))
+    } else if !remote_rejected_updates.is_empty() {
+        Err(GitPushError::RefUpdateRejectedByRemote(
+            remote_rejected_updates,
+        ))
     } else if remaining_remote_refs.is_empty() {
         Ok(())
     } else {
@@ -36,6 +36,7 @@ use std::time::SystemTime;
 use async_trait::async_trait;
 use futures::stream::BoxStream;
 use gix::bstr::BString;
+use gix::bstr::ByteSlice as _;
 use gix::objs::CommitRef;
 use gix::objs::CommitRefIter;
 use gix::objs::WriteTo;
@@ -69,8 +70,10 @@ use crate::backend::Timestamp;
 use crate::backend::Tree;
 use crate::backend::TreeId;
 use crate::backend::TreeValue;
+use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
+use crate::git_subprocess::GitSubprocessContext;
 use crate::index::Index;
 use crate::lock::FileLock;
 use crate::merge::Merge;
@@ -101,6 +104,8 @@ pub enum GitBackendInitError {
     #[error("Failed to open git repository")]
     OpenRepository(#[source] gix::open::Error),
     #[error(transparent)]
+    Config(ConfigGetError),
+    #[error(transparent)]
     Path(PathError),
 }
 
@@ -115,6 +120,8 @@ pub enum GitBackendLoadError {
     #[error("Failed to open git repository")]
     OpenRepository(#[source] gix::open::Error),
     #[error(transparent)]
+    Config(ConfigGetError),
+    #[error(transparent)]
     Path(PathError),
 }
 
@@ -159,6 +166,12 @@ pub struct GitBackend {
     empty_tree_id: TreeId,
     extra_metadata_store: TableStore,
     cached_extra_metadata: Mutex<Option<Arc<ReadonlyTable>>>,
+    /// Git executable used to fetch objects missing from a partial clone.
+    git_executable_path: PathBuf,
+    /// Name of the promisor remote once it is found in the config.
+    promisor_remote: Mutex<Option<String>>,
+    /// Objects which couldn't be fetched from the promisor remote.
+    unfetchable_ids: Mutex<HashSet<gix::ObjectId>>,
 }
 
 impl GitBackend {
@@ -166,7 +179,11 @@ impl GitBackend {
         "git"
     }
 
-    fn new(base_repo: gix::ThreadSafeRepository, extra_metadata_store: TableStore) -> Self {
+    fn new(
+        base_repo: gix::ThreadSafeRepository,
+        extra_metadata_store: TableStore,
+        git_executable_path: PathBuf,
+    ) -> Self {
         let repo = Mutex::new(base_repo.to_thread_local());
         let root_commit_id = CommitId::from_bytes(&[0; HASH_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
@@ -179,6 +196,9 @@ impl GitBackend {
             empty_tree_id,
             extra_metadata_store,
             cached_extra_metadata: Mutex::new(None),
+            git_executable_path,
+            promisor_remote: Mutex::new(None),
+            unfetchable_ids: Mutex::new(HashSet::new()),
         }
     }
 
@@ -194,7 +214,7 @@ impl GitBackend {
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendInitError::InitRepository)?;
-        Self::init_with_repo(store_path, git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, git_repo_path, git_repo)
     }
 
     /// Initializes backend by creating a new Git repo at the specified
@@ -218,7 +238,7 @@ impl GitBackend {
         )
         .map_err(GitBackendInitError::InitRepository)?;
         let git_repo_path = workspace_root.join(".git");
-        Self::init_with_repo(store_path, &git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, &git_repo_path, git_repo)
     }
 
     /// Initializes backend with an existing Git repo at the specified path.
@@ -238,10 +258,11 @@ impl GitBackend {
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendInitError::OpenRepository)?;
-        Self::init_with_repo(store_path, git_repo_path, git_repo)
+        Self::init_with_repo(settings, store_path, git_repo_path, git_repo)
     }
 
     fn init_with_repo(
+        settings: &UserSettings,
         store_path: &Path,
         git_repo_path: &Path,
         git_repo: gix::ThreadSafeRepository,
@@ -270,8 +291,15 @@ impl GitBackend {
                 .context(&target_path)
                 .map_err(GitBackendInitError::Path)?;
         };
+        let git_settings = settings
+            .git_settings()
+            .map_err(GitBackendInitError::Config)?;
         let extra_metadata_store = TableStore::init(extra_path, HASH_LENGTH);
-        Ok(GitBackend::new(git_repo, extra_metadata_store))
+        Ok(GitBackend::new(
+            git_repo,
+            extra_metadata_store,
+            git_settings.executable_path,
+        ))
     }
 
     pub fn load(
@@ -293,14 +321,95 @@ impl GitBackend {
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendLoadError::OpenRepository)?;
+        let git_settings = settings
+            .git_settings()
+            .map_err(GitBackendLoadError::Config)?;
         let extra_metadata_store = TableStore::load(store_path.join("extra"), HASH_LENGTH);
-        Ok(GitBackend::new(repo, extra_metadata_store))
+        Ok(GitBackend::new(
+            repo,
+            extra_metadata_store,
+            git_settings.executable_path,
+        ))
     }
 
     fn lock_git_repo(&self) -> MutexGuard<'_, gix::Repository> {
         self.repo.lock().unwrap()
     }
 
+    /// Looks up the object of the given `id`. If the repo is a partial clone
+    /// and the object is missing, it is fetched from the promisor remote
+    /// first.
+    ///
+    /// Callers reading many objects should prefetch them by
+    /// `fetch_missing_objects()` so that they are fetched at once.
+    fn find_object_or_fetch<'repo>(
+        &self,
+        repo: &'repo gix::Repository,
+        id: &impl ObjectId,
+    ) -> BackendResult<gix::Object<'repo>> {
+        let git_id = validate_git_object_id(id)?;
+        let err = match repo.find_object(git_id) {
+            Err(err @ gix::object::find::existing::Error::NotFound { .. }) => err,
+            result => return result.map_err(|err| map_not_found_err(err, id)),
+        };
+        if self.unfetchable_ids.lock().unwrap().contains(&git_id) {
+            return Err(map_not_found_err(err, id));
+        }
+        let Some(remote_name) = self.promisor_remote(repo) else {
+            return Err(map_not_found_err(err, id));
+        };
+        tracing::debug!(%git_id, remote_name, "fetching missing object from promisor remote");
+        GitSubprocessContext::new(repo.git_dir(), &self.git_executable_path)
+            .spawn_fetch_missing_objects(&remote_name, &[git_id])
+            .map_err(|err| {
+                self.unfetchable_ids.lock().unwrap().insert(git_id);
+                to_read_object_err(err, id)
+            })?;
+        repo.find_object(git_id)
+            .map_err(|err| map_not_found_err(err, id))
+    }
+
+    /// Fetches the objects of the given `ids` that are missing from a partial
+    /// clone by a single request to the promisor remote.
+    fn fetch_missing_objects(
+        &self,
+        repo: &gix::Repository,
+        ids: impl IntoIterator<Item = gix::ObjectId>,
+    ) -> BackendResult<()> {
+        let mut unfetchable_ids = self.unfetchable_ids.lock().unwrap();
+        let missing_ids = ids
+            .into_iter()
+            .filter(|id| !repo.has_object(id) && !unfetchable_ids.contains(id))
+            .unique()
+            .collect_vec();
+        if missing_ids.is_empty() {
+            return Ok(());
+        }
+        let Some(remote_name) = self.promisor_remote(repo) else {
+            // Reading the objects will report that they are not found.
+            return Ok(());
+        };
+        tracing::debug!(
+            count = missing_ids.len(),
+            remote_name,
+            "fetching missing objects from promisor remote"
+        );
+        GitSubprocessContext::new(repo.git_dir(), &self.git_executable_path)
+            .spawn_fetch_missing_objects(&remote_name, &missing_ids)
+            .map_err(|err| BackendError::Other(err.into()))?;
+        // Don't try to fetch objects the remote didn't send again.
+        unfetchable_ids.extend(missing_ids.into_iter().filter(|id| !repo.has_object(id)));
+        Ok(())
+    }
+
+    fn promisor_remote(&self, repo: &gix::Repository) -> Option<String> {
+        let mut cached_remote = self.promisor_remote.lock().unwrap();
+        if cached_remote.is_none() {
+            *cached_remote = find_promisor_remote(repo.git_dir());
+        }
+        cached_remote.clone()
+    }
+
     /// Returns new thread-local instance to access to the underlying Git repo.
     pub fn git_repo(&self) -> gix::Repository {
         self.base_repo.to_thread_local()
@@ -321,6 +430,50 @@ impl GitBackend {
         self.base_repo.work_dir()
     }
 
+    /// Writes an annotated tag object pointing to the `target` commit, and
+    /// returns its id. The tag ref isn't updated.
+    ///
+    /// If `sign_with` is specified, the signature is appended to the tag
+    /// message in the same way as `git tag --sign`.
+    pub fn write_tag(
+        &self,
+        name: &str,
+        target: &CommitId,
+        tagger: &Signature,
+        message: &str,
+        sign_with: Option<&mut SigningFn>,
+    ) -> BackendResult<gix::ObjectId> {
+        let to_write_err =
+            |err: Box<dyn std::error::Error + Send + Sync>| BackendError::WriteObject {
+                object_type: "tag",
+                source: err,
+            };
+        let mut tag = gix::objs::Tag {
+            target: validate_git_object_id(target)?,
+            target_kind: gix::objs::Kind::Commit,
+            name: name.into(),
+            tagger: Some(signature_to_git(tagger).into()),
+            message: message.into(),
+            pgp_signature: None,
+        };
+        if let Some(sign) = sign_with {
+            // The signature is separated from the message by a newline, which
+            // is also covered by the signature.
+            tag.message = message.trim_end_matches('\n').into();
+            let mut data = Vec::with_capacity(512);
+            tag.write_to(&mut data)
+                .map_err(|err| to_write_err(err.into()))?;
+            data.push(b'\n');
+            let sig = sign(&data).map_err(|err| to_write_err(err.into()))?;
+            tag.pgp_signature = Some(sig.into());
+        }
+        let locked_repo = self.lock_git_repo();
+        locked_repo
+            .write_object(&tag)
+            .map(|id| id.detach())
+            .map_err(|err| to_write_err(err.into()))
+    }
+
     fn cached_extra_metadata_table(&self) -> BackendResult<Arc<ReadonlyTable>> {
         let mut locked_head = self.cached_extra_metadata.lock().unwrap();
         match locked_head.as_ref() {
@@ -410,11 +563,9 @@ impl GitBackend {
     }
 
     fn read_file_sync(&self, id: &FileId) -> BackendResult<Box<dyn Read>> {
-        let git_blob_id = validate_git_object_id(id)?;
         let locked_repo = self.lock_git_repo();
-        let mut blob = locked_repo
-            .find_object(git_blob_id)
-            .map_err(|err| map_not_found_err(err, id))?
+        let mut blob = self
+            .find_object_or_fetch(&locked_repo, id)?
             .try_into_blob()
             .map_err(|err| to_read_object_err(err, id))?;
         Ok(Box::new(Cursor::new(blob.take_data())))
@@ -829,6 +980,29 @@ fn validate_git_object_id(id: &impl ObjectId) -> BackendResult<gix::ObjectId> {
     Ok(gix::ObjectId::from_bytes_or_panic(id.as_bytes()))
 }
 
+/// Returns the name of the promisor remote if the repo at `git_dir` is a
+/// partial clone.
+///
+/// The config file is re-read since the promisor remote may have been
+/// registered by a partial fetch after the repo was loaded. Once found, the
+/// remote is cached by `GitBackend`.
+fn find_promisor_remote(git_dir: &Path) -> Option<String> {
+    let config = gix::config::File::from_git_dir(git_dir.to_owned()).ok()?;
+    if let Some(name) = config.string("extensions.partialClone") {
+        return Some(name.to_str_lossy().into_owned());
+    }
+    let promisor_remote = config
+        .sections_by_name("remote")?
+        .filter_map(|section| section.header().subsection_name())
+        .map(|name| name.to_str_lossy().into_owned())
+        .find(|name| {
+            config
+                .boolean(format!("remote.{name}.promisor"))
+                .is_some_and(|promisor| promisor.unwrap_or(false))
+        });
+    promisor_remote
+}
+
 fn map_not_found_err(err: gix::object::find::existing::Error, id: &impl ObjectId) -> BackendError {
     if matches!(err, gix::object::find::existing::Error::NotFound { .. }) {
         BackendError::ObjectNotFound {
@@ -945,6 +1119,22 @@ impl Backend for GitBackend {
         1
     }
 
+    async fn prefetch_objects(&self, values: &[TreeValue]) -> BackendResult<()> {
+        let ids: Vec<_> = values
+            .iter()
+            .filter_map(|value| match value {
+                TreeValue::File { id, .. } => Some(validate_git_object_id(id)),
+                TreeValue::Symlink(id) => Some(validate_git_object_id(id)),
+                TreeValue::Tree(id) if *id != self.empty_tree_id => {
+                    Some(validate_git_object_id(id))
+                }
+                TreeValue::Tree(_) | TreeValue::GitSubmodule(_) | TreeValue::Conflict(_) => None,
+            })
+            .try_collect()?;
+        let locked_repo = self.lock_git_repo();
+        self.fetch_missing_objects(&locked_repo, ids)
+    }
+
     async fn read_file(&self, _path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
         self.read_file_sync(id)
     }
@@ -967,11 +1157,9 @@ impl Backend for GitBackend {
     }
 
     async fn read_symlink(&self, _path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
-        let git_blob_id = validate_git_object_id(id)?;
         let locked_repo = self.lock_git_repo();
-        let mut blob = locked_repo
-            .find_object(git_blob_id)
-            .map_err(|err| map_not_found_err(err, id))?
+        let mut blob = self
+            .find_object_or_fetch(&locked_repo, id)?
             .try_into_blob()
             .map_err(|err| to_read_object_err(err, id))?;
         let target = String::from_utf8(blob.take_data())
@@ -995,12 +1183,9 @@ impl Backend for GitBackend {
         if id == &self.empty_tree_id {
             return Ok(Tree::default());
         }
-        let git_tree_id = validate_git_object_id(id)?;
-
         let locked_repo = self.lock_git_repo();
-        let git_tree = locked_repo
-            .find_object(git_tree_id)
-            .map_err(|err| map_not_found_err(err, id))?
+        let git_tree = self
+            .find_object_or_fetch(&locked_repo, id)?
             .try_into_tree()
             .map_err(|err| to_read_object_err(err, id))?;
         let mut tree = Tree::default();
@@ -56,6 +56,42 @@ pub enum GitSubprocessError {
     External(String),
 }
 
+/// Credential helper which reads the credentials from the environment
+///
+/// See `gitcredentials(7)` for the protocol. Only the `get` action is
+/// answered.
+const CREDENTIAL_HELPER_CONFIG: &str = "credential.helper=!f() { test \"$1\" = get && \
+                                        printf 'username=%s\\npassword=%s\\n' \
+                                        \"$JJ_GIT_USERNAME\" \"$JJ_GIT_PASSWORD\"; }; f";
+const USERNAME_ENV_VAR: &str = "JJ_GIT_USERNAME";
+const PASSWORD_ENV_VAR: &str = "JJ_GIT_PASSWORD";
+
+/// Credentials provided to git by [`CREDENTIAL_HELPER_CONFIG`]
+struct GitCredentials {
+    username: String,
+    password: String,
+}
+
+/// Credentials git failed to prompt for because terminal prompts are disabled
+#[derive(Debug, PartialEq, Eq)]
+struct CredentialPrompt {
+    /// The URL credentials are requested for, without the username
+    url: String,
+    /// The username if git asked only for the password
+    username: Option<String>,
+}
+
+/// Result of a `git push`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct GitPushStats {
+    /// Refs which were pushed or were already up to date
+    pub pushed: Vec<String>,
+    /// Refs rejected by the local git process, with the reasons
+    pub rejected: Vec<(String, Option<String>)>,
+    /// Refs rejected by the remote, with the reasons
+    pub remote_rejected: Vec<(String, Option<String>)>,
+}
+
 /// Context for creating Git subprocesses
 pub(crate) struct GitSubprocessContext<'a> {
     git_dir: PathBuf,
@@ -96,6 +132,80 @@ impl<'a> GitSubprocessContext<'a> {
         git_cmd
     }
 
+    /// Create the Git command for an operation talking to a remote
+    ///
+    /// If the callbacks can provide credentials, git is told not to prompt
+    /// for them on the terminal. Instead, git fails, and the credentials are
+    /// requested through the callbacks and passed to the next attempt by an
+    /// inline credential helper.
+    fn create_remote_command(
+        &self,
+        callbacks: &RemoteCallbacks<'_>,
+        credentials: Option<&GitCredentials>,
+    ) -> Command {
+        let mut git_cmd = self.create_command();
+        if let Some(credentials) = credentials {
+            // The credential helper is appended to the ones configured by the
+            // user, so it's only used if the other helpers don't know the
+            // credentials. The credentials are passed through the environment
+            // so they don't appear in the command line.
+            git_cmd.args(["-c", CREDENTIAL_HELPER_CONFIG]);
+            git_cmd
+                .env(USERNAME_ENV_VAR, &credentials.username)
+                .env(PASSWORD_ENV_VAR, &credentials.password);
+        }
+        if callbacks.get_username_password.is_some() || callbacks.get_password.is_some() {
+            git_cmd.env("GIT_TERMINAL_PROMPT", "0");
+        }
+        git_cmd
+    }
+
+    /// Run the git command built by `build_command` until it no longer fails
+    /// because of missing credentials
+    ///
+    /// The credentials requested by git are asked for through the callbacks.
+    fn run_with_credentials(
+        &self,
+        callbacks: &mut RemoteCallbacks<'_>,
+        mut build_command: impl FnMut(&RemoteCallbacks<'_>, Option<&GitCredentials>) -> Command,
+    ) -> Result<Output, GitSubprocessError> {
+        let mut credentials: Option<GitCredentials> = None;
+        loop {
+            let command = build_command(callbacks, credentials.as_ref());
+            let output = wait_with_progress(self.spawn_cmd(command)?, callbacks)?;
+            if output.status.success() {
+                return Ok(output);
+            }
+            let Some(prompt) = parse_credential_prompt(&output.stderr) else {
+                return Ok(output);
+            };
+            // Don't ask again if git rejected the credentials we provided
+            if credentials.is_some() && prompt.username.is_none() {
+                return Ok(output);
+            }
+            let new_credentials = match &prompt.username {
+                None => callbacks
+                    .get_username_password
+                    .as_mut()
+                    .and_then(|cb| cb(&prompt.url))
+                    .map(|(username, password)| GitCredentials { username, password }),
+                Some(username) if credentials.is_none() => callbacks
+                    .get_password
+                    .as_mut()
+                    .and_then(|cb| cb(&prompt.url, username))
+                    .map(|password| GitCredentials {
+                        username: username.clone(),
+                        password,
+                    }),
+                Some(_) => None,
+            };
+            match new_credentials {
+                Some(new_credentials) => credentials = Some(new_credentials),
+                None => return Ok(output),
+            }
+        }
+    }
+
     /// Spawn the git command
     fn spawn_cmd(&self, mut git_cmd: Command) -> Result<Child, GitSubprocessError> {
         tracing::debug!(cmd = ?git_cmd, "spawning a git subprocess");
@@ -124,27 +234,75 @@ impl<'a> GitSubprocessContext<'a> {
         refspecs: &[RefSpec],
         callbacks: &mut RemoteCallbacks<'_>,
         depth: Option<NonZeroU32>,
+        filter: Option<&str>,
+        prune: bool,
     ) -> Result<Option<String>, GitSubprocessError> {
         if refspecs.is_empty() {
             return Ok(None);
         }
-        let mut command = self.create_command();
-        command.stdout(Stdio::piped());
-        // attempt to prune stale refs with --prune
-        // --no-write-fetch-head ensures our request is invisible to other parties
-        command.args(["fetch", "--prune", "--no-write-fetch-head"]);
-        if callbacks.progress.is_some() {
-            command.arg("--progress");
-        }
-        if let Some(d) = depth {
-            command.arg(format!("--depth={d}"));
+        let output = self.run_with_credentials(callbacks, |callbacks, credentials| {
+            let mut command = self.create_remote_command(callbacks, credentials);
+            command.stdout(Stdio::piped());
+            // --no-write-fetch-head ensures our request is invisible to other parties
+            command.args(["fetch", "--no-write-fetch-head"]);
+            if prune {
+                // attempt to prune stale refs
+                command.arg("--prune");
+            }
+            if callbacks.progress.is_some() {
+                command.arg("--progress");
+            }
+            if let Some(d) = depth {
+                command.arg(format!("--depth={d}"));
+            }
+            if let Some(filter) = filter {
+                command.arg(format!("--filter={filter}"));
+            }
+            command.arg("--").arg(remote_name);
+            command.args(refspecs.iter().map(|x| x.to_git_format()));
+            command
+        })?;
+
+        parse_git_fetch_output(output)
+    }
+
+    /// Fetch objects missing from a partial clone from the promisor remote
+    ///
+    /// This mirrors what git itself does to lazily fetch missing objects: the
+    /// objects are requested by id without negotiation, and no refs are
+    /// updated.
+    pub(crate) fn spawn_fetch_missing_objects(
+        &self,
+        remote_name: &str,
+        object_ids: &[gix::ObjectId],
+    ) -> Result<(), GitSubprocessError> {
+        if object_ids.is_empty() {
+            return Ok(());
         }
-        command.arg("--").arg(remote_name);
-        command.args(refspecs.iter().map(|x| x.to_git_format()));
+        let mut command = self.create_command();
+        command.stdout(Stdio::null());
+        command.args([
+            "-c",
+            "fetch.negotiationAlgorithm=noop",
+            "fetch",
+            "--no-tags",
+            "--no-write-fetch-head",
+            "--recurse-submodules=no",
+            "--filter=blob:none",
+            "--",
+            remote_name,
+        ]);
+        command.args(object_ids.iter().map(|id| id.to_string()));
 
-        let output = wait_with_progress(self.spawn_cmd(command)?, callbacks)?;
+        let output = wait_with_output(self.spawn_cmd(command)?)?;
 
-        parse_git_fetch_output(output)
+        if let Some(remote) = parse_no_such_remote(&output.stderr) {
+            return Err(GitSubprocessError::NoSuchRepository(remote));
+        }
+        if !output.status.success() {
+            return Err(external_git_error(&output.stderr));
+        }
+        Ok(())
     }
 
     /// Prune particular branches
@@ -192,43 +350,45 @@ impl<'a> GitSubprocessContext<'a> {
     /// Push references to git
     ///
     /// All pushes are forced, using --force-with-lease to perform a test&set
-    /// operation on the remote repository
-    ///
-    /// Return tuple with
-    ///     1. refs that failed to push
-    ///     2. refs that succeeded to push
+    /// operation on the remote repository. If `atomic` is true, either all
+    /// references are updated on the remote or none are.
     pub(crate) fn spawn_push(
         &self,
         remote_name: &str,
         references: &[RefToPush],
         callbacks: &mut RemoteCallbacks<'_>,
-    ) -> Result<(Vec<String>, Vec<String>), GitSubprocessError> {
-        let mut command = self.create_command();
-        command.stdout(Stdio::piped());
-        // Currently jj does not support commit hooks, so we prevent git from running
-        // them
-        //
-        // https://github.com/jj-vcs/jj/issues/3577 and https://github.com/jj-vcs/jj/issues/405
-        // offer more context
-        command.args(["push", "--porcelain", "--no-verify"]);
-        if callbacks.progress.is_some() {
-            command.arg("--progress");
-        }
-        command.args(
-            references
-                .iter()
-                .map(|reference| format!("--force-with-lease={}", reference.to_git_lease())),
-        );
-        command.args(["--", remote_name]);
-        // with --force-with-lease we cannot have the forced refspec,
-        // as it ignores the lease
-        command.args(
-            references
-                .iter()
-                .map(|r| r.refspec.to_git_format_not_forced()),
-        );
-
-        let output = wait_with_progress(self.spawn_cmd(command)?, callbacks)?;
+        atomic: bool,
+    ) -> Result<GitPushStats, GitSubprocessError> {
+        let output = self.run_with_credentials(callbacks, |callbacks, credentials| {
+            let mut command = self.create_remote_command(callbacks, credentials);
+            command.stdout(Stdio::piped());
+            // Currently jj does not support commit hooks, so we prevent git from running
+            // them
+            //
+            // https://github.com/jj-vcs/jj/issues/3577 and https://github.com/jj-vcs/jj/issues/405
+            // offer more context
+            command.args(["push", "--porcelain", "--no-verify"]);
+            if callbacks.progress.is_some() {
+                command.arg("--progress");
+            }
+            if atomic {
+                command.arg("--atomic");
+            }
+            command.args(
+                references
+                    .iter()
+                    .map(|reference| format!("--force-with-lease={}", reference.to_git_lease())),
+            );
+            command.args(["--", remote_name]);
+            // with --force-with-lease we cannot have the forced refspec,
+            // as it ignores the lease
+            command.args(
+                references
+                    .iter()
+                    .map(|r| r.refspec.to_git_format_not_forced()),
+            );
+            command
+        })?;
 
         parse_git_push_output(output)
     }
@@ -305,6 +465,40 @@ fn parse_no_remote_tracking_branch(stderr: &[u8]) -> Option<String> {
         .map(|branch| branch.to_str_lossy().into_owned())
 }
 
+/// Parse the credentials git couldn't prompt for
+///
+/// With `GIT_TERMINAL_PROMPT=0`, git fails with an error of the form:
+/// `fatal: could not read Username for '<url>': terminal prompts disabled`
+/// or, if the URL includes the username:
+/// `fatal: could not read Password for '<scheme>://<username>@<host>': terminal
+/// prompts disabled`
+fn parse_credential_prompt(stderr: &[u8]) -> Option<CredentialPrompt> {
+    let line = stderr
+        .lines()
+        .find(|line| line.ends_with(b"': terminal prompts disabled"))?;
+    let line = line.strip_prefix(b"fatal: could not read ")?;
+    let (kind, rest) = line.split_once_str(" for '")?;
+    let url = rest
+        .strip_suffix(b"': terminal prompts disabled")?
+        .to_str()
+        .ok()?;
+    match kind {
+        b"Username" => Some(CredentialPrompt {
+            url: url.to_owned(),
+            username: None,
+        }),
+        b"Password" => {
+            let (scheme, rest) = url.split_once("://")?;
+            let (username, host) = rest.split_once('@')?;
+            Some(CredentialPrompt {
+                url: format!("{scheme}://{host}"),
+                username: Some(username.to_owned()),
+            })
+        }
+        _ => None,
+    }
+}
+
 // return the fully qualified ref that failed to fetch
 //
 // note that git fetch only returns one error at a time
@@ -387,7 +581,9 @@ fn parse_git_remote_show_default_branch(
 // at times the summary is omitted
 //
 // <reason> is a human-readable explanation
-fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubprocessError> {
+//
+// Refs rejected by the remote have the `[remote rejected]` summary.
+fn parse_ref_pushes(stdout: &[u8]) -> Result<GitPushStats, GitSubprocessError> {
     if !stdout.starts_with(b"To ") {
         return Err(GitSubprocessError::External(format!(
             "Git push output unfamiliar:\n{}",
@@ -395,8 +591,7 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
         )));
     }
 
-    let mut pushed_refs = Vec::new();
-    let mut rejected_refs = Vec::new();
+    let mut push_stats = GitPushStats::default();
     for (idx, line) in stdout
         .lines()
         .skip(1)
@@ -416,11 +611,12 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
         let flag = it.next().ok_or_else(create_error)?;
         let reference = it.next().ok_or_else(create_error)?;
         // we capture the remaining elements to ensure the line is well formed
-        let _summary_or_comment = it.next().ok_or_else(create_error)?;
-        let _comment_opt = it.next();
+        let summary_or_comment = it.next().ok_or_else(create_error)?;
+        let comment_opt = it.next();
         if it.next().is_some() {
             return Err(create_error());
         }
+        let summary = comment_opt.unwrap_or(summary_or_comment);
 
         let full_refspec = reference
             .to_str()
@@ -450,11 +646,16 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
             //  * for a successfully pushed new ref
             //  =  for a ref that was up to date and did not need pushing.
             b"+" | b"-" | b"*" | b"=" | b" " => {
-                pushed_refs.push(reference);
+                push_stats.pushed.push(reference);
             }
             // ! for a ref that was rejected or failed to push; and
             b"!" => {
-                rejected_refs.push(reference);
+                let reason = parse_push_rejection_reason(summary);
+                if summary.starts_with(b"[remote rejected]") {
+                    push_stats.remote_rejected.push((reference, reason));
+                } else {
+                    push_stats.rejected.push((reference, reason));
+                }
             }
             unknown => {
                 return Err(GitSubprocessError::External(format!(
@@ -467,13 +668,19 @@ fn parse_ref_pushes(stdout: &[u8]) -> Result<(Vec<String>, Vec<String>), GitSubp
         }
     }
 
-    Ok((rejected_refs, pushed_refs))
+    Ok(push_stats)
 }
 
-// on Ok, return a tuple with
-//  1. list of failed references from test and set
-//  2. list of successful references pushed
-fn parse_git_push_output(output: Output) -> Result<(Vec<String>, Vec<String>), GitSubprocessError> {
+/// Parse the reason from the summary of a rejected ref
+///
+/// The summary is of the form: `[rejected] (<reason>)`
+fn parse_push_rejection_reason(summary: &[u8]) -> Option<String> {
+    let (_, reason) = summary.split_once_str(" (")?;
+    let reason = reason.strip_suffix(b")")?;
+    Some(reason.to_str_lossy().into_owned())
+}
+
+fn parse_git_push_output(output: Output) -> Result<GitPushStats, GitSubprocessError> {
     if output.status.success() {
         let ref_pushes = parse_ref_pushes(&output.stdout)?;
         return Ok(ref_pushes);
@@ -548,16 +755,27 @@ struct GitProgress {
     objects: (u64, u64),
     counted_objects: (u64, u64),
     compressed_objects: (u64, u64),
+    // bytes received so far, while objects are being received
+    received_bytes: Option<u64>,
 }
 
 impl GitProgress {
     fn to_progress(&self) -> Progress {
         Progress {
-            bytes_downloaded: None,
+            bytes_downloaded: self.received_bytes,
             overall: self.fraction() as f32 / self.total() as f32,
         }
     }
 
+    fn update_received_bytes(&mut self, line: &[u8]) {
+        let (frac, total) = self.objects;
+        self.received_bytes = if frac < total {
+            read_progress_bytes(line).or(self.received_bytes)
+        } else {
+            None
+        };
+    }
+
     fn fraction(&self) -> u64 {
         self.objects.0 + self.deltas.0 + self.counted_objects.0 + self.compressed_objects.0
     }
@@ -584,19 +802,37 @@ fn read_to_end_with_progress<R: Read>(
             break;
         }
 
-        if update_progress(line, &mut git_progress.objects, b"Receiving objects:")
-            || update_progress(line, &mut git_progress.deltas, b"Resolving deltas:")
-            || update_progress(
-                line,
-                &mut git_progress.counted_objects,
-                b"remote: Counting objects:",
-            )
-            || update_progress(
-                line,
-                &mut git_progress.compressed_objects,
-                b"remote: Compressing objects:",
-            )
+        // Objects are received on fetch, and counted, compressed and written
+        // locally on push.
+        let is_progress = if update_progress(line, &mut git_progress.objects, b"Receiving objects:")
         {
+            git_progress.update_received_bytes(line);
+            true
+        } else {
+            update_progress(line, &mut git_progress.objects, b"Writing objects:")
+                || update_progress(line, &mut git_progress.deltas, b"Resolving deltas:")
+                || update_progress(
+                    line,
+                    &mut git_progress.counted_objects,
+                    b"remote: Counting objects:",
+                )
+                || update_progress(
+                    line,
+                    &mut git_progress.counted_objects,
+                    b"Counting objects:",
+                )
+                || update_progress(
+                    line,
+                    &mut git_progress.compressed_objects,
+                    b"remote: Compressing objects:",
+                )
+                || update_progress(
+                    line,
+                    &mut git_progress.compressed_objects,
+                    b"Compressing objects:",
+                )
+        };
+        if is_progress {
             if let Some(cb) = callbacks.progress.as_mut() {
                 cb(&git_progress.to_progress());
             }
@@ -667,6 +903,26 @@ fn read_progress_line(line: &[u8]) -> Option<(u64, u64)> {
     (frac <= total).then_some((frac, total))
 }
 
+/// Read the transferred bytes from progress lines of the form:
+/// `<text> (<frac>/<total>), <size> <unit> | <rate> <unit>/s`
+///
+/// The size is rounded by git, so the returned value is approximate.
+fn read_progress_bytes(line: &[u8]) -> Option<u64> {
+    let (_prefix, suffix) = line.split_once_str("), ")?;
+    let (size, _rate) = suffix.split_once_str(" | ")?;
+    let (number, unit) = size.to_str().ok()?.trim().split_once(' ')?;
+    let multiplier: u64 = match unit {
+        "bytes" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return None,
+    };
+    let number: f64 = number.parse().ok()?;
+    Some((number * multiplier as f64) as u64)
+}
+
 /// Removes trailing spaces from sideband line, which may be padded by the `git`
 /// CLI in order to clear the previous progress line.
 fn trim_sideband_line(line: &[u8]) -> (&[u8], Option<u8>) {
@@ -707,6 +963,16 @@ and the repository exists. "###;
 =\tdeadbeef:refs/heads/bookmark5\tdeadbeef\t[new branch]
 !\tdeadbeef:refs/heads/bookmark6\tdeadbeef\t[new branch]
 Done";
+    const SAMPLE_PUSH_REJECTED_PORCELAIN_OUTPUT: &[u8] = b"To origin
+!\tdeadbeef:refs/heads/bookmark1\t[rejected] (stale info)
+!\tdeadbeef:refs/heads/bookmark2\t[rejected] (atomic push failed)
+!\tdeadbeef:refs/heads/bookmark3\t[remote rejected] (hook declined)
+=\tdeadbeef:refs/heads/bookmark4\t[up to date]
+Done";
+    const SAMPLE_USERNAME_PROMPT_ERROR: &[u8] =
+        b"fatal: could not read Username for 'https://example.com': terminal prompts disabled";
+    const SAMPLE_PASSWORD_PROMPT_ERROR: &[u8] = b"\
+fatal: could not read Password for 'https://user@example.com': terminal prompts disabled";
     const SAMPLE_OK_STDERR: &[u8] = b"";
 
     #[test]
@@ -778,10 +1044,14 @@ Done";
         assert!(parse_ref_pushes(SAMPLE_NO_SUCH_REMOTE_ERROR).is_err());
         assert!(parse_ref_pushes(SAMPLE_NO_REMOTE_REF_ERROR).is_err());
         assert!(parse_ref_pushes(SAMPLE_NO_REMOTE_TRACKING_BRANCH_ERROR).is_err());
-        let (failed, success) = parse_ref_pushes(SAMPLE_PUSH_REFS_PORCELAIN_OUTPUT).unwrap();
-        assert_eq!(failed, vec!["refs/heads/bookmark6".to_string()]);
+        let push_stats = parse_ref_pushes(SAMPLE_PUSH_REFS_PORCELAIN_OUTPUT).unwrap();
+        assert_eq!(
+            push_stats.rejected,
+            vec![("refs/heads/bookmark6".to_string(), None)]
+        );
+        assert!(push_stats.remote_rejected.is_empty());
         assert_eq!(
-            success,
+            push_stats.pushed,
             vec![
                 "refs/heads/bookmark1".to_string(),
                 "refs/heads/bookmark2".to_string(),
@@ -791,6 +1061,48 @@ Done";
             ]
         );
         assert!(parse_ref_pushes(SAMPLE_OK_STDERR).is_err());
+
+        let push_stats = parse_ref_pushes(SAMPLE_PUSH_REJECTED_PORCELAIN_OUTPUT).unwrap();
+        assert_eq!(
+            push_stats,
+            GitPushStats {
+                pushed: vec!["refs/heads/bookmark4".to_string()],
+                rejected: vec![
+                    (
+                        "refs/heads/bookmark1".to_string(),
+                        Some("stale info".to_string())
+                    ),
+                    (
+                        "refs/heads/bookmark2".to_string(),
+                        Some("atomic push failed".to_string())
+                    ),
+                ],
+                remote_rejected: vec![(
+                    "refs/heads/bookmark3".to_string(),
+                    Some("hook declined".to_string())
+                )],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_credential_prompt() {
+        assert_eq!(
+            parse_credential_prompt(SAMPLE_USERNAME_PROMPT_ERROR),
+            Some(CredentialPrompt {
+                url: "https://example.com".to_string(),
+                username: None,
+            })
+        );
+        assert_eq!(
+            parse_credential_prompt(SAMPLE_PASSWORD_PROMPT_ERROR),
+            Some(CredentialPrompt {
+                url: "https://example.com".to_string(),
+                username: Some("user".to_string()),
+            })
+        );
+        assert_eq!(parse_credential_prompt(SAMPLE_NO_SUCH_REMOTE_ERROR), None);
+        assert_eq!(parse_credential_prompt(SAMPLE_OK_STDERR), None);
     }
 
     #[test]
@@ -842,6 +1154,67 @@ Done";
         assert_eq!(output, b"blah blah\nsome error message");
     }
 
+    #[test]
+    fn test_read_to_end_with_byte_progress() {
+        let mut progress = Vec::new();
+        let mut callbacks = RemoteCallbacks::default();
+        let mut progress_cb = |p: &Progress| progress.push(p.clone());
+        callbacks.progress = Some(&mut progress_cb);
+        let sample = indoc::indoc! {"
+            Receiving objects:  25% (1/4), 512 bytes | 1.00 KiB/s\r\
+            Receiving objects:  50% (2/4), 1.50 MiB | 1.00 MiB/s\r\
+            Receiving objects: 100% (4/4), 3.00 MiB | 1.00 MiB/s, done.
+            Writing objects: 100% (4/4), 230 bytes | 230.00 KiB/s, done.
+        "};
+        let output = read_to_end_with_progress(sample.as_bytes(), &mut callbacks).unwrap();
+        assert_eq!(output, b"");
+        insta::assert_debug_snapshot!(progress, @r"
+        [
+            Progress {
+                bytes_downloaded: Some(
+                    512,
+                ),
+                overall: 0.25,
+            },
+            Progress {
+                bytes_downloaded: Some(
+                    1572864,
+                ),
+                overall: 0.5,
+            },
+            Progress {
+                bytes_downloaded: None,
+                overall: 1.0,
+            },
+            Progress {
+                bytes_downloaded: None,
+                overall: 1.0,
+            },
+        ]
+        ");
+    }
+
+    #[test]
+    fn test_read_progress_bytes() {
+        assert_eq!(
+            read_progress_bytes(b"Receiving objects:  50% (2/4), 512 bytes | 1.00 KiB/s\r"),
+            Some(512)
+        );
+        assert_eq!(
+            read_progress_bytes(b"Receiving objects:  50% (2/4), 1.50 KiB | 1.00 KiB/s\r"),
+            Some(1536)
+        );
+        assert_eq!(
+            read_progress_bytes(b"Receiving objects:  50% (2/4), 2.00 MiB | 1.00 MiB/s\r"),
+            Some(2 << 20)
+        );
+        assert_eq!(
+            read_progress_bytes(b"Receiving objects:  50% (2/4)\r"),
+            None
+        );
+        assert_eq!(read_progress_bytes(b"Resolving deltas: (0/1000)\r"), None);
+    }
+
     #[test]
     fn test_read_progress_line() {
         assert_eq!(
@@ -31,6 +31,7 @@ pub mod content_hash;
 pub mod absorb;
 pub mod annotate;
 pub mod backend;
+pub mod bisect;
 pub mod commit;
 pub mod commit_builder;
 pub mod config;
@@ -78,14 +79,21 @@ pub mod local_working_copy;
 pub mod lock;
 pub mod matchers;
 pub mod merge;
+pub mod merge_driver;
 pub mod merged_tree;
 pub mod object_id;
+pub mod op_bundle;
 pub mod op_heads_store;
+pub mod op_retention;
 pub mod op_store;
 pub mod op_walk;
 pub mod operation;
+pub mod opset;
+mod opset_parser;
+pub mod pointer_file;
 #[allow(missing_docs)]
 pub mod protos;
+pub mod recorded_resolutions;
 pub mod refs;
 pub mod repo;
 pub mod repo_path;
@@ -102,10 +110,12 @@ pub mod ssh_signing;
 pub mod stacked_table;
 pub mod store;
 pub mod str_util;
+pub mod submodule;
 pub mod submodule_store;
 #[cfg(feature = "testing")]
 pub mod test_signing_backend;
 pub mod time_util;
+pub mod trailer;
 pub mod transaction;
 pub mod tree;
 pub mod tree_builder;
@@ -378,7 +378,7 @@ pub fn commit_to_proto(commit: &Commit) -> crate::protos::local_store::Commit {
     proto
 }
 
-fn commit_from_proto(mut proto: crate::protos::local_store::Commit) -> Commit {
+pub(crate) fn commit_from_proto(mut proto: crate::protos::local_store::Commit) -> Commit {
     // Note how .take() sets the secure_sig field to None before we encode the data.
     // Needs to be done first since proto is partially moved a bunch below
     let secure_sig = proto.secure_sig.take().map(|sig| SecureSig {
@@ -408,7 +408,7 @@ fn commit_from_proto(mut proto: crate::protos::local_store::Commit) -> Commit {
     }
 }
 
-fn tree_to_proto(tree: &Tree) -> crate::protos::local_store::Tree {
+pub(crate) fn tree_to_proto(tree: &Tree) -> crate::protos::local_store::Tree {
     let mut proto = crate::protos::local_store::Tree::default();
     for entry in tree.entries() {
         proto.entries.push(crate::protos::local_store::tree::Entry {
@@ -419,7 +419,7 @@ fn tree_to_proto(tree: &Tree) -> crate::protos::local_store::Tree {
     proto
 }
 
-fn tree_from_proto(proto: crate::protos::local_store::Tree) -> Tree {
+pub(crate) fn tree_from_proto(proto: crate::protos::local_store::Tree) -> Tree {
     let mut tree = Tree::default();
     for proto_entry in proto.entries {
         let value = tree_value_from_proto(proto_entry.value.unwrap());
@@ -504,7 +504,7 @@ fn signature_from_proto(proto: crate::protos::local_store::commit::Signature) ->
     }
 }
 
-fn conflict_to_proto(conflict: &Conflict) -> crate::protos::local_store::Conflict {
+pub(crate) fn conflict_to_proto(conflict: &Conflict) -> crate::protos::local_store::Conflict {
     let mut proto = crate::protos::local_store::Conflict::default();
     for term in &conflict.removes {
         proto.removes.push(conflict_term_to_proto(term));
@@ -515,7 +515,7 @@ fn conflict_to_proto(conflict: &Conflict) -> crate::protos::local_store::Conflic
     proto
 }
 
-fn conflict_from_proto(proto: crate::protos::local_store::Conflict) -> Conflict {
+pub(crate) fn conflict_from_proto(proto: crate::protos::local_store::Conflict) -> Conflict {
     let removes = proto
         .removes
         .into_iter()
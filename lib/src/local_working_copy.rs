@@ -17,6 +17,7 @@
 
 use std::any::Any;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fs;
@@ -26,6 +27,7 @@ use std::fs::Metadata;
 use std::fs::OpenOptions;
 use std::io;
 use std::io::Read;
+use std::io::Seek as _;
 use std::io::Write;
 use std::iter;
 use std::mem;
@@ -69,11 +71,12 @@ use crate::conflicts;
 use crate::conflicts::choose_materialized_conflict_marker_len;
 use crate::conflicts::materialize_merge_result_to_bytes_with_marker_len;
 use crate::conflicts::materialize_tree_value;
-use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::ConflictMarkerOverrides;
 use crate::conflicts::MaterializedTreeValue;
-use crate::conflicts::MIN_CONFLICT_MARKER_LEN;
 use crate::file_util::check_symlink_support;
 use crate::file_util::try_symlink;
+#[cfg(target_os = "linux")]
+use crate::fsmonitor::inotify;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
 use crate::fsmonitor::FsmonitorSettings;
@@ -96,10 +99,16 @@ use crate::merged_tree::TreeDiffEntry;
 use crate::object_id::ObjectId;
 use crate::op_store::OperationId;
 use crate::op_store::WorkspaceId;
+use crate::pointer_file::Pointer;
+use crate::pointer_file::MAX_POINTER_SIZE;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathComponent;
 use crate::store::Store;
+use crate::submodule;
+use crate::submodule::SubmoduleFileStates;
+use crate::submodule::SubmoduleNames;
+use crate::submodule_store::SubmoduleStore;
 use crate::tree::Tree;
 use crate::working_copy::CheckoutError;
 use crate::working_copy::CheckoutOptions;
@@ -107,6 +116,8 @@ use crate::working_copy::CheckoutStats;
 use crate::working_copy::LockedWorkingCopy;
 use crate::working_copy::ResetError;
 use crate::working_copy::SnapshotError;
+use crate::working_copy::SnapshotFileAction;
+use crate::working_copy::SnapshotFilePolicy;
 use crate::working_copy::SnapshotOptions;
 use crate::working_copy::SnapshotProgress;
 use crate::working_copy::SnapshotStats;
@@ -421,6 +432,13 @@ pub struct TreeState {
     /// the repo is configured to use the Watchman filesystem monitor and
     /// Watchman has been queried at least once.
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+
+    /// The most recent clock value returned by the inotify filesystem monitor.
+    inotify_clock: Option<crate::protos::working_copy::InotifyClock>,
+
+    /// States of the files in checked-out submodules, keyed by the path of
+    /// the submodule.
+    submodule_file_states: BTreeMap<RepoPathBuf, SubmoduleFileStates>,
 }
 
 fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> FileState {
@@ -491,6 +509,39 @@ fn file_state_entry_to_proto(
     }
 }
 
+fn submodule_file_states_from_proto(
+    proto: &crate::protos::working_copy::SubmoduleFileStates,
+) -> (RepoPathBuf, SubmoduleFileStates) {
+    let file_states = proto
+        .file_states
+        .iter()
+        .map(|entry| {
+            let (path, state) = file_state_entry_from_proto(entry);
+            (path.to_owned(), state)
+        })
+        .collect();
+    let states = SubmoduleFileStates {
+        tree_id: TreeId::new(proto.tree_id.clone()),
+        file_states,
+    };
+    (RepoPathBuf::from_internal_string(&proto.path), states)
+}
+
+fn submodule_file_states_to_proto(
+    path: &RepoPath,
+    states: &SubmoduleFileStates,
+) -> crate::protos::working_copy::SubmoduleFileStates {
+    crate::protos::working_copy::SubmoduleFileStates {
+        path: path.as_internal_file_string().to_owned(),
+        tree_id: states.tree_id.to_bytes(),
+        file_states: states
+            .file_states
+            .iter()
+            .map(|(path, state)| file_state_entry_to_proto(path.clone(), state))
+            .collect(),
+    }
+}
+
 fn is_file_state_entries_proto_unique_and_sorted(
     data: &[crate::protos::working_copy::FileStateEntry],
 ) -> bool {
@@ -596,6 +647,28 @@ fn remove_old_file(disk_path: &Path) -> Result<bool, CheckoutError> {
     }
 }
 
+/// Returns the store of the submodule at `path` if the submodule has been
+/// initialized. The submodule `names` of the tree are read on demand.
+fn get_submodule(
+    options: &CheckoutOptions,
+    tree: &MergedTree,
+    names: &OnceLock<SubmoduleNames>,
+    path: &RepoPath,
+) -> Result<Option<Arc<Store>>, CheckoutError> {
+    let Some(submodule_store) = &options.submodule_store else {
+        return Ok(None);
+    };
+    let name = names
+        .get_or_init(|| SubmoduleNames::from_tree(tree))
+        .get(path);
+    submodule_store
+        .get_submodule(&name)
+        .map_err(|err| CheckoutError::Other {
+            message: format!("Failed to load submodule {name}"),
+            err: err.into(),
+        })
+}
+
 /// Checks if new file or symlink named `disk_path` can be created.
 ///
 /// If the file already exists, this function return `Ok(false)` to signal
@@ -686,7 +759,7 @@ fn mtime_from_metadata(metadata: &Metadata) -> MillisSinceEpoch {
     )
 }
 
-fn file_state(metadata: &Metadata) -> Option<FileState> {
+pub(crate) fn file_state(metadata: &Metadata) -> Option<FileState> {
     let metadata_file_type = metadata.file_type();
     let file_type = if metadata_file_type.is_dir() {
         None
@@ -716,9 +789,23 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
     })
 }
 
+/// Checks if the file looks binary in the same way as Git: by looking for a
+/// NUL byte in the first few kilobytes.
+fn is_binary_file(disk_path: &Path) -> Result<bool, SnapshotError> {
+    let mut head = vec![];
+    File::open(disk_path)
+        .and_then(|file| file.take(8000).read_to_end(&mut head))
+        .map_err(|err| SnapshotError::Other {
+            message: format!("Failed to read file {}", disk_path.display()),
+            err: err.into(),
+        })?;
+    Ok(head.contains(&0))
+}
+
 struct FsmonitorMatcher {
     matcher: Option<Box<dyn Matcher>>,
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    inotify_clock: Option<crate::protos::working_copy::InotifyClock>,
 }
 
 #[derive(Debug, Error)]
@@ -781,6 +868,8 @@ impl TreeState {
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
+            inotify_clock: None,
+            submodule_file_states: BTreeMap::new(),
         }
     }
 
@@ -844,6 +933,12 @@ impl TreeState {
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
         self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
         self.watchman_clock = proto.watchman_clock;
+        self.inotify_clock = proto.inotify_clock;
+        self.submodule_file_states = proto
+            .submodule_file_states
+            .iter()
+            .map(submodule_file_states_from_proto)
+            .collect();
         Ok(())
     }
 
@@ -871,6 +966,12 @@ impl TreeState {
         }
         proto.sparse_patterns = Some(sparse_patterns);
         proto.watchman_clock = self.watchman_clock.clone();
+        proto.inotify_clock = self.inotify_clock.clone();
+        proto.submodule_file_states = self
+            .submodule_file_states
+            .iter()
+            .map(|(path, states)| submodule_file_states_to_proto(path, states))
+            .collect();
 
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -905,6 +1006,22 @@ impl TreeState {
         self.watchman_clock.take();
     }
 
+    #[cfg(target_os = "linux")]
+    fn inotify_fsmonitor(&self) -> inotify::Fsmonitor {
+        inotify::Fsmonitor::new(&self.working_copy_path, &self.state_path.join("inotify"))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[instrument(skip(self))]
+    pub fn query_inotify(
+        &self,
+    ) -> Result<(Option<inotify::Clock>, Option<Vec<PathBuf>>), TreeStateError> {
+        let previous_clock = self.inotify_clock.clone().map(inotify::Clock::from);
+        self.inotify_fsmonitor()
+            .query_changed_files(previous_clock)
+            .map_err(|err| TreeStateError::Fsmonitor(Box::new(err)))
+    }
+
     #[cfg(feature = "watchman")]
     #[tokio::main(flavor = "current_thread")]
     #[instrument(skip(self))]
@@ -956,6 +1073,9 @@ impl TreeState {
             start_tracking_matcher,
             max_new_file_size,
             conflict_marker_style,
+            ref conflict_marker_overrides,
+            ref submodule_store,
+            ref file_policies,
         } = options;
 
         let sparse_matcher = self.sparse_matcher();
@@ -965,6 +1085,7 @@ impl TreeState {
         let FsmonitorMatcher {
             matcher: fsmonitor_matcher,
             watchman_clock,
+            inotify_clock,
         } = self.make_fsmonitor_matcher(fsmonitor_settings)?;
         let fsmonitor_matcher = match fsmonitor_matcher.as_ref() {
             None => &EverythingMatcher,
@@ -975,13 +1096,19 @@ impl TreeState {
         if matcher.visit(RepoPath::root()).is_nothing() {
             // No need to load the current tree, set up channels, etc.
             self.watchman_clock = watchman_clock;
+            self.inotify_clock = inotify_clock;
             return Ok((is_dirty, SnapshotStats::default()));
         }
 
+        let file_policies = file_policies
+            .iter()
+            .map(|policy| (policy, policy.paths.to_matcher()))
+            .collect_vec();
         let (tree_entries_tx, tree_entries_rx) = channel();
         let (file_states_tx, file_states_rx) = channel();
         let (untracked_paths_tx, untracked_paths_rx) = channel();
         let (deleted_files_tx, deleted_files_rx) = channel();
+        let (submodule_file_states_tx, submodule_file_states_rx) = channel();
 
         trace_span!("traverse filesystem").in_scope(|| -> Result<(), SnapshotError> {
             let snapshotter = FileSnapshotter {
@@ -994,10 +1121,17 @@ impl TreeState {
                 file_states_tx,
                 untracked_paths_tx,
                 deleted_files_tx,
+                submodule_file_states_tx,
                 error: OnceLock::new(),
                 progress,
                 max_new_file_size,
-                conflict_marker_style,
+                conflict_markers: &ConflictMarkerOverrides::new(
+                    conflict_marker_style,
+                    conflict_marker_overrides,
+                ),
+                submodule_store: submodule_store.as_deref(),
+                submodule_names: OnceLock::new(),
+                file_policies: &file_policies,
             };
             let directory_to_visit = DirectoryToVisit {
                 dir: RepoPathBuf::root(),
@@ -1040,6 +1174,21 @@ impl TreeState {
             self.file_states
                 .merge_in(changed_file_states, &deleted_files);
         });
+        trace_span!("process submodule file states").in_scope(|| {
+            for (path, states) in submodule_file_states_rx {
+                is_dirty |= self.submodule_file_states.get(&path) != Some(&states);
+                self.submodule_file_states.insert(path, states);
+            }
+            // Forget the states of submodules which are no longer tracked
+            let file_states = self.file_states.all();
+            let num_submodules = self.submodule_file_states.len();
+            self.submodule_file_states.retain(|path, _| {
+                file_states
+                    .get(path)
+                    .is_some_and(|state| state.file_type == FileType::GitSubmodule)
+            });
+            is_dirty |= self.submodule_file_states.len() != num_submodules;
+        });
         trace_span!("write tree").in_scope(|| {
             let new_tree_id = tree_builder.write_tree(&self.store).unwrap();
             is_dirty |= new_tree_id != self.tree_id;
@@ -1057,11 +1206,13 @@ impl TreeState {
         }
         // Since untracked paths aren't cached in the tree state, we'll need to
         // rescan the working directory changes to report or track them later.
-        // TODO: store untracked paths and update watchman_clock?
-        if stats.untracked_paths.is_empty() || watchman_clock.is_none() {
+        // TODO: store untracked paths and update fsmonitor clocks?
+        if stats.untracked_paths.is_empty() || (watchman_clock.is_none() && inotify_clock.is_none())
+        {
             self.watchman_clock = watchman_clock;
+            self.inotify_clock = inotify_clock;
         } else {
-            tracing::info!("not updating watchman clock because there are untracked files");
+            tracing::info!("not updating fsmonitor clock because there are untracked files");
         }
         Ok((is_dirty, stats))
     }
@@ -1071,15 +1222,23 @@ impl TreeState {
         &self,
         fsmonitor_settings: &FsmonitorSettings,
     ) -> Result<FsmonitorMatcher, SnapshotError> {
-        let (watchman_clock, changed_files) = match fsmonitor_settings {
-            FsmonitorSettings::None => (None, None),
-            FsmonitorSettings::Test { changed_files } => (None, Some(changed_files.clone())),
+        let mut watchman_clock = None;
+        let mut inotify_clock = None;
+        // Whether the changed paths may be directories, standing for everything
+        // below them
+        let mut changed_files_are_prefixes = false;
+        let changed_files = match fsmonitor_settings {
+            FsmonitorSettings::None => None,
+            FsmonitorSettings::Test { changed_files } => Some(changed_files.clone()),
             #[cfg(feature = "watchman")]
             FsmonitorSettings::Watchman(config) => match self.query_watchman(config) {
-                Ok((watchman_clock, changed_files)) => (Some(watchman_clock.into()), changed_files),
+                Ok((clock, changed_files)) => {
+                    watchman_clock = Some(clock.into());
+                    changed_files
+                }
                 Err(err) => {
                     tracing::warn!(?err, "Failed to query filesystem monitor");
-                    (None, None)
+                    None
                 }
             },
             #[cfg(not(feature = "watchman"))]
@@ -1091,6 +1250,27 @@ impl TreeState {
                         .into(),
                 });
             }
+            #[cfg(target_os = "linux")]
+            FsmonitorSettings::Inotify => match self.query_inotify() {
+                Ok((clock, changed_files)) => {
+                    inotify_clock = clock.map(Into::into);
+                    changed_files_are_prefixes = true;
+                    changed_files
+                }
+                Err(err) => {
+                    tracing::warn!(?err, "Failed to query filesystem monitor");
+                    None
+                }
+            },
+            #[cfg(not(target_os = "linux"))]
+            FsmonitorSettings::Inotify => {
+                return Err(SnapshotError::Other {
+                    message: "Failed to query the filesystem monitor".to_string(),
+                    err: "The inotify filesystem monitor is only supported on Linux (consider \
+                          disabling `core.fsmonitor`)"
+                        .into(),
+                });
+            }
         };
         let matcher: Option<Box<dyn Matcher>> = match changed_files {
             None => None,
@@ -1102,12 +1282,17 @@ impl TreeState {
                         .collect_vec()
                 });
 
-                Some(Box::new(FilesMatcher::new(repo_paths)))
+                if changed_files_are_prefixes {
+                    Some(Box::new(PrefixMatcher::new(repo_paths)))
+                } else {
+                    Some(Box::new(FilesMatcher::new(repo_paths)))
+                }
             }
         };
         Ok(FsmonitorMatcher {
             matcher,
             watchman_clock,
+            inotify_clock,
         })
     }
 }
@@ -1141,10 +1326,15 @@ struct FileSnapshotter<'a> {
     file_states_tx: Sender<(RepoPathBuf, FileState)>,
     untracked_paths_tx: Sender<(RepoPathBuf, UntrackedReason)>,
     deleted_files_tx: Sender<RepoPathBuf>,
+    submodule_file_states_tx: Sender<(RepoPathBuf, SubmoduleFileStates)>,
     error: OnceLock<SnapshotError>,
     progress: Option<&'a SnapshotProgress<'a>>,
     max_new_file_size: u64,
-    conflict_marker_style: ConflictMarkerStyle,
+    conflict_markers: &'a ConflictMarkerOverrides,
+    submodule_store: Option<&'a dyn SubmoduleStore>,
+    // Parsed on demand since most working copies don't have submodules
+    submodule_names: OnceLock<SubmoduleNames>,
+    file_policies: &'a [(&'a SnapshotFilePolicy, Box<dyn Matcher>)],
 }
 
 impl FileSnapshotter<'_> {
@@ -1236,6 +1426,9 @@ impl FileSnapshotter<'_> {
         let maybe_current_file_state = file_states.get_at(dir, name);
         if let Some(file_state) = &maybe_current_file_state {
             if file_state.file_type == FileType::GitSubmodule {
+                if file_type.is_dir() && self.matcher.matches(&path) {
+                    self.process_submodule(path, &entry.path())?;
+                }
                 return Ok(None);
             }
         }
@@ -1286,7 +1479,34 @@ impl FileSnapshotter<'_> {
                     message: format!("Failed to stat file {}", entry.path().display()),
                     err: err.into(),
                 })?;
-                if maybe_current_file_state.is_none() && metadata.len() > self.max_new_file_size {
+                let policy = if maybe_current_file_state.is_none() && metadata.is_file() {
+                    self.find_file_policy(&path, &entry.path(), metadata.len())?
+                } else {
+                    None
+                };
+                let policy_action = policy.map(|policy| policy.action);
+                if let Some(policy) = policy.filter(|policy| {
+                    matches!(
+                        policy.action,
+                        SnapshotFileAction::Skip | SnapshotFileAction::Manual
+                    )
+                }) {
+                    // Leave the file untracked as the policy says
+                    let reason = if policy.action == SnapshotFileAction::Skip {
+                        UntrackedReason::FileSkippedByPolicy {
+                            policy: policy.name.clone(),
+                        }
+                    } else {
+                        UntrackedReason::FileRequiresExplicitTracking {
+                            policy: policy.name.clone(),
+                        }
+                    };
+                    self.untracked_paths_tx.send((path, reason)).ok();
+                    Ok(None)
+                } else if maybe_current_file_state.is_none()
+                    && policy_action != Some(SnapshotFileAction::Pointer)
+                    && metadata.len() > self.max_new_file_size
+                {
                     // Leave the large file untracked
                     let reason = UntrackedReason::FileTooLarge {
                         size: metadata.len(),
@@ -1312,6 +1532,41 @@ impl FileSnapshotter<'_> {
         }
     }
 
+    /// Records changes to the files of a checked-out submodule as a new
+    /// submodule commit.
+    fn process_submodule(&self, path: RepoPathBuf, disk_dir: &Path) -> Result<(), SnapshotError> {
+        let Some(submodule_store) = self.submodule_store else {
+            return Ok(());
+        };
+        let current_value = self.current_tree.path_value(&path)?;
+        let Some(Some(TreeValue::GitSubmodule(id))) = current_value.as_resolved() else {
+            return Ok(());
+        };
+        let name = self
+            .submodule_names
+            .get_or_init(|| SubmoduleNames::from_tree(self.current_tree))
+            .get(&path);
+        let Some(snapshot) = submodule::snapshot_submodule(
+            submodule_store,
+            &name,
+            disk_dir,
+            id,
+            self.tree_state.submodule_file_states.get(&path),
+            self.tree_state.own_mtime,
+        )?
+        else {
+            return Ok(());
+        };
+        if let Some(new_id) = snapshot.new_id {
+            let tree_value = Merge::normal(TreeValue::GitSubmodule(new_id));
+            self.tree_entries_tx.send((path.clone(), tree_value)).ok();
+        }
+        self.submodule_file_states_tx
+            .send((path, snapshot.file_states))
+            .ok();
+        Ok(())
+    }
+
     /// Visits only paths we're already tracking.
     fn visit_tracked_files(&self, file_states: FileStates<'_>) -> Result<(), SnapshotError> {
         for (tracked_path, current_file_state) in file_states {
@@ -1472,6 +1727,32 @@ impl FileSnapshotter<'_> {
         &self.tree_state.store
     }
 
+    /// Returns the first file policy that applies to the file.
+    fn find_file_policy(
+        &self,
+        path: &RepoPath,
+        disk_path: &Path,
+        size: u64,
+    ) -> Result<Option<&SnapshotFilePolicy>, SnapshotError> {
+        let mut is_binary = None;
+        for (policy, matcher) in self.file_policies {
+            if !matcher.matches(path) || policy.min_size.is_some_and(|min| size < min) {
+                continue;
+            }
+            if policy.binary_only {
+                let is_binary = match is_binary {
+                    Some(is_binary) => is_binary,
+                    None => *is_binary.insert(is_binary_file(disk_path)?),
+                };
+                if !is_binary {
+                    continue;
+                }
+            }
+            return Ok(Some(policy));
+        }
+        Ok(None)
+    }
+
     async fn write_path_to_store(
         &self,
         repo_path: &RepoPath,
@@ -1508,10 +1789,11 @@ impl FileSnapshotter<'_> {
                 self.store(),
                 repo_path,
                 &content,
-                self.conflict_marker_style,
-                materialized_conflict_data.map_or(MIN_CONFLICT_MARKER_LEN, |data| {
-                    data.conflict_marker_len as usize
-                }),
+                self.conflict_markers.style(repo_path),
+                materialized_conflict_data.map_or_else(
+                    || self.conflict_markers.min_len(repo_path),
+                    |data| data.conflict_marker_len as usize,
+                ),
             )
             .block_on()?;
             match new_file_ids.into_resolved() {
@@ -1553,9 +1835,60 @@ impl FileSnapshotter<'_> {
             message: format!("Failed to open file {}", disk_path.display()),
             err: err.into(),
         })?;
+        if !self.file_policies.is_empty() {
+            let size = file
+                .metadata()
+                .map_err(|err| SnapshotError::Other {
+                    message: format!("Failed to stat file {}", disk_path.display()),
+                    err: err.into(),
+                })?
+                .len();
+            let policy = self.find_file_policy(path, disk_path, size)?;
+            if policy.is_some_and(|policy| policy.action == SnapshotFileAction::Pointer) {
+                return self
+                    .write_pointer_to_store(path, disk_path, file, size)
+                    .await;
+            }
+        }
         Ok(self.store().write_file(path, &mut file).await?)
     }
 
+    /// Moves the file content to the pointer store and writes the pointer to
+    /// the backend instead.
+    async fn write_pointer_to_store(
+        &self,
+        path: &RepoPath,
+        disk_path: &Path,
+        mut file: File,
+        size: u64,
+    ) -> Result<FileId, SnapshotError> {
+        let map_err = |err: io::Error| SnapshotError::Other {
+            message: format!("Failed to store file {} as pointer", disk_path.display()),
+            err: err.into(),
+        };
+        if size <= MAX_POINTER_SIZE as u64 {
+            // Pointers that couldn't be resolved on checkout are kept as is.
+            let mut content = vec![];
+            file.read_to_end(&mut content).map_err(map_err)?;
+            if Pointer::parse(&content).is_some() {
+                return Ok(self
+                    .store()
+                    .write_file(path, &mut content.as_slice())
+                    .await?);
+            }
+            file.rewind().map_err(map_err)?;
+        }
+        let pointer = self
+            .store()
+            .pointer_store()
+            .write(&mut file)
+            .map_err(map_err)?;
+        Ok(self
+            .store()
+            .write_file(path, &mut pointer.to_bytes().as_slice())
+            .await?)
+    }
+
     async fn write_symlink_to_store(
         &self,
         path: &RepoPath,
@@ -1589,6 +1922,42 @@ impl FileSnapshotter<'_> {
 
 /// Functions to update local-disk files from the store.
 impl TreeState {
+    /// Writes the file, replacing a pointer by the content it refers to if
+    /// the content is available in the pointer store.
+    fn write_file_or_pointer_content(
+        &self,
+        disk_path: &Path,
+        mut reader: Box<dyn Read>,
+        executable: bool,
+    ) -> Result<FileState, CheckoutError> {
+        let mut head = vec![];
+        reader
+            .by_ref()
+            .take(MAX_POINTER_SIZE as u64 + 1)
+            .read_to_end(&mut head)
+            .map_err(|err| CheckoutError::Other {
+                message: format!("Failed to read content of {}", disk_path.display()),
+                err: err.into(),
+            })?;
+        if let Some(pointer) = Pointer::parse(&head) {
+            let pointer_file =
+                self.store
+                    .pointer_store()
+                    .open(&pointer)
+                    .map_err(|err| CheckoutError::Other {
+                        message: format!(
+                            "Failed to read pointer content of {}",
+                            disk_path.display()
+                        ),
+                        err: err.into(),
+                    })?;
+            if let Some(mut pointer_file) = pointer_file {
+                return self.write_file(disk_path, &mut pointer_file, executable);
+            }
+        }
+        self.write_file(disk_path, &mut head.as_slice().chain(reader), executable)
+    }
+
     fn write_file(
         &self,
         disk_path: &Path,
@@ -1690,12 +2059,7 @@ impl TreeState {
             other => CheckoutError::InternalBackendError(other),
         })?;
         let stats = self
-            .update(
-                &old_tree,
-                new_tree,
-                self.sparse_matcher().as_ref(),
-                options.conflict_marker_style,
-            )
+            .update(&old_tree, new_tree, self.sparse_matcher().as_ref(), options)
             .block_on()?;
         self.tree_id = new_tree.id();
         Ok(stats)
@@ -1718,32 +2082,22 @@ impl TreeState {
         let removed_matcher = DifferenceMatcher::new(&old_matcher, &new_matcher);
         let empty_tree = MergedTree::resolved(Tree::empty(self.store.clone(), RepoPathBuf::root()));
         let added_stats = self
-            .update(
-                &empty_tree,
-                &tree,
-                &added_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&empty_tree, &tree, &added_matcher, options)
             .block_on()?;
         let removed_stats = self
-            .update(
-                &tree,
-                &empty_tree,
-                &removed_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&tree, &empty_tree, &removed_matcher, options)
             .block_on()?;
         self.sparse_patterns = sparse_patterns;
         assert_eq!(added_stats.updated_files, 0);
         assert_eq!(added_stats.removed_files, 0);
         assert_eq!(removed_stats.updated_files, 0);
         assert_eq!(removed_stats.added_files, 0);
-        assert_eq!(removed_stats.skipped_files, 0);
         Ok(CheckoutStats {
             updated_files: 0,
             added_files: added_stats.added_files,
             removed_files: removed_stats.removed_files,
-            skipped_files: added_stats.skipped_files,
+            // Modified files in submodules are kept when removing them.
+            skipped_files: added_stats.skipped_files + removed_stats.skipped_files,
         })
     }
 
@@ -1752,8 +2106,13 @@ impl TreeState {
         old_tree: &MergedTree,
         new_tree: &MergedTree,
         matcher: &dyn Matcher,
-        conflict_marker_style: ConflictMarkerStyle,
+        options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
+        let conflict_markers = ConflictMarkerOverrides::new(
+            options.conflict_marker_style,
+            &options.conflict_marker_overrides,
+        );
+        let pointer_matcher = options.pointer_paths.to_matcher();
         // TODO: maybe it's better not include the skipped counts in the "intended"
         // counts
         let mut stats = CheckoutStats {
@@ -1764,6 +2123,17 @@ impl TreeState {
         };
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
+        let old_names = OnceLock::new();
+        let new_names = OnceLock::new();
+        // Load the new contents at once instead of one by one if the backend
+        // loads objects lazily. Trees are cached, so diffing twice is cheap.
+        let mut new_values = Vec::new();
+        let mut prefetch_stream = old_tree.diff_stream(new_tree, matcher);
+        while let Some(TreeDiffEntry { values, .. }) = prefetch_stream.next().await {
+            let (_, after) = values?;
+            new_values.extend(after.into_iter().flatten());
+        }
+        self.store.prefetch_objects(&new_values).await?;
         let mut diff_stream = old_tree
             .diff_stream(new_tree, matcher)
             .map(|TreeDiffEntry { path, values }| async {
@@ -1787,20 +2157,39 @@ impl TreeState {
             }
 
             // Existing Git submodule can be a non-empty directory on disk. We
-            // shouldn't attempt to manage it as a tracked path.
+            // shouldn't attempt to manage it as a tracked path, but the files
+            // in it are updated if the submodule commit is available.
             //
             // TODO: It might be better to add general support for paths not
             // tracked by jj than processing submodules specially. For example,
             // paths excluded by .gitignore can be marked as such so that
             // newly-"unignored" paths won't be snapshotted automatically.
-            if matches!(before.as_normal(), Some(TreeValue::GitSubmodule(_)))
-                && matches!(after, MaterializedTreeValue::GitSubmodule(_))
+            if let (
+                Some(TreeValue::GitSubmodule(old_id)),
+                MaterializedTreeValue::GitSubmodule(new_id),
+            ) = (before.as_normal(), &after)
             {
-                eprintln!("ignoring git submodule at {path:?}");
+                if let Some(store) = get_submodule(options, new_tree, &new_names, &path)? {
+                    let disk_dir = path.to_fs_path(&self.working_copy_path)?;
+                    stats.skipped_files +=
+                        submodule::check_out_submodule(&store, &disk_dir, Some(old_id), new_id)?;
+                    self.submodule_file_states.remove(&path);
+                }
                 // Not updating the file state as if there were no diffs. Leave
                 // the state type as FileType::GitSubmodule if it was before.
                 continue;
             }
+            if let Some(TreeValue::GitSubmodule(old_id)) = before.as_normal() {
+                self.submodule_file_states.remove(&path);
+                if let Some(store) = get_submodule(options, old_tree, &old_names, &path)? {
+                    let disk_dir = path.to_fs_path(&self.working_copy_path)?;
+                    stats.skipped_files +=
+                        submodule::remove_submodule_files(&store, &disk_dir, old_id)?;
+                    // The directory can be removed if there are no untracked
+                    // files left.
+                    fs::remove_dir(&disk_dir).ok();
+                }
+            }
 
             // Create parent directories no matter if after.is_present(). This
             // ensures that the path never traverses symlinks.
@@ -1835,7 +2224,13 @@ impl TreeState {
                     executable,
                     mut reader,
                     ..
-                } => self.write_file(&disk_path, &mut reader, executable)?,
+                } => {
+                    if pointer_matcher.matches(&path) {
+                        self.write_file_or_pointer_content(&disk_path, reader, executable)?
+                    } else {
+                        self.write_file(&disk_path, &mut reader, executable)?
+                    }
+                }
                 MaterializedTreeValue::Symlink { id: _, target } => {
                     if self.symlink_support {
                         self.write_symlink(&disk_path, target)?
@@ -1843,8 +2238,11 @@ impl TreeState {
                         self.write_file(&disk_path, &mut target.as_bytes(), false)?
                     }
                 }
-                MaterializedTreeValue::GitSubmodule(_) => {
-                    eprintln!("ignoring git submodule at {path:?}");
+                MaterializedTreeValue::GitSubmodule(id) => {
+                    if let Some(store) = get_submodule(options, new_tree, &new_names, &path)? {
+                        stats.skipped_files +=
+                            submodule::check_out_submodule(&store, &disk_path, None, &id)?;
+                    }
                     FileState::for_gitsubmodule()
                 }
                 MaterializedTreeValue::Tree(_) => {
@@ -1855,10 +2253,11 @@ impl TreeState {
                     contents,
                     executable,
                 } => {
-                    let conflict_marker_len = choose_materialized_conflict_marker_len(&contents);
+                    let conflict_marker_len = choose_materialized_conflict_marker_len(&contents)
+                        .max(conflict_markers.min_len(&path));
                     let data = materialize_merge_result_to_bytes_with_marker_len(
                         &contents,
-                        conflict_marker_style,
+                        conflict_markers.style(&path),
                         conflict_marker_len,
                     )
                     .into();
@@ -2178,6 +2577,25 @@ impl LocalWorkingCopy {
                 err: err.into(),
             })
     }
+
+    /// Returns a handle to the inotify filesystem monitor of this working
+    /// copy.
+    #[cfg(target_os = "linux")]
+    pub fn inotify_fsmonitor(&self) -> inotify::Fsmonitor {
+        inotify::Fsmonitor::new(&self.working_copy_path, &self.state_path.join("inotify"))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn query_inotify(
+        &self,
+    ) -> Result<(Option<inotify::Clock>, Option<Vec<PathBuf>>), WorkingCopyStateError> {
+        self.tree_state()?
+            .query_inotify()
+            .map_err(|err| WorkingCopyStateError {
+                message: "Failed to query the filesystem monitor".to_string(),
+                err: err.into(),
+            })
+    }
 }
 
 pub struct LocalWorkingCopyFactory {}
@@ -543,10 +543,58 @@ impl MergedTreeValue {
     }
 }
 
+/// Kind of a conflict between tree values, as returned by
+/// [`Merge::conflict_kind()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictKind {
+    /// Conflicting changes to the contents of regular files, possibly
+    /// including deletions.
+    Content,
+    /// The contents of the files can be merged, but their executable bits
+    /// conflict.
+    ExecutableBit,
+    /// At least one of the terms is a symlink.
+    Symlink,
+    /// At least one of the terms is a directory and another is not.
+    FileDirectory,
+    /// At least one of the terms is a Git submodule.
+    GitSubmodule,
+}
+
 impl<T> Merge<Option<T>>
 where
     T: Borrow<TreeValue>,
 {
+    /// Determines which kind of conflict this is. If several kinds apply, the
+    /// first of file/directory, symlink, and Git submodule conflicts is
+    /// returned.
+    pub fn conflict_kind(&self) -> ConflictKind {
+        let values = self.iter().flatten().map(|value| value.borrow());
+        let (mut has_tree, mut has_symlink, mut has_submodule) = (false, false, false);
+        for value in values {
+            match value {
+                TreeValue::Tree(_) => has_tree = true,
+                TreeValue::Symlink(_) => has_symlink = true,
+                TreeValue::GitSubmodule(_) => has_submodule = true,
+                TreeValue::File { .. } | TreeValue::Conflict(_) => {}
+            }
+        }
+        if has_tree {
+            ConflictKind::FileDirectory
+        } else if has_symlink {
+            ConflictKind::Symlink
+        } else if has_submodule {
+            ConflictKind::GitSubmodule
+        } else if self
+            .to_file_merge()
+            .is_some_and(|file_ids| file_ids.resolve_trivial().is_some())
+        {
+            ConflictKind::ExecutableBit
+        } else {
+            ConflictKind::Content
+        }
+    }
+
     /// Whether this merge should be recursed into when doing directory walks.
     pub fn is_tree(&self) -> bool {
         self.is_present()
@@ -1167,4 +1215,48 @@ mod tests {
             c(&[0, 1, 2, 5, 4, 3, 6, 7, 8])
         );
     }
+
+    #[test]
+    fn test_conflict_kind() {
+        let file = |id: &'static str, executable| {
+            Some(TreeValue::File {
+                id: FileId::from_hex(id),
+                executable,
+            })
+        };
+        let symlink = Some(TreeValue::Symlink(backend::SymlinkId::from_hex("50")));
+        let tree = Some(TreeValue::Tree(TreeId::from_hex("70")));
+        let submodule = Some(TreeValue::GitSubmodule(backend::CommitId::from_hex("90")));
+
+        let base = file("10", false);
+        assert_eq!(
+            c(&[file("11", false), base.clone(), file("12", false)]).conflict_kind(),
+            ConflictKind::Content
+        );
+        assert_eq!(
+            c(&[file("11", true), base.clone(), None]).conflict_kind(),
+            ConflictKind::Content
+        );
+        assert_eq!(
+            c(&[file("11", true), base.clone(), file("12", false)]).conflict_kind(),
+            ConflictKind::Content
+        );
+        // Both sides added the same content with different executable bits
+        assert_eq!(
+            c(&[file("11", true), None, file("11", false)]).conflict_kind(),
+            ConflictKind::ExecutableBit
+        );
+        assert_eq!(
+            c(&[symlink.clone(), base.clone(), file("11", false)]).conflict_kind(),
+            ConflictKind::Symlink
+        );
+        assert_eq!(
+            c(&[submodule.clone(), base.clone(), None]).conflict_kind(),
+            ConflictKind::GitSubmodule
+        );
+        assert_eq!(
+            c(&[symlink, base, tree]).conflict_kind(),
+            ConflictKind::FileDirectory
+        );
+    }
 }
@@ -0,0 +1,377 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merge drivers which resolve file conflicts instead of the built-in
+//! line-based merge.
+//!
+//! Drivers are selected by fileset. If a driver fails to resolve a conflict,
+//! the file is merged by the built-in merge instead.
+
+#![warn(missing_docs)]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use regex::Captures;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::config::ConfigGetError;
+use crate::config::ConfigGetResultExt as _;
+use crate::files;
+use crate::files::MergeResult;
+use crate::fileset;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetExpression;
+use crate::matchers::Matcher;
+use crate::merge::Merge;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathUiConverter;
+use crate::settings::UserSettings;
+
+static VARIABLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$([a-z0-9_]+)\b").unwrap());
+
+/// Error that may occur while running a merge driver.
+#[derive(Debug, Error)]
+pub enum MergeDriverError {
+    /// The driver command couldn't be run.
+    #[error("Failed to run merge driver command {program}")]
+    Spawn {
+        /// Program name of the command.
+        program: String,
+        /// Underlying error.
+        #[source]
+        source: io::Error,
+    },
+    /// Failed to pass the file contents to or from the driver.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Resolves file conflicts.
+pub trait MergeDriver: Debug + Send + Sync {
+    /// Name of the driver.
+    fn name(&self) -> &str;
+
+    /// Merges the `contents` of the file at `path`. Returns `None` if the
+    /// driver can't resolve the conflict.
+    ///
+    /// The `contents` are simplified, so there are at least two sides.
+    fn merge(
+        &self,
+        path: &RepoPath,
+        contents: &Merge<Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>, MergeDriverError>;
+}
+
+/// Merges lines like the built-in merge, but resolves conflicting hunks by
+/// concatenating the lines from all sides.
+#[derive(Debug)]
+pub struct UnionMergeDriver;
+
+impl MergeDriver for UnionMergeDriver {
+    fn name(&self) -> &str {
+        "union"
+    }
+
+    fn merge(
+        &self,
+        _path: &RepoPath,
+        contents: &Merge<Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>, MergeDriverError> {
+        let hunks = match files::merge(contents) {
+            MergeResult::Resolved(content) => return Ok(Some(content.into())),
+            MergeResult::Conflict(hunks) => hunks,
+        };
+        let mut merged = vec![];
+        for hunk in &hunks {
+            if let Some(content) = hunk.as_resolved() {
+                merged.extend_from_slice(content);
+                continue;
+            }
+            for content in hunk.adds() {
+                // The last line of a side may not be terminated
+                if !merged.is_empty() && !merged.ends_with(b"\n") {
+                    merged.push(b'\n');
+                }
+                merged.extend_from_slice(content);
+            }
+        }
+        Ok(Some(merged))
+    }
+}
+
+/// Runs an external command to merge two sides of a file.
+///
+/// The command is passed the `$base`, `$left`, and `$right` files, and should
+/// write the merged content to the `$output` file, which initially contains
+/// the left side. The conflict is resolved if the command exits successfully.
+/// Files with more than two sides aren't passed to the command.
+#[derive(Debug)]
+pub struct ExternalMergeDriver {
+    name: String,
+    command: Vec<String>,
+}
+
+impl ExternalMergeDriver {
+    /// Creates a driver which runs the `command`. The first element of the
+    /// `command` is the program.
+    pub fn new(name: impl Into<String>, command: Vec<String>) -> Self {
+        assert!(!command.is_empty());
+        ExternalMergeDriver {
+            name: name.into(),
+            command,
+        }
+    }
+
+    fn write_inputs(
+        dir: &Path,
+        path: &RepoPath,
+        base: &[u8],
+        left: &[u8],
+        right: &[u8],
+    ) -> io::Result<HashMap<&'static str, PathBuf>> {
+        // Keep the file extension for commands which look at it
+        let suffix = path
+            .components()
+            .next_back()
+            .and_then(|name| name.as_internal_str().rsplit_once('.'))
+            .map_or(String::new(), |(_, ext)| format!(".{ext}"));
+        let mut paths = HashMap::new();
+        for (name, content) in [
+            ("base", base),
+            ("left", left),
+            ("right", right),
+            ("output", left),
+        ] {
+            let file_path = dir.join(format!("{name}{suffix}"));
+            fs::write(&file_path, content)?;
+            paths.insert(name, file_path);
+        }
+        Ok(paths)
+    }
+}
+
+impl MergeDriver for ExternalMergeDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn merge(
+        &self,
+        path: &RepoPath,
+        contents: &Merge<Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>, MergeDriverError> {
+        let [left, base, right] = contents.as_slice() else {
+            // External commands can only merge two sides
+            return Ok(None);
+        };
+        let temp_dir = tempfile::Builder::new()
+            .prefix("jj-merge-driver-")
+            .tempdir()?;
+        let paths = Self::write_inputs(temp_dir.path(), path, base, left, right)?;
+        let (program, args) = self.command.split_first().unwrap();
+        let args = args.iter().map(|arg| {
+            VARIABLE_REGEX
+                .replace_all(arg, |caps: &Captures| match &caps[1] {
+                    "path" => path.as_internal_file_string().to_owned(),
+                    name => paths
+                        .get(name)
+                        .map_or_else(|| caps[0].to_owned(), |p| p.display().to_string()),
+                })
+                .into_owned()
+        });
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null());
+        tracing::info!(?command, "running merge driver command");
+        let exit_status = command.status().map_err(|source| MergeDriverError::Spawn {
+            program: program.clone(),
+            source,
+        })?;
+        tracing::info!(?command, ?exit_status, "merge driver command exited");
+        if !exit_status.success() {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(&paths["output"])?))
+    }
+}
+
+/// Merge driver error which was ignored by falling back to the built-in merge.
+#[derive(Debug)]
+pub struct MergeDriverFailure {
+    /// Name of the driver.
+    pub driver: String,
+    /// Path of the file being merged.
+    pub path: RepoPathBuf,
+    /// The error.
+    pub error: MergeDriverError,
+}
+
+/// Set of merge drivers and the files they apply to.
+#[derive(Clone, Debug, Default)]
+pub struct MergeDrivers {
+    drivers: Vec<(Arc<dyn Matcher>, Arc<dyn MergeDriver>)>,
+    // Shared by clones so the failures can be reported by the caller
+    failures: Arc<Mutex<Vec<MergeDriverFailure>>>,
+}
+
+impl MergeDrivers {
+    /// Creates a set of drivers. If multiple filesets match a file, the first
+    /// driver is used.
+    pub fn new(drivers: Vec<(FilesetExpression, Arc<dyn MergeDriver>)>) -> Self {
+        let drivers = drivers
+            .into_iter()
+            .map(|(paths, driver)| (Arc::from(paths.to_matcher()), driver))
+            .collect();
+        MergeDrivers {
+            drivers,
+            failures: Default::default(),
+        }
+    }
+
+    /// Loads the drivers from the `merge-drivers` config table. The drivers
+    /// are ordered by name.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        // File patterns are relative to the workspace root
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: PathBuf::new(),
+            base: PathBuf::new(),
+        };
+        let names = settings.table_keys("merge-drivers").sorted().collect_vec();
+        let mut drivers = Vec::with_capacity(names.len());
+        for name in names {
+            let paths = settings.get_value_with(["merge-drivers", name, "paths"], |value| {
+                let text = value.as_str().ok_or("Expected a string")?;
+                fileset::parse_maybe_bare(&mut FilesetDiagnostics::new(), text, &path_converter)
+                    .map_err(Box::<dyn std::error::Error + Send + Sync>::from)
+            })?;
+            let builtin = settings
+                .get_value_with(["merge-drivers", name, "builtin"], |value| {
+                    match value.as_str() {
+                        Some("union") => Ok(Arc::new(UnionMergeDriver) as Arc<dyn MergeDriver>),
+                        Some(builtin) => Err(format!("Unknown built-in merge driver: {builtin}")),
+                        None => Err("Expected a string".to_owned()),
+                    }
+                })
+                .optional()?;
+            let driver = if let Some(driver) = builtin {
+                driver
+            } else {
+                settings.get_value_with(["merge-drivers", name, "command"], |value| {
+                    let command: Vec<String> = value
+                        .as_array()
+                        .and_then(|array| {
+                            array
+                                .iter()
+                                .map(|v| v.as_str().map(str::to_owned))
+                                .collect()
+                        })
+                        .ok_or("Expected an array of strings")?;
+                    if command.is_empty() {
+                        return Err("Expected a non-empty command");
+                    }
+                    Ok(Arc::new(ExternalMergeDriver::new(name, command)) as Arc<dyn MergeDriver>)
+                })?
+            };
+            drivers.push((paths, driver));
+        }
+        Ok(MergeDrivers::new(drivers))
+    }
+
+    /// Returns true if there are no drivers.
+    pub fn is_empty(&self) -> bool {
+        self.drivers.is_empty()
+    }
+
+    /// Returns the driver to use for the file at `path`.
+    pub fn find(&self, path: &RepoPath) -> Option<&dyn MergeDriver> {
+        self.drivers
+            .iter()
+            .find(|(matcher, _)| matcher.matches(path))
+            .map(|(_, driver)| driver.as_ref())
+    }
+
+    /// Merges the `contents` of the file at `path` by the driver for the
+    /// path. Returns `None` if there's no driver, or if the driver can't
+    /// resolve the conflict. Errors are recorded to be reported by
+    /// [`Self::take_failures()`].
+    pub fn merge(&self, path: &RepoPath, contents: &Merge<Vec<u8>>) -> Option<Vec<u8>> {
+        let driver = self.find(path)?;
+        match driver.merge(path, contents) {
+            Ok(merged) => merged,
+            Err(error) => {
+                tracing::warn!(?error, driver = driver.name(), ?path, "merge driver failed");
+                self.failures.lock().unwrap().push(MergeDriverFailure {
+                    driver: driver.name().to_owned(),
+                    path: path.to_owned(),
+                    error,
+                });
+                None
+            }
+        }
+    }
+
+    /// Returns the driver errors recorded since the last call.
+    pub fn take_failures(&self) -> Vec<MergeDriverFailure> {
+        std::mem::take(&mut *self.failures.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(driver: &dyn MergeDriver, contents: [&str; 3]) -> Option<String> {
+        let contents = Merge::from_vec(contents.map(|s| s.as_bytes().to_vec()).to_vec());
+        let path = RepoPath::from_internal_string("file.txt");
+        let merged = driver.merge(path, &contents).unwrap()?;
+        Some(String::from_utf8(merged).unwrap())
+    }
+
+    #[test]
+    fn test_union_merge_driver() {
+        // Clean merge
+        assert_eq!(
+            merge(&UnionMergeDriver, ["a\nb\nC\n", "a\nb\nc\n", "A\nb\nc\n"]),
+            Some("A\nb\nC\n".to_owned())
+        );
+        // Conflicting lines are concatenated
+        assert_eq!(
+            merge(
+                &UnionMergeDriver,
+                ["a\nleft\nb\n", "a\nb\n", "a\nright\nb\n"]
+            ),
+            Some("a\nleft\nright\nb\n".to_owned())
+        );
+        // Missing newline at end of file
+        assert_eq!(
+            merge(&UnionMergeDriver, ["a\nleft", "a\n", "a\nright"]),
+            Some("a\nleft\nright".to_owned())
+        );
+    }
+}
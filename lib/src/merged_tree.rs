@@ -161,7 +161,17 @@ impl MergedTree {
     /// Tries to resolve any conflicts, resolving any conflicts that can be
     /// automatically resolved and leaving the rest unresolved.
     pub fn resolve(&self) -> BackendResult<MergedTree> {
-        let merged = merge_trees(&self.trees)?;
+        self.resolve_impl(false)
+    }
+
+    /// Like `resolve()`, but tries the merge drivers configured for the
+    /// conflicted files first.
+    pub fn resolve_with_merge_drivers(&self) -> BackendResult<MergedTree> {
+        self.resolve_impl(true)
+    }
+
+    fn resolve_impl(&self, use_merge_drivers: bool) -> BackendResult<MergedTree> {
+        let merged = merge_trees(&self.trees, use_merge_drivers)?;
         // If the result can be resolved, then `merge_trees()` above would have returned
         // a resolved merge. However, that function will always preserve the arity of
         // conflicts it cannot resolve. So we simplify the conflict again
@@ -171,7 +181,7 @@ impl MergedTree {
         // particular,  that this last simplification doesn't enable further automatic
         // resolutions
         if cfg!(debug_assertions) {
-            let re_merged = merge_trees(&simplified).unwrap();
+            let re_merged = merge_trees(&simplified, use_merge_drivers).unwrap();
             debug_assert_eq!(re_merged, simplified);
         }
         Ok(MergedTree { trees: simplified })
@@ -325,6 +335,18 @@ impl MergedTree {
         self.merge_no_resolve(base, other).resolve()
     }
 
+    /// Like `merge()`, but tries the merge drivers configured for the
+    /// conflicted files first. This should only be used when rewriting
+    /// commits, since the drivers may run external commands.
+    pub fn merge_with_merge_drivers(
+        &self,
+        base: &MergedTree,
+        other: &MergedTree,
+    ) -> BackendResult<MergedTree> {
+        self.merge_no_resolve(base, other)
+            .resolve_with_merge_drivers()
+    }
+
     /// Merges this tree with `other`, using `base` as base, without attempting
     /// to resolve file conflicts.
     pub fn merge_no_resolve(&self, base: &MergedTree, other: &MergedTree) -> MergedTree {
@@ -438,7 +460,7 @@ fn trees_value<'a>(trees: &'a Merge<Tree>, basename: &RepoPathComponent) -> Merg
 
 /// The returned conflict will either be resolved or have the same number of
 /// sides as the input.
-fn merge_trees(merge: &Merge<Tree>) -> BackendResult<Merge<Tree>> {
+fn merge_trees(merge: &Merge<Tree>, use_merge_drivers: bool) -> BackendResult<Merge<Tree>> {
     if let Some(tree) = merge.resolve_trivial() {
         return Ok(Merge::resolved(tree.clone()));
     }
@@ -454,7 +476,8 @@ fn merge_trees(merge: &Merge<Tree>) -> BackendResult<Merge<Tree>> {
     // TODO: Merge values concurrently
     for (basename, path_merge) in all_merged_tree_entries(merge) {
         let path = dir.join(basename);
-        let path_merge = merge_tree_values(store, &path, &path_merge).block_on()?;
+        let path_merge =
+            merge_tree_values(store, &path, &path_merge, use_merge_drivers).block_on()?;
         match path_merge.into_resolved() {
             Ok(value) => {
                 new_tree.set_or_remove(basename, value);
@@ -492,6 +515,7 @@ async fn merge_tree_values(
     store: &Arc<Store>,
     path: &RepoPath,
     values: &MergedTreeVal<'_>,
+    use_merge_drivers: bool,
 ) -> BackendResult<MergedTreeValue> {
     if let Some(resolved) = values.resolve_trivial() {
         return Ok(Merge::resolved(resolved.cloned()));
@@ -501,18 +525,19 @@ async fn merge_tree_values(
         // If all sides are trees or missing, merge the trees recursively, treating
         // missing trees as empty.
         let empty_tree_id = store.empty_tree_id();
-        let merged_tree = merge_trees(&trees)?;
+        let merged_tree = merge_trees(&trees, use_merge_drivers)?;
         Ok(merged_tree
             .map(|tree| (tree.id() != empty_tree_id).then(|| TreeValue::Tree(tree.id().clone()))))
     } else {
-        let maybe_resolved = try_resolve_file_values(store, path, values).await?;
+        let maybe_resolved =
+            try_resolve_file_values(store, path, values, use_merge_drivers).await?;
         Ok(maybe_resolved.unwrap_or_else(|| values.cloned()))
     }
 }
 
 /// Tries to resolve file conflicts by merging the file contents. Treats missing
 /// files as empty. If the file conflict cannot be resolved, returns the passed
-/// `values` unmodified.
+/// `values` unmodified. Merge drivers are not used.
 pub async fn resolve_file_values(
     store: &Arc<Store>,
     path: &RepoPath,
@@ -522,7 +547,7 @@ pub async fn resolve_file_values(
         return Ok(Merge::resolved(resolved.clone()));
     }
 
-    let maybe_resolved = try_resolve_file_values(store, path, &values).await?;
+    let maybe_resolved = try_resolve_file_values(store, path, &values, false).await?;
     Ok(maybe_resolved.unwrap_or(values))
 }
 
@@ -530,6 +555,7 @@ async fn try_resolve_file_values<T: Borrow<TreeValue>>(
     store: &Arc<Store>,
     path: &RepoPath,
     values: &Merge<Option<T>>,
+    use_merge_drivers: bool,
 ) -> BackendResult<Option<MergedTreeValue>> {
     // The values may contain trees canceling each other (notably padded absent
     // trees), so we need to simplify them first.
@@ -538,7 +564,9 @@ async fn try_resolve_file_values<T: Borrow<TreeValue>>(
         .simplify();
     // No fast path for simplified.is_resolved(). If it could be resolved, it would
     // have been caught by values.resolve_trivial() above.
-    if let Some(resolved) = try_resolve_file_conflict(store, path, &simplified).await? {
+    if let Some(resolved) =
+        try_resolve_file_conflict(store, path, &simplified, use_merge_drivers).await?
+    {
         Ok(Some(Merge::normal(resolved)))
     } else {
         // Failed to merge the files, or the paths are not files
@@ -0,0 +1,486 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Self-contained bundles of operations and the objects they refer to.
+//!
+//! A bundle can be used to reproduce the operation log and the commit graph of
+//! another repository, e.g. to debug a problem reported by a user.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Read as _;
+use std::io::Write;
+use std::mem;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use prost::Message as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::ConflictId;
+use crate::backend::FileId;
+use crate::backend::MergedTreeId;
+use crate::backend::SymlinkId;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
+use crate::commit::Commit;
+use crate::dag_walk;
+use crate::local_backend;
+use crate::merge::Merge;
+use crate::object_id::ObjectId;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
+use crate::op_store::ViewId;
+use crate::op_walk;
+use crate::operation::Operation;
+use crate::protos::op_bundle as proto;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::signing::OperationSigner;
+use crate::simple_op_store;
+use crate::store::Store;
+use crate::view::View;
+
+/// Error that may occur while exporting or importing an operation bundle.
+#[derive(Debug, Error)]
+pub enum OpBundleError {
+    /// The bundle data couldn't be decoded.
+    #[error("Failed to decode operation bundle")]
+    Decode(#[source] prost::DecodeError),
+    /// The bundle was created by a different kind of store.
+    #[error(
+        "The bundle was created by {store_kind} {bundle_name:?}, but the repo uses \
+         {repo_name:?}"
+    )]
+    IncompatibleStore {
+        /// Kind of the store (e.g. "backend")
+        store_kind: &'static str,
+        /// Name of the store that created the bundle.
+        bundle_name: String,
+        /// Name of the store used by the repo.
+        repo_name: String,
+    },
+    /// The imported object didn't have the recorded id.
+    #[error("Imported {object_type} {expected} but got {actual}")]
+    IdMismatch {
+        /// Type of the object
+        object_type: &'static str,
+        /// Hex id recorded in the bundle.
+        expected: String,
+        /// Hex id of the imported object.
+        actual: String,
+    },
+    /// The bundle contained an invalid path.
+    #[error("Invalid path {0:?} in operation bundle")]
+    InvalidPath(String),
+    /// Failed to read or write objects.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Failed to read or write operations or views.
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+    /// Failed to write the bundle.
+    #[error("Failed to write operation bundle")]
+    Io(#[from] std::io::Error),
+}
+
+/// Number of entries exported or imported.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpBundleStats {
+    /// Number of operations.
+    pub operation_count: usize,
+    /// Number of commits.
+    pub commit_count: usize,
+    /// Number of trees, files, symlinks, and conflicts.
+    pub object_count: usize,
+}
+
+/// Result of `import_bundle()`.
+#[derive(Clone, Debug)]
+pub struct ImportedBundle {
+    /// Heads of the imported operations.
+    pub head_ids: Vec<OperationId>,
+    /// Number of imported entries.
+    pub stats: OpBundleStats,
+    /// Number of reparented operations which were signed, but couldn't be
+    /// signed again.
+    pub unsigned_count: usize,
+}
+
+/// Writes the given operations and their views to `out`.
+///
+/// If `with_objects` is true, the commits referenced by the views, their
+/// ancestors and predecessors, and the trees and files of these commits are
+/// included in the bundle. The root operation and the root commit are never
+/// included since every repo has them.
+pub fn export_bundle(
+    store: &Arc<Store>,
+    op_store: &dyn OpStore,
+    ops: &[Operation],
+    with_objects: bool,
+    out: &mut dyn Write,
+) -> Result<OpBundleStats, OpBundleError> {
+    let mut bundle = proto::OpBundle {
+        backend_name: store.backend_name().to_owned(),
+        op_store_name: op_store.name().to_owned(),
+        ..Default::default()
+    };
+    let mut exported_view_ids = HashSet::new();
+    let mut views = vec![];
+    for op in ops.iter().unique_by(|op| op.id()) {
+        if op.id() == op_store.root_operation_id() {
+            continue;
+        }
+        bundle.operations.push(proto::Operation {
+            id: op.id().to_bytes(),
+            data: Some(simple_op_store::operation_to_proto(op.store_operation())),
+        });
+        if exported_view_ids.insert(op.view_id().clone()) {
+            let view = op_store.read_view(op.view_id())?;
+            bundle.views.push(proto::View {
+                id: op.view_id().to_bytes(),
+                data: Some(simple_op_store::view_to_proto(&view)),
+            });
+            views.push(View::new(view));
+        }
+    }
+    if with_objects {
+        let commit_ids = views
+            .iter()
+            .flat_map(|view| view.all_referenced_commit_ids())
+            .cloned()
+            .collect_vec();
+        ObjectExporter::new(store, &mut bundle).export_commits(commit_ids)?;
+    }
+    out.write_all(&bundle.encode_to_vec())?;
+    Ok(bundle_stats(&bundle))
+}
+
+/// Reads operations and objects from the bundle `data`, and writes them to
+/// the stores.
+///
+/// The exported operations whose parents aren't included in the bundle are
+/// reparented onto the root operation, and signed again by the `signer` if
+/// they were signed. The caller should merge the returned `head_ids` into the
+/// operation log.
+pub fn import_bundle(
+    store: &Arc<Store>,
+    op_store: &dyn OpStore,
+    data: &[u8],
+    signer: Option<&OperationSigner>,
+) -> Result<ImportedBundle, OpBundleError> {
+    let bundle = proto::OpBundle::decode(data).map_err(OpBundleError::Decode)?;
+    check_store_name("backend", &bundle.backend_name, store.backend_name())?;
+    check_store_name("operation store", &bundle.op_store_name, op_store.name())?;
+    let stats = bundle_stats(&bundle);
+
+    // Objects are imported first so the repo can be loaded at the imported
+    // operations.
+    for file in &bundle.files {
+        let path = parse_path(&file.path)?;
+        let id = store
+            .write_file(&path, &mut file.content.as_slice())
+            .block_on()?;
+        check_id("file", &FileId::new(file.id.clone()), &id)?;
+    }
+    for symlink in &bundle.symlinks {
+        let path = parse_path(&symlink.path)?;
+        let id = store.write_symlink(&path, &symlink.target).block_on()?;
+        check_id("symlink", &SymlinkId::new(symlink.id.clone()), &id)?;
+    }
+    for conflict in &bundle.conflicts {
+        let path = parse_path(&conflict.path)?;
+        let data = local_backend::conflict_from_proto(conflict.data.clone().unwrap_or_default());
+        let id = store.write_conflict(&path, &Merge::from_backend_conflict(data))?;
+        check_id("conflict", &ConflictId::new(conflict.id.clone()), &id)?;
+    }
+    for tree in &bundle.trees {
+        let dir = parse_path(&tree.dir)?;
+        let data = local_backend::tree_from_proto(tree.data.clone().unwrap_or_default());
+        let written = store.write_tree(&dir, data).block_on()?;
+        check_id("tree", &TreeId::new(tree.id.clone()), written.id())?;
+    }
+    for commit in &bundle.commits {
+        let mut data = local_backend::commit_from_proto(commit.data.clone().unwrap_or_default());
+        // The signature can't be written as is. Sign the commit again with the
+        // recorded signature, which should produce the same object.
+        let written = if let Some(secure_sig) = data.secure_sig.take() {
+            let mut sign = |_: &[u8]| Ok(secure_sig.sig.clone());
+            store.write_commit(data, Some(&mut sign)).block_on()?
+        } else {
+            store.write_commit(data, None).block_on()?
+        };
+        check_id("commit", &CommitId::new(commit.id.clone()), written.id())?;
+    }
+
+    for view in &bundle.views {
+        let data = simple_op_store::view_from_proto(view.data.clone().unwrap_or_default());
+        let id = op_store.write_view(&data)?;
+        check_id("view", &ViewId::new(view.id.clone()), &id)?;
+    }
+    let op_protos: HashMap<OperationId, &proto::Operation> = bundle
+        .operations
+        .iter()
+        .map(|op| (OperationId::new(op.id.clone()), op))
+        .collect();
+    let parent_ids_in_bundle = |op: &proto::Operation| {
+        let parents = op.data.as_ref().map_or(&[][..], |data| &data.parents);
+        parents
+            .iter()
+            .map(|id| OperationId::new(id.clone()))
+            .filter(|id| op_protos.contains_key(id))
+            .collect_vec()
+    };
+    let sorted_ids = dag_walk::topo_order_forward(
+        op_protos.keys().cloned(),
+        |id| id.clone(),
+        |id| parent_ids_in_bundle(op_protos[id]),
+    );
+    // Operations may be rewritten if their ancestors were reparented.
+    let mut rewritten_ids = HashMap::new();
+    let mut unsigned_count = 0;
+    for old_id in sorted_ids {
+        let op = op_protos[&old_id];
+        let mut data = simple_op_store::operation_from_proto(op.data.clone().unwrap_or_default());
+        let old_parent_ids = mem::take(&mut data.parents);
+        data.parents = old_parent_ids
+            .iter()
+            .filter_map(|id| rewritten_ids.get(id).cloned())
+            .collect();
+        if data.parents.is_empty() {
+            data.parents.push(op_store.root_operation_id().clone());
+        }
+        let new_id = if data.parents == old_parent_ids {
+            op_store.write_operation(&data)?
+        } else {
+            op_walk::write_rewritten_operation(op_store, data, signer, &mut unsigned_count)?
+        };
+        rewritten_ids.insert(old_id, new_id);
+    }
+    let parent_ids: HashSet<_> = op_protos
+        .values()
+        .flat_map(|op| parent_ids_in_bundle(op))
+        .collect();
+    let head_ids = bundle
+        .operations
+        .iter()
+        .map(|op| OperationId::new(op.id.clone()))
+        .filter(|id| !parent_ids.contains(id))
+        .map(|id| rewritten_ids[&id].clone())
+        .collect();
+    Ok(ImportedBundle {
+        head_ids,
+        stats,
+        unsigned_count,
+    })
+}
+
+/// Collects commits and their trees into bundle.
+struct ObjectExporter<'a> {
+    store: &'a Arc<Store>,
+    bundle: &'a mut proto::OpBundle,
+    visited_trees: HashSet<TreeId>,
+    visited_values: HashSet<TreeValue>,
+}
+
+impl<'a> ObjectExporter<'a> {
+    fn new(store: &'a Arc<Store>, bundle: &'a mut proto::OpBundle) -> Self {
+        ObjectExporter {
+            store,
+            bundle,
+            visited_trees: HashSet::new(),
+            visited_values: HashSet::new(),
+        }
+    }
+
+    fn export_commits(&mut self, head_ids: Vec<CommitId>) -> Result<(), OpBundleError> {
+        let store = self.store;
+        let root_commit_id = store.root_commit_id();
+        let heads: Vec<_> = head_ids
+            .iter()
+            .unique()
+            .filter(|id| *id != root_commit_id)
+            .map(|id| store.get_commit(id))
+            .try_collect()?;
+        // Parents must be readable, whereas predecessors may have been
+        // garbage collected.
+        let commits = dag_walk::topo_order_reverse_ok(
+            heads.into_iter().map(Ok),
+            |commit: &Commit| commit.id().clone(),
+            |commit: &Commit| {
+                let parents = commit
+                    .parent_ids()
+                    .iter()
+                    .filter(|id| *id != root_commit_id)
+                    .map(|id| store.get_commit(id).map_err(OpBundleError::from));
+                let predecessors = commit
+                    .predecessor_ids()
+                    .iter()
+                    .filter(|id| *id != root_commit_id)
+                    .filter_map(|id| match store.get_commit(id) {
+                        Ok(commit) => Some(Ok(commit)),
+                        Err(BackendError::ObjectNotFound { .. }) => None,
+                        Err(err) => Some(Err(err.into())),
+                    });
+                parents.chain(predecessors).collect_vec()
+            },
+        )?;
+        // Write commits in topological order so that parents and predecessors
+        // precede their descendants and successors.
+        for commit in commits.iter().rev() {
+            let mut data = local_backend::commit_to_proto(commit.store_commit());
+            data.secure_sig = commit
+                .store_commit()
+                .secure_sig
+                .as_ref()
+                .map(|sig| sig.sig.clone());
+            self.bundle.commits.push(proto::Commit {
+                id: commit.id().to_bytes(),
+                data: Some(data),
+            });
+            match commit.tree_id() {
+                MergedTreeId::Legacy(tree_id) => self.export_tree(RepoPath::root(), tree_id)?,
+                MergedTreeId::Merge(tree_ids) => {
+                    for tree_id in tree_ids.iter() {
+                        self.export_tree(RepoPath::root(), tree_id)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn export_tree(&mut self, dir: &RepoPath, id: &TreeId) -> Result<(), OpBundleError> {
+        if !self.visited_trees.insert(id.clone()) {
+            return Ok(());
+        }
+        let tree = self.store.get_tree(dir.to_owned(), id)?;
+        for entry in tree.data().entries() {
+            let path = dir.join(entry.name());
+            let value = entry.value();
+            if let TreeValue::Tree(sub_id) = value {
+                self.export_tree(&path, sub_id)?;
+            } else if self.visited_values.insert(value.clone()) {
+                self.export_value(&path, value)?;
+            }
+        }
+        self.bundle.trees.push(proto::Tree {
+            id: id.to_bytes(),
+            dir: dir.as_internal_file_string().to_owned(),
+            data: Some(local_backend::tree_to_proto(tree.data())),
+        });
+        Ok(())
+    }
+
+    fn export_value(&mut self, path: &RepoPath, value: &TreeValue) -> Result<(), OpBundleError> {
+        let path_str = path.as_internal_file_string().to_owned();
+        match value {
+            TreeValue::File { id, .. } => {
+                let mut content = vec![];
+                self.store
+                    .read_file(path, id)?
+                    .read_to_end(&mut content)
+                    .map_err(|err| BackendError::ReadFile {
+                        path: path.to_owned(),
+                        id: id.clone(),
+                        source: err.into(),
+                    })?;
+                self.bundle.files.push(proto::File {
+                    id: id.to_bytes(),
+                    path: path_str,
+                    content,
+                });
+            }
+            TreeValue::Symlink(id) => {
+                let target = self.store.read_symlink(path, id)?;
+                self.bundle.symlinks.push(proto::Symlink {
+                    id: id.to_bytes(),
+                    path: path_str,
+                    target,
+                });
+            }
+            TreeValue::Conflict(id) => {
+                let conflict = self.store.read_conflict(path, id)?;
+                for value in conflict.iter().flatten() {
+                    if let TreeValue::Tree(sub_id) = value {
+                        self.export_tree(path, sub_id)?;
+                    } else if self.visited_values.insert(value.clone()) {
+                        self.export_value(path, value)?;
+                    }
+                }
+                self.bundle.conflicts.push(proto::Conflict {
+                    id: id.to_bytes(),
+                    path: path_str,
+                    data: Some(local_backend::conflict_to_proto(
+                        &conflict.into_backend_conflict(),
+                    )),
+                });
+            }
+            // Submodule commits aren't stored in this repo.
+            TreeValue::GitSubmodule(_) | TreeValue::Tree(_) => {}
+        }
+        Ok(())
+    }
+}
+
+fn bundle_stats(bundle: &proto::OpBundle) -> OpBundleStats {
+    OpBundleStats {
+        operation_count: bundle.operations.len(),
+        commit_count: bundle.commits.len(),
+        object_count: bundle.trees.len()
+            + bundle.files.len()
+            + bundle.symlinks.len()
+            + bundle.conflicts.len(),
+    }
+}
+
+fn check_store_name(
+    store_kind: &'static str,
+    bundle_name: &str,
+    repo_name: &str,
+) -> Result<(), OpBundleError> {
+    if bundle_name == repo_name {
+        Ok(())
+    } else {
+        Err(OpBundleError::IncompatibleStore {
+            store_kind,
+            bundle_name: bundle_name.to_owned(),
+            repo_name: repo_name.to_owned(),
+        })
+    }
+}
+
+fn check_id<T: ObjectId + PartialEq>(
+    object_type: &'static str,
+    expected: &T,
+    actual: &T,
+) -> Result<(), OpBundleError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(OpBundleError::IdMismatch {
+            object_type,
+            expected: expected.hex(),
+            actual: actual.hex(),
+        })
+    }
+}
+
+fn parse_path(value: &str) -> Result<RepoPathBuf, OpBundleError> {
+    RepoPathBuf::from_relative_path(value).map_err(|_| OpBundleError::InvalidPath(value.to_owned()))
+}
@@ -0,0 +1,135 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Retention policy for pruning old operations from the operation log.
+
+use std::collections::HashSet;
+
+use crate::backend::Timestamp;
+use crate::config::ConfigGetError;
+use crate::config::ConfigGetResultExt as _;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreResult;
+use crate::op_store::RemoteView;
+use crate::op_store::View;
+use crate::op_walk;
+use crate::op_walk::ReparentStats;
+use crate::operation::Operation;
+use crate::settings::UserSettings;
+use crate::signing::OperationSigner;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Describes which operations should be kept when pruning the operation log.
+///
+/// An operation is retained if it matches any of the criteria. If neither
+/// `keep_days` nor `keep_count` is set, the policy is disabled and no
+/// operation is pruned.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpRetentionPolicy {
+    /// Keep operations that ended within this number of days.
+    pub keep_days: Option<u64>,
+    /// Keep this number of the latest operations.
+    pub keep_count: Option<usize>,
+    /// Keep operations that changed local or remote bookmarks.
+    pub keep_bookmark_ops: bool,
+}
+
+impl OpRetentionPolicy {
+    /// Loads the retention policy from the `operation.retention` config.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(OpRetentionPolicy {
+            keep_days: settings.get("operation.retention.keep-days").optional()?,
+            keep_count: settings.get("operation.retention.keep-count").optional()?,
+            keep_bookmark_ops: settings.get_bool("operation.retention.keep-bookmark-ops")?,
+        })
+    }
+
+    /// Returns true if the policy may prune any operations.
+    pub fn is_enabled(&self) -> bool {
+        self.keep_days.is_some() || self.keep_count.is_some()
+    }
+}
+
+/// Removes operations that don't match the retention `policy` from the
+/// ancestors of `head_ops`.
+///
+/// The retained operations are reparented by `op_walk::retain_ancestors()`.
+/// The caller should update the operation heads (and the working-copy
+/// operations) to point to the returned `new_head_ids`. The removed operations
+/// can then be deleted by `OpStore::gc()`.
+pub fn prune_operations(
+    op_store: &dyn OpStore,
+    head_ops: &[Operation],
+    policy: &OpRetentionPolicy,
+    now: &Timestamp,
+    signer: Option<&OperationSigner>,
+) -> OpStoreResult<ReparentStats> {
+    if !policy.is_enabled() {
+        return Ok(ReparentStats {
+            new_head_ids: head_ops.iter().map(|op| op.id().clone()).collect(),
+            rewritten_count: 0,
+            unreachable_count: 0,
+            unsigned_count: 0,
+        });
+    }
+    let keep_after = policy.keep_days.map(|days| {
+        let days = i64::try_from(days).unwrap_or(i64::MAX);
+        now.timestamp
+            .0
+            .saturating_sub(days.saturating_mul(MILLIS_PER_DAY))
+    });
+    let mut retained_ids = HashSet::new();
+    for (index, op) in op_walk::walk_ancestors(head_ops).enumerate() {
+        let op = op?;
+        let is_latest = policy.keep_count.is_some_and(|count| index < count);
+        let is_recent = keep_after.is_some_and(|t| op.metadata().end_time.timestamp.0 >= t);
+        if is_latest || is_recent || (policy.keep_bookmark_ops && touches_bookmarks(&op)?) {
+            retained_ids.insert(op.id().clone());
+        }
+    }
+    op_walk::retain_ancestors(op_store, head_ops, signer, |op| {
+        Ok(retained_ids.contains(op.id()))
+    })
+}
+
+/// Returns true if the operation changed bookmarks compared to all of its
+/// parents.
+fn touches_bookmarks(op: &Operation) -> OpStoreResult<bool> {
+    if op.parent_ids().is_empty() {
+        return Ok(false);
+    }
+    let op_store = op.op_store();
+    let view = op_store.read_view(op.view_id())?;
+    for parent in op.parents() {
+        let parent_view = op_store.read_view(parent?.view_id())?;
+        if same_bookmarks(&view, &parent_view) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn same_bookmarks(view1: &View, view2: &View) -> bool {
+    fn remote_bookmarks(view: &View) -> impl Iterator<Item = (&String, &RemoteView)> {
+        view.remote_views
+            .iter()
+            .filter(|(_, remote_view)| !remote_view.bookmarks.is_empty())
+    }
+    view1.local_bookmarks == view2.local_bookmarks
+        && itertools::equal(
+            remote_bookmarks(view1).map(|(name, view)| (name, &view.bookmarks)),
+            remote_bookmarks(view2).map(|(name, view)| (name, &view.bookmarks)),
+        )
+}
@@ -18,6 +18,7 @@ use std::any::Any;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Error;
 use std::fmt::Formatter;
@@ -31,12 +32,15 @@ use thiserror::Error;
 use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
+use crate::content_hash::blake2b_hash;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
 use crate::merge::Merge;
 use crate::object_id::id_type;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId;
 use crate::object_id::PrefixResolution;
+use crate::refs::RemoteRefSymbolBuf;
 
 #[derive(ContentHash, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub struct WorkspaceId(String);
@@ -391,11 +395,32 @@ pub(crate) fn flatten_remote_bookmarks(
 /// taken and it will be checked that the current head of the operation
 /// graph is unchanged. If the current head has changed, there has been
 /// concurrent operation.
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Operation {
     pub view_id: ViewId,
     pub parents: Vec<OperationId>,
     pub metadata: OperationMetadata,
+    /// Signature of the data returned by [`Operation::signing_data()`].
+    pub signature: Option<Vec<u8>>,
+}
+
+impl ContentHash for Operation {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let Operation {
+            view_id,
+            parents,
+            metadata,
+            signature,
+        } = self;
+        view_id.hash(state);
+        parents.hash(state);
+        metadata.hash(state);
+        // Unsigned operations hash the same as they did before operations
+        // could be signed.
+        if let Some(signature) = signature {
+            signature.hash(state);
+        }
+    }
 }
 
 impl Operation {
@@ -412,16 +437,30 @@ impl Operation {
             username: "".to_string(),
             is_snapshot: false,
             tags: HashMap::new(),
+            conflicted_refs: vec![],
         };
         Operation {
             view_id: root_view_id,
             parents: vec![],
             metadata,
+            signature: None,
         }
     }
+
+    /// Returns the data to be signed to sign this operation.
+    ///
+    /// This is the hash of the operation excluding the signature, so it doesn't
+    /// depend on how the operation is serialized by the operation store.
+    pub fn signing_data(&self) -> Vec<u8> {
+        let unsigned = Operation {
+            signature: None,
+            ..self.clone()
+        };
+        hex::encode(blake2b_hash(&unsigned)).into_bytes()
+    }
 }
 
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct OperationMetadata {
     pub start_time: Timestamp,
     pub end_time: Timestamp,
@@ -433,6 +472,57 @@ pub struct OperationMetadata {
     /// copy.
     pub is_snapshot: bool,
     pub tags: HashMap<String, String>,
+    /// Refs which became conflicted by merging concurrent operations.
+    pub conflicted_refs: Vec<ViewRefName>,
+}
+
+impl ContentHash for OperationMetadata {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let OperationMetadata {
+            start_time,
+            end_time,
+            description,
+            hostname,
+            username,
+            is_snapshot,
+            tags,
+            conflicted_refs,
+        } = self;
+        start_time.hash(state);
+        end_time.hash(state);
+        description.hash(state);
+        hostname.hash(state);
+        username.hash(state);
+        is_snapshot.hash(state);
+        tags.hash(state);
+        // Only merge operations can have conflicted refs. Don't hash the empty
+        // list so the ids of the other operations stay the same.
+        if !conflicted_refs.is_empty() {
+            conflicted_refs.hash(state);
+        }
+    }
+}
+
+/// Name of a ref stored in a view.
+#[derive(ContentHash, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ViewRefName {
+    LocalBookmark(String),
+    RemoteBookmark(RemoteRefSymbolBuf),
+    Tag(String),
+    GitRef(String),
+    GitHead,
+}
+
+impl fmt::Display for ViewRefName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViewRefName::LocalBookmark(name) => write!(f, "bookmark {name}"),
+            ViewRefName::RemoteBookmark(symbol) => write!(f, "remote bookmark {symbol}"),
+            ViewRefName::Tag(name) => write!(f, "tag {name}"),
+            ViewRefName::GitRef(name) => write!(f, "git ref {name}"),
+            ViewRefName::GitHead => write!(f, "git HEAD"),
+        }
+    }
 }
 
 /// Data to be loaded into the root operation/view.
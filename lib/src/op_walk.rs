@@ -24,24 +24,31 @@ use itertools::Itertools as _;
 use thiserror::Error;
 
 use crate::dag_walk;
-use crate::object_id::HexPrefix;
-use crate::object_id::PrefixResolution;
 use crate::op_heads_store;
 use crate::op_heads_store::OpHeadResolutionError;
 use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
+use crate::op_store;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::operation::Operation;
+use crate::opset;
+use crate::opset::OpsetExpression;
+use crate::opset::OpsetParseError;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
 use crate::repo::RepoLoader;
+use crate::signing::OperationSigner;
+use crate::time_util::DatePatternContext;
 
 /// Error that may occur during evaluation of operation set expression.
 #[derive(Debug, Error)]
 pub enum OpsetEvaluationError {
+    /// Failed to parse operation set expression.
+    #[error(transparent)]
+    OpsetParse(#[from] OpsetParseError),
     /// Failed to resolve operation set expression.
     #[error(transparent)]
     OpsetResolution(#[from] OpsetResolutionError),
@@ -120,73 +127,85 @@ pub fn resolve_op_at(
     head_ops: &[Operation],
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
-    let get_current_op = || match head_ops {
-        [head_op] => Ok(head_op.clone()),
-        [] => Err(OpsetResolutionError::EmptyOperations("@".to_owned()).into()),
-        _ => Err(OpsetResolutionError::MultipleOperations {
-            expr: "@".to_owned(),
-            candidates: head_ops.iter().map(|op| op.id().clone()).collect(),
-        }
-        .into()),
-    };
+    let get_current_op = || current_op_at(head_ops);
     let get_head_ops = || Ok(head_ops.to_vec());
     resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
 }
 
-/// Resolves operation set expression with the given "@" symbol resolution
-/// callbacks.
+/// Resolves operation set expression which should match exactly one
+/// operation.
 fn resolve_single_op(
     op_store: &Arc<dyn OpStore>,
     get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
     get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
-    let op_symbol = op_str.trim_end_matches(['-', '+']);
-    let op_postfix = &op_str[op_symbol.len()..];
-    let head_ops = op_postfix.contains('+').then(get_head_ops).transpose()?;
-    let mut operation = match op_symbol {
-        "@" => get_current_op(),
-        s => resolve_single_op_from_store(op_store, s),
-    }?;
-    for c in op_postfix.chars() {
-        let mut neighbor_ops = match c {
-            '-' => operation.parents().try_collect()?,
-            '+' => find_child_ops(head_ops.as_ref().unwrap(), operation.id())?,
-            _ => unreachable!(),
-        };
-        operation = match neighbor_ops.len() {
-            0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()))?,
-            1 => neighbor_ops.pop().unwrap(),
-            _ => Err(OpsetResolutionError::MultipleOperations {
-                expr: op_str.to_owned(),
-                candidates: neighbor_ops.iter().map(|op| op.id().clone()).collect(),
-            })?,
-        };
+    let mut ops = resolve_opset(op_store, get_current_op, get_head_ops, op_str)?;
+    match ops.len() {
+        0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()).into()),
+        1 => Ok(ops.pop().unwrap()),
+        _ => Err(OpsetResolutionError::MultipleOperations {
+            expr: op_str.to_owned(),
+            candidates: ops.iter().map(|op| op.id().clone()).collect(),
+        }
+        .into()),
     }
-    Ok(operation)
 }
 
-fn resolve_single_op_from_store(
+/// Resolves operation set expression at the given head operations. Unlike
+/// `resolve_op_at()`, the expression may match any number of operations.
+///
+/// The returned operations are sorted in reverse topological order.
+pub fn resolve_opset_at(
     op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
     op_str: &str,
-) -> Result<Operation, OpsetEvaluationError> {
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
+    let get_current_op = || current_op_at(head_ops);
+    let get_head_ops = || Ok(head_ops.to_vec());
+    resolve_opset(op_store, get_current_op, get_head_ops, op_str)
+}
+
+/// Evaluates the parsed operation set `expression` at the given head
+/// operations.
+///
+/// The returned operations are sorted in reverse topological order.
+pub fn evaluate_opset_at(
+    op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
+    expression: &OpsetExpression,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
+    let get_current_op = || current_op_at(head_ops);
+    let get_head_ops = || Ok(head_ops.to_vec());
+    opset::evaluate(expression, op_store, get_current_op, get_head_ops)
+}
+
+fn current_op_at(head_ops: &[Operation]) -> Result<Operation, OpsetEvaluationError> {
+    match head_ops {
+        [head_op] => Ok(head_op.clone()),
+        [] => Err(OpsetResolutionError::EmptyOperations("@".to_owned()).into()),
+        _ => Err(OpsetResolutionError::MultipleOperations {
+            expr: "@".to_owned(),
+            candidates: head_ops.iter().map(|op| op.id().clone()).collect(),
+        }
+        .into()),
+    }
+}
+
+fn resolve_opset(
+    op_store: &Arc<dyn OpStore>,
+    get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
+    get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
+    op_str: &str,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
+    // An empty string isn't a valid expression, but is reported as an invalid
+    // ID for compatibility.
     if op_str.is_empty() {
         return Err(OpsetResolutionError::InvalidIdPrefix(op_str.to_owned()).into());
     }
-    let prefix = HexPrefix::new(op_str)
-        .ok_or_else(|| OpsetResolutionError::InvalidIdPrefix(op_str.to_owned()))?;
-    match op_store.resolve_operation_id_prefix(&prefix)? {
-        PrefixResolution::NoMatch => {
-            Err(OpsetResolutionError::NoSuchOperation(op_str.to_owned()).into())
-        }
-        PrefixResolution::SingleMatch(op_id) => {
-            let data = op_store.read_operation(&op_id)?;
-            Ok(Operation::new(op_store.clone(), op_id, data))
-        }
-        PrefixResolution::AmbiguousMatch => {
-            Err(OpsetResolutionError::AmbiguousIdPrefix(op_str.to_owned()).into())
-        }
-    }
+    let context = DatePatternContext::from(chrono::Local::now());
+    let expression = opset::parse(op_str, &context)?;
+    opset::evaluate(&expression, op_store, get_current_op, get_head_ops)
 }
 
 /// Loads the current head operations. The returned operations may contain
@@ -208,20 +227,6 @@ pub fn get_current_head_ops(
     Ok(head_ops)
 }
 
-/// Looks up children of the `root_op_id` by traversing from the `head_ops`.
-///
-/// This will be slow if the `root_op_id` is far away (or unreachable) from the
-/// `head_ops`.
-fn find_child_ops(
-    head_ops: &[Operation],
-    root_op_id: &OperationId,
-) -> OpStoreResult<Vec<Operation>> {
-    walk_ancestors(head_ops)
-        .take_while(|res| res.as_ref().map_or(true, |op| op.id() != root_op_id))
-        .filter_ok(|op| op.parent_ids().iter().any(|id| id == root_op_id))
-        .try_collect()
-}
-
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct OperationByEndTime(Operation);
 
@@ -270,6 +275,9 @@ pub struct ReparentStats {
     /// The number of ancestor operations that become unreachable from the
     /// rewritten heads.
     pub unreachable_count: usize,
+    /// The number of rewritten operations which were signed, but couldn't be
+    /// signed again.
+    pub unsigned_count: usize,
 }
 
 /// Reparents the operation range `root_ops..head_ops` onto the `dest_op`.
@@ -286,6 +294,7 @@ pub fn reparent_range(
     root_ops: &[Operation],
     head_ops: &[Operation],
     dest_op: &Operation,
+    signer: Option<&OperationSigner>,
 ) -> OpStoreResult<ReparentStats> {
     // Calculate ::root_ops to exclude them from the source range and count the
     // number of operations that become unreachable.
@@ -307,6 +316,7 @@ pub fn reparent_range(
         "root operation cannot be rewritten"
     );
     let mut rewritten_ids = HashMap::new();
+    let mut unsigned_count = 0;
     for old_op in ops_to_reparent.into_iter().rev() {
         let mut data = old_op.store_operation().clone();
         let mut dest_once = Some(dest_op.id());
@@ -316,7 +326,7 @@ pub fn reparent_range(
             .filter_map(|id| rewritten_ids.get(id).or_else(|| dest_once.take()))
             .cloned()
             .collect();
-        let new_id = op_store.write_operation(&data)?;
+        let new_id = write_rewritten_operation(op_store, data, signer, &mut unsigned_count)?;
         rewritten_ids.insert(old_op.id().clone(), new_id);
     }
 
@@ -330,5 +340,94 @@ pub fn reparent_range(
         new_head_ids,
         rewritten_count: rewritten_ids.len(),
         unreachable_count: unreachable_ids.len(),
+        unsigned_count,
     })
 }
+
+/// Rewrites `head_ops` and their ancestors so that only the operations for
+/// which `keep` returns true are retained.
+///
+/// Unlike `reparent_range()`, the operations to be removed needn't be a
+/// contiguous range. Each retained operation is reparented onto the nearest
+/// retained ancestors, or onto the root operation if there are none. The
+/// `head_ops` and the root operation are always retained.
+pub fn retain_ancestors(
+    op_store: &dyn OpStore,
+    head_ops: &[Operation],
+    signer: Option<&OperationSigner>,
+    mut keep: impl FnMut(&Operation) -> OpStoreResult<bool>,
+) -> OpStoreResult<ReparentStats> {
+    let root_op_id = op_store.root_operation_id();
+    let head_op_ids: HashSet<&OperationId> = head_ops.iter().map(|op| op.id()).collect();
+    let ops_to_visit: Vec<_> = walk_ancestors(head_ops).try_collect()?;
+
+    // Maps old operation id to the new ids of the nearest retained operations.
+    let mut retained_ids: HashMap<OperationId, Vec<OperationId>> = HashMap::new();
+    let mut rewritten_count = 0;
+    let mut unreachable_count = 0;
+    let mut unsigned_count = 0;
+    for old_op in ops_to_visit.iter().rev() {
+        if old_op.id() == root_op_id {
+            retained_ids.insert(root_op_id.clone(), vec![root_op_id.clone()]);
+            continue;
+        }
+        let new_parent_ids = old_op
+            .parent_ids()
+            .iter()
+            .flat_map(|id| &retained_ids[id])
+            .unique()
+            .cloned()
+            .collect_vec();
+        if head_op_ids.contains(old_op.id()) || keep(old_op)? {
+            let new_id = if new_parent_ids == old_op.parent_ids() {
+                old_op.id().clone()
+            } else {
+                let mut data = old_op.store_operation().clone();
+                data.parents = new_parent_ids;
+                rewritten_count += 1;
+                write_rewritten_operation(op_store, data, signer, &mut unsigned_count)?
+            };
+            retained_ids.insert(old_op.id().clone(), vec![new_id]);
+        } else {
+            unreachable_count += 1;
+            retained_ids.insert(old_op.id().clone(), new_parent_ids);
+        }
+    }
+
+    let new_head_ids = head_ops
+        .iter()
+        .map(|op| retained_ids[op.id()][0].clone())
+        .collect();
+    Ok(ReparentStats {
+        new_head_ids,
+        rewritten_count,
+        unreachable_count,
+        unsigned_count,
+    })
+}
+
+/// Writes the rewritten operation `data`. The signature no longer matches the
+/// rewritten operation, so a signed operation is signed again by the `signer`.
+/// If that isn't possible, the operation is written unsigned, and the
+/// `unsigned_count` is incremented.
+pub(crate) fn write_rewritten_operation(
+    op_store: &dyn OpStore,
+    mut data: op_store::Operation,
+    signer: Option<&OperationSigner>,
+    unsigned_count: &mut usize,
+) -> OpStoreResult<OperationId> {
+    if data.signature.is_some() {
+        let signed = signer.is_some_and(|signer| match signer.sign(&mut data) {
+            Ok(()) => true,
+            Err(err) => {
+                tracing::warn!(?err, "failed to sign rewritten operation");
+                false
+            }
+        });
+        if !signed {
+            data.signature = None;
+            *unsigned_count += 1;
+        }
+    }
+    op_store.write_operation(&data)
+}
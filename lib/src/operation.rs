@@ -28,6 +28,9 @@ use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::op_store::OperationMetadata;
 use crate::op_store::ViewId;
+use crate::signing::SignResult;
+use crate::signing::Signer;
+use crate::signing::Verification;
 use crate::view::View;
 
 /// A wrapper around [`op_store::Operation`] that defines additional methods and
@@ -120,4 +123,18 @@ impl Operation {
     pub fn store_operation(&self) -> &op_store::Operation {
         &self.data
     }
+
+    /// A quick way to just check if a signature is present.
+    pub fn is_signed(&self) -> bool {
+        self.data.signature.is_some()
+    }
+
+    /// A slow (but cached) way to get the full verification.
+    pub fn verification(&self, signer: &Signer) -> SignResult<Option<Verification>> {
+        self.data
+            .signature
+            .as_ref()
+            .map(|sig| signer.verify_operation(&self.id, &self.data.signing_data(), sig))
+            .transpose()
+    }
 }
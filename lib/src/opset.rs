@@ -0,0 +1,680 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functional language for selecting a set of operations.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use once_cell::unsync::OnceCell;
+
+use crate::dsl_util::collect_similar;
+use crate::object_id::HexPrefix;
+use crate::object_id::PrefixResolution;
+use crate::op_store::OpStore;
+use crate::op_store::OpStoreResult;
+use crate::op_store::View;
+use crate::op_walk;
+use crate::op_walk::OpsetEvaluationError;
+use crate::op_walk::OpsetResolutionError;
+use crate::operation::Operation;
+use crate::opset_parser::BinaryOp;
+use crate::opset_parser::ExpressionKind;
+use crate::opset_parser::ExpressionNode;
+use crate::opset_parser::FunctionCallNode;
+pub use crate::opset_parser::OpsetParseError;
+pub use crate::opset_parser::OpsetParseErrorKind;
+use crate::opset_parser::UnaryOp;
+use crate::str_util::StringPattern;
+use crate::time_util::DatePattern;
+use crate::time_util::DatePatternContext;
+
+/// Operation set expression.
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub enum OpsetExpression {
+    /// Matches nothing.
+    None,
+    /// Matches all operations reachable from the current heads.
+    All,
+    /// The current operation `@`.
+    Current,
+    /// The current head operations.
+    Heads,
+    /// The root operation.
+    Root,
+    /// Operation ID or prefix.
+    Id(String),
+    /// Parents of the operations.
+    Parents(Rc<OpsetExpression>),
+    /// Children of the operations.
+    Children(Rc<OpsetExpression>),
+    /// Ancestors of the `heads`, optionally limited to the given `depth`.
+    Ancestors {
+        heads: Rc<OpsetExpression>,
+        depth: Option<u64>,
+    },
+    /// Descendants of the operations.
+    Descendants(Rc<OpsetExpression>),
+    /// Descendants of `roots` which are ancestors of `heads`: `roots::heads`
+    DagRange {
+        roots: Rc<OpsetExpression>,
+        heads: Rc<OpsetExpression>,
+    },
+    /// Ancestors of `heads` which aren't ancestors of `roots`: `roots..heads`
+    Range {
+        roots: Rc<OpsetExpression>,
+        heads: Rc<OpsetExpression>,
+    },
+    /// Operations matching the predicate.
+    Filter(OpsetFilterPredicate),
+    /// Union of the operation sets.
+    Union(Rc<OpsetExpression>, Rc<OpsetExpression>),
+    /// Intersection of the operation sets.
+    Intersection(Rc<OpsetExpression>, Rc<OpsetExpression>),
+    /// Operations in the first set which aren't in the second set.
+    Difference(Rc<OpsetExpression>, Rc<OpsetExpression>),
+}
+
+/// Predicate to filter operations by their metadata or view.
+#[derive(Clone, Debug)]
+pub enum OpsetFilterPredicate {
+    /// Command line arguments of the operation.
+    Command(StringPattern),
+    /// Operation description.
+    Description(StringPattern),
+    /// `<username>@<hostname>` of the operation.
+    User(StringPattern),
+    /// End time of the operation.
+    Time(DatePattern),
+    /// Working-copy snapshot operation.
+    Snapshot,
+    /// Operation that changed the working-copy commit of the matching
+    /// workspaces.
+    Workspace(StringPattern),
+    /// Operation that changed the matching local bookmarks.
+    TouchedBookmark(StringPattern),
+}
+
+impl OpsetFilterPredicate {
+    fn matches(&self, op: &Operation) -> OpStoreResult<bool> {
+        let metadata = op.metadata();
+        match self {
+            OpsetFilterPredicate::Command(pattern) => Ok(metadata
+                .tags
+                .get("args")
+                .is_some_and(|args| pattern.matches(args))),
+            OpsetFilterPredicate::Description(pattern) => {
+                Ok(pattern.matches(&metadata.description))
+            }
+            OpsetFilterPredicate::User(pattern) => {
+                let user = format!("{}@{}", metadata.username, metadata.hostname);
+                Ok(pattern.matches(&user))
+            }
+            OpsetFilterPredicate::Time(pattern) => Ok(pattern.matches(&metadata.end_time)),
+            OpsetFilterPredicate::Snapshot => Ok(metadata.is_snapshot),
+            OpsetFilterPredicate::Workspace(pattern) => changed_view_with(op, |view1, view2| {
+                view1
+                    .wc_commit_ids
+                    .iter()
+                    .filter(|(id, _)| pattern.matches(id.as_str()))
+                    .any(|(id, commit_id)| view2.wc_commit_ids.get(id) != Some(commit_id))
+            }),
+            OpsetFilterPredicate::TouchedBookmark(pattern) => {
+                changed_view_with(op, |view1, view2| {
+                    let bookmarks1 = pattern.filter_btree_map(&view1.local_bookmarks);
+                    let bookmarks2 = pattern.filter_btree_map(&view2.local_bookmarks);
+                    !itertools::equal(bookmarks1, bookmarks2)
+                })
+            }
+        }
+    }
+}
+
+/// Returns true if `is_changed(view, parent_view)` returns true for all
+/// parents of the operation.
+fn changed_view_with(
+    op: &Operation,
+    mut is_changed: impl FnMut(&View, &View) -> bool,
+) -> OpStoreResult<bool> {
+    if op.parent_ids().is_empty() {
+        return Ok(false);
+    }
+    let op_store = op.op_store();
+    let view = op_store.read_view(op.view_id())?;
+    for parent in op.parents() {
+        let parent_view = op_store.read_view(parent?.view_id())?;
+        if !is_changed(&view, &parent_view) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+impl OpsetExpression {
+    fn union(self: &Rc<Self>, other: &Rc<Self>) -> Rc<Self> {
+        Rc::new(OpsetExpression::Union(self.clone(), other.clone()))
+    }
+
+    fn intersection(self: &Rc<Self>, other: &Rc<Self>) -> Rc<Self> {
+        Rc::new(OpsetExpression::Intersection(self.clone(), other.clone()))
+    }
+
+    fn difference(self: &Rc<Self>, other: &Rc<Self>) -> Rc<Self> {
+        Rc::new(OpsetExpression::Difference(self.clone(), other.clone()))
+    }
+}
+
+type OpsetFunction =
+    fn(&FunctionCallNode, &DatePatternContext) -> OpsetParseResult<OpsetExpression>;
+
+type OpsetParseResult<T> = Result<T, OpsetParseError>;
+
+static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, OpsetFunction>> = Lazy::new(|| {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map: HashMap<&'static str, OpsetFunction> = HashMap::new();
+    map.insert("none", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::None)
+    });
+    map.insert("all", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::All)
+    });
+    map.insert("root", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::Root)
+    });
+    map.insert("parents", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = resolve_expression(arg, context)?;
+        Ok(OpsetExpression::Parents(expression))
+    });
+    map.insert("children", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = resolve_expression(arg, context)?;
+        Ok(OpsetExpression::Children(expression))
+    });
+    map.insert("ancestors", |function, context| {
+        let ([heads_arg], [depth_opt_arg]) = function.expect_arguments()?;
+        let heads = resolve_expression(heads_arg, context)?;
+        let depth = depth_opt_arg
+            .map(|arg| expect_literal("integer", arg))
+            .transpose()?;
+        Ok(OpsetExpression::Ancestors { heads, depth })
+    });
+    map.insert("descendants", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let expression = resolve_expression(arg, context)?;
+        Ok(OpsetExpression::Descendants(expression))
+    });
+    map.insert("command", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(OpsetExpression::Filter(OpsetFilterPredicate::Command(
+            pattern,
+        )))
+    });
+    map.insert("description", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(OpsetExpression::Filter(OpsetFilterPredicate::Description(
+            pattern,
+        )))
+    });
+    map.insert("user", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(OpsetExpression::Filter(OpsetFilterPredicate::User(pattern)))
+    });
+    map.insert("time", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_date_pattern(arg, context)?;
+        Ok(OpsetExpression::Filter(OpsetFilterPredicate::Time(pattern)))
+    });
+    map.insert("snapshot", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::Filter(OpsetFilterPredicate::Snapshot))
+    });
+    map.insert("workspace", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(OpsetExpression::Filter(OpsetFilterPredicate::Workspace(
+            pattern,
+        )))
+    });
+    map.insert("touched_bookmark", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(arg)?;
+        Ok(OpsetExpression::Filter(
+            OpsetFilterPredicate::TouchedBookmark(pattern),
+        ))
+    });
+    map
+});
+
+fn expect_literal<T: std::str::FromStr>(
+    type_name: &str,
+    node: &ExpressionNode,
+) -> OpsetParseResult<T> {
+    let make_error = || {
+        OpsetParseError::expression(
+            format!("Expected expression of type {type_name}"),
+            node.span,
+        )
+    };
+    match &node.kind {
+        ExpressionKind::Identifier(name) => name.parse().map_err(|_| make_error()),
+        ExpressionKind::String(name) => name.parse().map_err(|_| make_error()),
+        _ => Err(make_error()),
+    }
+}
+
+fn expect_pattern_with<T, E: Into<Box<dyn std::error::Error + Send + Sync>>>(
+    type_name: &str,
+    node: &ExpressionNode,
+    parse_pattern: impl FnOnce(&str, Option<&str>) -> Result<T, E>,
+) -> OpsetParseResult<T> {
+    let wrap_error = |err: E| {
+        OpsetParseError::expression(format!("Invalid {type_name}"), node.span).with_source(err)
+    };
+    match &node.kind {
+        ExpressionKind::Identifier(name) => parse_pattern(name, None).map_err(wrap_error),
+        ExpressionKind::String(name) => parse_pattern(name, None).map_err(wrap_error),
+        ExpressionKind::StringPattern { kind, value } => {
+            parse_pattern(value, Some(kind)).map_err(wrap_error)
+        }
+        _ => Err(OpsetParseError::expression(
+            format!("Expected expression of {type_name}"),
+            node.span,
+        )),
+    }
+}
+
+fn expect_string_pattern(node: &ExpressionNode) -> OpsetParseResult<StringPattern> {
+    expect_pattern_with("string pattern", node, |value, kind| match kind {
+        Some(kind) => StringPattern::from_str_kind(value, kind),
+        None => Ok(StringPattern::Substring(value.to_owned())),
+    })
+}
+
+fn expect_date_pattern(
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<DatePattern> {
+    expect_pattern_with(
+        "date pattern",
+        node,
+        |value, kind| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+            match kind {
+                None => Err("Date pattern must specify 'after' or 'before'".into()),
+                Some(kind) => Ok(context.parse_relative(value, kind)?),
+            }
+        },
+    )
+}
+
+fn resolve_function(
+    function: &FunctionCallNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<OpsetExpression> {
+    if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
+        func(function, context)
+    } else {
+        Err(OpsetParseError::new(
+            OpsetParseErrorKind::NoSuchFunction {
+                name: function.name.to_owned(),
+                candidates: collect_similar(function.name, BUILTIN_FUNCTION_MAP.keys()),
+            },
+            function.name_span,
+        ))
+    }
+}
+
+fn resolve_expression(
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<Rc<OpsetExpression>> {
+    let expression = match &node.kind {
+        ExpressionKind::Identifier(name) => OpsetExpression::Id((*name).to_owned()),
+        ExpressionKind::String(_) | ExpressionKind::StringPattern { .. } => {
+            return Err(OpsetParseError::expression(
+                "Expected operation ID or expression",
+                node.span,
+            ));
+        }
+        ExpressionKind::AtOperation => OpsetExpression::Current,
+        ExpressionKind::DagRangeAll => OpsetExpression::All,
+        ExpressionKind::RangeAll => OpsetExpression::Range {
+            roots: Rc::new(OpsetExpression::Root),
+            heads: Rc::new(OpsetExpression::Heads),
+        },
+        ExpressionKind::Unary(op, arg_node) => {
+            let arg = resolve_expression(arg_node, context)?;
+            match op {
+                UnaryOp::Negate => OpsetExpression::Difference(Rc::new(OpsetExpression::All), arg),
+                UnaryOp::DagRangePre => OpsetExpression::Ancestors {
+                    heads: arg,
+                    depth: None,
+                },
+                UnaryOp::DagRangePost => OpsetExpression::Descendants(arg),
+                UnaryOp::RangePre => OpsetExpression::Range {
+                    roots: Rc::new(OpsetExpression::Root),
+                    heads: arg,
+                },
+                UnaryOp::RangePost => OpsetExpression::Range {
+                    roots: arg,
+                    heads: Rc::new(OpsetExpression::Heads),
+                },
+                UnaryOp::Parents => OpsetExpression::Parents(arg),
+                UnaryOp::Children => OpsetExpression::Children(arg),
+            }
+        }
+        ExpressionKind::Binary(op, lhs_node, rhs_node) => {
+            let lhs = resolve_expression(lhs_node, context)?;
+            let rhs = resolve_expression(rhs_node, context)?;
+            match op {
+                BinaryOp::Intersection => return Ok(lhs.intersection(&rhs)),
+                BinaryOp::Difference => return Ok(lhs.difference(&rhs)),
+                BinaryOp::DagRange => OpsetExpression::DagRange {
+                    roots: lhs,
+                    heads: rhs,
+                },
+                BinaryOp::Range => OpsetExpression::Range {
+                    roots: lhs,
+                    heads: rhs,
+                },
+            }
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            let expressions: Vec<_> = nodes
+                .iter()
+                .map(|node| resolve_expression(node, context))
+                .try_collect()?;
+            return Ok(expressions
+                .into_iter()
+                .reduce(|lhs, rhs| lhs.union(&rhs))
+                .unwrap());
+        }
+        ExpressionKind::FunctionCall(function) => resolve_function(function, context)?,
+    };
+    Ok(Rc::new(expression))
+}
+
+/// Parses text into `OpsetExpression`.
+pub fn parse(text: &str, context: &DatePatternContext) -> OpsetParseResult<Rc<OpsetExpression>> {
+    let node = crate::opset_parser::parse_program(text)?;
+    resolve_expression(&node, context)
+}
+
+/// Evaluates the operation set `expression`.
+///
+/// The `@` symbol is resolved by `get_current_op`, and the current heads are
+/// loaded by `get_head_ops` on demand. The returned operations are sorted in
+/// reverse topological order.
+pub fn evaluate(
+    expression: &OpsetExpression,
+    op_store: &Arc<dyn OpStore>,
+    get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
+    get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
+    let evaluator = Evaluator {
+        op_store,
+        current_op: LazyValue::new(get_current_op),
+        head_ops: LazyValue::new(get_head_ops),
+        all_ops: OnceCell::new(),
+    };
+    let ops = evaluator.evaluate(expression)?;
+    Ok(sort_ops(ops)?)
+}
+
+/// Sorts the operation set in reverse topological order.
+fn sort_ops(mut ops: HashSet<Operation>) -> OpStoreResult<Vec<Operation>> {
+    if ops.len() <= 1 {
+        return Ok(ops.into_iter().collect());
+    }
+    let heads = ops.iter().cloned().collect_vec();
+    let mut sorted = Vec::with_capacity(ops.len());
+    for op in op_walk::walk_ancestors(&heads) {
+        let op = op?;
+        if ops.remove(&op) {
+            sorted.push(op);
+            if ops.is_empty() {
+                break;
+            }
+        }
+    }
+    Ok(sorted)
+}
+
+/// Value which is computed at most once on demand.
+struct LazyValue<T, F> {
+    cell: OnceCell<T>,
+    init: std::cell::Cell<Option<F>>,
+}
+
+impl<T, F: FnOnce() -> Result<T, OpsetEvaluationError>> LazyValue<T, F> {
+    fn new(init: F) -> Self {
+        LazyValue {
+            cell: OnceCell::new(),
+            init: std::cell::Cell::new(Some(init)),
+        }
+    }
+
+    fn get(&self) -> Result<&T, OpsetEvaluationError> {
+        self.cell.get_or_try_init(|| {
+            let init = self.init.take().expect("initializer should be called once");
+            init()
+        })
+    }
+}
+
+struct Evaluator<'a, F1, F2> {
+    op_store: &'a Arc<dyn OpStore>,
+    current_op: LazyValue<Operation, F1>,
+    head_ops: LazyValue<Vec<Operation>, F2>,
+    /// All operations reachable from the heads in reverse topological order.
+    all_ops: OnceCell<Vec<Operation>>,
+}
+
+impl<F1, F2> Evaluator<'_, F1, F2>
+where
+    F1: FnOnce() -> Result<Operation, OpsetEvaluationError>,
+    F2: FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
+{
+    fn all_ops(&self) -> Result<&[Operation], OpsetEvaluationError> {
+        let ops = self.all_ops.get_or_try_init(|| {
+            let head_ops = self.head_ops.get()?;
+            let ops = op_walk::walk_ancestors(head_ops).try_collect()?;
+            Ok::<_, OpsetEvaluationError>(ops)
+        })?;
+        Ok(ops)
+    }
+
+    fn evaluate(
+        &self,
+        expression: &OpsetExpression,
+    ) -> Result<HashSet<Operation>, OpsetEvaluationError> {
+        match expression {
+            OpsetExpression::None => Ok(HashSet::new()),
+            OpsetExpression::All => Ok(self.all_ops()?.iter().cloned().collect()),
+            OpsetExpression::Current => Ok(HashSet::from([self.current_op.get()?.clone()])),
+            OpsetExpression::Heads => Ok(self.head_ops.get()?.iter().cloned().collect()),
+            OpsetExpression::Root => {
+                let root_op_id = self.op_store.root_operation_id();
+                let data = self.op_store.read_operation(root_op_id)?;
+                let op = Operation::new(self.op_store.clone(), root_op_id.clone(), data);
+                Ok(HashSet::from([op]))
+            }
+            OpsetExpression::Id(prefix) => Ok(HashSet::from([resolve_op_id_prefix(
+                self.op_store,
+                prefix,
+            )?])),
+            OpsetExpression::Parents(expression) => {
+                let ops = self.evaluate(expression)?;
+                let parents = ops.iter().flat_map(|op| op.parents()).try_collect()?;
+                Ok(parents)
+            }
+            OpsetExpression::Children(expression) => {
+                let mut ops = self.evaluate(expression)?;
+                let ids: HashSet<_> = ops.iter().map(|op| op.id().clone()).collect();
+                let mut children = HashSet::new();
+                // Children are visited before their parents. Stop once all
+                // parents have been visited.
+                for op in op_walk::walk_ancestors(self.head_ops.get()?) {
+                    if ops.is_empty() {
+                        break;
+                    }
+                    let op = op?;
+                    ops.remove(&op);
+                    if op.parent_ids().iter().any(|id| ids.contains(id)) {
+                        children.insert(op);
+                    }
+                }
+                Ok(children)
+            }
+            OpsetExpression::Ancestors { heads, depth } => {
+                let heads = self.evaluate(heads)?;
+                match depth {
+                    None => {
+                        let heads = heads.into_iter().collect_vec();
+                        Ok(op_walk::walk_ancestors(&heads).try_collect()?)
+                    }
+                    Some(depth) => {
+                        let mut ancestors = HashSet::new();
+                        let mut generation = heads;
+                        for _ in 0..*depth {
+                            let next_generation: HashSet<_> = generation
+                                .iter()
+                                .flat_map(|op| op.parents())
+                                .filter_ok(|op| !ancestors.contains(op))
+                                .try_collect()?;
+                            ancestors.extend(generation);
+                            generation = next_generation;
+                            if generation.is_empty() {
+                                break;
+                            }
+                        }
+                        Ok(ancestors)
+                    }
+                }
+            }
+            OpsetExpression::Descendants(roots) => {
+                let roots = self.evaluate(roots)?;
+                Ok(self.descendants(roots)?)
+            }
+            OpsetExpression::DagRange { roots, heads } => {
+                let roots = self.evaluate(roots)?;
+                let heads = self.evaluate(heads)?.into_iter().collect_vec();
+                let mut ancestors: HashSet<_> = op_walk::walk_ancestors(&heads).try_collect()?;
+                let descendants = self.descendants(roots)?;
+                ancestors.retain(|op| descendants.contains(op));
+                Ok(ancestors)
+            }
+            OpsetExpression::Range { roots, heads } => {
+                let roots = self.evaluate(roots)?.into_iter().collect_vec();
+                let heads = self.evaluate(heads)?.into_iter().collect_vec();
+                let unwanted: HashSet<_> = op_walk::walk_ancestors(&roots).try_collect()?;
+                Ok(op_walk::walk_ancestors(&heads)
+                    .filter_ok(|op| !unwanted.contains(op))
+                    .try_collect()?)
+            }
+            OpsetExpression::Filter(predicate) => self.filter(self.all_ops()?, predicate),
+            OpsetExpression::Union(expression1, expression2) => {
+                let mut ops1 = self.evaluate(expression1)?;
+                ops1.extend(self.evaluate(expression2)?);
+                Ok(ops1)
+            }
+            OpsetExpression::Intersection(expression1, expression2) => {
+                // Apply filter to the other set instead of scanning all operations.
+                match (expression1.as_ref(), expression2.as_ref()) {
+                    (_, OpsetExpression::Filter(predicate)) => {
+                        let ops1 = self.evaluate(expression1)?;
+                        self.filter(ops1.iter(), predicate)
+                    }
+                    (OpsetExpression::Filter(predicate), _) => {
+                        let ops2 = self.evaluate(expression2)?;
+                        self.filter(ops2.iter(), predicate)
+                    }
+                    _ => {
+                        let mut ops1 = self.evaluate(expression1)?;
+                        let ops2 = self.evaluate(expression2)?;
+                        ops1.retain(|op| ops2.contains(op));
+                        Ok(ops1)
+                    }
+                }
+            }
+            OpsetExpression::Difference(expression1, expression2) => {
+                let mut ops1 = self.evaluate(expression1)?;
+                let ops2 = self.evaluate(expression2)?;
+                ops1.retain(|op| !ops2.contains(op));
+                Ok(ops1)
+            }
+        }
+    }
+
+    fn descendants(
+        &self,
+        roots: HashSet<Operation>,
+    ) -> Result<HashSet<Operation>, OpsetEvaluationError> {
+        let mut ids: HashSet<_> = roots.iter().map(|op| op.id().clone()).collect();
+        let mut descendants = roots;
+        for op in self.all_ops()?.iter().rev() {
+            if op.parent_ids().iter().any(|id| ids.contains(id)) {
+                ids.insert(op.id().clone());
+                descendants.insert(op.clone());
+            }
+        }
+        Ok(descendants)
+    }
+
+    fn filter<'b>(
+        &self,
+        ops: impl IntoIterator<Item = &'b Operation>,
+        predicate: &OpsetFilterPredicate,
+    ) -> Result<HashSet<Operation>, OpsetEvaluationError> {
+        let mut matched = HashSet::new();
+        for op in ops {
+            if predicate.matches(op)? {
+                matched.insert(op.clone());
+            }
+        }
+        Ok(matched)
+    }
+}
+
+fn resolve_op_id_prefix(
+    op_store: &Arc<dyn OpStore>,
+    op_str: &str,
+) -> Result<Operation, OpsetEvaluationError> {
+    let prefix = HexPrefix::new(op_str)
+        .ok_or_else(|| OpsetResolutionError::InvalidIdPrefix(op_str.to_owned()))?;
+    match op_store.resolve_operation_id_prefix(&prefix)? {
+        PrefixResolution::NoMatch => {
+            Err(OpsetResolutionError::NoSuchOperation(op_str.to_owned()).into())
+        }
+        PrefixResolution::SingleMatch(op_id) => {
+            let data = op_store.read_operation(&op_id)?;
+            Ok(Operation::new(op_store.clone(), op_id, data))
+        }
+        PrefixResolution::AmbiguousMatch => {
+            Err(OpsetResolutionError::AmbiguousIdPrefix(op_str.to_owned()).into())
+        }
+    }
+}
@@ -0,0 +1,565 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parser for the opset language.
+
+use std::collections::HashSet;
+use std::error;
+
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use pest::iterators::Pair;
+use pest::pratt_parser::Assoc;
+use pest::pratt_parser::Op;
+use pest::pratt_parser::PrattParser;
+use pest::Parser;
+use pest_derive::Parser;
+use thiserror::Error;
+
+use crate::dsl_util;
+use crate::dsl_util::InvalidArguments;
+use crate::dsl_util::StringLiteralParser;
+
+#[derive(Parser)]
+#[grammar = "opset.pest"]
+struct OpsetParser;
+
+const STRING_LITERAL_PARSER: StringLiteralParser<Rule> = StringLiteralParser {
+    content_rule: Rule::string_content,
+    escape_rule: Rule::string_escape,
+};
+
+impl Rule {
+    fn to_symbol(self) -> Option<&'static str> {
+        match self {
+            Rule::EOI => None,
+            Rule::whitespace => None,
+            Rule::identifier_part => None,
+            Rule::identifier => None,
+            Rule::strict_identifier_part => None,
+            Rule::strict_identifier => None,
+            Rule::symbol => None,
+            Rule::string_escape => None,
+            Rule::string_content_char => None,
+            Rule::string_content => None,
+            Rule::string_literal => None,
+            Rule::raw_string_content => None,
+            Rule::raw_string_literal => None,
+            Rule::at_op => Some("@"),
+            Rule::pattern_kind_op => Some(":"),
+            Rule::parents_op => Some("-"),
+            Rule::children_op => Some("+"),
+            Rule::dag_range_op
+            | Rule::dag_range_pre_op
+            | Rule::dag_range_post_op
+            | Rule::dag_range_all_op => Some("::"),
+            Rule::range_op | Rule::range_pre_op | Rule::range_post_op | Rule::range_all_op => {
+                Some("..")
+            }
+            Rule::range_ops => None,
+            Rule::range_pre_ops => None,
+            Rule::range_post_ops => None,
+            Rule::range_all_ops => None,
+            Rule::negate_op => Some("~"),
+            Rule::union_op => Some("|"),
+            Rule::intersection_op => Some("&"),
+            Rule::difference_op => Some("~"),
+            Rule::infix_op => None,
+            Rule::function => None,
+            Rule::function_name => None,
+            Rule::function_arguments => None,
+            Rule::string_pattern => None,
+            Rule::primary => None,
+            Rule::neighbors_expression => None,
+            Rule::range_expression => None,
+            Rule::expression => None,
+            Rule::program => None,
+        }
+    }
+}
+
+/// Result of opset parsing and name resolution.
+pub type OpsetParseResult<T> = Result<T, OpsetParseError>;
+
+/// Error occurred during opset parsing and name resolution.
+#[derive(Debug, Error)]
+#[error("{pest_error}")]
+pub struct OpsetParseError {
+    kind: OpsetParseErrorKind,
+    pest_error: Box<pest::error::Error<Rule>>,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+/// Categories of opset parsing and name resolution error.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum OpsetParseErrorKind {
+    #[error("Syntax error")]
+    SyntaxError,
+    #[error("Function `{name}` doesn't exist")]
+    NoSuchFunction {
+        name: String,
+        candidates: Vec<String>,
+    },
+    #[error("Function `{name}`: {message}")]
+    InvalidArguments { name: String, message: String },
+    #[error("{0}")]
+    Expression(String),
+}
+
+impl OpsetParseError {
+    pub(super) fn new(kind: OpsetParseErrorKind, span: pest::Span<'_>) -> Self {
+        let message = kind.to_string();
+        let pest_error = Box::new(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            span,
+        ));
+        OpsetParseError {
+            kind,
+            pest_error,
+            source: None,
+        }
+    }
+
+    pub(super) fn with_source(
+        mut self,
+        source: impl Into<Box<dyn error::Error + Send + Sync>>,
+    ) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Some other expression error.
+    pub(super) fn expression(message: impl Into<String>, span: pest::Span<'_>) -> Self {
+        OpsetParseError::new(OpsetParseErrorKind::Expression(message.into()), span)
+    }
+
+    /// Category of the underlying error.
+    pub fn kind(&self) -> &OpsetParseErrorKind {
+        &self.kind
+    }
+}
+
+impl From<pest::error::Error<Rule>> for OpsetParseError {
+    fn from(err: pest::error::Error<Rule>) -> Self {
+        OpsetParseError {
+            kind: OpsetParseErrorKind::SyntaxError,
+            pest_error: Box::new(rename_rules_in_pest_error(err)),
+            source: None,
+        }
+    }
+}
+
+impl From<InvalidArguments<'_>> for OpsetParseError {
+    fn from(err: InvalidArguments<'_>) -> Self {
+        let kind = OpsetParseErrorKind::InvalidArguments {
+            name: err.name.to_owned(),
+            message: err.message,
+        };
+        Self::new(kind, err.span)
+    }
+}
+
+fn rename_rules_in_pest_error(mut err: pest::error::Error<Rule>) -> pest::error::Error<Rule> {
+    let pest::error::ErrorVariant::ParsingError {
+        positives,
+        negatives,
+    } = &mut err.variant
+    else {
+        return err;
+    };
+
+    // Remove duplicated symbols such as "::" and "..".
+    let mut known_syms = HashSet::new();
+    positives.retain(|rule| rule.to_symbol().map_or(true, |sym| known_syms.insert(sym)));
+    let mut known_syms = HashSet::new();
+    negatives.retain(|rule| rule.to_symbol().map_or(true, |sym| known_syms.insert(sym)));
+    err.renamed_rules(|rule| {
+        rule.to_symbol()
+            .map(|sym| format!("`{sym}`"))
+            .unwrap_or_else(|| format!("<{rule:?}>"))
+    })
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExpressionKind<'i> {
+    /// Unquoted symbol.
+    Identifier(&'i str),
+    /// Quoted symbol or string.
+    String(String),
+    /// `<kind>:<value>`
+    StringPattern {
+        kind: &'i str,
+        value: String,
+    },
+    /// `@`
+    AtOperation,
+    /// `::`
+    DagRangeAll,
+    /// `..`
+    RangeAll,
+    Unary(UnaryOp, Box<ExpressionNode<'i>>),
+    Binary(BinaryOp, Box<ExpressionNode<'i>>, Box<ExpressionNode<'i>>),
+    /// `x | y | ..`
+    UnionAll(Vec<ExpressionNode<'i>>),
+    FunctionCall(Box<FunctionCallNode<'i>>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnaryOp {
+    /// `~x`
+    Negate,
+    /// `::x`
+    DagRangePre,
+    /// `x::`
+    DagRangePost,
+    /// `..x`
+    RangePre,
+    /// `x..`
+    RangePost,
+    /// `x-`
+    Parents,
+    /// `x+`
+    Children,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BinaryOp {
+    /// `&`
+    Intersection,
+    /// `~`
+    Difference,
+    /// `::`
+    DagRange,
+    /// `..`
+    Range,
+}
+
+pub type ExpressionNode<'i> = dsl_util::ExpressionNode<'i, ExpressionKind<'i>>;
+pub type FunctionCallNode<'i> = dsl_util::FunctionCallNode<'i, ExpressionKind<'i>>;
+
+fn union_nodes<'i>(lhs: ExpressionNode<'i>, rhs: ExpressionNode<'i>) -> ExpressionNode<'i> {
+    let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+    let expr = match lhs.kind {
+        // Flatten "x | y | z" to save recursion stack.
+        ExpressionKind::UnionAll(mut nodes) => {
+            nodes.push(rhs);
+            ExpressionKind::UnionAll(nodes)
+        }
+        _ => ExpressionKind::UnionAll(vec![lhs, rhs]),
+    };
+    ExpressionNode::new(expr, span)
+}
+
+fn parse_function_call_node(pair: Pair<Rule>) -> OpsetParseResult<FunctionCallNode> {
+    assert_eq!(pair.as_rule(), Rule::function);
+    let (name_pair, args_pair) = pair.into_inner().collect_tuple().unwrap();
+    assert_eq!(name_pair.as_rule(), Rule::function_name);
+    assert_eq!(args_pair.as_rule(), Rule::function_arguments);
+    let name_span = name_pair.as_span();
+    let args_span = args_pair.as_span();
+    let name = name_pair.as_str();
+    let args = args_pair
+        .into_inner()
+        .map(parse_expression_node)
+        .try_collect()?;
+    Ok(FunctionCallNode {
+        name,
+        name_span,
+        args,
+        keyword_args: vec![], // unsupported
+        args_span,
+    })
+}
+
+fn parse_as_string_literal(pair: Pair<Rule>) -> String {
+    match pair.as_rule() {
+        Rule::identifier => pair.as_str().to_owned(),
+        Rule::string_literal => STRING_LITERAL_PARSER.parse(pair.into_inner()),
+        Rule::raw_string_literal => {
+            let (content,) = pair.into_inner().collect_tuple().unwrap();
+            assert_eq!(content.as_rule(), Rule::raw_string_content);
+            content.as_str().to_owned()
+        }
+        r => panic!("unexpected string literal rule: {r:?}"),
+    }
+}
+
+fn parse_primary_node(pair: Pair<Rule>) -> OpsetParseResult<ExpressionNode> {
+    assert_eq!(pair.as_rule(), Rule::primary);
+    let first = pair.into_inner().next().unwrap();
+    let span = first.as_span();
+    let expr = match first.as_rule() {
+        Rule::expression => return parse_expression_node(first),
+        Rule::function => {
+            let function = Box::new(parse_function_call_node(first)?);
+            ExpressionKind::FunctionCall(function)
+        }
+        Rule::string_pattern => {
+            let (lhs, op, rhs) = first.into_inner().collect_tuple().unwrap();
+            assert_eq!(lhs.as_rule(), Rule::strict_identifier);
+            assert_eq!(op.as_rule(), Rule::pattern_kind_op);
+            let kind = lhs.as_str();
+            let value = parse_as_string_literal(rhs);
+            ExpressionKind::StringPattern { kind, value }
+        }
+        Rule::identifier => ExpressionKind::Identifier(first.as_str()),
+        Rule::string_literal | Rule::raw_string_literal => {
+            ExpressionKind::String(parse_as_string_literal(first))
+        }
+        Rule::at_op => ExpressionKind::AtOperation,
+        r => panic!("unexpected primary rule: {r:?}"),
+    };
+    Ok(ExpressionNode::new(expr, span))
+}
+
+fn parse_expression_node(pair: Pair<Rule>) -> OpsetParseResult<ExpressionNode> {
+    assert_eq!(pair.as_rule(), Rule::expression);
+    static PRATT: Lazy<PrattParser<Rule>> = Lazy::new(|| {
+        PrattParser::new()
+            .op(Op::infix(Rule::union_op, Assoc::Left))
+            .op(Op::infix(Rule::intersection_op, Assoc::Left)
+                | Op::infix(Rule::difference_op, Assoc::Left))
+            .op(Op::prefix(Rule::negate_op))
+            // Ranges can't be nested without parentheses. Associativity doesn't matter.
+            .op(Op::infix(Rule::dag_range_op, Assoc::Left) | Op::infix(Rule::range_op, Assoc::Left))
+            .op(Op::prefix(Rule::dag_range_pre_op) | Op::prefix(Rule::range_pre_op))
+            .op(Op::postfix(Rule::dag_range_post_op) | Op::postfix(Rule::range_post_op))
+            // Neighbors
+            .op(Op::postfix(Rule::parents_op) | Op::postfix(Rule::children_op))
+    });
+    PRATT
+        .map_primary(|primary| {
+            let expr = match primary.as_rule() {
+                Rule::primary => return parse_primary_node(primary),
+                Rule::dag_range_all_op => ExpressionKind::DagRangeAll,
+                Rule::range_all_op => ExpressionKind::RangeAll,
+                r => panic!("unexpected primary rule {r:?}"),
+            };
+            Ok(ExpressionNode::new(expr, primary.as_span()))
+        })
+        .map_prefix(|op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::negate_op => UnaryOp::Negate,
+                Rule::dag_range_pre_op => UnaryOp::DagRangePre,
+                Rule::range_pre_op => UnaryOp::RangePre,
+                r => panic!("unexpected prefix operator rule {r:?}"),
+            };
+            let rhs = Box::new(rhs?);
+            let span = op.as_span().start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Unary(op_kind, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_postfix(|lhs, op| {
+            let op_kind = match op.as_rule() {
+                Rule::dag_range_post_op => UnaryOp::DagRangePost,
+                Rule::range_post_op => UnaryOp::RangePost,
+                Rule::parents_op => UnaryOp::Parents,
+                Rule::children_op => UnaryOp::Children,
+                r => panic!("unexpected postfix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let span = lhs.span.start_pos().span(&op.as_span().end_pos());
+            let expr = ExpressionKind::Unary(op_kind, lhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_infix(|lhs, op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::union_op => return Ok(union_nodes(lhs?, rhs?)),
+                Rule::intersection_op => BinaryOp::Intersection,
+                Rule::difference_op => BinaryOp::Difference,
+                Rule::dag_range_op => BinaryOp::DagRange,
+                Rule::range_op => BinaryOp::Range,
+                r => panic!("unexpected infix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let rhs = Box::new(rhs?);
+            let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Binary(op_kind, lhs, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .parse(pair.into_inner())
+}
+
+/// Parses text into expression tree. No name resolution is made at this stage.
+pub fn parse_program(text: &str) -> OpsetParseResult<ExpressionNode<'_>> {
+    let mut pairs = OpsetParser::parse(Rule::program, text)?;
+    let first = pairs.next().unwrap();
+    parse_expression_node(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_into_kind(text: &str) -> Result<ExpressionKind<'_>, OpsetParseErrorKind> {
+        parse_program(text)
+            .map(|node| node.kind)
+            .map_err(|err| err.kind)
+    }
+
+    fn parse_normalized(text: &str) -> ExpressionNode<'_> {
+        normalize_tree(parse_program(text).unwrap())
+    }
+
+    /// Drops auxiliary data from parsed tree so it can be compared with other.
+    fn normalize_tree(node: ExpressionNode) -> ExpressionNode {
+        fn empty_span() -> pest::Span<'static> {
+            pest::Span::new("", 0, 0).unwrap()
+        }
+
+        fn normalize_list(nodes: Vec<ExpressionNode>) -> Vec<ExpressionNode> {
+            nodes.into_iter().map(normalize_tree).collect()
+        }
+
+        fn normalize_function_call(function: FunctionCallNode) -> FunctionCallNode {
+            FunctionCallNode {
+                name: function.name,
+                name_span: empty_span(),
+                args: normalize_list(function.args),
+                keyword_args: vec![],
+                args_span: empty_span(),
+            }
+        }
+
+        let normalized_kind = match node.kind {
+            ExpressionKind::Identifier(_)
+            | ExpressionKind::String(_)
+            | ExpressionKind::StringPattern { .. }
+            | ExpressionKind::AtOperation
+            | ExpressionKind::DagRangeAll
+            | ExpressionKind::RangeAll => node.kind,
+            ExpressionKind::Unary(op, arg) => {
+                let arg = Box::new(normalize_tree(*arg));
+                ExpressionKind::Unary(op, arg)
+            }
+            ExpressionKind::Binary(op, lhs, rhs) => {
+                let lhs = Box::new(normalize_tree(*lhs));
+                let rhs = Box::new(normalize_tree(*rhs));
+                ExpressionKind::Binary(op, lhs, rhs)
+            }
+            ExpressionKind::UnionAll(nodes) => {
+                let nodes = normalize_list(nodes);
+                ExpressionKind::UnionAll(nodes)
+            }
+            ExpressionKind::FunctionCall(function) => {
+                let function = Box::new(normalize_function_call(*function));
+                ExpressionKind::FunctionCall(function)
+            }
+        };
+        ExpressionNode {
+            kind: normalized_kind,
+            span: empty_span(),
+        }
+    }
+
+    #[test]
+    fn test_parse_tree_eq() {
+        assert_eq!(
+            parse_normalized(r#" foo( x ) | ~bar:"baz" "#),
+            parse_normalized(r#"(foo(x))|(~(bar:"baz"))"#)
+        );
+        assert_ne!(parse_normalized(r#" foo "#), parse_normalized(r#" "foo" "#));
+    }
+
+    #[test]
+    fn test_parse_operation_id() {
+        assert_eq!(
+            parse_into_kind("abc123"),
+            Ok(ExpressionKind::Identifier("abc123"))
+        );
+        assert_eq!(parse_into_kind("@"), Ok(ExpressionKind::AtOperation));
+        assert_eq!(
+            parse_normalized("abc123--"),
+            parse_normalized("((abc123)-)-")
+        );
+        assert_eq!(parse_normalized("@-+"), parse_normalized("(@-)+"));
+        assert_eq!(parse_into_kind("@@"), Err(OpsetParseErrorKind::SyntaxError));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_into_kind("::"), Ok(ExpressionKind::DagRangeAll));
+        assert_eq!(parse_into_kind(".."), Ok(ExpressionKind::RangeAll));
+        assert_matches::assert_matches!(
+            parse_into_kind("::@"),
+            Ok(ExpressionKind::Unary(UnaryOp::DagRangePre, _))
+        );
+        assert_matches::assert_matches!(
+            parse_into_kind("@-::"),
+            Ok(ExpressionKind::Unary(UnaryOp::DagRangePost, _))
+        );
+        assert_matches::assert_matches!(
+            parse_into_kind("..@"),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePre, _))
+        );
+        assert_matches::assert_matches!(
+            parse_into_kind("abc.."),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePost, _))
+        );
+        assert_matches::assert_matches!(
+            parse_into_kind("abc::@-"),
+            Ok(ExpressionKind::Binary(BinaryOp::DagRange, _, _))
+        );
+        assert_matches::assert_matches!(
+            parse_into_kind("abc..@-"),
+            Ok(ExpressionKind::Binary(BinaryOp::Range, _, _))
+        );
+        // Ranges can't be nested without parentheses.
+        assert_eq!(
+            parse_into_kind("a..b..c"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+        assert_eq!(
+            parse_normalized("::a | b::c & ~d"),
+            parse_normalized("(::a) | ((b::c) & (~d))")
+        );
+    }
+
+    #[test]
+    fn test_parse_function_call() {
+        assert_eq!(
+            parse_normalized(r#"command("git push")"#),
+            parse_normalized(r#"command('git push')"#)
+        );
+        let node = parse_program(r#"time(after:"2 days ago")"#).unwrap();
+        let ExpressionKind::FunctionCall(function) = node.kind else {
+            panic!("unexpected expression: {:?}", node.kind);
+        };
+        assert_eq!(function.name, "time");
+        assert_eq!(
+            function.args[0].kind,
+            ExpressionKind::StringPattern {
+                kind: "after",
+                value: "2 days ago".to_owned(),
+            }
+        );
+        assert_eq!(
+            parse_into_kind("5foo(x)"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        insta::assert_snapshot!(parse_program("@|").unwrap_err().to_string(), @r"
+         --> 1:3
+          |
+        1 | @|
+          |   ^---
+          |
+          = expected `::`, `..`, `~`, or <primary>
+        ");
+    }
+}
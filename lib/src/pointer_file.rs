@@ -0,0 +1,341 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pointer files for large working-copy files.
+//!
+//! When a snapshot policy says so, the content of a file is moved out of the
+//! backend into a [`PointerStore`] and the tree records a small text file
+//! that refers to it instead. On checkout, pointers whose content is available
+//! in the store are replaced by the content. Pointers to content that isn't in
+//! the store are written to disk as is.
+
+#![warn(missing_docs)]
+
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use blake2::Blake2b512;
+use digest::Digest as _;
+use tempfile::NamedTempFile;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::CommitId;
+use crate::backend::FileId;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::index::Index;
+use crate::object_id::ObjectId as _;
+use crate::store::Store;
+use crate::tree::Tree;
+
+const POINTER_HEADER: &str = "version jj-pointer/v1\n";
+
+/// Upper bound of the size of the serialized pointer. Files larger than this
+/// don't have to be inspected.
+pub const MAX_POINTER_SIZE: usize = 256;
+
+/// Reference to file content stored in a [`PointerStore`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pointer {
+    /// Hex-encoded BLAKE2b-512 hash of the content.
+    pub hash: String,
+    /// Size of the content in bytes.
+    pub size: u64,
+}
+
+impl Pointer {
+    /// Serializes the pointer as stored in the tree.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        format!(
+            "{POINTER_HEADER}oid blake2b:{hash}\nsize {size}\n",
+            hash = self.hash,
+            size = self.size
+        )
+        .into_bytes()
+    }
+
+    /// Parses the serialized pointer. Returns `None` if the `data` isn't a
+    /// valid pointer.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(data).ok()?;
+        let mut lines = text.strip_prefix(POINTER_HEADER)?.lines();
+        let hash = lines.next()?.strip_prefix("oid blake2b:")?;
+        let size = lines.next()?.strip_prefix("size ")?.parse().ok()?;
+        if lines.next().is_some()
+            || hash.len() != 128
+            || !hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        {
+            return None;
+        }
+        Some(Pointer {
+            hash: hash.to_owned(),
+            size,
+        })
+    }
+}
+
+/// Content-addressed store of the files replaced by pointers.
+#[derive(Clone, Debug)]
+pub struct PointerStore {
+    dir: PathBuf,
+}
+
+impl PointerStore {
+    /// Creates a store backed by the `dir`. The directory is created when the
+    /// first object is written.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        PointerStore { dir: dir.into() }
+    }
+
+    /// Directory where the objects are stored.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn object_path(&self, pointer: &Pointer) -> PathBuf {
+        self.dir.join(&pointer.hash)
+    }
+
+    /// Copies the `contents` into the store and returns the pointer to it.
+    pub fn write(&self, contents: &mut dyn Read) -> io::Result<Pointer> {
+        fs::create_dir_all(&self.dir)?;
+        let temp_file = NamedTempFile::new_in(&self.dir)?;
+        let mut file = temp_file.as_file();
+        let mut hasher = Blake2b512::new();
+        let mut buff: Vec<u8> = vec![0; 1 << 14];
+        let mut size = 0;
+        loop {
+            let bytes_read = contents.read(&mut buff)?;
+            if bytes_read == 0 {
+                break;
+            }
+            let bytes = &buff[..bytes_read];
+            file.write_all(bytes)?;
+            hasher.update(bytes);
+            size += bytes_read as u64;
+        }
+        file.flush()?;
+        let pointer = Pointer {
+            hash: hex::encode(hasher.finalize()),
+            size,
+        };
+        persist_content_addressed_temp_file(temp_file, self.object_path(&pointer))?;
+        Ok(pointer)
+    }
+
+    /// Opens the content referred to by the `pointer`. Returns `None` if the
+    /// store doesn't have it.
+    pub fn open(&self, pointer: &Pointer) -> io::Result<Option<File>> {
+        match File::open(self.object_path(pointer)) {
+            Ok(file) => Ok(Some(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Deletes the objects which aren't `referenced`, except for the ones
+    /// modified after `keep_newer`.
+    pub fn gc(&self, referenced: &HashSet<String>, keep_newer: SystemTime) -> io::Result<()> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let entry = entry?;
+            if entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| referenced.contains(name))
+            {
+                continue;
+            }
+            // Recent objects and temporary files may be referenced by
+            // snapshots in progress.
+            if entry.metadata()?.modified()? > keep_newer {
+                continue;
+            }
+            match fs::remove_file(entry.path()) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> io::Result<bool> {
+        match fs::read_dir(&self.dir) {
+            Ok(mut entries) => Ok(entries.next().is_none()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Deletes the objects of the pointer store of the `store` which aren't
+/// referenced by any commit in the `index`, except for the objects modified
+/// after `keep_newer`.
+pub fn gc(store: &Arc<Store>, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
+    let pointer_store = store.pointer_store();
+    let to_backend_error = |err: io::Error| BackendError::Other(err.into());
+    // Don't bother reading all the trees if there's nothing to collect
+    if pointer_store.is_empty().map_err(to_backend_error)? {
+        return Ok(());
+    }
+    let mut pending: Vec<CommitId> = index
+        .all_heads_for_gc()
+        .map_err(|err| BackendError::Other(err.into()))?
+        .collect();
+    let mut visited_commits = HashSet::new();
+    let mut collector = PointerCollector::default();
+    while let Some(id) = pending.pop() {
+        if !visited_commits.insert(id.clone()) {
+            continue;
+        }
+        let commit = store.get_commit(&id)?;
+        for tree in commit.tree()?.as_merge().iter() {
+            if collector.visited_trees.insert(tree.id().clone()) {
+                collector.visit_tree(tree)?;
+            }
+        }
+        pending.extend(commit.parent_ids().iter().cloned());
+    }
+    pointer_store
+        .gc(&collector.pointers, keep_newer)
+        .map_err(to_backend_error)
+}
+
+/// Collects the pointers stored in trees. Trees and files shared by many
+/// commits are read once.
+#[derive(Default)]
+struct PointerCollector {
+    visited_trees: HashSet<TreeId>,
+    visited_files: HashSet<FileId>,
+    pointers: HashSet<String>,
+}
+
+impl PointerCollector {
+    fn visit_tree(&mut self, tree: &Tree) -> BackendResult<()> {
+        for entry in tree.entries_non_recursive() {
+            match entry.value() {
+                TreeValue::Tree(id) => {
+                    if !self.visited_trees.insert(id.clone()) {
+                        continue;
+                    }
+                    if let Some(sub_tree) = tree.sub_tree(entry.name())? {
+                        self.visit_tree(&sub_tree)?;
+                    }
+                }
+                TreeValue::File { id, .. } => {
+                    if !self.visited_files.insert(id.clone()) {
+                        continue;
+                    }
+                    let path = tree.dir().join(entry.name());
+                    let mut head = vec![];
+                    tree.store()
+                        .read_file(&path, id)?
+                        .take(MAX_POINTER_SIZE as u64 + 1)
+                        .read_to_end(&mut head)
+                        .map_err(|err| BackendError::ReadObject {
+                            object_type: id.object_type(),
+                            hash: id.hex(),
+                            source: err.into(),
+                        })?;
+                    if let Some(pointer) = Pointer::parse(&head) {
+                        self.pointers.insert(pointer.hash);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pointer_round_trip() {
+        let pointer = Pointer {
+            hash: "0a".repeat(64),
+            size: 42,
+        };
+        assert_eq!(Pointer::parse(&pointer.to_bytes()), Some(pointer.clone()));
+        assert!(pointer.to_bytes().len() <= MAX_POINTER_SIZE);
+
+        assert_eq!(Pointer::parse(b""), None);
+        assert_eq!(Pointer::parse(b"version jj-pointer/v1\n"), None);
+        let mut data = pointer.to_bytes();
+        data.extend_from_slice(b"extra\n");
+        assert_eq!(Pointer::parse(&data), None);
+        let data = String::from_utf8(pointer.to_bytes())
+            .unwrap()
+            .replace("0a", "0A");
+        assert_eq!(Pointer::parse(data.as_bytes()), None);
+    }
+
+    #[test]
+    fn test_pointer_store() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = PointerStore::new(temp_dir.path().join("pointers"));
+        let pointer = store.write(&mut &b"content"[..]).unwrap();
+        assert_eq!(pointer.size, 7);
+        let mut content = String::new();
+        store
+            .open(&pointer)
+            .unwrap()
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "content");
+
+        let missing = Pointer {
+            hash: "00".repeat(64),
+            size: 0,
+        };
+        assert!(store.open(&missing).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_pointer_store_gc() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = PointerStore::new(temp_dir.path().join("pointers"));
+        // Nothing to collect before the first object is written
+        store.gc(&HashSet::new(), SystemTime::now()).unwrap();
+
+        let kept = store.write(&mut &b"kept"[..]).unwrap();
+        let deleted = store.write(&mut &b"deleted"[..]).unwrap();
+        let referenced = HashSet::from([kept.hash.clone()]);
+
+        // Objects newer than the threshold are kept
+        store.gc(&referenced, SystemTime::UNIX_EPOCH).unwrap();
+        assert!(store.open(&deleted).unwrap().is_some());
+
+        store.gc(&referenced, SystemTime::now()).unwrap();
+        assert!(store.open(&kept).unwrap().is_some());
+        assert!(store.open(&deleted).unwrap().is_none());
+    }
+}
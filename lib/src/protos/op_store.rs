@@ -132,6 +132,10 @@ pub struct Operation {
     pub parents: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
     #[prost(message, optional, tag = "3")]
     pub metadata: ::core::option::Option<OperationMetadata>,
+    /// Signature of the operation made by the signing backend. See
+    /// `Operation::signing_data()` for the signed data.
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub signature: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
 /// TODO: Share with store.proto? Do we even need the timezone here?
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -142,6 +146,38 @@ pub struct Timestamp {
     #[prost(int32, tag = "2")]
     pub tz_offset: i32,
 }
+/// Name of a ref stored in a view.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ViewRefName {
+    #[prost(oneof = "view_ref_name::Value", tags = "1, 2, 3, 4, 5")]
+    pub value: ::core::option::Option<view_ref_name::Value>,
+}
+/// Nested message and enum types in `ViewRefName`.
+pub mod view_ref_name {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RemoteBookmark {
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub remote: ::prost::alloc::string::String,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        #[prost(string, tag = "1")]
+        LocalBookmark(::prost::alloc::string::String),
+        #[prost(message, tag = "2")]
+        RemoteBookmark(RemoteBookmark),
+        #[prost(string, tag = "3")]
+        Tag(::prost::alloc::string::String),
+        #[prost(string, tag = "4")]
+        GitRef(::prost::alloc::string::String),
+        #[prost(bool, tag = "5")]
+        GitHead(bool),
+    }
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OperationMetadata {
@@ -162,6 +198,9 @@ pub struct OperationMetadata {
         ::prost::alloc::string::String,
         ::prost::alloc::string::String,
     >,
+    /// Refs which became conflicted by merging concurrent operations.
+    #[prost(message, repeated, tag = "8")]
+    pub conflicted_refs: ::prost::alloc::vec::Vec<ViewRefName>,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -30,6 +30,18 @@ pub struct FileStateEntry {
     #[prost(message, optional, tag = "2")]
     pub state: ::core::option::Option<FileState>,
 }
+/// File states of a checked-out Git submodule.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubmoduleFileStates {
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+    /// The submodule tree the file states were recorded against.
+    #[prost(bytes = "vec", tag = "2")]
+    pub tree_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, repeated, tag = "3")]
+    pub file_states: ::prost::alloc::vec::Vec<FileStateEntry>,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SparsePatterns {
@@ -53,6 +65,10 @@ pub struct TreeState {
     pub sparse_patterns: ::core::option::Option<SparsePatterns>,
     #[prost(message, optional, tag = "4")]
     pub watchman_clock: ::core::option::Option<WatchmanClock>,
+    #[prost(message, optional, tag = "7")]
+    pub inotify_clock: ::core::option::Option<InotifyClock>,
+    #[prost(message, repeated, tag = "8")]
+    pub submodule_file_states: ::prost::alloc::vec::Vec<SubmoduleFileStates>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -73,6 +89,16 @@ pub mod watchman_clock {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InotifyClock {
+    /// Identifies the journal of a particular daemon instance.
+    #[prost(string, tag = "1")]
+    pub instance: ::prost::alloc::string::String,
+    /// Position in the journal.
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Checkout {
     /// The operation at which the working copy was updated.
     #[prost(bytes = "vec", tag = "2")]
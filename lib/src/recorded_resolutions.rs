@@ -0,0 +1,606 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recorded resolutions of conflict hunks, similar to Git's "rerere".
+//!
+//! When a conflicted file is resolved, the resolution of each conflicting hunk
+//! is recorded, keyed by the hash of the hunk's sides. When the same hunk
+//! appears in a later merge, the recorded resolution is reused.
+
+#![warn(missing_docs)]
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use blake2::Blake2b512;
+use bstr::BString;
+use bstr::ByteSlice as _;
+use digest::Digest as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::FileId;
+use crate::backend::TreeValue;
+use crate::config::ConfigGetError;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::files;
+use crate::files::MergeResult;
+use crate::matchers::Matcher;
+use crate::merge::Merge;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::MergedTreeBuilder;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::settings::UserSettings;
+use crate::store::Store;
+
+const REUSED_LOG_NAME: &str = "reused";
+
+/// Error that may occur while recording resolutions.
+#[derive(Debug, Error)]
+pub enum RecordedResolutionsError {
+    /// Failed to read the conflicted or resolved files.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Failed to access the recorded resolutions.
+    #[error(transparent)]
+    Path(#[from] PathError),
+}
+
+/// Recorded resolution which was reused when merging a file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReusedResolution {
+    /// Path of the file the resolution was applied to.
+    pub path: RepoPathBuf,
+    /// Hex-encoded key of the conflict hunk.
+    pub key: String,
+}
+
+/// File content resolved by recorded resolutions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedContent {
+    /// Merged content of the file.
+    pub content: Vec<u8>,
+    /// Recorded resolutions which were used for the conflicting hunks.
+    pub reused: Vec<ReusedResolution>,
+}
+
+/// Store of recorded conflict resolutions.
+#[derive(Clone, Debug)]
+pub struct RecordedResolutions {
+    dir: PathBuf,
+}
+
+impl RecordedResolutions {
+    /// Creates a store backed by the `dir`. The directory is created when the
+    /// first resolution is recorded.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        RecordedResolutions { dir: dir.into() }
+    }
+
+    /// Returns the store in the repo at `repo_path`.
+    pub fn for_repo(repo_path: &Path) -> Self {
+        Self::new(repo_path.join("recorded_resolutions"))
+    }
+
+    /// Returns the store in the repo at `repo_path` if `rerere.enabled` is
+    /// set.
+    pub fn from_settings(
+        settings: &UserSettings,
+        repo_path: &Path,
+    ) -> Result<Option<Self>, ConfigGetError> {
+        let enabled = settings.get_bool("rerere.enabled")?;
+        Ok(enabled.then(|| Self::for_repo(repo_path)))
+    }
+
+    /// Directory where the resolutions are stored.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Records the resolutions of the conflicting `hunks` of the file at
+    /// `path`, as found in the `resolved` content. Returns the number of
+    /// recorded hunks.
+    ///
+    /// Hunks are only recorded if the non-conflicting parts of the file are
+    /// unchanged up to the hunk, and if the resolution of the hunk can be
+    /// told apart from the surrounding non-conflicting parts.
+    pub fn record(
+        &self,
+        path: &RepoPath,
+        hunks: &[Merge<BString>],
+        resolved: &[u8],
+    ) -> Result<usize, PathError> {
+        let resolutions = find_hunk_resolutions(hunks, resolved);
+        if resolutions.is_empty() {
+            return Ok(0);
+        }
+        fs::create_dir_all(&self.dir).context(&self.dir)?;
+        for (hunk, resolution) in &resolutions {
+            let mut data = path.as_internal_file_string().as_bytes().to_vec();
+            data.push(b'\0');
+            data.extend_from_slice(resolution);
+            let record_path = self.dir.join(hunk_key(hunk));
+            let mut temp_file = NamedTempFile::new_in(&self.dir).context(&self.dir)?;
+            temp_file.write_all(&data).context(temp_file.path())?;
+            temp_file
+                .persist(&record_path)
+                .map_err(|err| err.error)
+                .context(&record_path)?;
+        }
+        Ok(resolutions.len())
+    }
+
+    /// Resolves the conflicting `hunks` of the file at `path` by the recorded
+    /// resolutions. Returns `None` unless resolutions of all conflicting hunks
+    /// are recorded.
+    ///
+    /// The reused resolutions aren't logged until [`Self::log_reused()`] is
+    /// called.
+    pub fn resolve(
+        &self,
+        path: &RepoPath,
+        hunks: &[Merge<BString>],
+    ) -> Result<Option<ResolvedContent>, PathError> {
+        let mut merged = vec![];
+        let mut reused = vec![];
+        for hunk in hunks {
+            if let Some(content) = hunk.as_resolved() {
+                merged.extend_from_slice(content);
+                continue;
+            }
+            let key = hunk_key(hunk);
+            let Some((_, resolution)) = self.read_record(&key)? else {
+                return Ok(None);
+            };
+            merged.extend_from_slice(&resolution);
+            reused.push(ReusedResolution {
+                path: path.to_owned(),
+                key,
+            });
+        }
+        Ok(Some(ResolvedContent {
+            content: merged,
+            reused,
+        }))
+    }
+
+    /// Resolves the file conflicts in the `tree` whose hunks all have recorded
+    /// resolutions. Returns the new tree and the reused resolutions.
+    pub fn resolve_tree(
+        &self,
+        tree: &MergedTree,
+    ) -> Result<(MergedTree, Vec<ReusedResolution>), RecordedResolutionsError> {
+        let store = tree.store();
+        let mut tree_builder = MergedTreeBuilder::new(tree.id());
+        let mut reused = vec![];
+        for (path, value) in tree.conflicts() {
+            let value = value?;
+            let (Some(file_ids), Some(executable)) =
+                (value.to_file_merge(), value.to_executable_merge())
+            else {
+                continue;
+            };
+            let (Some(file_ids), Some(&executable)) = (
+                file_ids.maybe_map(|id| id.clone()),
+                executable.resolve_trivial(),
+            ) else {
+                continue;
+            };
+            // Simplify like the tree merge does so the hunks are the same
+            let file_ids = file_ids.simplify();
+            let contents = file_ids.try_map(|id| read_file(store, &path, id))?;
+            let MergeResult::Conflict(hunks) = files::merge(&contents) else {
+                continue;
+            };
+            let Some(resolved) = self.resolve(&path, &hunks)? else {
+                continue;
+            };
+            let id = store
+                .write_file(&path, &mut resolved.content.as_slice())
+                .block_on()?;
+            tree_builder.set_or_remove(path, Merge::normal(TreeValue::File { id, executable }));
+            reused.extend(resolved.reused);
+        }
+        if reused.is_empty() {
+            return Ok((tree.clone(), reused));
+        }
+        let tree_id = tree_builder.write_tree(store)?;
+        Ok((store.get_root_tree(&tree_id)?, reused))
+    }
+
+    /// Records the resolutions of files which are conflicted in the
+    /// `old_tree` but resolved in the `new_tree`. Returns the number of
+    /// recorded hunks.
+    pub fn record_tree(
+        &self,
+        store: &Store,
+        old_tree: &MergedTree,
+        new_tree: &MergedTree,
+    ) -> Result<usize, RecordedResolutionsError> {
+        let mut count = 0;
+        for (path, old_value) in old_tree.conflicts() {
+            let Some(file_ids) = old_value?.to_file_merge() else {
+                continue;
+            };
+            let Some(file_ids) = file_ids.maybe_map(|id| id.clone()) else {
+                continue;
+            };
+            // Simplify like the tree merge does so the hunks are the same
+            let file_ids = file_ids.simplify();
+            let new_value = new_tree.path_value(&path)?;
+            let Some(Some(TreeValue::File { id: new_id, .. })) = new_value.as_resolved() else {
+                continue;
+            };
+            let contents = file_ids.try_map(|id| read_file(store, &path, id))?;
+            let MergeResult::Conflict(hunks) = files::merge(&contents) else {
+                continue;
+            };
+            let resolved = read_file(store, &path, new_id)?;
+            count += self.record(&path, &hunks, &resolved)?;
+        }
+        Ok(count)
+    }
+
+    /// Returns the resolutions which were reused, most recently reused first.
+    pub fn reused(&self) -> Result<Vec<ReusedResolution>, PathError> {
+        let log_path = self.dir.join(REUSED_LOG_NAME);
+        let data = match fs::read(&log_path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err).context(&log_path),
+        };
+        let mut reused = vec![];
+        for line in data.lines().rev() {
+            let Some((key, path)) = line.split_once_str(" ") else {
+                continue;
+            };
+            let (Ok(key), Ok(path)) = (key.to_str(), path.to_str()) else {
+                continue;
+            };
+            // The resolution may have been forgotten since
+            if !self.dir.join(key).exists() {
+                continue;
+            }
+            let Ok(path) = RepoPathBuf::from_relative_path(path) else {
+                continue;
+            };
+            reused.push(ReusedResolution {
+                path,
+                key: key.to_owned(),
+            });
+        }
+        Ok(reused.into_iter().unique().collect())
+    }
+
+    /// Removes the resolutions recorded for or reused in the files matching
+    /// the `matcher`. Returns the number of removed resolutions.
+    pub fn forget(&self, matcher: &dyn Matcher) -> Result<usize, PathError> {
+        let mut keys = self
+            .reused()?
+            .into_iter()
+            .filter(|reused| matcher.matches(&reused.path))
+            .map(|reused| reused.key)
+            .collect_vec();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err).context(&self.dir),
+        };
+        for entry in entries {
+            let entry = entry.context(&self.dir)?;
+            let Ok(key) = entry.file_name().into_string() else {
+                continue;
+            };
+            if !is_key(&key) {
+                continue;
+            }
+            if let Some((path, _)) = self.read_record(&key)? {
+                if matcher.matches(&path) {
+                    keys.push(key);
+                }
+            }
+        }
+        let mut count = 0;
+        for key in keys.iter().unique() {
+            let record_path = self.dir.join(key);
+            match fs::remove_file(&record_path) {
+                Ok(()) => count += 1,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err).context(&record_path),
+            }
+        }
+        Ok(count)
+    }
+
+    fn read_record(&self, key: &str) -> Result<Option<(RepoPathBuf, Vec<u8>)>, PathError> {
+        let record_path = self.dir.join(key);
+        let data = match fs::read(&record_path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).context(&record_path),
+        };
+        let parsed = data.split_once_str(b"\0").and_then(|(path, resolution)| {
+            let path = RepoPathBuf::from_relative_path(path.to_str().ok()?).ok()?;
+            Some((path, resolution.to_vec()))
+        });
+        Ok(parsed)
+    }
+
+    /// Appends the `reused` resolutions to the log read by [`Self::reused()`].
+    pub fn log_reused(&self, reused: &[ReusedResolution]) -> Result<(), PathError> {
+        if reused.is_empty() {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir).context(&self.dir)?;
+        let log_path = self.dir.join(REUSED_LOG_NAME);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .context(&log_path)?;
+        let data: String = reused
+            .iter()
+            .map(|reused| format!("{} {}\n", reused.key, reused.path.as_internal_file_string()))
+            .collect();
+        file.write_all(data.as_bytes()).context(&log_path)
+    }
+}
+
+/// Returns the key of the conflict `hunk`. The order of the sides doesn't
+/// matter.
+fn hunk_key(hunk: &Merge<BString>) -> String {
+    let mut hasher = Blake2b512::new();
+    let removes = hunk.removes().sorted().collect_vec();
+    let adds = hunk.adds().sorted().collect_vec();
+    for terms in [removes, adds] {
+        hasher.update((terms.len() as u64).to_le_bytes());
+        for term in terms {
+            hasher.update((term.len() as u64).to_le_bytes());
+            hasher.update(term);
+        }
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn is_key(name: &str) -> bool {
+    name.len() == 128 && name.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Finds the resolution of each conflicting hunk by locating the resolved
+/// hunks in the `resolved` content.
+///
+/// The resolved hunks may occur more than once in the `resolved` content, so
+/// all possible alignments are considered. A conflicting hunk is only recorded
+/// if all alignments agree on where its resolution starts and ends.
+fn find_hunk_resolutions<'a>(
+    hunks: &'a [Merge<BString>],
+    resolved: &'a [u8],
+) -> Vec<(&'a Merge<BString>, &'a [u8])> {
+    let is_line_start = |pos: usize| pos == 0 || resolved[pos - 1] == b'\n';
+    // Returns the possible end positions of the hunk at `index` if it starts
+    // at `start`
+    let hunk_ends = |index: usize, start: usize| -> Vec<usize> {
+        if let Some(content) = hunks[index].as_resolved() {
+            return resolved[start..]
+                .starts_with(content)
+                .then_some(start + content.len())
+                .into_iter()
+                .collect();
+        }
+        match hunks.get(index + 1).and_then(|hunk| hunk.as_resolved()) {
+            // The resolved hunk must start at a line boundary after the
+            // resolution of the conflict
+            Some(next_content) => (start..=resolved.len())
+                .filter(|&end| is_line_start(end) && resolved[end..].starts_with(next_content))
+                .collect(),
+            None if index + 1 == hunks.len() => vec![resolved.len()],
+            None => (start..=resolved.len())
+                .filter(|&end| is_line_start(end))
+                .collect(),
+        }
+    };
+
+    // Find the possible start positions of each hunk, up to the last hunk
+    // which can be located. Hunks after modified context aren't recorded.
+    let mut starts: Vec<BTreeSet<usize>> = vec![BTreeSet::from([0])];
+    while starts.len() <= hunks.len() {
+        let index = starts.len() - 1;
+        let ends: BTreeSet<usize> = starts[index]
+            .iter()
+            .flat_map(|&start| hunk_ends(index, start))
+            .collect();
+        if ends.is_empty() {
+            break;
+        }
+        starts.push(ends);
+    }
+
+    // If all hunks are located, the last one must end at the end of the file
+    if starts.len() > hunks.len() && starts[hunks.len()].contains(&resolved.len()) {
+        starts[hunks.len()] = BTreeSet::from([resolved.len()]);
+    }
+    // Keep only the start positions which lead to the last located hunk
+    for index in (0..starts.len() - 1).rev() {
+        let (head, tail) = starts.split_at_mut(index + 1);
+        let next_starts = &tail[0];
+        head[index].retain(|&start| {
+            hunk_ends(index, start)
+                .iter()
+                .any(|end| next_starts.contains(end))
+        });
+    }
+
+    let mut resolutions = vec![];
+    for (index, bounds) in starts.windows(2).enumerate() {
+        let hunk = &hunks[index];
+        if hunk.is_resolved() {
+            continue;
+        }
+        if let (Ok(&start), Ok(&end)) = (
+            bounds[0].iter().exactly_one(),
+            bounds[1].iter().exactly_one(),
+        ) {
+            resolutions.push((hunk, &resolved[start..end]));
+        }
+    }
+    resolutions
+}
+
+fn read_file(store: &Store, path: &RepoPath, id: &FileId) -> Result<Vec<u8>, BackendError> {
+    let mut content = vec![];
+    store
+        .read_file(path, id)?
+        .read_to_end(&mut content)
+        .map_err(|err| BackendError::ReadFile {
+            path: path.to_owned(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchers::EverythingMatcher;
+    use crate::matchers::FilesMatcher;
+
+    fn conflict_hunks(contents: [&str; 3]) -> Vec<Merge<BString>> {
+        let contents = Merge::from_vec(contents.to_vec());
+        match files::merge(&contents) {
+            MergeResult::Resolved(_) => panic!("expected conflict"),
+            MergeResult::Conflict(hunks) => hunks,
+        }
+    }
+
+    #[test]
+    fn test_record_and_resolve() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let resolutions = RecordedResolutions::new(temp_dir.path().join("resolutions"));
+        let path = RepoPath::from_internal_string("file");
+        let hunks = conflict_hunks([
+            "a\nleft 1\nb\nc\nleft 2\nd\n",
+            "a\nbase 1\nb\nc\nbase 2\nd\n",
+            "a\nright 1\nb\nc\nright 2\nd\n",
+        ]);
+        assert_eq!(resolutions.resolve(path, &hunks).unwrap(), None);
+        assert!(resolutions.reused().unwrap().is_empty());
+
+        // Both hunks are recorded
+        let resolved = "a\nresolved 1\nb\nc\nresolved 2\nd\n";
+        assert_eq!(
+            resolutions
+                .record(path, &hunks, resolved.as_bytes())
+                .unwrap(),
+            2
+        );
+        let ResolvedContent { content, reused } =
+            resolutions.resolve(path, &hunks).unwrap().unwrap();
+        assert_eq!(content, resolved.as_bytes());
+        assert_eq!(reused.len(), 2);
+        // Reused resolutions are only listed once logged
+        assert!(resolutions.reused().unwrap().is_empty());
+        resolutions.log_reused(&reused).unwrap();
+        assert_eq!(resolutions.reused().unwrap().len(), 2);
+
+        // The order of sides doesn't matter, and the same hunk is resolved in
+        // a different context
+        let other_path = RepoPath::from_internal_string("other");
+        let swapped_hunks = conflict_hunks(["x\nright 1\ny\n", "x\nbase 1\ny\n", "x\nleft 1\ny\n"]);
+        let ResolvedContent { content, reused } = resolutions
+            .resolve(other_path, &swapped_hunks)
+            .unwrap()
+            .unwrap();
+        assert_eq!(content, b"x\nresolved 1\ny\n");
+        resolutions.log_reused(&reused).unwrap();
+        assert_eq!(resolutions.reused().unwrap()[0].path, other_path.to_owned());
+
+        // Forget the resolutions recorded for or reused at the other path
+        let matcher = FilesMatcher::new([other_path]);
+        assert_eq!(resolutions.forget(&matcher).unwrap(), 1);
+        assert_eq!(resolutions.resolve(path, &hunks).unwrap(), None);
+        assert_eq!(resolutions.forget(&EverythingMatcher).unwrap(), 1);
+        assert!(resolutions.reused().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_ambiguous_resolution() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let resolutions = RecordedResolutions::new(temp_dir.path().join("resolutions"));
+        let path = RepoPath::from_internal_string("file");
+        let hunks = conflict_hunks(["L1\n}\nL2\n", "B1\n}\nB2\n", "R1\n}\nR2\n"]);
+        // The resolution contains the context line between the hunks, so it's
+        // unknown which "}" belongs to the context
+        let resolved = "if x {\n}\n}\nR2\n";
+        assert_eq!(
+            resolutions
+                .record(path, &hunks, resolved.as_bytes())
+                .unwrap(),
+            0
+        );
+        assert_eq!(resolutions.resolve(path, &hunks).unwrap(), None);
+
+        // The context line may be part of a resolution if the split is still
+        // unambiguous
+        let hunks = conflict_hunks(["L1\nx\nL2\n}\n", "B1\nx\nB2\n}\n", "R1\nx\nR2\n}\n"]);
+        let resolved = "r1\nx\nif y {\n}\n}\n";
+        assert_eq!(
+            resolutions
+                .record(path, &hunks, resolved.as_bytes())
+                .unwrap(),
+            2
+        );
+        let content = resolutions.resolve(path, &hunks).unwrap().unwrap().content;
+        assert_eq!(content, resolved.as_bytes());
+    }
+
+    #[test]
+    fn test_record_modified_context() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let resolutions = RecordedResolutions::new(temp_dir.path().join("resolutions"));
+        let path = RepoPath::from_internal_string("file");
+        let hunks = conflict_hunks([
+            "a\nleft 1\nb\nc\nleft 2\nd\n",
+            "a\nbase 1\nb\nc\nbase 2\nd\n",
+            "a\nright 1\nb\nc\nright 2\nd\n",
+        ]);
+        // Only the hunk before the modified context is recorded
+        let resolved = "a\nresolved 1\nb\nc\nresolved 2\nD\n";
+        assert_eq!(
+            resolutions
+                .record(path, &hunks, resolved.as_bytes())
+                .unwrap(),
+            1
+        );
+        // Nothing is recorded if the leading context is modified
+        let resolved = "A\nresolved 1\nb\nc\nresolved 2\nd\n";
+        assert_eq!(
+            resolutions
+                .record(path, &hunks, resolved.as_bytes())
+                .unwrap(),
+            0
+        );
+    }
+}
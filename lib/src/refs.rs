@@ -20,6 +20,7 @@ use std::fmt::Display;
 use itertools::EitherOrBoth;
 
 use crate::backend::CommitId;
+use crate::content_hash::ContentHash;
 use crate::index::Index;
 use crate::merge::trivial_merge;
 use crate::merge::Merge;
@@ -175,7 +176,7 @@ fn find_pair_to_remove(
 ///
 /// This type can be displayed in `{name}@{remote}` form, with quoting and
 /// escaping if necessary.
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(ContentHash, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct RemoteRefSymbolBuf {
     /// Local name.
     pub name: String,
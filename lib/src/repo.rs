@@ -42,6 +42,7 @@ use crate::commit::Commit;
 use crate::commit::CommitByCommitterTimestamp;
 use crate::commit_builder::CommitBuilder;
 use crate::commit_builder::DetachedCommitBuilder;
+use crate::config::ConfigGetError;
 use crate::dag_walk;
 use crate::default_index::DefaultIndexStore;
 use crate::default_index::DefaultMutableIndex;
@@ -56,6 +57,8 @@ use crate::index::MutableIndex;
 use crate::index::ReadonlyIndex;
 use crate::local_backend::LocalBackend;
 use crate::merge::MergeBuilder;
+use crate::merge_driver::MergeDrivers;
+use crate::merged_tree::MergedTree;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId;
 use crate::object_id::PrefixResolution;
@@ -74,6 +77,10 @@ use crate::op_store::RemoteRefState;
 use crate::op_store::RootOperationData;
 use crate::op_store::WorkspaceId;
 use crate::operation::Operation;
+use crate::pointer_file::PointerStore;
+use crate::recorded_resolutions::RecordedResolutions;
+use crate::recorded_resolutions::RecordedResolutionsError;
+use crate::recorded_resolutions::ReusedResolution;
 use crate::refs::diff_named_ref_targets;
 use crate::refs::diff_named_remote_refs;
 use crate::refs::merge_ref_targets;
@@ -98,6 +105,7 @@ use crate::submodule_store::SubmoduleStore;
 use crate::transaction::Transaction;
 use crate::view::RenameWorkspaceError;
 use crate::view::View;
+use crate::view::ViewPortion;
 
 pub trait Repo {
     /// Base repository that contains all committed data. Returns `self` if this
@@ -154,6 +162,10 @@ pub enum RepoInitError {
     OpHeadsStore(#[from] OpHeadsStoreError),
     #[error(transparent)]
     Path(#[from] PathError),
+    #[error("Failed to load merge drivers")]
+    MergeDrivers(#[from] ConfigGetError),
+    #[error("Failed to load recorded resolutions settings")]
+    RecordedResolutions(#[source] ConfigGetError),
 }
 
 impl ReadonlyRepo {
@@ -172,7 +184,7 @@ impl ReadonlyRepo {
     }
 
     pub fn default_submodule_store_initializer() -> &'static SubmoduleStoreInitializer<'static> {
-        &|_settings, store_path| Ok(Box::new(DefaultSubmoduleStore::init(store_path)))
+        &|settings, store_path| Ok(Box::new(DefaultSubmoduleStore::init(settings, store_path)))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -193,7 +205,17 @@ impl ReadonlyRepo {
         let backend = backend_initializer(settings, &store_path)?;
         let backend_path = store_path.join("type");
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
-        let store = Store::new(backend, signer);
+        let merge_drivers = MergeDrivers::from_settings(settings)?;
+        let recorded_resolutions = RecordedResolutions::from_settings(settings, &repo_path)
+            .map_err(RepoInitError::RecordedResolutions)?;
+        let pointer_store = PointerStore::new(repo_path.join("pointers"));
+        let store = Store::new(
+            backend,
+            signer,
+            merge_drivers,
+            recorded_resolutions,
+            pointer_store,
+        );
 
         let op_store_path = repo_path.join("op_store");
         fs::create_dir(&op_store_path).context(&op_store_path)?;
@@ -442,7 +464,9 @@ impl Default for StoreFactories {
         // SubmoduleStores
         factories.add_submodule_store(
             DefaultSubmoduleStore::name(),
-            Box::new(|_settings, store_path| Ok(Box::new(DefaultSubmoduleStore::load(store_path)))),
+            Box::new(|settings, store_path| {
+                Ok(Box::new(DefaultSubmoduleStore::load(settings, store_path)))
+            }),
         );
 
         factories
@@ -465,6 +489,10 @@ pub enum StoreLoadError {
     Backend(#[from] BackendLoadError),
     #[error(transparent)]
     Signing(#[from] SignInitError),
+    #[error("Failed to load merge drivers")]
+    MergeDrivers(#[source] ConfigGetError),
+    #[error("Failed to load recorded resolutions settings")]
+    RecordedResolutions(#[source] ConfigGetError),
 }
 
 impl StoreFactories {
@@ -666,6 +694,10 @@ impl RepoLoader {
         let store = Store::new(
             store_factories.load_backend(settings, &repo_path.join("store"))?,
             Signer::from_settings(settings)?,
+            MergeDrivers::from_settings(settings).map_err(StoreLoadError::MergeDrivers)?,
+            RecordedResolutions::from_settings(settings, repo_path)
+                .map_err(StoreLoadError::RecordedResolutions)?,
+            PointerStore::new(repo_path.join("pointers")),
         );
         let root_op_data = RootOperationData {
             root_commit_id: store.root_commit_id().clone(),
@@ -692,6 +724,15 @@ impl RepoLoader {
         })
     }
 
+    /// Returns a copy of this loader which uses `op_heads_store` instead of
+    /// the repo's operation heads.
+    pub fn with_op_heads_store(&self, op_heads_store: Arc<dyn OpHeadsStore>) -> Self {
+        Self {
+            op_heads_store,
+            ..self.clone()
+        }
+    }
+
     pub fn settings(&self) -> &UserSettings {
         &self.settings
     }
@@ -853,6 +894,8 @@ pub struct MutableRepo {
     //   commits. However, if the type is `Abandoned`, a new working-copy commit should be created
     //   on top of all of the new commits instead.
     parent_mapping: HashMap<CommitId, Rewrite>,
+    // Recorded conflict resolutions reused by rewrites in this transaction.
+    reused_resolutions: Vec<ReusedResolution>,
 }
 
 impl MutableRepo {
@@ -868,6 +911,7 @@ impl MutableRepo {
             index: mut_index,
             view: DirtyCell::with_clean(mut_view),
             parent_mapping: Default::default(),
+            reused_resolutions: vec![],
         }
     }
 
@@ -893,6 +937,35 @@ impl MutableRepo {
         (self.index, self.view.into_inner())
     }
 
+    /// Resolves file conflicts in the `tree` by the recorded resolutions, if
+    /// `rerere.enabled` is set. The reused resolutions are logged when the
+    /// transaction is committed.
+    pub fn apply_recorded_resolutions(&mut self, tree: MergedTree) -> BackendResult<MergedTree> {
+        let store = self.store().clone();
+        let Some(resolutions) = store.recorded_resolutions() else {
+            return Ok(tree);
+        };
+        if !tree.has_conflict() {
+            return Ok(tree);
+        }
+        match resolutions.resolve_tree(&tree) {
+            Ok((new_tree, reused)) => {
+                self.reused_resolutions.extend(reused);
+                Ok(new_tree)
+            }
+            Err(RecordedResolutionsError::Backend(err)) => Err(err),
+            Err(RecordedResolutionsError::Path(err)) => {
+                tracing::warn!(?err, "failed to read recorded resolutions");
+                Ok(tree)
+            }
+        }
+    }
+
+    /// Recorded conflict resolutions reused by rewrites in this transaction.
+    pub fn reused_resolutions(&self) -> &[ReusedResolution] {
+        &self.reused_resolutions
+    }
+
     /// Returns a [`CommitBuilder`] to write new commit to the repo.
     pub fn new_commit(&mut self, parents: Vec<CommitId>, tree_id: MergedTreeId) -> CommitBuilder {
         let settings = self.base_repo.settings();
@@ -1659,38 +1732,33 @@ impl MutableRepo {
         Ok(())
     }
 
+    /// Applies the changes from `base_repo` to `other_repo` within the given
+    /// `portion` of the view.
+    ///
+    /// Unlike `merge()`, the other portions of the view, including the heads,
+    /// are left unchanged except that newly referenced commits become visible.
+    pub fn merge_view_portion(
+        &mut self,
+        base_repo: &ReadonlyRepo,
+        other_repo: &ReadonlyRepo,
+        portion: &ViewPortion,
+    ) {
+        self.index.merge_in(base_repo.readonly_index());
+        self.index.merge_in(other_repo.readonly_index());
+
+        let view = self.view.get_mut();
+        let index = self.index.as_index();
+        view.merge_portion(index, &base_repo.view, &other_repo.view, portion);
+        self.view.mark_dirty();
+    }
+
     pub fn merge_index(&mut self, other_repo: &ReadonlyRepo) {
         self.index.merge_in(other_repo.readonly_index());
     }
 
     fn merge_view(&mut self, base: &View, other: &View) -> BackendResult<()> {
         // Merge working-copy commits. If there's a conflict, we keep the self side.
-        for (workspace_id, base_wc_commit) in base.wc_commit_ids() {
-            let self_wc_commit = self.view().get_wc_commit_id(workspace_id);
-            let other_wc_commit = other.get_wc_commit_id(workspace_id);
-            if other_wc_commit == Some(base_wc_commit) || other_wc_commit == self_wc_commit {
-                // The other side didn't change or both sides changed in the
-                // same way.
-            } else if let Some(other_wc_commit) = other_wc_commit {
-                if self_wc_commit == Some(base_wc_commit) {
-                    self.view_mut()
-                        .set_wc_commit(workspace_id.clone(), other_wc_commit.clone());
-                }
-            } else {
-                // The other side removed the workspace. We want to remove it even if the self
-                // side changed the working-copy commit.
-                self.view_mut().remove_wc_commit(workspace_id);
-            }
-        }
-        for (workspace_id, other_wc_commit) in other.wc_commit_ids() {
-            if self.view().get_wc_commit_id(workspace_id).is_none()
-                && base.get_wc_commit_id(workspace_id).is_none()
-            {
-                // The other side added the workspace.
-                self.view_mut()
-                    .set_wc_commit(workspace_id.clone(), other_wc_commit.clone());
-            }
-        }
+        self.view_mut().merge_wc_commit_ids(base, other);
         let base_heads = base.heads().iter().cloned().collect_vec();
         let own_heads = self.view().heads().iter().cloned().collect_vec();
         let other_heads = other.heads().iter().cloned().collect_vec();
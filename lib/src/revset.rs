@@ -65,6 +65,7 @@ pub use crate::revset_parser::RevsetDiagnostics;
 pub use crate::revset_parser::RevsetParseError;
 pub use crate::revset_parser::RevsetParseErrorKind;
 pub use crate::revset_parser::UnaryOp;
+use crate::signing::SigStatus;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::time_util::DatePattern;
@@ -159,6 +160,9 @@ pub enum RevsetFilterPredicate {
     Description(StringPattern),
     /// Commits with first line of the description matching the pattern.
     Subject(StringPattern),
+    /// Commits with description trailer of the `key` whose value matches the
+    /// `value` pattern. The key is compared case-insensitively.
+    Trailer { key: String, value: StringPattern },
     /// Commits with author name matching the pattern.
     AuthorName(StringPattern),
     /// Commits with author email matching the pattern.
@@ -180,10 +184,26 @@ pub enum RevsetFilterPredicate {
     },
     /// Commits with conflicts
     HasConflict,
+    /// Commits with cryptographic signature matching the filter.
+    Signed(SignatureFilter),
     /// Custom predicates provided by extensions
     Extension(Rc<dyn RevsetFilterExtension>),
 }
 
+/// Selects commits by cryptographic signature.
+#[derive(Clone, Debug)]
+pub enum SignatureFilter {
+    /// Any signed commits. The signature isn't verified.
+    Any,
+    /// Commits whose signature was verified with the status.
+    Status(SigStatus),
+    /// Commits whose signature couldn't be verified due to error.
+    Invalid,
+    /// Commits with a good signature whose signing key or signer matches the
+    /// pattern.
+    Key(StringPattern),
+}
+
 mod private {
     /// Defines [`RevsetExpression`] variants depending on resolution state.
     pub trait ExpressionState {
@@ -198,7 +218,7 @@ mod private {
     pub enum ResolvedExpressionState {}
 }
 
-use private::ExpressionState;
+pub(crate) use private::ExpressionState;
 use private::ResolvedExpressionState;
 use private::UserExpressionState;
 
@@ -843,6 +863,17 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         let predicate = RevsetFilterPredicate::Subject(pattern);
         Ok(RevsetExpression::filter(predicate))
     });
+    map.insert("trailers", |diagnostics, function, _context| {
+        let ([key_arg], [value_opt_arg]) = function.expect_arguments()?;
+        let key = expect_literal(diagnostics, "string", key_arg)?;
+        let value = if let Some(value_arg) = value_opt_arg {
+            expect_string_pattern(diagnostics, value_arg)?
+        } else {
+            StringPattern::everything()
+        };
+        let predicate = RevsetFilterPredicate::Trailer { key, value };
+        Ok(RevsetExpression::filter(predicate))
+    });
     map.insert("author", |diagnostics, function, _context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_string_pattern(diagnostics, arg)?;
@@ -973,6 +1004,36 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
     });
     // TODO: Remove in jj 0.28+
     map.insert("conflict", map["conflicts"]);
+    map.insert("signed", |diagnostics, function, _context| {
+        let ([], [status_opt_arg]) = function.expect_arguments()?;
+        let filter = if let Some(status_arg) = status_opt_arg {
+            let status: String = expect_literal(diagnostics, "string", status_arg)?;
+            match status.as_str() {
+                "good" => SignatureFilter::Status(SigStatus::Good),
+                "bad" => SignatureFilter::Status(SigStatus::Bad),
+                "unknown" => SignatureFilter::Status(SigStatus::Unknown),
+                "invalid" => SignatureFilter::Invalid,
+                _ => {
+                    return Err(RevsetParseError::expression(
+                        "Expected signature status: good, bad, unknown, or invalid",
+                        status_arg.span,
+                    ));
+                }
+            }
+        } else {
+            SignatureFilter::Any
+        };
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Signed(
+            filter,
+        )))
+    });
+    map.insert("signed_by", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(diagnostics, arg)?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Signed(
+            SignatureFilter::Key(pattern),
+        )))
+    });
     map.insert("present", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let expression = lower_expression(diagnostics, arg, context)?;
@@ -2671,6 +2732,10 @@ impl<'a> RevsetParseContext<'a> {
     pub fn symbol_resolvers(&self) -> &[impl AsRef<dyn SymbolResolverExtension>] {
         self.extensions.symbol_resolvers()
     }
+
+    pub fn workspace(&self) -> Option<&RevsetWorkspaceContext<'a>> {
+        self.workspace.as_ref()
+    }
 }
 
 /// Workspace information needed to parse revset expression.
@@ -3270,6 +3335,46 @@ mod tests {
         insta::assert_debug_snapshot!(
             parse("description(\"(foo)\")").unwrap(),
             @r###"Filter(Description(Substring("(foo)")))"###);
+        insta::assert_debug_snapshot!(
+            parse("trailers(Reviewed-by)").unwrap(),
+            @r#"
+        Filter(
+            Trailer {
+                key: "Reviewed-by",
+                value: Substring(""),
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse(r#"trailers("Signed-off-by", exact:"foo")"#).unwrap(),
+            @r#"
+        Filter(
+            Trailer {
+                key: "Signed-off-by",
+                value: Exact("foo"),
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("trailers(visible_heads())").unwrap_err().kind(),
+            @r#"Expression("Expected expression of type string")"#);
+        assert!(parse("trailers()").is_err());
+        insta::assert_debug_snapshot!(
+            parse("signed()").unwrap(),
+            @"Filter(Signed(Any))");
+        insta::assert_debug_snapshot!(
+            parse("signed(good)").unwrap(),
+            @"Filter(Signed(Status(Good)))");
+        insta::assert_debug_snapshot!(
+            parse(r#"signed("invalid")"#).unwrap(),
+            @"Filter(Signed(Invalid))");
+        insta::assert_debug_snapshot!(
+            parse("signed(ok)").unwrap_err().kind(),
+            @r#"Expression("Expected signature status: good, bad, unknown, or invalid")"#);
+        insta::assert_debug_snapshot!(
+            parse("signed_by(exact:alice)").unwrap(),
+            @r#"Filter(Signed(Key(Exact("alice"))))"#);
+        assert!(parse("signed_by()").is_err());
         assert!(parse("mine(foo)").is_err());
         insta::assert_debug_snapshot!(
             parse_with_workspace("empty()", &WorkspaceId::default()).unwrap(),
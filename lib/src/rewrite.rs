@@ -252,9 +252,10 @@ impl<'repo> CommitRewriter<'repo> {
             let old_base_tree = merge_commit_trees(self.mut_repo, &old_parents)?;
             let new_base_tree = merge_commit_trees(self.mut_repo, &new_parents)?;
             let old_tree = self.old_commit.tree()?;
+            let new_tree = new_base_tree.merge_with_merge_drivers(&old_base_tree, &old_tree)?;
             (
                 old_base_tree.id() == *self.old_commit.tree_id(),
-                new_base_tree.merge(&old_base_tree, &old_tree)?.id(),
+                self.mut_repo.apply_recorded_resolutions(new_tree)?.id(),
             )
         };
         // Ensure we don't abandon commits with multiple parents (merge commits), even
@@ -1134,9 +1135,10 @@ pub fn squash_commits<'repo>(
     // Apply the selected changes onto the destination
     let mut destination_tree = rewritten_destination.tree()?;
     for source in &source_commits {
-        destination_tree =
-            destination_tree.merge(&source.commit.parent_tree, &source.commit.selected_tree)?;
+        destination_tree = destination_tree
+            .merge_with_merge_drivers(&source.commit.parent_tree, &source.commit.selected_tree)?;
     }
+    let destination_tree = repo.apply_recorded_resolutions(destination_tree)?;
     let mut predecessors = vec![destination.id().clone()];
     predecessors.extend(
         source_commits
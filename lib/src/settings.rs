@@ -61,6 +61,7 @@ pub struct GitSettings {
     pub abandon_unreachable_commits: bool,
     pub subprocess: bool,
     pub executable_path: PathBuf,
+    pub push_atomic: bool,
 }
 
 impl GitSettings {
@@ -70,6 +71,7 @@ impl GitSettings {
             abandon_unreachable_commits: settings.get_bool("git.abandon-unreachable-commits")?,
             subprocess: settings.get_bool("git.subprocess")?,
             executable_path: settings.get("git.executable-path")?,
+            push_atomic: settings.get_bool("git.push-atomic")?,
         })
     }
 }
@@ -81,6 +83,7 @@ impl Default for GitSettings {
             abandon_unreachable_commits: true,
             subprocess: true,
             executable_path: PathBuf::from("git"),
+            push_atomic: false,
         }
     }
 }
@@ -95,6 +98,8 @@ pub struct SignSettings {
     pub user_email: String,
     /// The signing backend specific key, to be passed to the signing backend.
     pub key: Option<String>,
+    /// Whether to sign operations in addition to commits.
+    pub sign_operations: bool,
 }
 
 impl SignSettings {
@@ -108,6 +113,9 @@ impl SignSettings {
             behavior,
             user_email: settings.user_email().to_owned(),
             key: settings.get_string("signing.key").ok(),
+            sign_operations: settings
+                .get_bool("signing.sign-operations")
+                .unwrap_or(false),
         }
     }
 
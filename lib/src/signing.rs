@@ -17,6 +17,7 @@
 
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::hash::Hash;
 use std::sync::Mutex;
 
 use clru::CLruCache;
@@ -25,6 +26,9 @@ use thiserror::Error;
 use crate::backend::CommitId;
 use crate::config::ConfigGetError;
 use crate::gpg_signing::GpgBackend;
+use crate::op_store;
+use crate::op_store::OperationId;
+use crate::settings::SignSettings;
 use crate::settings::UserSettings;
 use crate::ssh_signing::SshBackend;
 use crate::store::COMMIT_CACHE_CAPACITY;
@@ -171,6 +175,7 @@ pub struct Signer {
     /// for ownership reasons.
     backends: Vec<Box<dyn SigningBackend>>,
     cache: Mutex<CLruCache<CommitId, Verification>>,
+    operation_cache: Mutex<CLruCache<OperationId, Verification>>,
 }
 
 impl Signer {
@@ -209,6 +214,7 @@ impl Signer {
             main_backend,
             backends: other_backends,
             cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
+            operation_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
         }
     }
 
@@ -234,11 +240,34 @@ impl Signer {
         data: &[u8],
         signature: &[u8],
     ) -> SignResult<Verification> {
-        let cached = self.cache.lock().unwrap().get(commit_id).cloned();
+        self.verify_cached(&self.cache, commit_id, data, signature)
+    }
+
+    /// Looks for backend that can verify the signature of the operation and
+    /// returns the result of its verification.
+    pub fn verify_operation(
+        &self,
+        operation_id: &OperationId,
+        data: &[u8],
+        signature: &[u8],
+    ) -> SignResult<Verification> {
+        self.verify_cached(&self.operation_cache, operation_id, data, signature)
+    }
+
+    fn verify_cached<K: Clone + Eq + Hash>(
+        &self,
+        cache: &Mutex<CLruCache<K, Verification>>,
+        id: &K,
+        data: &[u8],
+        signature: &[u8],
+    ) -> SignResult<Verification> {
+        let cached = cache.lock().unwrap().get(id).cloned();
         if let Some(check) = cached {
             return Ok(check);
         }
 
+        let mut is_unknown = false;
+        let mut is_invalid_format = false;
         let verification = self
             .main_backend
             .iter()
@@ -247,8 +276,14 @@ impl Signer {
             // skip unknown and invalid sigs to allow other backends that can read to try
             // for example, we might have gpg and sq, both of which could read a PGP signature
             .find_map(|backend| match backend.verify(data, signature) {
-                Ok(check) if check.status == SigStatus::Unknown => None,
-                Err(SignError::InvalidSignatureFormat) => None,
+                Ok(check) if check.status == SigStatus::Unknown => {
+                    is_unknown = true;
+                    None
+                }
+                Err(SignError::InvalidSignatureFormat) => {
+                    is_invalid_format = true;
+                    None
+                }
                 e => Some(e),
             })
             .transpose()?;
@@ -258,22 +293,48 @@ impl Signer {
             // realistically this is unlikely, but technically
             // it's correct to not cache unknowns here
             if verification.status != SigStatus::Unknown {
-                self.cache
-                    .lock()
-                    .unwrap()
-                    .put(commit_id.clone(), verification.clone());
+                cache.lock().unwrap().put(id.clone(), verification.clone());
             }
             Ok(verification)
+        } else if is_invalid_format && !is_unknown {
+            // every backend which could read the signature rejected it
+            Err(SignError::InvalidSignatureFormat)
         } else {
             // now here it's correct to cache unknowns, as we don't
             // have a backend that knows how to handle this signature
             //
             // not sure about how much of an optimization this is
-            self.cache
+            cache
                 .lock()
                 .unwrap()
-                .put(commit_id.clone(), Verification::unknown());
+                .put(id.clone(), Verification::unknown());
             Ok(Verification::unknown())
         }
     }
 }
+
+/// Signs operations with the configured signing backend and key.
+#[derive(Clone, Copy, Debug)]
+pub struct OperationSigner<'a> {
+    signer: &'a Signer,
+    key: Option<&'a str>,
+}
+
+impl<'a> OperationSigner<'a> {
+    /// Returns a signer if operations are configured to be signed and a
+    /// signing backend is available.
+    pub fn new(signer: &'a Signer, settings: &'a SignSettings) -> Option<Self> {
+        (settings.sign_operations && signer.can_sign()).then_some(OperationSigner {
+            signer,
+            key: settings.key.as_deref(),
+        })
+    }
+
+    /// Signs the operation, replacing its existing signature.
+    pub fn sign(&self, operation: &mut op_store::Operation) -> SignResult<()> {
+        operation.signature = None;
+        let signature = self.signer.sign(&operation.signing_data(), self.key)?;
+        operation.signature = Some(signature);
+        Ok(())
+    }
+}
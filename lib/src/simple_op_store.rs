@@ -59,7 +59,9 @@ use crate::op_store::RemoteView;
 use crate::op_store::RootOperationData;
 use crate::op_store::View;
 use crate::op_store::ViewId;
+use crate::op_store::ViewRefName;
 use crate::op_store::WorkspaceId;
+use crate::refs::RemoteRefSymbolBuf;
 
 // BLAKE2b-512 hash length in bytes
 const OPERATION_ID_LENGTH: usize = 64;
@@ -410,6 +412,11 @@ fn operation_metadata_to_proto(
         username: metadata.username.clone(),
         is_snapshot: metadata.is_snapshot,
         tags: metadata.tags.clone(),
+        conflicted_refs: metadata
+            .conflicted_refs
+            .iter()
+            .map(view_ref_name_to_proto)
+            .collect(),
     }
 }
 
@@ -426,13 +433,60 @@ fn operation_metadata_from_proto(
         username: proto.username,
         is_snapshot: proto.is_snapshot,
         tags: proto.tags,
+        conflicted_refs: proto
+            .conflicted_refs
+            .into_iter()
+            .filter_map(view_ref_name_from_proto)
+            .collect(),
     }
 }
 
-fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operation {
+fn view_ref_name_to_proto(name: &ViewRefName) -> crate::protos::op_store::ViewRefName {
+    let value = match name {
+        ViewRefName::LocalBookmark(name) => {
+            crate::protos::op_store::view_ref_name::Value::LocalBookmark(name.clone())
+        }
+        ViewRefName::RemoteBookmark(symbol) => {
+            crate::protos::op_store::view_ref_name::Value::RemoteBookmark(
+                crate::protos::op_store::view_ref_name::RemoteBookmark {
+                    name: symbol.name.clone(),
+                    remote: symbol.remote.clone(),
+                },
+            )
+        }
+        ViewRefName::Tag(name) => crate::protos::op_store::view_ref_name::Value::Tag(name.clone()),
+        ViewRefName::GitRef(name) => {
+            crate::protos::op_store::view_ref_name::Value::GitRef(name.clone())
+        }
+        ViewRefName::GitHead => crate::protos::op_store::view_ref_name::Value::GitHead(true),
+    };
+    crate::protos::op_store::ViewRefName { value: Some(value) }
+}
+
+fn view_ref_name_from_proto(proto: crate::protos::op_store::ViewRefName) -> Option<ViewRefName> {
+    // Unknown ref kinds written by newer jj are ignored.
+    let name = match proto.value? {
+        crate::protos::op_store::view_ref_name::Value::LocalBookmark(name) => {
+            ViewRefName::LocalBookmark(name)
+        }
+        crate::protos::op_store::view_ref_name::Value::RemoteBookmark(symbol) => {
+            ViewRefName::RemoteBookmark(RemoteRefSymbolBuf {
+                name: symbol.name,
+                remote: symbol.remote,
+            })
+        }
+        crate::protos::op_store::view_ref_name::Value::Tag(name) => ViewRefName::Tag(name),
+        crate::protos::op_store::view_ref_name::Value::GitRef(name) => ViewRefName::GitRef(name),
+        crate::protos::op_store::view_ref_name::Value::GitHead(_) => ViewRefName::GitHead,
+    };
+    Some(name)
+}
+
+pub(crate) fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operation {
     let mut proto = crate::protos::op_store::Operation {
         view_id: operation.view_id.as_bytes().to_vec(),
         metadata: Some(operation_metadata_to_proto(&operation.metadata)),
+        signature: operation.signature.clone(),
         ..Default::default()
     };
     for parent in &operation.parents {
@@ -441,7 +495,7 @@ fn operation_to_proto(operation: &Operation) -> crate::protos::op_store::Operati
     proto
 }
 
-fn operation_from_proto(proto: crate::protos::op_store::Operation) -> Operation {
+pub(crate) fn operation_from_proto(proto: crate::protos::op_store::Operation) -> Operation {
     let parents = proto.parents.into_iter().map(OperationId::new).collect();
     let view_id = ViewId::new(proto.view_id);
     let metadata = operation_metadata_from_proto(proto.metadata.unwrap_or_default());
@@ -449,10 +503,11 @@ fn operation_from_proto(proto: crate::protos::op_store::Operation) -> Operation
         view_id,
         parents,
         metadata,
+        signature: proto.signature,
     }
 }
 
-fn view_to_proto(view: &View) -> crate::protos::op_store::View {
+pub(crate) fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     let mut proto = crate::protos::op_store::View {
         ..Default::default()
     };
@@ -487,7 +542,7 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
     proto
 }
 
-fn view_from_proto(proto: crate::protos::op_store::View) -> View {
+pub(crate) fn view_from_proto(proto: crate::protos::op_store::View) -> View {
     let mut view = View::empty();
     // For compatibility with old repos before we had support for multiple working
     // copies
@@ -782,7 +837,9 @@ mod tests {
                     "key1".to_string() => "value1".to_string(),
                     "key2".to_string() => "value2".to_string(),
                 },
+                conflicted_refs: vec![],
             },
+            signature: None,
         }
     }
 
@@ -830,6 +887,26 @@ mod tests {
         assert_eq!(read_operation, operation);
     }
 
+    #[test]
+    fn test_read_write_signed_operation() {
+        let temp_dir = new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data).unwrap();
+        let unsigned_operation = create_operation();
+        let operation = Operation {
+            signature: Some(b"signature".to_vec()),
+            ..unsigned_operation.clone()
+        };
+        let op_id = store.write_operation(&operation).unwrap();
+        let read_operation = store.read_operation(&op_id).unwrap();
+        assert_eq!(read_operation, operation);
+        // The signature is part of the operation ID, but not of the signed data
+        assert_ne!(op_id, store.write_operation(&unsigned_operation).unwrap());
+        assert_eq!(operation.signing_data(), unsigned_operation.signing_data());
+    }
+
     #[test]
     fn test_bookmark_views_legacy_roundtrip() {
         let new_remote_ref = |target: &RefTarget| RemoteRef {
@@ -38,11 +38,15 @@ use crate::backend::MergedTreeId;
 use crate::backend::SigningFn;
 use crate::backend::SymlinkId;
 use crate::backend::TreeId;
+use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::index::Index;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
+use crate::merge_driver::MergeDrivers;
 use crate::merged_tree::MergedTree;
+use crate::pointer_file::PointerStore;
+use crate::recorded_resolutions::RecordedResolutions;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::signing::Signer;
@@ -59,6 +63,9 @@ const TREE_CACHE_CAPACITY: usize = 1000;
 pub struct Store {
     backend: Box<dyn Backend>,
     signer: Signer,
+    merge_drivers: MergeDrivers,
+    recorded_resolutions: Option<RecordedResolutions>,
+    pointer_store: PointerStore,
     commit_cache: Mutex<CLruCache<CommitId, Arc<backend::Commit>>>,
     tree_cache: Mutex<CLruCache<(RepoPathBuf, TreeId), Arc<backend::Tree>>>,
 }
@@ -72,15 +79,28 @@ impl Debug for Store {
 }
 
 impl Store {
-    pub fn new(backend: Box<dyn Backend>, signer: Signer) -> Arc<Self> {
+    pub fn new(
+        backend: Box<dyn Backend>,
+        signer: Signer,
+        merge_drivers: MergeDrivers,
+        recorded_resolutions: Option<RecordedResolutions>,
+        pointer_store: PointerStore,
+    ) -> Arc<Self> {
         Arc::new(Store {
             backend,
             signer,
+            merge_drivers,
+            recorded_resolutions,
+            pointer_store,
             commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
             tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
         })
     }
 
+    pub fn backend_name(&self) -> &str {
+        self.backend.name()
+    }
+
     pub fn backend_impl(&self) -> &dyn Any {
         self.backend.as_any()
     }
@@ -89,6 +109,20 @@ impl Store {
         &self.signer
     }
 
+    pub fn merge_drivers(&self) -> &MergeDrivers {
+        &self.merge_drivers
+    }
+
+    /// Recorded conflict resolutions to reuse, if enabled.
+    pub fn recorded_resolutions(&self) -> Option<&RecordedResolutions> {
+        self.recorded_resolutions.as_ref()
+    }
+
+    /// Store of the working-copy file contents replaced by pointers.
+    pub fn pointer_store(&self) -> &PointerStore {
+        &self.pointer_store
+    }
+
     pub fn get_copy_records(
         &self,
         paths: Option<&[RepoPathBuf]>,
@@ -230,6 +264,10 @@ impl Store {
         Ok(Tree::new(self.clone(), path.to_owned(), tree_id, data))
     }
 
+    pub async fn prefetch_objects(&self, values: &[TreeValue]) -> BackendResult<()> {
+        self.backend.prefetch_objects(values).await
+    }
+
     pub fn read_file(&self, path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
         self.read_file_async(path, id).block_on()
     }
@@ -0,0 +1,597 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checking out and snapshotting Git submodules in working copies.
+//!
+//! A submodule is represented in the tree by a `TreeValue::GitSubmodule`
+//! pointing to a commit in the repository of the submodule, which lives in
+//! the [`SubmoduleStore`]. Like Git, an empty submodule directory means that
+//! the submodule isn't checked out.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read as _;
+use std::path::Path;
+use std::sync::Arc;
+
+use futures::StreamExt as _;
+use pollster::FutureExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::MillisSinceEpoch;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
+use crate::gitignore::GitIgnoreFile;
+use crate::local_working_copy::file_state;
+use crate::local_working_copy::FileState;
+use crate::local_working_copy::FileType;
+use crate::matchers::EverythingMatcher;
+use crate::merge::MergedTreeValue;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::TreeDiffEntry;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathComponent;
+use crate::store::Store;
+use crate::submodule_store::SubmoduleStore;
+use crate::tree::Tree;
+use crate::tree_builder::TreeBuilder;
+use crate::working_copy::CheckoutError;
+use crate::working_copy::SnapshotError;
+
+/// Returns the name of the submodule at `path` as configured in the
+/// `.gitmodules` file of the `tree`. Falls back to the path if the submodule
+/// isn't configured there.
+pub fn submodule_name(tree: &MergedTree, path: &RepoPath) -> String {
+    SubmoduleNames::from_tree(tree).get(path)
+}
+
+/// Names of the submodules configured in the `.gitmodules` file of a tree,
+/// keyed by path. Use this instead of [`submodule_name()`] to look up many
+/// submodules without parsing the file again.
+#[derive(Clone, Debug, Default)]
+pub struct SubmoduleNames {
+    names: HashMap<String, String>,
+}
+
+impl SubmoduleNames {
+    /// Reads the submodule names from the `.gitmodules` file of the `tree`.
+    /// A missing or invalid file configures no names.
+    pub fn from_tree(tree: &MergedTree) -> Self {
+        #[cfg(feature = "git")]
+        if let Ok(submodules) = read_gitmodules(tree) {
+            let names = submodules
+                .into_values()
+                .map(|submodule| (submodule.path, submodule.name))
+                .collect();
+            return SubmoduleNames { names };
+        }
+        #[cfg(not(feature = "git"))]
+        let _ = tree;
+        SubmoduleNames::default()
+    }
+
+    /// Returns the name of the submodule at `path`. Falls back to the path if
+    /// the submodule isn't configured.
+    pub fn get(&self, path: &RepoPath) -> String {
+        match self.names.get(path.as_internal_file_string()) {
+            Some(name) => name.clone(),
+            None => path.as_internal_file_string().to_owned(),
+        }
+    }
+}
+
+/// Reads the submodule configuration from the `.gitmodules` file of the
+/// `tree`. Returns an empty map if there's no such file.
+#[cfg(feature = "git")]
+pub fn read_gitmodules(
+    tree: &MergedTree,
+) -> Result<
+    std::collections::BTreeMap<String, crate::git::SubmoduleConfig>,
+    Box<dyn std::error::Error + Send + Sync>,
+> {
+    let gitmodules_path = RepoPath::from_internal_string(".gitmodules");
+    match tree.path_value(gitmodules_path)?.into_resolved() {
+        Ok(None) => Ok(Default::default()),
+        Ok(Some(TreeValue::File { id, .. })) => {
+            let mut reader = tree.store().read_file(gitmodules_path, &id)?;
+            Ok(crate::git::parse_gitmodules(&mut reader)?)
+        }
+        _ => Err(".gitmodules is not a file".into()),
+    }
+}
+
+/// Returns true if the submodule directory exists and isn't empty.
+pub fn is_submodule_populated(disk_dir: &Path) -> bool {
+    disk_dir
+        .read_dir()
+        .is_ok_and(|mut entries| entries.next().is_some())
+}
+
+/// Writes the files of the submodule commit `new_id` to `disk_dir`.
+///
+/// If the directory is populated with the commit `old_id`, only the files
+/// that differ between the two commits are updated, and files that don't
+/// exist in the new commit are removed. Otherwise, all files are written.
+/// Nothing is written if the commit `new_id` hasn't been fetched.
+///
+/// Files which were modified on disk since `old_id` was checked out, or which
+/// are unknown to `old_id`, are left alone. Returns the number of such
+/// skipped files.
+pub fn check_out_submodule(
+    store: &Arc<Store>,
+    disk_dir: &Path,
+    old_id: Option<&CommitId>,
+    new_id: &CommitId,
+) -> Result<u32, CheckoutError> {
+    let new_tree = match store.get_commit(new_id) {
+        Ok(commit) => commit.tree()?,
+        // The commit hasn't been fetched yet.
+        Err(BackendError::ObjectNotFound { .. }) => return Ok(0),
+        Err(err) => return Err(err.into()),
+    };
+    let old_tree = match old_id {
+        Some(old_id) if is_submodule_populated(disk_dir) => match store.get_commit(old_id) {
+            Ok(commit) => commit.tree()?,
+            Err(BackendError::ObjectNotFound { .. }) => empty_tree(store),
+            Err(err) => return Err(err.into()),
+        },
+        _ => empty_tree(store),
+    };
+    fs::create_dir_all(disk_dir).map_err(|err| CheckoutError::Other {
+        message: format!("Failed to create directory {}", disk_dir.display()),
+        err: err.into(),
+    })?;
+    update_files(store, disk_dir, &old_tree, &new_tree)
+}
+
+/// Removes the files of the submodule commit `id` from `disk_dir`. Untracked
+/// and modified files are left alone. Returns the number of such skipped
+/// files.
+///
+/// Nothing is removed if the commit `id` hasn't been fetched, since the files
+/// in the directory can't be told apart from untracked files.
+pub fn remove_submodule_files(
+    store: &Arc<Store>,
+    disk_dir: &Path,
+    id: &CommitId,
+) -> Result<u32, CheckoutError> {
+    if !is_submodule_populated(disk_dir) {
+        return Ok(0);
+    }
+    let old_tree = match store.get_commit(id) {
+        Ok(commit) => commit.tree()?,
+        Err(BackendError::ObjectNotFound { .. }) => return Ok(0),
+        Err(err) => return Err(err.into()),
+    };
+    update_files(store, disk_dir, &old_tree, &empty_tree(store))
+}
+
+fn empty_tree(store: &Arc<Store>) -> MergedTree {
+    MergedTree::resolved(Tree::empty(store.clone(), RepoPathBuf::root()))
+}
+
+fn update_files(
+    store: &Arc<Store>,
+    disk_dir: &Path,
+    old_tree: &MergedTree,
+    new_tree: &MergedTree,
+) -> Result<u32, CheckoutError> {
+    let diff_entries: Vec<TreeDiffEntry> = old_tree
+        .diff_stream(new_tree, &EverythingMatcher)
+        .collect()
+        .block_on();
+    let mut skipped_files = 0;
+    for TreeDiffEntry { path, values } in diff_entries {
+        let (before, after) = values?;
+        let disk_path = path.to_fs_path(disk_dir)?;
+        if !is_unmodified(store, &path, &disk_path, &before)? {
+            skipped_files += 1;
+            continue;
+        }
+        remove_file(&disk_path)?;
+        match after.into_resolved() {
+            Ok(None) => remove_empty_parent_dirs(disk_dir, &disk_path),
+            Ok(Some(TreeValue::File { id, executable })) => {
+                create_parent_dir(&disk_path)?;
+                let mut reader = store.read_file(&path, &id)?;
+                write_file(&disk_path, &mut reader, executable)?;
+            }
+            Ok(Some(TreeValue::Symlink(id))) => {
+                create_parent_dir(&disk_path)?;
+                let target = store.read_symlink(&path, &id)?;
+                write_symlink(&disk_path, &target)?;
+            }
+            Ok(Some(TreeValue::GitSubmodule(_))) => {
+                // Nested submodules aren't checked out, but the directory is
+                // created to reserve the path.
+                fs::create_dir_all(&disk_path).map_err(|err| CheckoutError::Other {
+                    message: format!("Failed to create directory {}", disk_path.display()),
+                    err: err.into(),
+                })?;
+            }
+            Ok(Some(TreeValue::Tree(_) | TreeValue::Conflict(_))) | Err(_) => {
+                return Err(CheckoutError::Other {
+                    message: format!("Unexpected tree entry at {path:?} in submodule"),
+                    err: "Submodule commits cannot contain conflicts".into(),
+                });
+            }
+        }
+    }
+    Ok(skipped_files)
+}
+
+/// Returns true if the file at `disk_path` is missing or still matches the
+/// `old_value`, so it can be replaced without losing local changes.
+fn is_unmodified(
+    store: &Store,
+    path: &RepoPath,
+    disk_path: &Path,
+    old_value: &MergedTreeValue,
+) -> Result<bool, CheckoutError> {
+    let read_error = |err: io::Error| CheckoutError::Other {
+        message: format!("Failed to read {}", disk_path.display()),
+        err: err.into(),
+    };
+    let metadata = match disk_path.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(err) => return Err(read_error(err)),
+    };
+    let old_content = match old_value.as_resolved() {
+        // Directories are removed only when their last file is removed.
+        Some(None | Some(TreeValue::GitSubmodule(_))) => return Ok(metadata.is_dir()),
+        Some(Some(TreeValue::File { id, .. })) if metadata.is_file() => {
+            let mut content = vec![];
+            store
+                .read_file(path, id)?
+                .read_to_end(&mut content)
+                .map_err(read_error)?;
+            content
+        }
+        Some(Some(TreeValue::Symlink(id))) if metadata.is_symlink() => {
+            let target = fs::read_link(disk_path).map_err(read_error)?;
+            return Ok(target.to_str() == Some(store.read_symlink(path, id)?.as_str()));
+        }
+        // Symlinks are checked out as files if unsupported.
+        Some(Some(TreeValue::Symlink(id))) if metadata.is_file() => {
+            store.read_symlink(path, id)?.into_bytes()
+        }
+        _ => return Ok(false),
+    };
+    let disk_content = fs::read(disk_path).map_err(read_error)?;
+    Ok(disk_content == old_content)
+}
+
+fn remove_file(disk_path: &Path) -> Result<(), CheckoutError> {
+    match fs::remove_file(disk_path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        // The path may be a directory, which will be removed when its last
+        // file is removed.
+        Err(_) if disk_path.symlink_metadata().is_ok_and(|m| m.is_dir()) => Ok(()),
+        Err(err) => Err(CheckoutError::Other {
+            message: format!("Failed to remove file {}", disk_path.display()),
+            err: err.into(),
+        }),
+    }
+}
+
+fn remove_empty_parent_dirs(disk_dir: &Path, disk_path: &Path) {
+    let mut parent_dir = disk_path.parent().unwrap();
+    while parent_dir != disk_dir && fs::remove_dir(parent_dir).is_ok() {
+        parent_dir = parent_dir.parent().unwrap();
+    }
+}
+
+fn create_parent_dir(disk_path: &Path) -> Result<(), CheckoutError> {
+    let parent_dir = disk_path.parent().unwrap();
+    fs::create_dir_all(parent_dir).map_err(|err| CheckoutError::Other {
+        message: format!("Failed to create directory {}", parent_dir.display()),
+        err: err.into(),
+    })
+}
+
+fn write_file(
+    disk_path: &Path,
+    contents: &mut dyn io::Read,
+    executable: bool,
+) -> Result<(), CheckoutError> {
+    let write_error = |err: io::Error| CheckoutError::Other {
+        message: format!("Failed to write file {}", disk_path.display()),
+        err: err.into(),
+    };
+    let mut file = File::create(disk_path).map_err(write_error)?;
+    io::copy(contents, &mut file).map_err(write_error)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        let mode = if executable { 0o755 } else { 0o644 };
+        fs::set_permissions(disk_path, fs::Permissions::from_mode(mode)).map_err(write_error)?;
+    }
+    #[cfg(windows)]
+    let _ = executable;
+    Ok(())
+}
+
+fn write_symlink(disk_path: &Path, target: &str) -> Result<(), CheckoutError> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, disk_path).map_err(|err| CheckoutError::Other {
+            message: format!("Failed to create symlink {}", disk_path.display()),
+            err: err.into(),
+        })
+    }
+    #[cfg(windows)]
+    {
+        write_file(disk_path, &mut target.as_bytes(), false)
+    }
+}
+
+/// States of the files of a checked-out submodule, recorded against the
+/// submodule tree `tree_id`. Files whose state hasn't changed since aren't
+/// read again when the submodule is snapshotted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmoduleFileStates {
+    /// Tree the files were snapshotted as.
+    pub tree_id: TreeId,
+    /// States of the files, keyed by their path in the submodule.
+    pub file_states: BTreeMap<RepoPathBuf, FileState>,
+}
+
+/// Result of [`snapshot_submodule()`].
+#[derive(Clone, Debug)]
+pub struct SubmoduleSnapshot {
+    /// New submodule commit, if the files differ from the old commit.
+    pub new_id: Option<CommitId>,
+    /// States of the snapshotted files.
+    pub file_states: SubmoduleFileStates,
+}
+
+/// Snapshots the files of the submodule `name` checked out in `disk_dir`.
+///
+/// Files whose state matches the `old_file_states` are assumed to be
+/// unchanged. Like the working-copy file states, a recorded state is only
+/// trusted if the file was modified before `own_mtime`, the time the states
+/// were saved.
+///
+/// The new submodule commit on top of `id` is only created if the files
+/// differ from the commit `id`. Returns `None` if the submodule isn't
+/// initialized or checked out.
+pub fn snapshot_submodule(
+    submodule_store: &dyn SubmoduleStore,
+    name: &str,
+    disk_dir: &Path,
+    id: &CommitId,
+    old_file_states: Option<&SubmoduleFileStates>,
+    own_mtime: MillisSinceEpoch,
+) -> Result<Option<SubmoduleSnapshot>, SnapshotError> {
+    let submodule_error = |err: Box<dyn std::error::Error + Send + Sync>| SnapshotError::Other {
+        message: format!("Failed to snapshot submodule {name}"),
+        err,
+    };
+    let Some(store) = submodule_store
+        .get_submodule(name)
+        .map_err(|err| submodule_error(err.into()))?
+    else {
+        return Ok(None);
+    };
+    if !is_submodule_populated(disk_dir) {
+        return Ok(None);
+    }
+    let commit = match store.get_commit(id) {
+        Ok(commit) => commit,
+        // The commit hasn't been fetched, so the directory wasn't populated
+        // by us.
+        Err(BackendError::ObjectNotFound { .. }) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let old_tree = commit.tree()?;
+    let old_tree_id = old_tree.as_merge().as_resolved().map(|tree| tree.id());
+    // The recorded states are only valid for the tree they were recorded
+    // against
+    let old_file_states = old_file_states
+        .filter(|states| Some(&states.tree_id) == old_tree_id)
+        .map(|states| &states.file_states);
+    let mut snapshotter = SubmoduleSnapshotter {
+        store: &store,
+        tree_builder: store.tree_builder(store.empty_tree_id().clone()),
+        old_tree: &old_tree,
+        old_file_states,
+        own_mtime,
+        new_file_states: BTreeMap::new(),
+    };
+    snapshotter.snapshot_dir(RepoPath::root(), disk_dir, GitIgnoreFile::empty())?;
+    let SubmoduleSnapshotter {
+        tree_builder,
+        new_file_states,
+        ..
+    } = snapshotter;
+    let new_tree_id = tree_builder.write_tree()?;
+    let new_id = if old_tree_id == Some(&new_tree_id) {
+        None
+    } else {
+        let new_id = submodule_store
+            .write_submodule_commit(name, id, &new_tree_id)
+            .map_err(|err| submodule_error(err.into()))?;
+        Some(new_id)
+    };
+    Ok(Some(SubmoduleSnapshot {
+        new_id,
+        file_states: SubmoduleFileStates {
+            tree_id: new_tree_id,
+            file_states: new_file_states,
+        },
+    }))
+}
+
+struct SubmoduleSnapshotter<'a> {
+    store: &'a Arc<Store>,
+    tree_builder: TreeBuilder,
+    old_tree: &'a MergedTree,
+    old_file_states: Option<&'a BTreeMap<RepoPathBuf, FileState>>,
+    own_mtime: MillisSinceEpoch,
+    new_file_states: BTreeMap<RepoPathBuf, FileState>,
+}
+
+impl SubmoduleSnapshotter<'_> {
+    fn snapshot_dir(
+        &mut self,
+        dir: &RepoPath,
+        disk_dir: &Path,
+        git_ignore: Arc<GitIgnoreFile>,
+    ) -> Result<(), SnapshotError> {
+        let read_error = |path: &Path, err: io::Error| SnapshotError::Other {
+            message: format!("Failed to read {}", path.display()),
+            err: err.into(),
+        };
+        let git_ignore = git_ignore
+            .chain_with_file(&dir.to_internal_dir_string(), disk_dir.join(".gitignore"))?;
+        let entries = disk_dir
+            .read_dir()
+            .map_err(|err| read_error(disk_dir, err))?;
+        for entry in entries {
+            let entry = entry.map_err(|err| read_error(disk_dir, err))?;
+            let name = entry
+                .file_name()
+                .into_string()
+                .map_err(|path| SnapshotError::InvalidUtf8Path { path })?;
+            if name == ".git" || name == ".jj" {
+                continue;
+            }
+            let path = dir.join(RepoPathComponent::new(&name));
+            let disk_path = entry.path();
+            let file_type = entry
+                .file_type()
+                .map_err(|err| read_error(&disk_path, err))?;
+            let old_value = self.old_tree.path_value(&path)?;
+            if let Some(Some(TreeValue::GitSubmodule(id))) = old_value.as_resolved() {
+                // Keep nested submodules as they are.
+                self.tree_builder
+                    .set(path, TreeValue::GitSubmodule(id.clone()));
+            } else if file_type.is_dir() {
+                if git_ignore.matches(&path.to_internal_dir_string()) {
+                    continue;
+                }
+                self.snapshot_dir(&path, &disk_path, git_ignore.clone())?;
+            } else if old_value.is_absent() && git_ignore.matches(path.as_internal_file_string()) {
+                continue;
+            } else if file_type.is_symlink() || file_type.is_file() {
+                let metadata = entry
+                    .metadata()
+                    .map_err(|err| read_error(&disk_path, err))?;
+                let Some(new_file_state) = file_state(&metadata) else {
+                    continue;
+                };
+                let value = match self.unchanged_value(&path, &old_value, &new_file_state) {
+                    Some(value) => value,
+                    None if file_type.is_symlink() => self.write_symlink(&path, &disk_path)?,
+                    None => self.write_file(&path, &disk_path, &old_value, &new_file_state)?,
+                };
+                self.tree_builder.set(path.clone(), value);
+                self.new_file_states.insert(path, new_file_state);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the old value of the file at `path` if the file appears
+    /// unchanged since its state was recorded.
+    fn unchanged_value(
+        &self,
+        path: &RepoPath,
+        old_value: &MergedTreeValue,
+        new_file_state: &FileState,
+    ) -> Option<TreeValue> {
+        let old_file_state = self.old_file_states?.get(path)?;
+        if !new_file_state.is_clean(old_file_state) || old_file_state.mtime >= self.own_mtime {
+            return None;
+        }
+        match old_value.as_resolved() {
+            Some(Some(value @ (TreeValue::File { .. } | TreeValue::Symlink(_)))) => {
+                Some(value.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn write_symlink(&self, path: &RepoPath, disk_path: &Path) -> Result<TreeValue, SnapshotError> {
+        let target = fs::read_link(disk_path).map_err(|err| SnapshotError::Other {
+            message: format!("Failed to read {}", disk_path.display()),
+            err: err.into(),
+        })?;
+        let target = target.into_os_string().into_string().map_err(|_| {
+            SnapshotError::InvalidUtf8SymlinkTarget {
+                path: disk_path.to_owned(),
+            }
+        })?;
+        let id = self.store.write_symlink(path, &target).block_on()?;
+        Ok(TreeValue::Symlink(id))
+    }
+
+    fn write_file(
+        &self,
+        path: &RepoPath,
+        disk_path: &Path,
+        old_value: &MergedTreeValue,
+        new_file_state: &FileState,
+    ) -> Result<TreeValue, SnapshotError> {
+        #[cfg(unix)]
+        let executable = {
+            let _ = old_value;
+            matches!(
+                new_file_state.file_type,
+                FileType::Normal { executable: true }
+            )
+        };
+        // The executable bit isn't tracked on Windows, so keep the old one.
+        #[cfg(windows)]
+        let executable = {
+            let _ = new_file_state;
+            matches!(
+                old_value.as_resolved(),
+                Some(Some(TreeValue::File {
+                    executable: true,
+                    ..
+                }))
+            )
+        };
+        let mut file = File::open(disk_path).map_err(|err| SnapshotError::Other {
+            message: format!("Failed to read {}", disk_path.display()),
+            err: err.into(),
+        })?;
+        let id = self.store.write_file(path, &mut file).block_on()?;
+        Ok(TreeValue::File { id, executable })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_submodule_populated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let disk_dir = temp_dir.path().join("sub");
+        assert!(!is_submodule_populated(&disk_dir));
+        fs::create_dir(&disk_dir).unwrap();
+        assert!(!is_submodule_populated(&disk_dir));
+        fs::write(disk_dir.join("file"), "").unwrap();
+        assert!(is_submodule_populated(&disk_dir));
+    }
+}
@@ -15,7 +15,59 @@
 #![allow(missing_docs)]
 
 use std::fmt::Debug;
+use std::sync::Arc;
 
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::TreeId;
+use crate::store::Store;
+
+/// Error that may occur while accessing the repositories of submodules.
+#[derive(Debug, Error)]
+pub enum SubmoduleStoreError {
+    #[error("Submodule {name} has not been initialized")]
+    NotInitialized { name: String },
+    #[error("Failed to fetch submodule {name} from {url}")]
+    Fetch {
+        name: String,
+        url: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error("{message}")]
+    Other {
+        message: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+pub type SubmoduleStoreResult<T> = Result<T, SubmoduleStoreError>;
+
+/// Stores the repositories of Git submodules. Submodules are identified by the
+/// names in `.gitmodules`.
 pub trait SubmoduleStore: Send + Sync + Debug {
     fn name(&self) -> &str;
+
+    /// Returns the store of the submodule `name`, or `None` if the submodule
+    /// hasn't been initialized.
+    fn get_submodule(&self, name: &str) -> SubmoduleStoreResult<Option<Arc<Store>>>;
+
+    /// Initializes an empty repository for the submodule `name` unless it
+    /// already exists.
+    fn init_submodule(&self, name: &str) -> SubmoduleStoreResult<Arc<Store>>;
+
+    /// Fetches the branches and tags of the submodule `name` from `url`.
+    fn fetch_submodule(&self, name: &str, url: &str) -> SubmoduleStoreResult<()>;
+
+    /// Creates a commit on top of `parent_id` in the submodule `name`, which
+    /// records the files of the working copy.
+    fn write_submodule_commit(
+        &self,
+        name: &str,
+        parent_id: &CommitId,
+        tree_id: &TreeId,
+    ) -> SubmoduleStoreResult<CommitId>;
 }
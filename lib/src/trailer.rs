@@ -0,0 +1,217 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of `Key: value` trailers at the end of commit descriptions.
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+/// A `Key: value` line in the trailer block of a description.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trailer {
+    /// Trailer key such as `Signed-off-by`.
+    pub key: String,
+    /// Trailer value with the surrounding whitespace trimmed. Continuation
+    /// lines are joined with a space.
+    pub value: String,
+}
+
+impl Trailer {
+    /// Returns true if the trailer has the given key. Keys are compared
+    /// case-insensitively.
+    pub fn has_key(&self, key: &str) -> bool {
+        self.key.eq_ignore_ascii_case(key)
+    }
+}
+
+/// Error that may occur while parsing trailers.
+#[derive(Debug, Error)]
+pub enum TrailerParseError {
+    /// The line is neither `Key: value` nor a continuation line.
+    #[error("Invalid trailer line: {line}")]
+    NonTrailerLine {
+        /// Content of the line.
+        line: String,
+    },
+}
+
+/// Parses the trailers of the `description`.
+///
+/// The trailer block is the last paragraph of the description, which must
+/// consist of `Key: value` lines only. Lines starting with whitespace continue
+/// the value of the previous trailer. The first paragraph is the subject, and
+/// is never parsed as trailers.
+pub fn parse_description_trailers(description: &str) -> Vec<Trailer> {
+    let lines = description.lines().collect_vec();
+    let Some(end) = lines.iter().rposition(|line| !is_blank(line)) else {
+        return vec![];
+    };
+    let Some(start) = lines[..end].iter().rposition(|line| is_blank(line)) else {
+        return vec![];
+    };
+    parse_trailer_lines(&lines[start + 1..=end]).unwrap_or_default()
+}
+
+/// Parses `text` which should consist of trailer lines only.
+pub fn parse_trailers(text: &str) -> Result<Vec<Trailer>, TrailerParseError> {
+    let lines = text.lines().filter(|line| !is_blank(line)).collect_vec();
+    parse_trailer_lines(&lines)
+}
+
+fn parse_trailer_lines(lines: &[&str]) -> Result<Vec<Trailer>, TrailerParseError> {
+    let mut trailers: Vec<Trailer> = vec![];
+    for &line in lines {
+        let non_trailer_line = || TrailerParseError::NonTrailerLine {
+            line: line.to_owned(),
+        };
+        if line.starts_with(char::is_whitespace) {
+            let trailer = trailers.last_mut().ok_or_else(non_trailer_line)?;
+            if !trailer.value.is_empty() {
+                trailer.value.push(' ');
+            }
+            trailer.value.push_str(line.trim());
+            continue;
+        }
+        let (key, value) = line.split_once(':').ok_or_else(non_trailer_line)?;
+        let key = key.trim_end();
+        if !is_valid_key(key) {
+            return Err(non_trailer_line());
+        }
+        trailers.push(Trailer {
+            key: key.to_owned(),
+            value: value.trim().to_owned(),
+        });
+    }
+    Ok(trailers)
+}
+
+/// Adds the `trailers` to the trailer block of the `description`.
+///
+/// Trailers which already exist with the same key and value are skipped, so
+/// adding the same trailers again is a no-op. If the description has no
+/// trailer block, a new paragraph is appended.
+pub fn add_trailers(description: &str, trailers: &[Trailer]) -> String {
+    let mut existing = parse_description_trailers(description);
+    let mut new_lines = String::new();
+    for trailer in trailers {
+        let exists = existing
+            .iter()
+            .any(|t| t.has_key(&trailer.key) && t.value == trailer.value);
+        if !exists {
+            new_lines.push_str(&format!("{}: {}\n", trailer.key, trailer.value));
+            existing.push(trailer.clone());
+        }
+    }
+    if new_lines.is_empty() {
+        return description.to_owned();
+    }
+    let body = description.trim_end();
+    if body.is_empty() {
+        // Leave the subject line empty so the trailers won't be parsed as a
+        // subject.
+        format!("\n{new_lines}")
+    } else if parse_description_trailers(description).is_empty() {
+        format!("{body}\n\n{new_lines}")
+    } else {
+        format!("{body}\n{new_lines}")
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trailer(key: &str, value: &str) -> Trailer {
+        Trailer {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_parse_description_trailers() {
+        assert_eq!(parse_description_trailers(""), vec![]);
+        assert_eq!(parse_description_trailers("Key: value\n"), vec![]);
+        assert_eq!(
+            parse_description_trailers("subject\n\nbody\n\nA: 1\nb-c:  2 \n\n"),
+            vec![trailer("A", "1"), trailer("b-c", "2")]
+        );
+        // Continuation lines
+        assert_eq!(
+            parse_description_trailers("subject\n\nA: 1\n  2\n\t3\nB:\n 4\n"),
+            vec![trailer("A", "1 2 3"), trailer("B", "4")]
+        );
+        // Empty subject
+        assert_eq!(
+            parse_description_trailers("\nA: 1\n"),
+            vec![trailer("A", "1")]
+        );
+        // Not all lines are trailers
+        assert_eq!(
+            parse_description_trailers("subject\n\nA: 1\nnot a trailer\n"),
+            vec![]
+        );
+        assert_eq!(parse_description_trailers("subject\n\nA B: 1\n"), vec![]);
+        assert_eq!(parse_description_trailers("subject\n\n 1\nA: 1\n"), vec![]);
+        // Only the last paragraph is parsed
+        assert_eq!(
+            parse_description_trailers("subject\n\nA: 1\n\nbody\n"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_parse_trailers() {
+        assert_eq!(
+            parse_trailers("A: 1\n\nB: 2").unwrap(),
+            vec![trailer("A", "1"), trailer("B", "2")]
+        );
+        assert!(parse_trailers("A 1").is_err());
+        assert!(parse_trailers(": 1").is_err());
+    }
+
+    #[test]
+    fn test_add_trailers() {
+        let trailers = [trailer("A", "1"), trailer("B", "2")];
+        assert_eq!(add_trailers("", &trailers), "\nA: 1\nB: 2\n");
+        assert_eq!(
+            add_trailers("subject\n", &trailers),
+            "subject\n\nA: 1\nB: 2\n"
+        );
+        assert_eq!(
+            add_trailers("subject\n\nB: 2\nC: 3\n", &trailers),
+            "subject\n\nB: 2\nC: 3\nA: 1\n"
+        );
+        // Existing trailers are compared by case-insensitive key and value
+        assert_eq!(
+            add_trailers("subject\n\na: 1\nb: 3\n", &trailers),
+            "subject\n\na: 1\nb: 3\nB: 2\n"
+        );
+        // Adding the same trailers again is a no-op
+        let description = add_trailers("subject\n\nbody\n", &trailers);
+        assert_eq!(add_trailers(&description, &trailers), description);
+        assert_eq!(
+            add_trailers("subject", &[trailer("A", "1"), trailer("A", "1")]),
+            "subject\n\nA: 1\n"
+        );
+    }
+}
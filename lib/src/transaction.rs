@@ -24,6 +24,7 @@ use crate::index::ReadonlyIndex;
 use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store;
+use crate::op_store::OpStoreError;
 use crate::op_store::OperationMetadata;
 use crate::operation::Operation;
 use crate::repo::MutableRepo;
@@ -31,7 +32,10 @@ use crate::repo::ReadonlyRepo;
 use crate::repo::Repo;
 use crate::repo::RepoLoader;
 use crate::repo::RepoLoaderError;
+use crate::settings::SignSettings;
 use crate::settings::UserSettings;
+use crate::signing::OperationSigner;
+use crate::signing::SignError;
 use crate::view::View;
 
 /// An in-memory representation of a repo and any changes being made to it.
@@ -50,6 +54,7 @@ pub struct Transaction {
     parent_ops: Vec<Operation>,
     op_metadata: OperationMetadata,
     end_time: Option<Timestamp>,
+    sign_settings: SignSettings,
 }
 
 impl Transaction {
@@ -57,11 +62,13 @@ impl Transaction {
         let parent_ops = vec![mut_repo.base_repo().operation().clone()];
         let op_metadata = create_op_metadata(user_settings, "".to_string(), false);
         let end_time = user_settings.operation_timestamp();
+        let sign_settings = user_settings.sign_settings();
         Transaction {
             mut_repo,
             parent_ops,
             op_metadata,
             end_time,
+            sign_settings,
         }
     }
 
@@ -95,6 +102,18 @@ impl Transaction {
         self.parent_ops.push(other_op);
         let merged_repo = self.repo_mut();
         merged_repo.merge(&base_repo, &other_repo)?;
+        self.record_conflicted_refs()?;
+        Ok(())
+    }
+
+    /// Records the refs that became conflicted by merging the parent
+    /// operations so users can tell why they are conflicted.
+    fn record_conflicted_refs(&mut self) -> Result<(), OpStoreError> {
+        let parent_views: Vec<_> = self.parent_ops.iter().map(|op| op.view()).try_collect()?;
+        self.op_metadata.conflicted_refs = self
+            .mut_repo
+            .view()
+            .new_ref_conflicts(&parent_views.iter().collect_vec());
         Ok(())
     }
 
@@ -121,17 +140,35 @@ impl Transaction {
             "BUG: Descendants have not been rebased after the last rewrites."
         );
         let base_repo = mut_repo.base_repo().clone();
+        if let Some(resolutions) = base_repo.store().recorded_resolutions() {
+            // The log is informational, so the operation is recorded anyway
+            if let Err(err) = resolutions.log_reused(mut_repo.reused_resolutions()) {
+                tracing::warn!(?err, "failed to log reused conflict resolutions");
+            }
+        }
         let (mut_index, view) = mut_repo.consume();
 
         let view_id = base_repo.op_store().write_view(view.store_view()).unwrap();
         self.op_metadata.description = description.into();
         self.op_metadata.end_time = self.end_time.unwrap_or_else(Timestamp::now);
         let parents = self.parent_ops.iter().map(|op| op.id().clone()).collect();
-        let store_operation = op_store::Operation {
+        let mut store_operation = op_store::Operation {
             view_id,
             parents,
             metadata: self.op_metadata,
+            signature: None,
         };
+        let mut sign_error = None;
+        if let Some(signer) = OperationSigner::new(base_repo.store().signer(), &self.sign_settings)
+        {
+            // The operation has to be recorded anyway, so the error is
+            // returned to the caller to report. Unsigned operations are also
+            // reported by `jj op verify`.
+            if let Err(err) = signer.sign(&mut store_operation) {
+                tracing::warn!(?err, "failed to sign operation");
+                sign_error = Some(err);
+            }
+        }
         let new_op_id = base_repo
             .op_store()
             .write_operation(&store_operation)
@@ -142,7 +179,7 @@ impl Transaction {
             .index_store()
             .write_index(mut_index, &operation)
             .unwrap();
-        UnpublishedOperation::new(base_repo.loader(), operation, view, index)
+        UnpublishedOperation::new(base_repo.loader(), operation, view, index, sign_error)
     }
 }
 
@@ -165,6 +202,7 @@ pub fn create_op_metadata(
         username,
         is_snapshot,
         tags: Default::default(),
+        conflicted_refs: vec![],
     }
 }
 
@@ -180,6 +218,7 @@ pub fn create_op_metadata(
 pub struct UnpublishedOperation {
     op_heads_store: Arc<dyn OpHeadsStore>,
     repo: Arc<ReadonlyRepo>,
+    sign_error: Option<SignError>,
 }
 
 impl UnpublishedOperation {
@@ -188,10 +227,12 @@ impl UnpublishedOperation {
         operation: Operation,
         view: View,
         index: Box<dyn ReadonlyIndex>,
+        sign_error: Option<SignError>,
     ) -> Self {
         UnpublishedOperation {
             op_heads_store: repo_loader.op_heads_store().clone(),
             repo: repo_loader.create_from(operation, view, index),
+            sign_error,
         }
     }
 
@@ -199,6 +240,12 @@ impl UnpublishedOperation {
         self.repo.operation()
     }
 
+    /// The error if the operation was configured to be signed but couldn't
+    /// be. The operation is recorded unsigned in that case.
+    pub fn sign_error(&self) -> Option<&SignError> {
+        self.sign_error.as_ref()
+    }
+
     pub fn publish(self) -> Result<Arc<ReadonlyRepo>, OpHeadsStoreError> {
         let _lock = self.op_heads_store.lock()?;
         self.op_heads_store
@@ -398,7 +398,7 @@ fn merge_tree_value(
                 Err(conflict) => {
                     let conflict_borrowed = conflict.map(|value| value.as_ref());
                     if let Some(tree_value) =
-                        try_resolve_file_conflict(store, &filename, &conflict_borrowed)
+                        try_resolve_file_conflict(store, &filename, &conflict_borrowed, false)
                             .block_on()?
                     {
                         Some(tree_value)
@@ -412,7 +412,9 @@ fn merge_tree_value(
     })
 }
 
-/// Resolves file-level conflict by merging content hunks.
+/// Resolves file-level conflict by merging content hunks. If
+/// `use_merge_drivers` is true, the merge driver configured for the file is
+/// tried first.
 ///
 /// The input `conflict` is supposed to be simplified. It shouldn't contain
 /// non-file values that cancel each other.
@@ -420,6 +422,7 @@ pub async fn try_resolve_file_conflict(
     store: &Store,
     filename: &RepoPath,
     conflict: &MergedTreeVal<'_>,
+    use_merge_drivers: bool,
 ) -> BackendResult<Option<TreeValue>> {
     // If there are any non-file or any missing parts in the conflict, we can't
     // merge it. We check early so we don't waste time reading file contents if
@@ -471,14 +474,19 @@ pub async fn try_resolve_file_conflict(
         BackendResult::Ok(content)
     });
     let contents = Merge::from_vec(try_join_all(content_futures).await?);
-    let merge_result = files::merge(&contents);
-    match merge_result {
-        MergeResult::Resolved(merged_content) => {
-            let id = store
-                .write_file(filename, &mut merged_content.as_slice())
-                .await?;
-            Ok(Some(TreeValue::File { id, executable }))
+    let driver_content = use_merge_drivers
+        .then(|| store.merge_drivers().merge(filename, &contents))
+        .flatten();
+    let merged_content = if let Some(merged_content) = driver_content {
+        merged_content
+    } else {
+        match files::merge(&contents) {
+            MergeResult::Resolved(merged_content) => merged_content.into(),
+            MergeResult::Conflict(_) => return Ok(None),
         }
-        MergeResult::Conflict(_) => Ok(None),
-    }
+    };
+    let id = store
+        .write_file(filename, &mut merged_content.as_slice())
+        .await?;
+    Ok(Some(TreeValue::File { id, executable }))
 }
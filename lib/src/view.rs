@@ -22,11 +22,13 @@ use itertools::Itertools;
 use thiserror::Error;
 
 use crate::backend::CommitId;
+use crate::index::Index;
 use crate::op_store;
 use crate::op_store::BookmarkTarget;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt as _;
 use crate::op_store::RemoteRef;
+use crate::op_store::ViewRefName;
 use crate::op_store::WorkspaceId;
 use crate::refs;
 use crate::refs::LocalAndRemoteRef;
@@ -396,6 +398,139 @@ impl View {
         )
     }
 
+    /// Merges working-copy commits changed from `base` to `other`. If there's
+    /// a conflict, the self side is kept.
+    pub(crate) fn merge_wc_commit_ids(&mut self, base: &View, other: &View) {
+        for (workspace_id, base_wc_commit) in base.wc_commit_ids() {
+            let self_wc_commit = self.get_wc_commit_id(workspace_id);
+            let other_wc_commit = other.get_wc_commit_id(workspace_id);
+            if other_wc_commit == Some(base_wc_commit) || other_wc_commit == self_wc_commit {
+                // The other side didn't change or both sides changed in the
+                // same way.
+            } else if let Some(other_wc_commit) = other_wc_commit {
+                if self_wc_commit == Some(base_wc_commit) {
+                    self.set_wc_commit(workspace_id.clone(), other_wc_commit.clone());
+                }
+            } else {
+                // The other side removed the workspace. We want to remove it even if the self
+                // side changed the working-copy commit.
+                self.remove_wc_commit(workspace_id);
+            }
+        }
+        for (workspace_id, other_wc_commit) in other.wc_commit_ids() {
+            if self.get_wc_commit_id(workspace_id).is_none()
+                && base.get_wc_commit_id(workspace_id).is_none()
+            {
+                // The other side added the workspace.
+                self.set_wc_commit(workspace_id.clone(), other_wc_commit.clone());
+            }
+        }
+    }
+
+    /// Applies the changes from `base` to `other` within the given `portion`
+    /// of the view. Conflicting ref changes are recorded as conflicted refs.
+    ///
+    /// Commits newly referenced by the local bookmarks or the working copies
+    /// are added to the heads. The caller should enforce the view invariants
+    /// afterwards.
+    pub fn merge_portion(
+        &mut self,
+        index: &dyn Index,
+        base: &View,
+        other: &View,
+        portion: &ViewPortion,
+    ) {
+        match portion {
+            ViewPortion::Bookmarks(pattern) => {
+                let changed_bookmarks =
+                    refs::diff_named_ref_targets(base.local_bookmarks(), other.local_bookmarks())
+                        .filter(|(name, _)| pattern.matches(name))
+                        .map(|(name, (base_target, other_target))| {
+                            (name.to_owned(), base_target.clone(), other_target.clone())
+                        })
+                        .collect_vec();
+                for (name, base_target, other_target) in changed_bookmarks {
+                    let self_target = self.get_local_bookmark(&name);
+                    let new_target =
+                        refs::merge_ref_targets(index, self_target, &base_target, &other_target);
+                    for id in new_target.added_ids() {
+                        self.add_head(id);
+                    }
+                    self.set_local_bookmark_target(&name, new_target);
+                }
+            }
+            ViewPortion::RemoteTracking => {
+                let changed_remote_bookmarks = refs::diff_named_remote_refs(
+                    base.all_remote_bookmarks(),
+                    other.all_remote_bookmarks(),
+                );
+                for (symbol, (base_ref, other_ref)) in changed_remote_bookmarks {
+                    let self_ref = self.get_remote_bookmark(symbol);
+                    let new_ref = refs::merge_remote_refs(index, self_ref, base_ref, other_ref);
+                    self.set_remote_bookmark(symbol, new_ref);
+                }
+            }
+            ViewPortion::WorkingCopies => {
+                self.merge_wc_commit_ids(base, other);
+                let wc_commit_ids = self.wc_commit_ids().values().cloned().collect_vec();
+                for id in &wc_commit_ids {
+                    self.add_head(id);
+                }
+            }
+        }
+    }
+
+    /// Returns the target of the given ref.
+    pub fn get_ref_target(&self, name: &ViewRefName) -> &RefTarget {
+        match name {
+            ViewRefName::LocalBookmark(name) => self.get_local_bookmark(name),
+            ViewRefName::RemoteBookmark(symbol) => {
+                &self.get_remote_bookmark(symbol.as_ref()).target
+            }
+            ViewRefName::Tag(name) => self.get_tag(name),
+            ViewRefName::GitRef(name) => self.get_git_ref(name),
+            ViewRefName::GitHead => self.git_head(),
+        }
+    }
+
+    /// Iterates all refs and their targets.
+    fn all_ref_targets(&self) -> impl Iterator<Item = (ViewRefName, &RefTarget)> {
+        itertools::chain!(
+            self.local_bookmarks()
+                .map(|(name, target)| (ViewRefName::LocalBookmark(name.to_owned()), target)),
+            self.all_remote_bookmarks().map(|(symbol, remote_ref)| {
+                (
+                    ViewRefName::RemoteBookmark(symbol.to_owned()),
+                    &remote_ref.target,
+                )
+            }),
+            self.tags()
+                .iter()
+                .map(|(name, target)| (ViewRefName::Tag(name.clone()), target)),
+            self.git_refs()
+                .iter()
+                .map(|(name, target)| (ViewRefName::GitRef(name.clone()), target)),
+            [(ViewRefName::GitHead, self.git_head())],
+        )
+    }
+
+    /// Returns the refs which are conflicted in this view but not in any of
+    /// the `parent_views`.
+    ///
+    /// This can be used to find the conflicts introduced by merging
+    /// concurrent operations.
+    pub fn new_ref_conflicts(&self, parent_views: &[&View]) -> Vec<ViewRefName> {
+        self.all_ref_targets()
+            .filter(|(_, target)| target.has_conflict())
+            .map(|(name, _)| name)
+            .filter(|name| {
+                parent_views
+                    .iter()
+                    .all(|view| !view.get_ref_target(name).has_conflict())
+            })
+            .collect()
+    }
+
     pub fn set_view(&mut self, data: op_store::View) {
         self.data = data;
     }
@@ -409,6 +544,17 @@ impl View {
     }
 }
 
+/// Portion of the view to be merged by [`View::merge_portion()`].
+#[derive(Clone, Debug)]
+pub enum ViewPortion {
+    /// Local bookmarks matching the pattern.
+    Bookmarks(StringPattern),
+    /// Remote-tracking bookmarks of all remotes.
+    RemoteTracking,
+    /// Working-copy commits of all workspaces.
+    WorkingCopies,
+}
+
 /// Error from attempts to rename a workspace
 #[derive(Debug, Error)]
 pub enum RenameWorkspaceError {
@@ -28,8 +28,10 @@ use tracing::instrument;
 use crate::backend::BackendError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
+use crate::conflicts::ConflictMarkerOverride;
 use crate::conflicts::ConflictMarkerStyle;
 use crate::dag_walk;
+use crate::fileset::FilesetExpression;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::gitignore::GitIgnoreError;
 use crate::gitignore::GitIgnoreFile;
@@ -47,6 +49,7 @@ use crate::repo_path::InvalidRepoPathError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::store::Store;
+use crate::submodule_store::SubmoduleStore;
 
 /// The trait all working-copy implementations must implement.
 pub trait WorkingCopy: Send {
@@ -221,6 +224,14 @@ pub struct SnapshotOptions<'a> {
     pub max_new_file_size: u64,
     /// Expected conflict marker style for checking for changed files.
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// Per-path overrides of the conflict marker style.
+    pub conflict_marker_overrides: Vec<ConflictMarkerOverride>,
+    /// Store of the Git submodules. If set, changes to checked-out submodules
+    /// are recorded as new submodule commits.
+    pub submodule_store: Option<Arc<dyn SubmoduleStore>>,
+    /// Policies deciding how to snapshot matching files. The first policy
+    /// that applies to a file wins.
+    pub file_policies: Vec<SnapshotFilePolicy>,
 }
 
 impl SnapshotOptions<'_> {
@@ -233,10 +244,41 @@ impl SnapshotOptions<'_> {
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
             conflict_marker_style: ConflictMarkerStyle::default(),
+            conflict_marker_overrides: vec![],
+            submodule_store: None,
+            file_policies: vec![],
         }
     }
 }
 
+/// Policy deciding how to snapshot the files matching it.
+#[derive(Clone, Debug)]
+pub struct SnapshotFilePolicy {
+    /// Name of the policy, used when reporting affected files.
+    pub name: String,
+    /// Files the policy may apply to.
+    pub paths: FilesetExpression,
+    /// If set, the policy only applies to files at least this large.
+    pub min_size: Option<u64>,
+    /// If true, the policy only applies to files that look binary.
+    pub binary_only: bool,
+    /// What to do with the files the policy applies to.
+    pub action: SnapshotFileAction,
+}
+
+/// What to do with a file when snapshotting it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SnapshotFileAction {
+    /// Leave new files untracked.
+    Skip,
+    /// Leave new files untracked until they are explicitly tracked.
+    Manual,
+    /// Track the file, but replace its content in the tree with a pointer to
+    /// the content in a local store.
+    Pointer,
+}
+
 /// A callback for getting progress updates.
 pub type SnapshotProgress<'a> = dyn Fn(&RepoPath) + 'a + Sync;
 
@@ -259,6 +301,16 @@ pub enum UntrackedReason {
     },
     /// File does not match the fileset specified in snapshot.auto-track.
     FileNotAutoTracked,
+    /// File was skipped by a snapshot policy.
+    FileSkippedByPolicy {
+        /// Name of the policy.
+        policy: String,
+    },
+    /// File has to be tracked explicitly as required by a snapshot policy.
+    FileRequiresExplicitTracking {
+        /// Name of the policy.
+        policy: String,
+    },
 }
 
 /// Options used when checking out a tree in the working copy.
@@ -266,6 +318,14 @@ pub enum UntrackedReason {
 pub struct CheckoutOptions {
     /// Conflict marker style to use when materializing files
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// Per-path overrides of the conflict marker style and length.
+    pub conflict_marker_overrides: Vec<ConflictMarkerOverride>,
+    /// Store of the Git submodules. If set, submodule commits available in
+    /// the store are checked out.
+    pub submodule_store: Option<Arc<dyn SubmoduleStore>>,
+    /// Files that may be stored as pointers. Pointer files matching them are
+    /// replaced with the content in the pointer store.
+    pub pointer_paths: FilesetExpression,
 }
 
 impl CheckoutOptions {
@@ -273,6 +333,9 @@ impl CheckoutOptions {
     pub fn empty_for_test() -> Self {
         CheckoutOptions {
             conflict_marker_style: ConflictMarkerStyle::default(),
+            conflict_marker_overrides: vec![],
+            submodule_store: None,
+            pointer_paths: FilesetExpression::none(),
         }
     }
 }
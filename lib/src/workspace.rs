@@ -28,11 +28,13 @@ use thiserror::Error;
 use crate::backend::BackendInitError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
+use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::local_backend::LocalBackend;
 use crate::local_working_copy::LocalWorkingCopy;
 use crate::local_working_copy::LocalWorkingCopyFactory;
+use crate::op_heads_store::OpHeadsStore as _;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store::OperationId;
 use crate::op_store::WorkspaceId;
@@ -46,12 +48,14 @@ use crate::repo::ReadonlyRepo;
 use crate::repo::Repo;
 use crate::repo::RepoInitError;
 use crate::repo::RepoLoader;
+use crate::repo::RepoLoaderError;
 use crate::repo::StoreFactories;
 use crate::repo::StoreLoadError;
 use crate::repo::SubmoduleStoreInitializer;
 use crate::settings::UserSettings;
 use crate::signing::SignInitError;
 use crate::signing::Signer;
+use crate::simple_op_heads_store::SimpleOpHeadsStore;
 use crate::store::Store;
 use crate::working_copy::CheckoutError;
 use crate::working_copy::CheckoutOptions;
@@ -76,9 +80,15 @@ pub enum WorkspaceInitError {
     #[error(transparent)]
     OpHeadsStore(#[from] OpHeadsStoreError),
     #[error(transparent)]
+    RepoLoader(#[from] RepoLoaderError),
+    #[error(transparent)]
     Backend(#[from] BackendInitError),
     #[error(transparent)]
     SignInit(#[from] SignInitError),
+    #[error("Failed to load merge drivers")]
+    MergeDrivers(#[source] ConfigGetError),
+    #[error("Failed to load recorded resolutions settings")]
+    RecordedResolutions(#[source] ConfigGetError),
 }
 
 #[derive(Error, Debug)]
@@ -307,6 +317,10 @@ impl Workspace {
                 RepoInitError::Backend(err) => WorkspaceInitError::Backend(err),
                 RepoInitError::OpHeadsStore(err) => WorkspaceInitError::OpHeadsStore(err),
                 RepoInitError::Path(err) => WorkspaceInitError::Path(err),
+                RepoInitError::MergeDrivers(err) => WorkspaceInitError::MergeDrivers(err),
+                RepoInitError::RecordedResolutions(err) => {
+                    WorkspaceInitError::RecordedResolutions(err)
+                }
             })?;
             let (working_copy, repo) = init_working_copy(
                 &repo,
@@ -352,7 +366,57 @@ impl Workspace {
         workspace_id: WorkspaceId,
     ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
         let jj_dir = create_jj_dir(workspace_root)?;
+        Self::init_with_existing_repo(
+            workspace_root,
+            &jj_dir,
+            repo_path,
+            repo,
+            working_copy_factory,
+            workspace_id,
+        )
+    }
+
+    /// Initializes a sandbox workspace at `repo`'s operation.
+    ///
+    /// The sandbox keeps its own operation heads in `.jj/op_heads/`, so the
+    /// operations made in it aren't visible to the other workspaces until
+    /// they're merged with [`Workspace::merge_sandbox_op_heads()`].
+    pub fn init_sandbox_with_existing_repo(
+        workspace_root: &Path,
+        repo_path: &Path,
+        repo: &Arc<ReadonlyRepo>,
+        working_copy_factory: &dyn WorkingCopyFactory,
+        workspace_id: WorkspaceId,
+    ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
+        let jj_dir = create_jj_dir(workspace_root)?;
+        let op_heads_path = jj_dir.join("op_heads");
+        fs::create_dir(&op_heads_path).context(&op_heads_path)?;
+        let op_heads_store = SimpleOpHeadsStore::init(&op_heads_path).map_err(|err| err.0)?;
+        let op_heads_type_path = op_heads_path.join("type");
+        fs::write(&op_heads_type_path, SimpleOpHeadsStore::name()).context(&op_heads_type_path)?;
+        op_heads_store.update_op_heads(&[], repo.op_id())?;
+        let repo = repo
+            .loader()
+            .with_op_heads_store(Arc::new(op_heads_store))
+            .load_at(repo.operation())?;
+        Self::init_with_existing_repo(
+            workspace_root,
+            &jj_dir,
+            repo_path,
+            &repo,
+            working_copy_factory,
+            workspace_id,
+        )
+    }
 
+    fn init_with_existing_repo(
+        workspace_root: &Path,
+        jj_dir: &Path,
+        repo_path: &Path,
+        repo: &Arc<ReadonlyRepo>,
+        working_copy_factory: &dyn WorkingCopyFactory,
+        workspace_id: WorkspaceId,
+    ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
         let repo_dir = dunce::canonicalize(repo_path).context(repo_path)?;
         let repo_file_path = jj_dir.join("repo");
         let mut repo_file = File::create(&repo_file_path).context(&repo_file_path)?;
@@ -368,7 +432,7 @@ impl Workspace {
         let (working_copy, repo) = init_working_copy(
             repo,
             workspace_root,
-            &jj_dir,
+            jj_dir,
             working_copy_factory,
             workspace_id,
         )?;
@@ -408,6 +472,25 @@ impl Workspace {
         &self.repo_loader
     }
 
+    /// Returns true if this workspace keeps its own operation heads.
+    pub fn is_sandbox(&self) -> bool {
+        self.workspace_root.join(".jj").join("op_heads").is_dir()
+    }
+
+    /// Adds the operation heads of this sandbox workspace to the heads of
+    /// `repo_loader`, so they are merged like concurrent operations the next
+    /// time the repo is loaded there.
+    pub fn merge_sandbox_op_heads(
+        &self,
+        repo_loader: &RepoLoader,
+    ) -> Result<Vec<OperationId>, OpHeadsStoreError> {
+        let op_heads = self.repo_loader.op_heads_store().get_op_heads()?;
+        for op_head in &op_heads {
+            repo_loader.op_heads_store().update_op_heads(&[], op_head)?;
+        }
+        Ok(op_heads)
+    }
+
     /// Settings for this workspace.
     pub fn settings(&self) -> &UserSettings {
         self.repo_loader.settings()
@@ -593,8 +676,14 @@ impl WorkspaceLoader for DefaultWorkspaceLoader {
         store_factories: &StoreFactories,
         working_copy_factories: &WorkingCopyFactories,
     ) -> Result<Workspace, WorkspaceLoadError> {
-        let repo_loader =
+        let mut repo_loader =
             RepoLoader::init_from_file_system(user_settings, &self.repo_path, store_factories)?;
+        let op_heads_path = self.workspace_root.join(".jj").join("op_heads");
+        if op_heads_path.is_dir() {
+            let op_heads_store =
+                store_factories.load_op_heads_store(user_settings, &op_heads_path)?;
+            repo_loader = repo_loader.with_op_heads_store(Arc::from(op_heads_store));
+        }
         let working_copy_factory = get_working_copy_factory(self, working_copy_factories)?;
         let working_copy = self.load_working_copy(repo_loader.store(), working_copy_factory)?;
         let workspace = Workspace::new(
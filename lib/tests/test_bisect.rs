@@ -0,0 +1,235 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_matches::assert_matches;
+use jj_lib::bisect::BisectionError;
+use jj_lib::bisect::BisectionState;
+use jj_lib::bisect::BisectionStateError;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Evaluation;
+use jj_lib::commit::Commit;
+use testutils::new_temp_dir;
+use testutils::CommitGraphBuilder;
+use testutils::TestRepo;
+
+fn mark(state: &mut BisectionState, commit: &Commit, evaluation: Evaluation) {
+    state.mark(commit.id().clone(), evaluation);
+}
+
+#[test]
+fn test_bisect_linear() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // F
+    // E
+    // D
+    // C
+    // B
+    // A
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d = graph_builder.commit_with_parents(&[&commit_c]);
+    let commit_e = graph_builder.commit_with_parents(&[&commit_d]);
+    let commit_f = graph_builder.commit_with_parents(&[&commit_e]);
+    let repo = tx.commit("test").unwrap();
+
+    // Nothing to bisect without bad commits
+    let mut state = BisectionState::new();
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Found(vec![])
+    );
+
+    // The root commit is assumed to be good
+    mark(&mut state, &commit_f, Evaluation::Bad);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Evaluate {
+            commit_id: commit_c.id().clone(),
+            num_untested: 5,
+        }
+    );
+
+    mark(&mut state, &commit_c, Evaluation::Good);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Evaluate {
+            commit_id: commit_d.id().clone(),
+            num_untested: 2,
+        }
+    );
+
+    mark(&mut state, &commit_d, Evaluation::Skip);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Evaluate {
+            commit_id: commit_e.id().clone(),
+            num_untested: 1,
+        }
+    );
+
+    mark(&mut state, &commit_e, Evaluation::Bad);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Indeterminate(vec![commit_e.id().clone(), commit_d.id().clone()])
+    );
+
+    // Re-evaluation overrides the previous mark
+    mark(&mut state, &commit_d, Evaluation::Good);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Found(vec![commit_e.id().clone()])
+    );
+    assert_eq!(state.commits_marked(Evaluation::Skip).count(), 0);
+}
+
+#[test]
+fn test_bisect_merge() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    //   E
+    //  /|
+    // C D
+    // | |
+    // B |
+    //  \|
+    //   A
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_e = graph_builder.commit_with_parents(&[&commit_c, &commit_d]);
+    let repo = tx.commit("test").unwrap();
+
+    // Commits on both sides of the merge are candidates
+    let mut state = BisectionState::new();
+    mark(&mut state, &commit_e, Evaluation::Bad);
+    mark(&mut state, &commit_a, Evaluation::Good);
+    mark(&mut state, &commit_c, Evaluation::Good);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Evaluate {
+            commit_id: commit_d.id().clone(),
+            num_untested: 1,
+        }
+    );
+
+    // Candidates are limited to the common ancestors of the bad commits, so a
+    // bad commit which is an ancestor of a good commit leaves no candidates
+    mark(&mut state, &commit_b, Evaluation::Bad);
+    assert_matches!(
+        state.next_step(repo.as_ref()),
+        Err(BisectionError::InconsistentEvaluations)
+    );
+}
+
+#[test]
+fn test_bisect_branchy() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // F is a merge of E4, D1, D2, D3, and D4
+    // E4 -> E3 -> E2 -> E1 -> B
+    // D1, D2, D3, D4 -> B
+    // B -> A
+    //
+    // The side branches are created first, so they come last in the topological
+    // order of the candidates.
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_d1 = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d2 = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d3 = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d4 = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_e1 = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_e2 = graph_builder.commit_with_parents(&[&commit_e1]);
+    let commit_e3 = graph_builder.commit_with_parents(&[&commit_e2]);
+    let commit_e4 = graph_builder.commit_with_parents(&[&commit_e3]);
+    let commit_f = graph_builder
+        .commit_with_parents(&[&commit_e4, &commit_d1, &commit_d2, &commit_d3, &commit_d4]);
+    let repo = tx.commit("test").unwrap();
+
+    // E4 has 5 of the 10 candidates as ancestors, whereas the middle of the
+    // untested commits in topological order would be a side branch.
+    let mut state = BisectionState::new();
+    mark(&mut state, &commit_f, Evaluation::Bad);
+    mark(&mut state, &commit_a, Evaluation::Good);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Evaluate {
+            commit_id: commit_e4.id().clone(),
+            num_untested: 9,
+        }
+    );
+
+    // The side branches split the remaining candidates equally, so the one
+    // which comes last in the topological order is picked
+    mark(&mut state, &commit_e4, Evaluation::Good);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Evaluate {
+            commit_id: commit_d1.id().clone(),
+            num_untested: 4,
+        }
+    );
+
+    mark(&mut state, &commit_d1, Evaluation::Good);
+    mark(&mut state, &commit_d2, Evaluation::Good);
+    mark(&mut state, &commit_d3, Evaluation::Bad);
+    assert_eq!(
+        state.next_step(repo.as_ref()).unwrap(),
+        BisectionStep::Found(vec![commit_d3.id().clone()])
+    );
+}
+
+#[test]
+fn test_bisection_state_save_load() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let temp_dir = new_temp_dir();
+    let path = temp_dir.path().join("bisect");
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+
+    assert_eq!(BisectionState::load(&path).unwrap(), None);
+
+    let mut state = BisectionState::new();
+    mark(&mut state, &commit_b, Evaluation::Bad);
+    mark(&mut state, &commit_a, Evaluation::Skip);
+    state.save(&path).unwrap();
+    assert_eq!(BisectionState::load(&path).unwrap(), Some(state.clone()));
+
+    // Saving again overwrites the file
+    mark(&mut state, &commit_a, Evaluation::Good);
+    state.save(&path).unwrap();
+    assert_eq!(BisectionState::load(&path).unwrap(), Some(state));
+
+    std::fs::write(&path, "bad 0123\nmaybe 0123\n").unwrap();
+    assert_matches!(
+        BisectionState::load(&path),
+        Err(BisectionStateError::InvalidLine { line_number: 2, .. })
+    );
+}
@@ -508,7 +508,12 @@ fn test_materialize_parse_roundtrip() {
 
     // The first add should always be from the left side
     insta::assert_debug_snapshot!(
-        parse_conflict(materialized.as_bytes(), conflict.num_sides(), MIN_CONFLICT_MARKER_LEN),
+        parse_conflict(
+            materialized.as_bytes(),
+            conflict.num_sides(),
+            ConflictMarkerStyle::Diff,
+            MIN_CONFLICT_MARKER_LEN
+        ),
         @r###"
     Some(
         [
@@ -583,6 +588,7 @@ fn test_materialize_parse_roundtrip_different_markers() {
         ConflictMarkerStyle::Diff,
         ConflictMarkerStyle::Snapshot,
         ConflictMarkerStyle::Git,
+        ConflictMarkerStyle::Zdiff3,
     ];
 
     // For every pair of conflict marker styles, materialize the conflict using the
@@ -610,6 +616,150 @@ fn test_materialize_parse_roundtrip_different_markers() {
     }
 }
 
+#[test]
+fn test_materialize_parse_roundtrip_zdiff3() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    let path = RepoPath::from_internal_string("file");
+    let base_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            line 2
+            line 3
+            line 4
+        "},
+    );
+    let left_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            line 2 both
+            line 3 left
+            line 4 both
+            line 5
+        "},
+    );
+    let right_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            line 2 both
+            line 3 right
+            line 4 both
+            line 5
+        "},
+    );
+
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone())],
+        vec![Some(left_id.clone()), Some(right_id.clone())],
+    );
+    let materialized =
+        materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Zdiff3);
+    insta::assert_snapshot!(materialized, @r"
+    line 1
+    line 2 both
+    <<<<<<< Side #1 (Conflict 1 of 1, 1 common line above)
+    line 3 left
+    ||||||| Base
+    line 2
+    line 3
+    line 4
+    =======
+    line 3 right
+    >>>>>>> Side #2 (Conflict 1 of 1 ends, 2 common lines below)
+    line 4 both
+    line 5
+    ");
+
+    // The common lines are restored to the sides, but not to the base
+    insta::assert_debug_snapshot!(
+        parse_conflict(
+            materialized.as_bytes(),
+            conflict.num_sides(),
+            ConflictMarkerStyle::Zdiff3,
+            MIN_CONFLICT_MARKER_LEN
+        ),
+        @r#"
+        Some(
+            [
+                Resolved(
+                    "line 1\n",
+                ),
+                Conflicted(
+                    [
+                        "line 2 both\nline 3 left\nline 4 both\nline 5\n",
+                        "line 2\nline 3\nline 4\n",
+                        "line 2 both\nline 3 right\nline 4 both\nline 5\n",
+                    ],
+                ),
+            ],
+        )
+        "#);
+
+    // The common lines are left outside of the conflict with other styles
+    insta::assert_debug_snapshot!(
+        parse_conflict(
+            materialized.as_bytes(),
+            conflict.num_sides(),
+            ConflictMarkerStyle::Git,
+            MIN_CONFLICT_MARKER_LEN
+        ),
+        @r#"
+        Some(
+            [
+                Resolved(
+                    "line 1\nline 2 both\n",
+                ),
+                Conflicted(
+                    [
+                        "line 3 left\n",
+                        "line 2\nline 3\nline 4\n",
+                        "line 3 right\n",
+                    ],
+                ),
+                Resolved(
+                    "line 4 both\nline 5\n",
+                ),
+            ],
+        )
+        "#);
+
+    let parsed = update_from_content(
+        &conflict,
+        store,
+        path,
+        materialized.replace("line 1", "line 1 edited").as_bytes(),
+        ConflictMarkerStyle::Zdiff3,
+        MIN_CONFLICT_MARKER_LEN,
+    )
+    .block_on()
+    .unwrap();
+    insta::assert_snapshot!(materialize_conflict_string(store, path, &parsed, ConflictMarkerStyle::Git), @r"
+    line 1 edited
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    line 2 both
+    line 3 left
+    line 4 both
+    line 5
+    ||||||| Base
+    line 2
+    line 3
+    line 4
+    =======
+    line 2 both
+    line 3 right
+    line 4 both
+    line 5
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    ");
+}
+
 #[test]
 fn test_materialize_conflict_no_newlines_at_eof() {
     let test_repo = TestRepo::init();
@@ -641,7 +791,7 @@ fn test_materialize_conflict_no_newlines_at_eof() {
     insta::assert_debug_snapshot!(
         parse_conflict(
             materialized.as_bytes(),
-            conflict.num_sides(),
+            conflict.num_sides(), ConflictMarkerStyle::Diff,
             MIN_CONFLICT_MARKER_LEN
         ),
         @r#"
@@ -823,6 +973,7 @@ line 4
 line 5
 "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -845,7 +996,7 @@ fn test_parse_conflict_simple() {
             >>>>>>>
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r###"
@@ -882,7 +1033,7 @@ fn test_parse_conflict_simple() {
             >>>>>>> More and more text
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r###"
@@ -922,7 +1073,7 @@ fn test_parse_conflict_simple() {
             >>>>>>> Random text
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -962,7 +1113,7 @@ fn test_parse_conflict_simple() {
             >>>>>>> Random text
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1001,7 +1152,7 @@ fn test_parse_conflict_simple() {
             >>>>>>> End
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1038,7 +1189,7 @@ fn test_parse_conflict_simple() {
             >>>>>>> End
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1077,7 +1228,7 @@ fn test_parse_conflict_simple() {
             >>>>>>>>>>>
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1124,7 +1275,7 @@ fn test_parse_conflict_multi_way() {
                 >>>>>>>
                 line 5
                 "},
-            3,
+            3, ConflictMarkerStyle::Diff,
             7
         ),
         @r###"
@@ -1168,7 +1319,7 @@ fn test_parse_conflict_multi_way() {
             >>>>>>> Random text
             line 5
             "},
-            3,
+            3, ConflictMarkerStyle::Diff,
             7
         ),
         @r###"
@@ -1215,7 +1366,7 @@ fn test_parse_conflict_multi_way() {
             >>>>>>> Random text
             line 5
             "},
-            3,
+            3, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1259,7 +1410,7 @@ fn test_parse_conflict_crlf_markers() {
             >>>>>>>\r
             line 5\r
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1305,7 +1456,7 @@ fn test_parse_conflict_diff_stripped_whitespace() {
             >>>>>>>
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1349,6 +1500,7 @@ fn test_parse_conflict_wrong_arity() {
             line 5
             "},
             3,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1371,6 +1523,7 @@ fn test_parse_conflict_malformed_missing_removes() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1395,6 +1548,7 @@ fn test_parse_conflict_malformed_marker() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1420,6 +1574,7 @@ fn test_parse_conflict_malformed_diff() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1443,6 +1598,7 @@ fn test_parse_conflict_snapshot_missing_header() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1464,6 +1620,7 @@ fn test_parse_conflict_wrong_git_style() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1487,6 +1644,7 @@ fn test_parse_conflict_git_reordered_headers() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1514,6 +1672,7 @@ fn test_parse_conflict_git_too_many_sides() {
             line 5
             "},
             3,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1538,6 +1697,7 @@ fn test_parse_conflict_mixed_header_styles() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1557,6 +1717,7 @@ fn test_parse_conflict_mixed_header_styles() {
             line 5
             "},
             2,
+            ConflictMarkerStyle::Diff,
             7
         ),
         None
@@ -1574,7 +1735,7 @@ fn test_parse_conflict_mixed_header_styles() {
             >>>>>>> Conflict 1 of 1 ends
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -1610,7 +1771,7 @@ fn test_parse_conflict_mixed_header_styles() {
             >>>>>>> Side #2 (Conflict 1 of 1 ends)
             line 5
             "},
-            2,
+            2, ConflictMarkerStyle::Diff,
             7
         ),
         @r#"
@@ -15,6 +15,7 @@
 use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::fs;
+use std::io::Read as _;
 use std::io::Write;
 use std::iter;
 use std::path::Path;
@@ -36,6 +37,8 @@ use jj_lib::backend::Timestamp;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::commit_builder::CommitBuilder;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
 use jj_lib::git;
 use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitBranchPushTargets;
@@ -64,10 +67,12 @@ use jj_lib::settings::GitSettings;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::Signer;
 use jj_lib::str_util::StringPattern;
+use jj_lib::test_signing_backend::TestSigningBackend;
 use jj_lib::tree_builder::TreeBuilder;
 use jj_lib::workspace::Workspace;
 use maplit::btreemap;
 use maplit::hashset;
+use pollster::FutureExt as _;
 use tempfile::TempDir;
 use test_case::test_case;
 use testutils::commit_transactions;
@@ -76,6 +81,7 @@ use testutils::write_random_commit;
 use testutils::CommitGraphBuilder;
 use testutils::TestRepo;
 use testutils::TestRepoBackend;
+use testutils::TestWorkspace;
 
 /// Describes successful `fetch()` result.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -143,6 +149,7 @@ fn git_fetch(
         branch_names,
         git::RemoteCallbacks::default(),
         None,
+        None,
     )?;
     let default_branch =
         git_fetch.get_default_branch(remote_name, git::RemoteCallbacks::default())?;
@@ -2250,6 +2257,117 @@ fn test_export_undo_reexport() {
     );
 }
 
+#[test]
+fn test_set_and_delete_tag() {
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, r#"signing.key = "impeccable""#).unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Git, signer, &settings);
+    let repo = &test_workspace.repo;
+    let git_repo = get_git_repo(repo);
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit = write_random_commit(mut_repo);
+
+    // Lightweight tags point to the commit once exported
+    git::set_tag(mut_repo, "light", commit.id(), None).unwrap();
+    assert_eq!(
+        mut_repo.get_tag("light"),
+        RefTarget::normal(commit.id().clone())
+    );
+    assert!(git_repo
+        .try_find_reference("refs/tags/light")
+        .unwrap()
+        .is_none());
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert_eq!(
+        git_repo
+            .find_reference("refs/tags/light")
+            .unwrap()
+            .target()
+            .id(),
+        git_id(&commit)
+    );
+    assert_eq!(
+        mut_repo.get_git_ref("refs/tags/light"),
+        RefTarget::normal(commit.id().clone())
+    );
+
+    // Annotated tags point to signed tag objects
+    let annotation = git::GitTagAnnotation {
+        message: "Release\n".to_owned(),
+        sign: true,
+    };
+    git::set_tag(mut_repo, "annotated", commit.id(), Some(&annotation)).unwrap();
+    assert_eq!(
+        mut_repo.get_tag("annotated"),
+        RefTarget::normal(commit.id().clone())
+    );
+    assert!(git_repo
+        .try_find_reference("refs/tags/annotated")
+        .unwrap()
+        .is_none());
+
+    // Tags set by jj survive reimport before they're exported
+    git::import_refs(mut_repo, &GitSettings::default()).unwrap();
+    assert_eq!(
+        mut_repo.get_tag("annotated"),
+        RefTarget::normal(commit.id().clone())
+    );
+
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    let tag = git_repo
+        .find_reference("refs/tags/annotated")
+        .unwrap()
+        .peel_to_kind(gix::object::Kind::Tag)
+        .unwrap()
+        .into_tag();
+    let tag = tag.decode().unwrap();
+    assert_eq!(tag.target(), git_id(&commit));
+    assert_eq!(tag.name, "annotated");
+    // gix only parses PGP signatures, so the test signature is part of the
+    // message.
+    assert!(tag
+        .message
+        .starts_with(b"Release\n--- JJ-TEST-SIGNATURE ---\nKEY: impeccable\n"));
+    git::import_refs(mut_repo, &GitSettings::default()).unwrap();
+    assert_eq!(
+        mut_repo.get_tag("annotated"),
+        RefTarget::normal(commit.id().clone())
+    );
+
+    // Annotated tags can be moved
+    let commit2 = write_random_commit(mut_repo);
+    git::set_tag(mut_repo, "annotated", commit2.id(), Some(&annotation)).unwrap();
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    let tag = git_repo
+        .find_reference("refs/tags/annotated")
+        .unwrap()
+        .peel_to_kind(gix::object::Kind::Tag)
+        .unwrap()
+        .into_tag();
+    assert_eq!(tag.decode().unwrap().target(), git_id(&commit2));
+
+    // Deleted tags are deleted from the Git repo once exported
+    mut_repo.set_tag_target("annotated", RefTarget::absent());
+    assert!(git_repo
+        .try_find_reference("refs/tags/annotated")
+        .unwrap()
+        .is_some());
+    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git_repo
+        .try_find_reference("refs/tags/annotated")
+        .unwrap()
+        .is_none());
+    git::import_refs(mut_repo, &GitSettings::default()).unwrap();
+    assert!(mut_repo.get_tag("annotated").is_absent());
+}
+
 #[test]
 fn test_reset_head_to_root() {
     // Create colocated workspace
@@ -3054,6 +3172,149 @@ fn test_fetch_no_such_remote(subprocess: bool) {
     assert!(matches!(result, Err(GitFetchError::NoSuchRemote(_))));
 }
 
+#[test]
+fn test_fetch_with_filter_git2() {
+    let test_data = GitRepoData::create();
+    let git_settings = get_git_settings(false);
+    let mut tx = test_data.repo.start_transaction();
+    let mut git_fetch = GitFetch::new(tx.repo_mut(), &git_settings).unwrap();
+    let result = git_fetch.fetch(
+        "origin",
+        &[StringPattern::everything()],
+        git::RemoteCallbacks::default(),
+        None,
+        Some("blob:none"),
+    );
+    assert_matches!(result, Err(GitFetchError::PartialCloneUnsupported));
+}
+
+#[test]
+fn test_fetch_with_filter_subprocess() {
+    let test_data = GitRepoData::create();
+    let git_settings = get_git_settings(true);
+    let mut origin_config = fs::OpenOptions::new()
+        .append(true)
+        .open(test_data.origin_repo.path().join("config"))
+        .unwrap();
+    writeln!(origin_config, "[uploadpack]\n\tallowFilter = true").unwrap();
+    let commit_result = testutils::git::add_commit(
+        &test_data.origin_repo,
+        "refs/heads/main",
+        "file",
+        b"content",
+        "message",
+        &[],
+    );
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut git_fetch = GitFetch::new(tx.repo_mut(), &git_settings).unwrap();
+    git_fetch
+        .fetch(
+            "origin",
+            &[StringPattern::everything()],
+            git::RemoteCallbacks::default(),
+            None,
+            Some("blob:none"),
+        )
+        .unwrap();
+    git_fetch.import_refs().unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    // The commit and its tree are fetched, but the blob isn't
+    let commit = repo
+        .store()
+        .get_commit(&jj_id(commit_result.commit_id))
+        .unwrap();
+    let file_path = RepoPath::from_internal_string("file");
+    let file_value = commit.tree().unwrap().path_value(file_path).unwrap();
+    let Some(TreeValue::File { id: file_id, .. }) = file_value.as_normal() else {
+        panic!("unexpected file value: {file_value:?}");
+    };
+    let git_blob_id = gix::ObjectId::from_bytes_or_panic(file_id.as_bytes());
+    assert!(!testutils::git::open(test_data.git_repo.path()).has_object(git_blob_id));
+
+    // Reading the file fetches the blob from the promisor remote
+    let mut content = vec![];
+    repo.store()
+        .read_file(file_path, file_id)
+        .unwrap()
+        .read_to_end(&mut content)
+        .unwrap();
+    assert_eq!(content, b"content");
+    assert!(testutils::git::open(test_data.git_repo.path()).has_object(git_blob_id));
+}
+
+#[test]
+fn test_fetch_with_filter_prefetch_objects() {
+    let test_data = GitRepoData::create();
+    let git_settings = get_git_settings(true);
+    let mut origin_config = fs::OpenOptions::new()
+        .append(true)
+        .open(test_data.origin_repo.path().join("config"))
+        .unwrap();
+    writeln!(origin_config, "[uploadpack]\n\tallowFilter = true").unwrap();
+    let commit_result1 = testutils::git::add_commit(
+        &test_data.origin_repo,
+        "refs/heads/main",
+        "file1",
+        b"content1",
+        "message1",
+        &[],
+    );
+    let commit_result2 = testutils::git::add_commit(
+        &test_data.origin_repo,
+        "refs/heads/main",
+        "file2",
+        b"content2",
+        "message2",
+        &[commit_result1.commit_id],
+    );
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut git_fetch = GitFetch::new(tx.repo_mut(), &git_settings).unwrap();
+    git_fetch
+        .fetch(
+            "origin",
+            &[StringPattern::everything()],
+            git::RemoteCallbacks::default(),
+            None,
+            Some("blob:none"),
+        )
+        .unwrap();
+    git_fetch.import_refs().unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let commit = repo
+        .store()
+        .get_commit(&jj_id(commit_result2.commit_id))
+        .unwrap();
+    let tree = commit.tree().unwrap();
+    let values = ["file1", "file2"]
+        .map(|name| {
+            let value = tree
+                .path_value(RepoPath::from_internal_string(name))
+                .unwrap();
+            value.into_resolved().unwrap().unwrap()
+        })
+        .to_vec();
+    let blob_ids = values
+        .iter()
+        .map(|value| {
+            let TreeValue::File { id, .. } = value else {
+                panic!("unexpected file value: {value:?}");
+            };
+            gix::ObjectId::from_bytes_or_panic(id.as_bytes())
+        })
+        .collect_vec();
+    let git_repo = testutils::git::open(test_data.git_repo.path());
+    assert!(blob_ids.iter().all(|&id| !git_repo.has_object(id)));
+
+    // Both blobs are fetched from the promisor remote
+    repo.store().prefetch_objects(&values).block_on().unwrap();
+    let git_repo = testutils::git::open(test_data.git_repo.path());
+    assert!(blob_ids.iter().all(|&id| git_repo.has_object(id)));
+}
+
 #[test]
 fn test_fetch_multiple_branches() {
     let test_data = GitRepoData::create();
@@ -14,6 +14,7 @@
 
 use std::collections::HashSet;
 use std::fs;
+use std::path::Path;
 use std::sync::Arc;
 
 use assert_matches::assert_matches;
@@ -27,6 +28,7 @@ use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::default_index::DefaultIndexStoreError;
 use jj_lib::default_index::DefaultMutableIndex;
 use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::index::Index as _;
 use jj_lib::object_id::HexPrefix;
 use jj_lib::object_id::ObjectId as _;
@@ -37,11 +39,15 @@ use jj_lib::refs::RemoteRefSymbol;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::revset::ResolvedExpression;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::GENERATION_RANGE_FULL;
 use maplit::hashset;
 use testutils::commit_transactions;
 use testutils::create_random_commit;
+use testutils::create_tree;
 use testutils::test_backend::TestBackend;
 use testutils::write_random_commit;
 use testutils::CommitGraphBuilder;
@@ -723,6 +729,210 @@ fn test_reindex_missing_commit() {
     assert_matches!(err, DefaultIndexStoreError::IndexCommits { op_id, .. } if op_id == *bad_op_id);
 }
 
+fn list_dir_names(dir: &Path) -> HashSet<String> {
+    dir.read_dir()
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_changed_path_index() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+    let index_dir = test_repo.repo_path().join("index");
+
+    let path_a = RepoPath::from_internal_string("a");
+    let path_b = RepoPath::from_internal_string("dir/b");
+    let tree1 = create_tree(repo, &[(path_a, "1")]);
+    let tree2 = create_tree(repo, &[(path_a, "1"), (path_b, "1")]);
+    let tree3 = create_tree(repo, &[(path_a, "2"), (path_b, "1")]);
+
+    let mut tx = repo.start_transaction();
+    let commit1 = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree1.id())
+        .write()
+        .unwrap();
+    let commit2 = tx
+        .repo_mut()
+        .new_commit(vec![commit1.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let resolve = |repo: &Arc<ReadonlyRepo>, file_path: &RepoPath| -> Vec<CommitId> {
+        let expression = RevsetExpression::filter(RevsetFilterPredicate::File(
+            FilesetExpression::prefix_path(file_path.to_owned()),
+        ));
+        let revset = expression.evaluate(repo.as_ref()).unwrap();
+        revset.iter().map(Result::unwrap).collect()
+    };
+
+    // Rebuild index with changed paths
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    assert!(!default_index_store.is_changed_path_index_enabled());
+    default_index_store.reinit().unwrap();
+    default_index_store.enable_changed_path_index().unwrap();
+    default_index_store
+        .build_index_at_operation(repo.operation(), repo.store())
+        .unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(
+        list_dir_names(&index_dir.join("changed_paths")),
+        list_dir_names(&index_dir.join("segments"))
+    );
+    assert_eq!(resolve(&repo, path_a), vec![commit1.id().clone()]);
+    assert_eq!(resolve(&repo, path_b), vec![commit2.id().clone()]);
+    assert_eq!(
+        resolve(&repo, RepoPath::from_internal_string("dir")),
+        vec![commit2.id().clone()]
+    );
+
+    // New commits aren't indexed, but should be resolved by diffing trees
+    let mut tx = repo.start_transaction();
+    let commit3 = tx
+        .repo_mut()
+        .new_commit(vec![commit2.id().clone()], tree3.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+    assert_ne!(
+        list_dir_names(&index_dir.join("changed_paths")),
+        list_dir_names(&index_dir.join("segments"))
+    );
+    assert_eq!(
+        resolve(&repo, path_a),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(resolve(&repo, path_b), vec![commit2.id().clone()]);
+
+    // Missing changed-path files shouldn't be built when loading the index
+    fs::remove_dir_all(index_dir.join("changed_paths")).unwrap();
+    default_index_store.enable_changed_path_index().unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert!(list_dir_names(&index_dir.join("changed_paths")).is_empty());
+    assert_eq!(
+        resolve(&repo, path_a),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+
+    // Missing changed-path files should be built explicitly
+    default_index_store
+        .build_index_at_operation(repo.operation(), repo.store())
+        .unwrap();
+    assert!(!list_dir_names(&index_dir.join("changed_paths")).is_empty());
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(
+        resolve(&repo, path_a),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+
+    default_index_store.disable_changed_path_index().unwrap();
+    assert!(!index_dir.join("changed_paths").exists());
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(resolve(&repo, path_b), vec![commit2.id().clone()]);
+}
+
+#[test]
+fn test_changed_path_index_squashed() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+    let index_dir = test_repo.repo_path().join("index");
+
+    let path_a = RepoPath::from_internal_string("a");
+    let path_b = RepoPath::from_internal_string("b");
+    let tree1 = create_tree(repo, &[(path_a, "1")]);
+    let tree2 = create_tree(repo, &[(path_a, "1"), (path_b, "1")]);
+    let tree3 = create_tree(repo, &[(path_a, "2"), (path_b, "1")]);
+    let tree4 = create_tree(repo, &[(path_a, "2"), (path_b, "2")]);
+
+    let mut tx = repo.start_transaction();
+    let commit1 = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree1.id())
+        .write()
+        .unwrap();
+    let commit2 = tx
+        .repo_mut()
+        .new_commit(vec![commit1.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let resolve = |repo: &Arc<ReadonlyRepo>, file_path: &RepoPath| -> Vec<CommitId> {
+        let expression = RevsetExpression::filter(RevsetFilterPredicate::File(
+            FilesetExpression::prefix_path(file_path.to_owned()),
+        ));
+        let revset = expression.evaluate(repo.as_ref()).unwrap();
+        revset.iter().map(Result::unwrap).collect()
+    };
+    let segment_name = |repo: &Arc<ReadonlyRepo>| {
+        let op_file = index_dir.join("operations").join(repo.op_id().hex());
+        fs::read_to_string(op_file).unwrap()
+    };
+
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    default_index_store.reinit().unwrap();
+    default_index_store.enable_changed_path_index().unwrap();
+    default_index_store
+        .build_index_at_operation(repo.operation(), repo.store())
+        .unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let indexed_segment_name = segment_name(&repo);
+    assert!(list_dir_names(&index_dir.join("changed_paths")).contains(&indexed_segment_name));
+
+    // Adding as many commits as the indexed segment has squashes the segments.
+    // The changed paths of the indexed commits should be carried forward.
+    let mut tx = repo.start_transaction();
+    let commit3 = tx
+        .repo_mut()
+        .new_commit(vec![commit2.id().clone()], tree3.id())
+        .write()
+        .unwrap();
+    let commit4 = tx
+        .repo_mut()
+        .new_commit(vec![commit3.id().clone()], tree4.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+    let squashed_segment_name = segment_name(&repo);
+    assert_ne!(squashed_segment_name, indexed_segment_name);
+    assert!(list_dir_names(&index_dir.join("changed_paths")).contains(&squashed_segment_name));
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(
+        resolve(&repo, path_a),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve(&repo, path_b),
+        vec![commit4.id().clone(), commit2.id().clone()]
+    );
+
+    // The commits not yet indexed should be indexed explicitly
+    let changed_path_file = index_dir.join("changed_paths").join(&squashed_segment_name);
+    let partial_data = fs::read(&changed_path_file).unwrap();
+    default_index_store
+        .build_index_at_operation(repo.operation(), repo.store())
+        .unwrap();
+    assert_ne!(fs::read(&changed_path_file).unwrap(), partial_data);
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(
+        resolve(&repo, path_a),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve(&repo, path_b),
+        vec![commit4.id().clone(), commit2.id().clone()]
+    );
+}
+
 /// Test that .jj/repo/index/type is created when the repo is created.
 #[test]
 fn test_index_store_type() {
@@ -19,6 +19,8 @@ use std::os::unix::net::UnixListener;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use assert_matches::assert_matches;
 use indoc::indoc;
@@ -28,6 +30,9 @@ use jj_lib::backend::TreeId;
 use jj_lib::backend::TreeValue;
 use jj_lib::file_util::check_symlink_support;
 use jj_lib::file_util::try_symlink;
+use jj_lib::fileset;
+use jj_lib::fileset::FilesetDiagnostics;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::local_working_copy::LocalWorkingCopy;
@@ -37,15 +42,19 @@ use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::op_store::OperationId;
 use jj_lib::op_store::WorkspaceId;
+use jj_lib::pointer_file::Pointer;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathComponent;
+use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::secret_backend::SecretBackend;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::SnapshotFileAction;
+use jj_lib::working_copy::SnapshotFilePolicy;
 use jj_lib::working_copy::SnapshotOptions;
 use jj_lib::working_copy::UntrackedReason;
 use jj_lib::working_copy::WorkingCopy;
@@ -1447,6 +1456,258 @@ fn test_git_submodule(gitignore_content: &str) {
     assert_eq!(stats.skipped_files, 1);
 }
 
+#[test]
+fn test_git_submodule_check_out_and_snapshot() {
+    // Tests that initialized submodules are checked out and snapshotted.
+
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let store = repo.store().clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let submodule_store = repo.submodule_store().clone();
+    let checkout_options = CheckoutOptions {
+        submodule_store: Some(submodule_store.clone()),
+        ..CheckoutOptions::empty_for_test()
+    };
+    let snapshot_options = SnapshotOptions {
+        submodule_store: Some(submodule_store.clone()),
+        ..SnapshotOptions::empty_for_test()
+    };
+
+    // Without .gitmodules, the submodule is named after its path.
+    let submodule_path = RepoPath::from_internal_string("sub");
+    let file_path = RepoPath::from_internal_string("dir/file");
+    let sub_store = submodule_store.init_submodule("sub").unwrap();
+    let mut sub_tree_builder = sub_store.tree_builder(sub_store.empty_tree_id().clone());
+    sub_tree_builder.set(
+        file_path.to_owned(),
+        TreeValue::File {
+            id: testutils::write_file(&sub_store, file_path, "1\n"),
+            executable: false,
+        },
+    );
+    let sub_tree_id = sub_tree_builder.write_tree().unwrap();
+    let sub_commit_id = submodule_store
+        .write_submodule_commit("sub", sub_store.root_commit_id(), &sub_tree_id)
+        .unwrap();
+
+    let mut tree_builder = MergedTreeBuilder::new(store.empty_merged_tree_id());
+    tree_builder.set_or_remove(
+        submodule_path.to_owned(),
+        Merge::normal(TreeValue::GitSubmodule(sub_commit_id.clone())),
+    );
+    let tree_id = tree_builder.write_tree(&store).unwrap();
+    let commit = commit_with_tree(&store, tree_id.clone());
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(repo.op_id().clone(), None, &commit, &checkout_options)
+        .unwrap();
+
+    // The files of the submodule commit are written
+    let disk_path =
+        file_path.to_fs_path_unchecked(&submodule_path.to_fs_path_unchecked(&workspace_root));
+    assert_eq!(std::fs::read_to_string(&disk_path).unwrap(), "1\n");
+
+    // Snapshotting unchanged files doesn't create a submodule commit
+    let (new_tree, _stats) = test_workspace
+        .snapshot_with_options(&snapshot_options)
+        .unwrap();
+    assert_eq!(new_tree.id(), tree_id);
+
+    // Modified files are recorded as a new submodule commit
+    std::fs::write(&disk_path, "2\n").unwrap();
+    let (new_tree, _stats) = test_workspace
+        .snapshot_with_options(&snapshot_options)
+        .unwrap();
+    let new_value = new_tree.path_value(submodule_path).unwrap();
+    let Some(Some(TreeValue::GitSubmodule(new_sub_commit_id))) = new_value.as_resolved() else {
+        panic!("unexpected value {new_value:?}");
+    };
+    let new_sub_commit = sub_store.get_commit(new_sub_commit_id).unwrap();
+    assert_eq!(new_sub_commit.parent_ids(), [sub_commit_id]);
+    let new_sub_value = new_sub_commit
+        .tree()
+        .unwrap()
+        .path_value(file_path)
+        .unwrap();
+    let Some(Some(TreeValue::File { id, .. })) = new_sub_value.as_resolved() else {
+        panic!("unexpected value {new_sub_value:?}");
+    };
+    assert_eq!(testutils::read_file(&sub_store, file_path, id), b"2\n");
+
+    // Checking out the empty tree removes the submodule files
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &store.root_commit(),
+        &checkout_options,
+    )
+    .unwrap();
+    assert!(!submodule_path
+        .to_fs_path_unchecked(&workspace_root)
+        .exists());
+}
+
+#[test]
+fn test_git_submodule_check_out_keeps_modified_files() {
+    // Tests that local changes to submodule files aren't overwritten.
+
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let store = repo.store().clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let submodule_store = repo.submodule_store().clone();
+    let checkout_options = CheckoutOptions {
+        submodule_store: Some(submodule_store.clone()),
+        ..CheckoutOptions::empty_for_test()
+    };
+
+    let submodule_path = RepoPath::from_internal_string("sub");
+    let modified_path = RepoPath::from_internal_string("modified");
+    let unmodified_path = RepoPath::from_internal_string("unmodified");
+    let sub_store = submodule_store.init_submodule("sub").unwrap();
+    let create_commit = |content: &str| {
+        let mut sub_tree_builder = sub_store.tree_builder(sub_store.empty_tree_id().clone());
+        for path in [modified_path, unmodified_path] {
+            sub_tree_builder.set(
+                path.to_owned(),
+                TreeValue::File {
+                    id: testutils::write_file(&sub_store, path, content),
+                    executable: false,
+                },
+            );
+        }
+        let sub_tree_id = sub_tree_builder.write_tree().unwrap();
+        let sub_commit_id = submodule_store
+            .write_submodule_commit("sub", sub_store.root_commit_id(), &sub_tree_id)
+            .unwrap();
+        let mut tree_builder = MergedTreeBuilder::new(store.empty_merged_tree_id());
+        tree_builder.set_or_remove(
+            submodule_path.to_owned(),
+            Merge::normal(TreeValue::GitSubmodule(sub_commit_id)),
+        );
+        commit_with_tree(&store, tree_builder.write_tree(&store).unwrap())
+    };
+    let commit1 = create_commit("1\n");
+    let commit2 = create_commit("2\n");
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(repo.op_id().clone(), None, &commit1, &checkout_options)
+        .unwrap();
+
+    // Modify a file without snapshotting it
+    let submodule_dir = submodule_path.to_fs_path_unchecked(&workspace_root);
+    let modified_disk_path = modified_path.to_fs_path_unchecked(&submodule_dir);
+    let unmodified_disk_path = unmodified_path.to_fs_path_unchecked(&submodule_dir);
+    std::fs::write(&modified_disk_path, "local\n").unwrap();
+
+    // The modified file is skipped, and the other file is updated
+    let stats = ws
+        .check_out(repo.op_id().clone(), None, &commit2, &checkout_options)
+        .unwrap();
+    assert_eq!(stats.skipped_files, 1);
+    assert_eq!(
+        std::fs::read_to_string(&modified_disk_path).unwrap(),
+        "local\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&unmodified_disk_path).unwrap(),
+        "2\n"
+    );
+
+    // Removing the submodule also keeps the modified file, so the submodule
+    // directory can't be removed either
+    let stats = ws
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &store.root_commit(),
+            &checkout_options,
+        )
+        .unwrap();
+    assert_eq!(stats.skipped_files, 2);
+    assert!(modified_disk_path.exists());
+    assert!(!unmodified_disk_path.exists());
+}
+
+#[test]
+fn test_git_submodule_snapshot_reuses_file_states() {
+    // Tests that unchanged files of a submodule aren't read again.
+
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let store = repo.store().clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let submodule_store = repo.submodule_store().clone();
+    let checkout_options = CheckoutOptions {
+        submodule_store: Some(submodule_store.clone()),
+        ..CheckoutOptions::empty_for_test()
+    };
+    let snapshot_options = SnapshotOptions {
+        submodule_store: Some(submodule_store.clone()),
+        ..SnapshotOptions::empty_for_test()
+    };
+
+    let submodule_path = RepoPath::from_internal_string("sub");
+    let file_path = RepoPath::from_internal_string("file");
+    let sub_store = submodule_store.init_submodule("sub").unwrap();
+    let mut sub_tree_builder = sub_store.tree_builder(sub_store.empty_tree_id().clone());
+    sub_tree_builder.set(
+        file_path.to_owned(),
+        TreeValue::File {
+            id: testutils::write_file(&sub_store, file_path, "1\n"),
+            executable: false,
+        },
+    );
+    let sub_tree_id = sub_tree_builder.write_tree().unwrap();
+    let sub_commit_id = submodule_store
+        .write_submodule_commit("sub", sub_store.root_commit_id(), &sub_tree_id)
+        .unwrap();
+
+    let mut tree_builder = MergedTreeBuilder::new(store.empty_merged_tree_id());
+    tree_builder.set_or_remove(
+        submodule_path.to_owned(),
+        Merge::normal(TreeValue::GitSubmodule(sub_commit_id)),
+    );
+    let tree_id = tree_builder.write_tree(&store).unwrap();
+    let commit = commit_with_tree(&store, tree_id.clone());
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(repo.op_id().clone(), None, &commit, &checkout_options)
+        .unwrap();
+
+    // Backdate the file so its recorded state can be trusted
+    let disk_path =
+        file_path.to_fs_path_unchecked(&submodule_path.to_fs_path_unchecked(&workspace_root));
+    let old_mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let set_mtime = |mtime: SystemTime| {
+        let file = std::fs::File::options()
+            .write(true)
+            .open(&disk_path)
+            .unwrap();
+        file.set_modified(mtime).unwrap();
+    };
+    set_mtime(old_mtime);
+    let (new_tree, _stats) = test_workspace
+        .snapshot_with_options(&snapshot_options)
+        .unwrap();
+    assert_eq!(new_tree.id(), tree_id);
+
+    // A change that doesn't affect the file state isn't noticed, which means
+    // the file isn't read again
+    std::fs::write(&disk_path, "2\n").unwrap();
+    set_mtime(old_mtime);
+    let (new_tree, _stats) = test_workspace
+        .snapshot_with_options(&snapshot_options)
+        .unwrap();
+    assert_eq!(new_tree.id(), tree_id);
+
+    // Once the file state changes, the file is read
+    set_mtime(old_mtime + Duration::from_secs(1));
+    let (new_tree, _stats) = test_workspace
+        .snapshot_with_options(&snapshot_options)
+        .unwrap();
+    assert_ne!(new_tree.id(), tree_id);
+}
+
 #[test]
 fn test_check_out_existing_file_cannot_be_removed() {
     let mut test_workspace = TestWorkspace::init();
@@ -2149,6 +2410,81 @@ fn test_fsmonitor() {
     }
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fsmonitor_inotify() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let ws = &mut test_workspace.workspace;
+    let local_wc = |ws: &Workspace| {
+        ws.working_copy()
+            .as_any()
+            .downcast_ref::<LocalWorkingCopy>()
+            .unwrap()
+            .inotify_fsmonitor()
+    };
+
+    let fsmonitor = local_wc(ws);
+    let daemon = std::thread::spawn(move || fsmonitor.run_daemon());
+    while !local_wc(ws).is_daemon_running().unwrap() {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    let snapshot = |ws: &mut Workspace| {
+        let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+        let (tree_id, _stats) = locked_ws
+            .locked_wc()
+            .snapshot(&SnapshotOptions {
+                fsmonitor_settings: FsmonitorSettings::Inotify,
+                ..SnapshotOptions::empty_for_test()
+            })
+            .unwrap();
+        locked_ws.finish(repo.op_id().clone()).unwrap();
+        tree_id
+    };
+    let query = |ws: &Workspace| {
+        let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+        let (_clock, changed_files) = wc.query_inotify().unwrap();
+        changed_files.map(|paths| paths.into_iter().sorted().collect_vec())
+    };
+
+    // Without a clock, the whole working copy has to be scanned
+    let foo_path = RepoPath::from_internal_string("foo");
+    testutils::write_working_copy_file(&workspace_root, foo_path, "foo\n");
+    assert_eq!(query(ws), None);
+    let tree_id = snapshot(ws);
+    insta::assert_snapshot!(testutils::dump_tree(repo.store(), &tree_id), @r#"
+    tree d5e38c0a1b0ee5de47c5
+      file "foo" (e99c2057c15160add351): "foo\n"
+    "#);
+    assert_eq!(query(ws), Some(vec![]));
+
+    // Files in new directories are at least reported through the directory
+    let nested_path = RepoPath::from_internal_string("path/to/nested");
+    testutils::write_working_copy_file(&workspace_root, foo_path, "updated foo\n");
+    testutils::write_working_copy_file(&workspace_root, nested_path, "nested\n");
+    let changed_files = query(ws).unwrap();
+    assert!(changed_files.contains(&PathBuf::from("foo")));
+    assert!(changed_files.contains(&PathBuf::from("path")));
+    let tree_id = snapshot(ws);
+    insta::assert_snapshot!(testutils::dump_tree(repo.store(), &tree_id), @r#"
+    tree b151553a46f87eec523d
+      file "foo" (e0fbd106147cc04ccd05): "updated foo\n"
+      file "path/to/nested" (6209060941cd770c8d46): "nested\n"
+    "#);
+
+    std::fs::remove_dir_all(workspace_root.join("path")).unwrap();
+    let tree_id = snapshot(ws);
+    insta::assert_snapshot!(testutils::dump_tree(repo.store(), &tree_id), @r#"
+    tree ea25d6287db649f936c9
+      file "foo" (e0fbd106147cc04ccd05): "updated foo\n"
+    "#);
+
+    assert!(local_wc(ws).stop_daemon().unwrap());
+    daemon.join().unwrap().unwrap();
+}
+
 #[test]
 fn test_snapshot_max_new_file_size() {
     let mut test_workspace = TestWorkspace::init();
@@ -2231,3 +2567,145 @@ fn test_snapshot_max_new_file_size() {
         UntrackedReason::FileTooLarge { .. }
     );
 }
+
+#[test]
+fn test_snapshot_file_policies() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let store = repo.store();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let parse = |text: &str| {
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: "".into(),
+            base: "".into(),
+        };
+        fileset::parse(&mut FilesetDiagnostics::new(), text, &path_converter).unwrap()
+    };
+    let options = SnapshotOptions {
+        file_policies: vec![
+            SnapshotFilePolicy {
+                name: "large-binaries".to_owned(),
+                paths: FilesetExpression::all(),
+                min_size: Some(1024),
+                binary_only: true,
+                action: SnapshotFileAction::Pointer,
+            },
+            SnapshotFilePolicy {
+                name: "objects".to_owned(),
+                paths: parse("glob:*.o"),
+                min_size: None,
+                binary_only: false,
+                action: SnapshotFileAction::Skip,
+            },
+            SnapshotFilePolicy {
+                name: "datasets".to_owned(),
+                paths: parse("data"),
+                min_size: None,
+                binary_only: false,
+                action: SnapshotFileAction::Manual,
+            },
+        ],
+        max_new_file_size: 1024,
+        ..SnapshotOptions::empty_for_test()
+    };
+
+    let large_binary_path = RepoPath::from_internal_string("large.bin");
+    let small_binary_path = RepoPath::from_internal_string("small.bin");
+    let large_text_path = RepoPath::from_internal_string("large.txt");
+    let object_path = RepoPath::from_internal_string("main.o");
+    let dataset_path = RepoPath::from_internal_string("data/set");
+    let large_binary_content = [vec![0; 10], vec![b'x'; 2048]].concat();
+    std::fs::write(
+        large_binary_path.to_fs_path_unchecked(&workspace_root),
+        &large_binary_content,
+    )
+    .unwrap();
+    std::fs::write(
+        small_binary_path.to_fs_path_unchecked(&workspace_root),
+        [0; 10],
+    )
+    .unwrap();
+    std::fs::write(
+        large_text_path.to_fs_path_unchecked(&workspace_root),
+        vec![b'x'; 2048],
+    )
+    .unwrap();
+    std::fs::write(object_path.to_fs_path_unchecked(&workspace_root), "object").unwrap();
+    std::fs::create_dir(workspace_root.join("data")).unwrap();
+    std::fs::write(dataset_path.to_fs_path_unchecked(&workspace_root), "set").unwrap();
+
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(
+        tree.entries().map(|(path, _)| path).collect_vec(),
+        [large_binary_path.to_owned(), small_binary_path.to_owned()]
+    );
+    assert_matches!(
+        stats.untracked_paths.get(large_text_path),
+        Some(UntrackedReason::FileTooLarge { .. })
+    );
+    assert_matches!(
+        stats.untracked_paths.get(object_path),
+        Some(UntrackedReason::FileSkippedByPolicy { policy }) if policy == "objects"
+    );
+    assert_matches!(
+        stats.untracked_paths.get(dataset_path),
+        Some(UntrackedReason::FileRequiresExplicitTracking { policy }) if policy == "datasets"
+    );
+
+    // The tree records a pointer to the content of the large binary file
+    let Some(TreeValue::File { id, .. }) = tree
+        .path_value(large_binary_path)
+        .unwrap()
+        .into_resolved()
+        .unwrap()
+    else {
+        panic!("large binary file should be tracked");
+    };
+    let pointer = Pointer::parse(&testutils::read_file(store, large_binary_path, &id)).unwrap();
+    assert_eq!(pointer.size, large_binary_content.len() as u64);
+
+    // The pointer is checked out as is unless the path may be stored as a
+    // pointer
+    let commit = commit_with_tree(store, tree.id());
+    let empty_commit = commit_with_tree(store, store.empty_merged_tree_id());
+    let checkout_options = CheckoutOptions {
+        pointer_paths: FilesetExpression::all(),
+        ..CheckoutOptions::empty_for_test()
+    };
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &empty_commit,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    assert!(!large_binary_path
+        .to_fs_path_unchecked(&workspace_root)
+        .exists());
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+    assert_eq!(
+        Pointer::parse(
+            &std::fs::read(large_binary_path.to_fs_path_unchecked(&workspace_root)).unwrap()
+        ),
+        Some(pointer)
+    );
+
+    // Checking out the pointer restores the content
+    ws.check_out(repo.op_id().clone(), None, &empty_commit, &checkout_options)
+        .unwrap();
+    ws.check_out(repo.op_id().clone(), None, &commit, &checkout_options)
+        .unwrap();
+    assert_eq!(
+        std::fs::read(large_binary_path.to_fs_path_unchecked(&workspace_root)).unwrap(),
+        large_binary_content
+    );
+    let (new_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(new_tree.id(), tree.id());
+}
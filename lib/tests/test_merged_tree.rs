@@ -13,12 +13,15 @@
 // limitations under the License.
 
 use futures::StreamExt;
+use indoc::indoc;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::CopyRecord;
 use jj_lib::backend::FileId;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeValue;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
 use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
@@ -39,6 +42,7 @@ use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathComponent;
+use jj_lib::settings::UserSettings;
 use pollster::FutureExt as _;
 use pretty_assertions::assert_eq;
 use testutils::create_single_tree;
@@ -1568,3 +1572,134 @@ fn test_merge_simplify_file_conflict_with_absent() {
     let merged = child1_merged.merge(&parent_merged, &child2_merged).unwrap();
     assert_eq!(merged, expected_merged);
 }
+
+#[test]
+fn test_merge_with_merge_drivers() {
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(
+            ConfigSource::User,
+            indoc! {"
+                merge-drivers.changelog = { paths = 'glob:*.md', builtin = 'union' }
+                merge-drivers.missing = { paths = 'missing', command = ['jj-nonexistent-driver'] }
+            "},
+        )
+        .unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+
+    let changelog_path = RepoPath::from_internal_string("CHANGES.md");
+    let missing_path = RepoPath::from_internal_string("missing");
+    let other_path = RepoPath::from_internal_string("other");
+    let create_tree = |content: &str| {
+        create_single_tree(
+            repo,
+            &[
+                (changelog_path, &format!("a\n{content}b\n")),
+                (missing_path, content),
+                (other_path, content),
+            ],
+        )
+    };
+    let base = MergedTree::resolved(create_tree(""));
+    let side1 = MergedTree::resolved(create_tree("side1\n"));
+    let side2 = MergedTree::resolved(create_tree("side2\n"));
+
+    // Plain tree merges don't use merge drivers
+    let merged = side1.merge(&base, &side2).unwrap();
+    assert!(!merged.path_value(changelog_path).unwrap().is_resolved());
+    assert!(repo.store().merge_drivers().take_failures().is_empty());
+
+    let merged = side1.merge_with_merge_drivers(&base, &side2).unwrap();
+    // The union driver resolves the conflict
+    let expected = create_single_tree(repo, &[(changelog_path, "a\nside1\nside2\nb\n")]);
+    assert_eq!(
+        merged.path_value(changelog_path).unwrap(),
+        MergedTree::resolved(expected)
+            .path_value(changelog_path)
+            .unwrap()
+    );
+    // The built-in merge is used if the driver fails, and the error is
+    // recorded
+    assert!(!merged.path_value(missing_path).unwrap().is_resolved());
+    assert!(!merged.path_value(other_path).unwrap().is_resolved());
+    let failures = repo.store().merge_drivers().take_failures();
+    assert!(!failures.is_empty());
+    assert!(failures
+        .iter()
+        .all(|failure| failure.driver == "missing" && failure.path.as_ref() == missing_path));
+    assert!(repo.store().merge_drivers().take_failures().is_empty());
+}
+
+#[test]
+fn test_merge_with_recorded_resolutions() {
+    let mut config = testutils::base_user_config();
+    config.add_layer(ConfigLayer::parse(ConfigSource::User, "rerere.enabled = true").unwrap());
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = &test_repo.repo;
+    let store = repo.store();
+    let resolutions = store.recorded_resolutions().unwrap();
+
+    let path = RepoPath::from_internal_string("file");
+    let other_path = RepoPath::from_internal_string("other");
+    let create_tree = |header: &str, content: &str| {
+        MergedTree::resolved(create_single_tree(
+            repo,
+            &[
+                (path, &format!("{header}a\n{content}b\n")),
+                (other_path, &content.to_uppercase()),
+            ],
+        ))
+    };
+    let base = create_tree("", "");
+    let side1 = create_tree("", "side1\n");
+    let side2 = create_tree("", "side2\n");
+    let merged = side1.merge(&base, &side2).unwrap();
+    assert!(!merged.path_value(path).unwrap().is_resolved());
+
+    // Only the resolved file is recorded
+    let mut tree_builder = MergedTreeBuilder::new(merged.id());
+    let resolved = create_tree("", "resolved\n");
+    tree_builder.set_or_remove(path.to_owned(), resolved.path_value(path).unwrap());
+    let resolved_id = tree_builder.write_tree(store).unwrap();
+    let resolved = store.get_root_tree(&resolved_id).unwrap();
+    assert_eq!(
+        resolutions.record_tree(store, &merged, &resolved).unwrap(),
+        1
+    );
+
+    // Plain tree merges don't reuse the recorded resolutions
+    let base = create_tree("header\n", "");
+    let side1 = create_tree("header\n", "side1\n");
+    let side2 = create_tree("header\n", "side2\n");
+    let merged = side1.merge(&base, &side2).unwrap();
+    assert!(!merged.path_value(path).unwrap().is_resolved());
+
+    // The same conflict hunk is resolved even if the context changed
+    let mut tx = repo.start_transaction();
+    let merged = tx.repo_mut().apply_recorded_resolutions(merged).unwrap();
+    assert_eq!(
+        merged.path_value(path).unwrap(),
+        create_tree("header\n", "resolved\n")
+            .path_value(path)
+            .unwrap()
+    );
+    assert!(!merged.path_value(other_path).unwrap().is_resolved());
+    assert_eq!(tx.repo().reused_resolutions().len(), 1);
+
+    // The reused resolutions are logged when the transaction is committed
+    assert!(resolutions.reused().unwrap().is_empty());
+    tx.commit("test").unwrap();
+    assert_eq!(
+        resolutions
+            .reused()
+            .unwrap()
+            .iter()
+            .map(|r| r.path.as_ref())
+            .collect_vec(),
+        vec![path]
+    );
+}
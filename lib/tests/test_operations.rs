@@ -20,19 +20,32 @@ use std::time::SystemTime;
 use assert_matches::assert_matches;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::MillisSinceEpoch;
+use jj_lib::backend::Timestamp;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::object_id::ObjectId;
+use jj_lib::op_bundle;
+use jj_lib::op_bundle::OpBundleError;
+use jj_lib::op_retention;
+use jj_lib::op_retention::OpRetentionPolicy;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::ViewRefName;
+use jj_lib::op_store::WorkspaceId;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
 use jj_lib::operation::Operation;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::settings::UserSettings;
+use test_case::test_case;
 use testutils::create_random_commit;
+use testutils::create_tree;
 use testutils::write_random_commit;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 
 fn list_dir(dir: &Path) -> Vec<String> {
     std::fs::read_dir(dir)
@@ -152,6 +165,61 @@ fn test_concurrent_operations() {
     assert_eq!(list_dir(&op_heads_dir), vec![merged_op_id.hex()]);
 }
 
+#[test]
+fn test_concurrent_operations_conflicted_refs() {
+    // Test that the merge operation records the refs that became conflicted.
+    let test_repo = TestRepo::init();
+    let repo_0 = &test_repo.repo;
+
+    let mut tx = repo_0.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = write_random_commit(tx.repo_mut());
+    let commit_c = write_random_commit(tx.repo_mut());
+    for name in ["main", "feature"] {
+        tx.repo_mut()
+            .set_local_bookmark_target(name, RefTarget::normal(commit_a.id().clone()));
+    }
+    let repo_1 = tx.commit("test").unwrap();
+
+    let mut tx1 = repo_1.start_transaction();
+    tx1.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit_b.id().clone()));
+    tx1.repo_mut()
+        .set_local_bookmark_target("feature", RefTarget::normal(commit_b.id().clone()));
+    tx1.commit("transaction 1").unwrap();
+    let mut tx2 = repo_1.start_transaction();
+    tx2.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit_c.id().clone()));
+    tx2.repo_mut()
+        .set_local_bookmark_target("feature", RefTarget::normal(commit_b.id().clone()));
+    tx2.commit("transaction 2").unwrap();
+
+    let repo = repo_1.reload_at_head().unwrap();
+    assert_eq!(repo.operation().parent_ids().len(), 2);
+    assert!(repo.view().get_local_bookmark("main").has_conflict());
+    assert_eq!(
+        repo.operation().metadata().conflicted_refs,
+        vec![ViewRefName::LocalBookmark("main".to_owned())]
+    );
+    // The recorded refs should be read back from the operation store
+    let op = repo.op_store().read_operation(repo.op_id()).unwrap();
+    assert_eq!(
+        op.metadata.conflicted_refs,
+        vec![ViewRefName::LocalBookmark("main".to_owned())]
+    );
+
+    // No conflicts to record
+    let mut tx1 = repo.start_transaction();
+    write_random_commit(tx1.repo_mut());
+    tx1.commit("transaction 1").unwrap();
+    let mut tx2 = repo.start_transaction();
+    write_random_commit(tx2.repo_mut());
+    tx2.commit("transaction 2").unwrap();
+    let repo = repo.reload_at_head().unwrap();
+    assert_eq!(repo.operation().parent_ids().len(), 2);
+    assert!(repo.operation().metadata().conflicted_refs.is_empty());
+}
+
 fn assert_heads(repo: &dyn Repo, expected: Vec<&CommitId>) {
     let expected = expected.iter().cloned().cloned().collect();
     assert_eq!(*repo.view().heads(), expected);
@@ -251,6 +319,7 @@ fn test_reparent_range_linear() {
         slice::from_ref(repo_b.operation()),
         slice::from_ref(repo_d.operation()),
         repo_a.operation(),
+        None,
     )
     .unwrap();
     assert_eq!(stats.new_head_ids.len(), 1);
@@ -270,6 +339,7 @@ fn test_reparent_range_linear() {
         slice::from_ref(repo_d.operation()),
         slice::from_ref(repo_d.operation()),
         repo_a.operation(),
+        None,
     )
     .unwrap();
     assert_eq!(stats.new_head_ids, vec![repo_a.op_id().clone()]);
@@ -330,6 +400,7 @@ fn test_reparent_range_branchy() {
         slice::from_ref(repo_d.operation()),
         slice::from_ref(repo_g.operation()),
         repo_b.operation(),
+        None,
     )
     .unwrap();
     assert_eq!(stats.new_head_ids.len(), 1);
@@ -357,6 +428,7 @@ fn test_reparent_range_branchy() {
         slice::from_ref(repo_b.operation()),
         slice::from_ref(repo_g.operation()),
         repo_a.operation(),
+        None,
     )
     .unwrap();
     assert_eq!(stats.new_head_ids.len(), 1);
@@ -383,6 +455,7 @@ fn test_reparent_range_branchy() {
         &[op_e.clone(), op_f.clone()],
         slice::from_ref(repo_g.operation()),
         repo_d.operation(),
+        None,
     )
     .unwrap();
     assert_eq!(stats.new_head_ids.len(), 1);
@@ -405,6 +478,7 @@ fn test_reparent_range_branchy() {
         slice::from_ref(repo_c.operation()),
         slice::from_ref(&op_f),
         repo_d.operation(),
+        None,
     )
     .unwrap();
     assert_eq!(stats.new_head_ids.len(), 1);
@@ -416,6 +490,202 @@ fn test_reparent_range_branchy() {
     assert_eq!(new_op_f.parent_ids(), slice::from_ref(repo_d.op_id()));
 }
 
+#[test]
+fn test_retain_ancestors_branchy() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    let read_op = |id| loader.load_operation(id).unwrap();
+
+    fn op_parents<const N: usize>(op: &Operation) -> [Operation; N] {
+        let parents: Vec<_> = op.parents().try_collect().unwrap();
+        parents.try_into().unwrap()
+    }
+
+    // Set up branchy operation graph:
+    // G
+    // |\
+    // | F
+    // E |
+    // D |
+    // |/
+    // C
+    // B
+    // A
+    // 0 (initial)
+    let random_tx = |repo: &Arc<ReadonlyRepo>| {
+        let mut tx = repo.start_transaction();
+        write_random_commit(tx.repo_mut());
+        tx
+    };
+    let repo_a = random_tx(&repo_0).commit("op A").unwrap();
+    let repo_b = random_tx(&repo_a).commit("op B").unwrap();
+    let repo_c = random_tx(&repo_b).commit("op C").unwrap();
+    let repo_d = random_tx(&repo_c).commit("op D").unwrap();
+    let tx_e = random_tx(&repo_d);
+    let tx_f = random_tx(&repo_c);
+    let repo_g = testutils::commit_transactions(vec![tx_e, tx_f]);
+    let [_op_e, op_f] = op_parents(repo_g.operation());
+
+    // Retain nothing but the head and the root:
+    // G'
+    // 0 (initial)
+    let stats = op_walk::retain_ancestors(
+        op_store.as_ref(),
+        slice::from_ref(repo_g.operation()),
+        None,
+        |_| Ok(false),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids.len(), 1);
+    assert_eq!(stats.rewritten_count, 1);
+    assert_eq!(stats.unreachable_count, 6);
+    let new_op_g = read_op(&stats.new_head_ids[0]);
+    assert_eq!(new_op_g.metadata(), repo_g.operation().metadata());
+    assert_eq!(new_op_g.view_id(), repo_g.operation().view_id());
+    assert_eq!(
+        new_op_g.parent_ids(),
+        [op_store.root_operation_id().clone()]
+    );
+
+    // Retain D|F:
+    // G'
+    // |\
+    // | F'
+    // D'|
+    // |/
+    // 0 (initial)
+    let stats = op_walk::retain_ancestors(
+        op_store.as_ref(),
+        slice::from_ref(repo_g.operation()),
+        None,
+        |op| Ok(op.id() == repo_d.op_id() || op.id() == op_f.id()),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids.len(), 1);
+    assert_eq!(stats.rewritten_count, 3);
+    assert_eq!(stats.unreachable_count, 4);
+    let new_op_g = read_op(&stats.new_head_ids[0]);
+    let [new_op_d, new_op_f] = op_parents(&new_op_g);
+    assert_eq!(new_op_d.metadata(), repo_d.operation().metadata());
+    assert_eq!(
+        new_op_d.parent_ids(),
+        [op_store.root_operation_id().clone()]
+    );
+    assert_eq!(new_op_f.metadata(), op_f.metadata());
+    assert_eq!(
+        new_op_f.parent_ids(),
+        [op_store.root_operation_id().clone()]
+    );
+
+    // Retain everything
+    let stats = op_walk::retain_ancestors(
+        op_store.as_ref(),
+        slice::from_ref(repo_g.operation()),
+        None,
+        |_| Ok(true),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids, vec![repo_g.op_id().clone()]);
+    assert_eq!(stats.rewritten_count, 0);
+    assert_eq!(stats.unreachable_count, 0);
+}
+
+#[test]
+fn test_prune_operations() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    let read_op = |id| loader.load_operation(id).unwrap();
+
+    // Set up linear operation graph:
+    // D
+    // C
+    // B (sets bookmark)
+    // A
+    // 0 (initial)
+    let random_tx = |repo: &Arc<ReadonlyRepo>| {
+        let mut tx = repo.start_transaction();
+        write_random_commit(tx.repo_mut());
+        tx
+    };
+    let repo_a = random_tx(&repo_0).commit("op A").unwrap();
+    let mut tx = repo_a.start_transaction();
+    let commit = write_random_commit(tx.repo_mut());
+    tx.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit.id().clone()));
+    let repo_b = tx.commit("op B").unwrap();
+    let repo_c = random_tx(&repo_b).commit("op C").unwrap();
+    let repo_d = random_tx(&repo_c).commit("op D").unwrap();
+    let head_ops = slice::from_ref(repo_d.operation());
+    let now = repo_d.operation().metadata().end_time;
+
+    // Disabled policy
+    let policy = OpRetentionPolicy {
+        keep_bookmark_ops: true,
+        ..Default::default()
+    };
+    let stats =
+        op_retention::prune_operations(op_store.as_ref(), head_ops, &policy, &now, None).unwrap();
+    assert_eq!(stats.new_head_ids, vec![repo_d.op_id().clone()]);
+    assert_eq!(stats.unreachable_count, 0);
+
+    // Keep the latest 2 operations and the bookmark operation:
+    // D'
+    // C'
+    // B'
+    // 0 (initial)
+    let policy = OpRetentionPolicy {
+        keep_count: Some(2),
+        keep_bookmark_ops: true,
+        ..Default::default()
+    };
+    let stats =
+        op_retention::prune_operations(op_store.as_ref(), head_ops, &policy, &now, None).unwrap();
+    assert_eq!(stats.rewritten_count, 3);
+    assert_eq!(stats.unreachable_count, 1);
+    let new_op_d = read_op(&stats.new_head_ids[0]);
+    let new_op_c = read_op(&new_op_d.parent_ids()[0]);
+    assert_eq!(new_op_c.metadata(), repo_c.operation().metadata());
+    let new_op_b = read_op(&new_op_c.parent_ids()[0]);
+    assert_eq!(new_op_b.metadata(), repo_b.operation().metadata());
+    assert_eq!(
+        new_op_b.parent_ids(),
+        [op_store.root_operation_id().clone()]
+    );
+
+    // All operations are older than the threshold, but the head is kept:
+    // D'
+    // 0 (initial)
+    let policy = OpRetentionPolicy {
+        keep_days: Some(1),
+        ..Default::default()
+    };
+    let later = Timestamp {
+        timestamp: MillisSinceEpoch(now.timestamp.0 + 2 * 24 * 60 * 60 * 1000),
+        tz_offset: 0,
+    };
+    let stats =
+        op_retention::prune_operations(op_store.as_ref(), head_ops, &policy, &later, None).unwrap();
+    assert_eq!(stats.rewritten_count, 1);
+    assert_eq!(stats.unreachable_count, 3);
+    let new_op_d = read_op(&stats.new_head_ids[0]);
+    assert_eq!(
+        new_op_d.parent_ids(),
+        [op_store.root_operation_id().clone()]
+    );
+
+    // All operations are within the threshold
+    let stats =
+        op_retention::prune_operations(op_store.as_ref(), head_ops, &policy, &now, None).unwrap();
+    assert_eq!(stats.new_head_ids, vec![repo_d.op_id().clone()]);
+    assert_eq!(stats.unreachable_count, 0);
+}
+
 fn stable_op_id_settings() -> UserSettings {
     let mut config = testutils::base_user_config();
     config.add_layer(
@@ -604,6 +874,256 @@ fn test_resolve_op_parents_children() {
     );
 }
 
+#[test]
+fn test_resolve_opset() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+
+    // Set up operation graph:
+    // D (snapshot, "jj debug snapshot")
+    // C (sets bookmark, "jj bookmark set")
+    // B (sets working-copy commit)
+    // A ("jj new")
+    // 0 (root)
+    let mut tx = repo_0.start_transaction();
+    write_random_commit(tx.repo_mut());
+    tx.set_tag("args".to_owned(), "jj new".to_owned());
+    let repo_a = tx.commit("op A").unwrap();
+    let mut tx = repo_a.start_transaction();
+    let commit = write_random_commit(tx.repo_mut());
+    tx.repo_mut()
+        .set_wc_commit(WorkspaceId::new("ws".to_owned()), commit.id().clone())
+        .unwrap();
+    let repo_b = tx.commit("op B").unwrap();
+    let mut tx = repo_b.start_transaction();
+    tx.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit.id().clone()));
+    tx.set_tag("args".to_owned(), "jj bookmark set".to_owned());
+    let repo_c = tx.commit("op C").unwrap();
+    let mut tx = repo_c.start_transaction();
+    write_random_commit(tx.repo_mut());
+    tx.set_is_snapshot(true);
+    tx.set_tag("args".to_owned(), "jj debug snapshot".to_owned());
+    let repo_d = tx.commit("op D").unwrap();
+    let [op_0, op_a, op_b, op_c, op_d] =
+        [&repo_0, &repo_a, &repo_b, &repo_c, &repo_d].map(|repo| repo.operation().clone());
+
+    let op_store = repo_d.op_store();
+    let resolve =
+        |op_str: &str| op_walk::resolve_opset_at(op_store, slice::from_ref(&op_d), op_str).unwrap();
+
+    // Results are sorted in reverse topological order
+    assert_eq!(
+        resolve("::"),
+        [&op_d, &op_c, &op_b, &op_a, &op_0].map(Clone::clone)
+    );
+    assert_eq!(resolve("all()"), resolve("::@"));
+    assert_eq!(resolve("none()"), []);
+    assert_eq!(resolve("root()"), vec![op_0.clone()]);
+    assert_eq!(resolve(".."), resolve("~root()"));
+
+    // Ranges
+    assert_eq!(resolve("@--..@"), [op_d.clone(), op_c.clone()]);
+    assert_eq!(
+        resolve(&format!("{}::{}", op_a.id().hex(), op_c.id().hex())),
+        [op_c.clone(), op_b.clone(), op_a.clone()]
+    );
+    assert_eq!(resolve(&format!("{}..", op_b.id().hex())), resolve("@-|@"));
+    assert_eq!(
+        resolve(&format!("{}::", op_b.id().hex())),
+        [op_d.clone(), op_c.clone(), op_b.clone()]
+    );
+    assert_eq!(resolve("ancestors(@, 2)"), [op_d.clone(), op_c.clone()]);
+    assert_eq!(resolve("descendants(@---)"), resolve("@---::"));
+    assert_eq!(resolve("parents(@)"), vec![op_c.clone()]);
+    assert_eq!(resolve("children(@--)"), vec![op_c.clone()]);
+    assert_eq!(resolve("@- ~ @--::@-"), []);
+
+    // Filters
+    assert_eq!(resolve(r#"command("jj new")"#), vec![op_a.clone()]);
+    assert_eq!(
+        resolve("command(glob:'jj *e*')"),
+        [op_d.clone(), op_c.clone(), op_a.clone()]
+    );
+    assert_eq!(resolve("description(exact:'op B')"), vec![op_b.clone()]);
+    assert_eq!(resolve("snapshot()"), vec![op_d.clone()]);
+    assert_eq!(resolve("~snapshot() & @--.."), vec![op_c.clone()]);
+    assert_eq!(resolve("workspace(ws)"), vec![op_b.clone()]);
+    assert_eq!(resolve("touched_bookmark(main)"), vec![op_c.clone()]);
+    assert_eq!(resolve("touched_bookmark(other)"), []);
+    assert_eq!(resolve("time(before:'1970-01-02')"), vec![op_0.clone()]);
+    assert_eq!(
+        resolve("time(after:'1970-01-02') & ..@--"),
+        resolve("..@--")
+    );
+    let user = format!("{}@{}", op_a.metadata().username, op_a.metadata().hostname);
+    assert_eq!(
+        resolve(&format!("user(exact:'{user}')")).len(),
+        4 // all but the root operation
+    );
+
+    // Errors
+    assert_matches!(
+        op_walk::resolve_opset_at(op_store, slice::from_ref(&op_d), "foo("),
+        Err(OpsetEvaluationError::OpsetParse(_))
+    );
+    assert_matches!(
+        op_walk::resolve_opset_at(op_store, slice::from_ref(&op_d), "bar()"),
+        Err(OpsetEvaluationError::OpsetParse(_))
+    );
+    assert_matches!(
+        op_walk::resolve_opset_at(op_store, slice::from_ref(&op_d), "time(@)"),
+        Err(OpsetEvaluationError::OpsetParse(_))
+    );
+    assert_matches!(
+        op_walk::resolve_op_with_repo(&repo_d, "snapshot() | @-"),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::MultipleOperations { .. }
+        ))
+    );
+    assert_eq!(
+        op_walk::resolve_op_with_repo(&repo_d, "snapshot()").unwrap(),
+        op_d
+    );
+}
+
+#[test_case(TestRepoBackend::Local; "local backend")]
+#[test_case(TestRepoBackend::Git; "git backend")]
+fn test_export_import_bundle(backend: TestRepoBackend) {
+    let test_repo = TestRepo::init_with_backend(backend);
+    let repo_0 = test_repo.repo.clone();
+    let file_path = RepoPath::from_internal_string("dir/file");
+
+    let mut tx = repo_0.start_transaction();
+    let tree = create_tree(&repo_0, &[(file_path, "contents")]);
+    let commit_a = create_random_commit(tx.repo_mut())
+        .set_tree_id(tree.id())
+        .write()
+        .unwrap();
+    tx.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit_a.id().clone()));
+    let repo_1 = tx.commit("op1").unwrap();
+    let mut tx = repo_1.start_transaction();
+    let commit_b = create_random_commit(tx.repo_mut())
+        .set_parents(vec![commit_a.id().clone()])
+        .write()
+        .unwrap();
+    let repo_2 = tx.commit("op2").unwrap();
+
+    // Export all operations with objects
+    let ops: Vec<_> = op_walk::walk_ancestors(slice::from_ref(repo_2.operation()))
+        .try_collect()
+        .unwrap();
+    let mut data = vec![];
+    let stats = op_bundle::export_bundle(
+        repo_2.store(),
+        repo_2.op_store().as_ref(),
+        &ops,
+        true,
+        &mut data,
+    )
+    .unwrap();
+    assert_eq!(stats.operation_count, 2);
+    assert_eq!(stats.commit_count, 2);
+
+    let other_repo = TestRepo::init_with_backend(backend);
+    let other_repo = &other_repo.repo;
+    let imported = op_bundle::import_bundle(
+        other_repo.store(),
+        other_repo.op_store().as_ref(),
+        &data,
+        None,
+    )
+    .unwrap();
+    assert_eq!(imported.stats, stats);
+    assert_eq!(imported.head_ids, vec![repo_2.op_id().clone()]);
+    let loader = other_repo.loader();
+    let imported_repo = loader
+        .load_at(&loader.load_operation(repo_2.op_id()).unwrap())
+        .unwrap();
+    assert_eq!(
+        imported_repo.view().store_view(),
+        repo_2.view().store_view()
+    );
+    let imported_commit = imported_repo.store().get_commit(commit_b.id()).unwrap();
+    assert_eq!(imported_commit.store_commit(), commit_b.store_commit());
+    let imported_tree = imported_repo.store().get_root_tree(&tree.id()).unwrap();
+    assert_eq!(
+        imported_tree.path_value(file_path).unwrap(),
+        tree.path_value(file_path).unwrap()
+    );
+
+    // Export the last operation without objects. The operation is reparented
+    // onto the root operation.
+    let mut data = vec![];
+    let stats = op_bundle::export_bundle(
+        repo_2.store(),
+        repo_2.op_store().as_ref(),
+        slice::from_ref(repo_2.operation()),
+        false,
+        &mut data,
+    )
+    .unwrap();
+    assert_eq!(stats.operation_count, 1);
+    assert_eq!(stats.commit_count, 0);
+    let other_repo = TestRepo::init_with_backend(backend);
+    let other_repo = &other_repo.repo;
+    let imported = op_bundle::import_bundle(
+        other_repo.store(),
+        other_repo.op_store().as_ref(),
+        &data,
+        None,
+    )
+    .unwrap();
+    let [head_id] = imported.head_ids.as_slice() else {
+        panic!("unexpected heads: {:?}", imported.head_ids);
+    };
+    assert_ne!(head_id, repo_2.op_id());
+    let head_op = other_repo.loader().load_operation(head_id).unwrap();
+    assert_eq!(head_op.parent_ids(), slice::from_ref(other_repo.op_id()));
+    assert_eq!(head_op.view_id(), repo_2.operation().view_id());
+    assert!(other_repo.store().get_commit(commit_b.id()).is_err());
+}
+
+#[test]
+fn test_import_bundle_incompatible_backend() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Local);
+    let repo = &test_repo.repo;
+    let mut data = vec![];
+    op_bundle::export_bundle(
+        repo.store(),
+        repo.op_store().as_ref(),
+        slice::from_ref(repo.operation()),
+        true,
+        &mut data,
+    )
+    .unwrap();
+
+    let other_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let other_repo = &other_repo.repo;
+    assert_matches!(
+        op_bundle::import_bundle(
+            other_repo.store(),
+            other_repo.op_store().as_ref(),
+            &data,
+            None
+        ),
+        Err(OpBundleError::IncompatibleStore {
+            store_kind: "backend",
+            ..
+        })
+    );
+    assert_matches!(
+        op_bundle::import_bundle(
+            other_repo.store(),
+            other_repo.op_store().as_ref(),
+            b"garbage",
+            None
+        ),
+        Err(OpBundleError::Decode(_))
+    );
+}
+
 #[test]
 fn test_gc() {
     let settings = stable_op_id_settings();
@@ -2703,6 +2703,54 @@ fn test_evaluate_expression_description() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_trailers() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let commit1 = create_random_commit(mut_repo)
+        .set_description("commit 1\n\nReviewed-by: Alice <alice@example.com>\n")
+        .write()
+        .unwrap();
+    let commit2 = create_random_commit(mut_repo)
+        .set_parents(vec![commit1.id().clone()])
+        .set_description("commit 2\n\nChange-Id: I1234\nreviewed-by: Bob <bob@example.org>\n")
+        .write()
+        .unwrap();
+    create_random_commit(mut_repo)
+        .set_parents(vec![commit2.id().clone()])
+        .set_description("Reviewed-by: Carol <carol@example.com>\n")
+        .write()
+        .unwrap();
+
+    // Keys are matched case-insensitively
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers(Reviewed-by)"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers(change-id)"),
+        vec![commit2.id().clone()]
+    );
+    // Match value
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers(Reviewed-by, glob:'*@example.com>')"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers(Reviewed-by, exact:'Bob')"),
+        vec![]
+    );
+    // Subject line isn't parsed as trailer
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers(Reviewed-by, Carol)"),
+        vec![]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_author() {
     let test_repo = TestRepo::init();
@@ -1,14 +1,28 @@
+use std::slice;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::CommitId;
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
+use jj_lib::op_walk;
 use jj_lib::repo::Repo;
+use jj_lib::revset::RevsetEvaluationError;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::revset::SignatureFilter;
 use jj_lib::settings::UserSettings;
+use jj_lib::signing::OperationSigner;
 use jj_lib::signing::SigStatus;
 use jj_lib::signing::SignBehavior;
+use jj_lib::signing::SignError;
+use jj_lib::signing::SignResult;
 use jj_lib::signing::Signer;
+use jj_lib::signing::SigningBackend;
 use jj_lib::signing::Verification;
+use jj_lib::str_util::StringPattern;
 use jj_lib::test_signing_backend::TestSigningBackend;
 use test_case::test_case;
 use testutils::create_random_commit;
@@ -208,3 +222,248 @@ fn drop_behavior(backend: TestRepoBackend) {
     let rewritten_commit = repo.store().get_commit(rewritten.id()).unwrap();
     assert_eq!(rewritten_commit.verification().unwrap(), None);
 }
+
+/// Backend which verifies signatures as the status named by the key.
+#[derive(Debug)]
+struct StatusSigningBackend;
+
+impl SigningBackend for StatusSigningBackend {
+    fn name(&self) -> &str {
+        "status"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        signature.starts_with(b"status:")
+    }
+
+    fn sign(&self, _data: &[u8], key: Option<&str>) -> SignResult<Vec<u8>> {
+        Ok(format!("status:{}", key.unwrap()).into_bytes())
+    }
+
+    fn verify(&self, _data: &[u8], signature: &[u8]) -> SignResult<Verification> {
+        let key = std::str::from_utf8(&signature[b"status:".len()..]).unwrap();
+        let status = match key {
+            "good" => SigStatus::Good,
+            "bad" => SigStatus::Bad,
+            "unknown" => SigStatus::Unknown,
+            "invalid" => return Err(SignError::InvalidSignatureFormat),
+            _ => return Err(SignError::Backend("failed to verify".into())),
+        };
+        Ok(Verification::new(
+            status,
+            Some(key.to_owned()),
+            Some(format!("{key} signer")),
+        ))
+    }
+}
+
+#[test]
+fn signed_revsets() {
+    let settings = user_settings(SignBehavior::Drop);
+
+    let signer = Signer::new(Some(Box::new(StatusSigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Git, signer, &settings);
+    let repo = &test_workspace.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let mut create_commit = |key: Option<&str>| {
+        let builder = create_random_commit(mut_repo);
+        let builder = if let Some(key) = key {
+            builder
+                .set_sign_behavior(SignBehavior::Force)
+                .set_sign_key(key.to_owned())
+        } else {
+            builder.set_sign_behavior(SignBehavior::Drop)
+        };
+        builder.write().unwrap()
+    };
+    let unsigned = create_commit(None);
+    let good = create_commit(Some("good"));
+    let bad = create_commit(Some("bad"));
+    let unknown = create_commit(Some("unknown"));
+    let invalid = create_commit(Some("invalid"));
+
+    let mut_repo = &*tx.repo_mut();
+    let resolve = |filter: SignatureFilter| -> Vec<CommitId> {
+        let expression = RevsetExpression::filter(RevsetFilterPredicate::Signed(filter));
+        let revset = expression.evaluate(mut_repo).unwrap();
+        revset.iter().map(Result::unwrap).collect()
+    };
+    let signed = resolve(SignatureFilter::Any);
+    assert_eq!(signed.len(), 4);
+    assert!(!signed.contains(unsigned.id()));
+    assert_eq!(
+        resolve(SignatureFilter::Status(SigStatus::Good)),
+        [good.id().clone()]
+    );
+    assert_eq!(
+        resolve(SignatureFilter::Status(SigStatus::Bad)),
+        [bad.id().clone()]
+    );
+    assert_eq!(
+        resolve(SignatureFilter::Status(SigStatus::Unknown)),
+        [unknown.id().clone()]
+    );
+    assert_eq!(resolve(SignatureFilter::Invalid), [invalid.id().clone()]);
+    // Matches key or display name of good signatures
+    assert_eq!(
+        resolve(SignatureFilter::Key(StringPattern::exact("good"))),
+        [good.id().clone()]
+    );
+    assert_eq!(
+        resolve(SignatureFilter::Key(StringPattern::substring("signer"))),
+        [good.id().clone()]
+    );
+    // Bad or unknown signatures may claim any key
+    assert!(resolve(SignatureFilter::Key(StringPattern::exact("bad"))).is_empty());
+    assert!(resolve(SignatureFilter::Key(StringPattern::exact("unknown"))).is_empty());
+
+    // Errors other than invalid signatures are propagated
+    create_random_commit(tx.repo_mut())
+        .set_sign_behavior(SignBehavior::Force)
+        .set_sign_key("error".to_owned())
+        .write()
+        .unwrap();
+    let mut_repo = &*tx.repo_mut();
+    let evaluate = |filter: SignatureFilter| {
+        let expression = RevsetExpression::filter(RevsetFilterPredicate::Signed(filter));
+        let revset = expression.evaluate(mut_repo).unwrap();
+        revset.iter().collect::<Result<Vec<_>, _>>()
+    };
+    assert_eq!(evaluate(SignatureFilter::Any).unwrap().len(), 5);
+    assert_matches!(
+        evaluate(SignatureFilter::Invalid),
+        Err(RevsetEvaluationError::Other(_))
+    );
+    assert_matches!(
+        evaluate(SignatureFilter::Status(SigStatus::Good)),
+        Err(RevsetEvaluationError::Other(_))
+    );
+}
+
+#[test_case(TestRepoBackend::Local ; "local backend")]
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn sign_operations(backend: TestRepoBackend) {
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(
+            ConfigSource::User,
+            r#"
+            signing.key = "impeccable"
+            signing.sign-operations = true
+            "#,
+        )
+        .unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace = TestWorkspace::init_with_backend_and_signer(backend, signer, &settings);
+    let repo = &test_workspace.repo;
+    let signer = repo.store().signer();
+
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+    let op = repo.operation();
+    assert!(op.is_signed());
+    assert_eq!(op.verification(signer).unwrap(), good_verification());
+
+    // The signature doesn't match the operation if it's altered
+    let op_store = repo.op_store();
+    let mut data = op.store_operation().clone();
+    data.metadata.description = "tampered".to_owned();
+    let tampered_id = op_store.write_operation(&data).unwrap();
+    let tampered_op = repo.loader().load_operation(&tampered_id).unwrap();
+    assert_eq!(
+        tampered_op.verification(signer).unwrap().unwrap().status,
+        SigStatus::Bad
+    );
+
+    // Reparented operations are signed again
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let child_repo = tx.commit("child").unwrap();
+    let root_op = repo.loader().root_operation();
+    let sign_settings = settings.sign_settings();
+    let op_signer = OperationSigner::new(signer, &sign_settings);
+    assert!(op_signer.is_some());
+    let stats = op_walk::reparent_range(
+        op_store.as_ref(),
+        slice::from_ref(op),
+        slice::from_ref(child_repo.operation()),
+        &root_op,
+        op_signer.as_ref(),
+    )
+    .unwrap();
+    assert_eq!(stats.rewritten_count, 1);
+    assert_eq!(stats.unsigned_count, 0);
+    let new_op = repo
+        .loader()
+        .load_operation(&stats.new_head_ids[0])
+        .unwrap();
+    assert_eq!(new_op.parent_ids(), slice::from_ref(root_op.id()));
+    assert_eq!(new_op.verification(signer).unwrap(), good_verification());
+
+    // The signature is dropped if there's no signer
+    let stats = op_walk::reparent_range(
+        op_store.as_ref(),
+        slice::from_ref(op),
+        slice::from_ref(child_repo.operation()),
+        &root_op,
+        None,
+    )
+    .unwrap();
+    assert_eq!(stats.unsigned_count, 1);
+    let new_op = repo
+        .loader()
+        .load_operation(&stats.new_head_ids[0])
+        .unwrap();
+    assert!(!new_op.is_signed());
+}
+
+/// Backend which fails to sign.
+#[derive(Debug)]
+struct FailingSigningBackend;
+
+impl SigningBackend for FailingSigningBackend {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    fn can_read(&self, _signature: &[u8]) -> bool {
+        false
+    }
+
+    fn sign(&self, _data: &[u8], _key: Option<&str>) -> SignResult<Vec<u8>> {
+        Err(SignError::Backend("no key".into()))
+    }
+
+    fn verify(&self, _data: &[u8], _signature: &[u8]) -> SignResult<Verification> {
+        Err(SignError::InvalidSignatureFormat)
+    }
+}
+
+#[test]
+fn sign_operations_failure() {
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, "signing.sign-operations = true").unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+
+    let signer = Signer::new(Some(Box::new(FailingSigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Local, signer, &settings);
+    let repo = &test_workspace.repo;
+
+    // The operation is recorded unsigned, and the error is returned
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let unpublished_op = tx.write("test");
+    assert_matches!(unpublished_op.sign_error(), Some(SignError::Backend(_)));
+    let repo = unpublished_op.publish().unwrap();
+    assert!(!repo.operation().is_signed());
+}
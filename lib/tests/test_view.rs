@@ -14,13 +14,20 @@
 
 use std::collections::BTreeMap;
 
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
 use jj_lib::op_store::BookmarkTarget;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::RemoteRefState;
+use jj_lib::op_store::View as OpStoreView;
+use jj_lib::op_store::ViewRefName;
 use jj_lib::op_store::WorkspaceId;
 use jj_lib::refs::RemoteRefSymbol;
 use jj_lib::repo::Repo;
+use jj_lib::str_util::StringPattern;
+use jj_lib::view::View;
+use jj_lib::view::ViewPortion;
 use maplit::btreemap;
 use maplit::hashset;
 use test_case::test_case;
@@ -297,6 +304,178 @@ fn test_merge_views_bookmarks() {
     );
 }
 
+#[test]
+fn test_merge_view_portion() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let ws_id = WorkspaceId::new("ws".to_owned());
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = write_random_commit(tx.repo_mut());
+    let commit_c = write_random_commit(tx.repo_mut());
+    let remote_ref = |commit: &Commit| RemoteRef {
+        target: RefTarget::normal(commit.id().clone()),
+        state: RemoteRefState::Tracking,
+    };
+    tx.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit_a.id().clone()));
+    tx.repo_mut()
+        .set_local_bookmark_target("feature", RefTarget::normal(commit_a.id().clone()));
+    tx.repo_mut()
+        .set_remote_bookmark(remote_symbol("main", "origin"), remote_ref(&commit_a));
+    tx.repo_mut()
+        .set_wc_commit(ws_id.clone(), commit_a.id().clone())
+        .unwrap();
+    let repo_0 = tx.commit("test").unwrap();
+
+    // Operation to be reverted
+    let mut tx = repo_0.start_transaction();
+    tx.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit_b.id().clone()));
+    tx.repo_mut()
+        .set_local_bookmark_target("feature", RefTarget::normal(commit_b.id().clone()));
+    tx.repo_mut()
+        .set_remote_bookmark(remote_symbol("main", "origin"), remote_ref(&commit_b));
+    tx.repo_mut()
+        .set_wc_commit(ws_id.clone(), commit_b.id().clone())
+        .unwrap();
+    let repo_1 = tx.commit("test").unwrap();
+
+    // Later operation
+    let mut tx = repo_1.start_transaction();
+    tx.repo_mut()
+        .set_local_bookmark_target("feature", RefTarget::normal(commit_c.id().clone()));
+    let repo_2 = tx.commit("test").unwrap();
+
+    // Only the matching bookmark is reverted
+    let mut tx = repo_2.start_transaction();
+    let portion = ViewPortion::Bookmarks(StringPattern::exact("main"));
+    tx.repo_mut().merge_view_portion(&repo_1, &repo_0, &portion);
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(
+        *repo.view().get_local_bookmark("main"),
+        RefTarget::normal(commit_a.id().clone())
+    );
+    assert_eq!(
+        *repo.view().get_local_bookmark("feature"),
+        RefTarget::normal(commit_c.id().clone())
+    );
+    assert_eq!(
+        *repo
+            .view()
+            .get_remote_bookmark(remote_symbol("main", "origin")),
+        remote_ref(&commit_b)
+    );
+    assert_eq!(repo.view().get_wc_commit_id(&ws_id), Some(commit_b.id()));
+
+    // Conflicting change is recorded as conflicted bookmark
+    let mut tx = repo_2.start_transaction();
+    let portion = ViewPortion::Bookmarks(StringPattern::everything());
+    tx.repo_mut().merge_view_portion(&repo_1, &repo_0, &portion);
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(
+        *repo.view().get_local_bookmark("main"),
+        RefTarget::normal(commit_a.id().clone())
+    );
+    assert_eq!(
+        *repo.view().get_local_bookmark("feature"),
+        RefTarget::from_legacy_form(
+            [commit_b.id().clone()],
+            [commit_c.id().clone(), commit_a.id().clone()]
+        )
+    );
+
+    // Remote-tracking bookmarks
+    let mut tx = repo_2.start_transaction();
+    let portion = ViewPortion::RemoteTracking;
+    tx.repo_mut().merge_view_portion(&repo_1, &repo_0, &portion);
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(
+        *repo.view().get_local_bookmark("main"),
+        RefTarget::normal(commit_b.id().clone())
+    );
+    assert_eq!(
+        *repo
+            .view()
+            .get_remote_bookmark(remote_symbol("main", "origin")),
+        remote_ref(&commit_a)
+    );
+
+    // Working-copy commits
+    let mut tx = repo_2.start_transaction();
+    let portion = ViewPortion::WorkingCopies;
+    tx.repo_mut().merge_view_portion(&repo_1, &repo_0, &portion);
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(repo.view().get_wc_commit_id(&ws_id), Some(commit_a.id()));
+    assert_eq!(
+        *repo.view().get_local_bookmark("main"),
+        RefTarget::normal(commit_b.id().clone())
+    );
+}
+
+#[test]
+fn test_new_ref_conflicts() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = write_random_commit(tx.repo_mut());
+    let commit_c = write_random_commit(tx.repo_mut());
+    let conflict = RefTarget::from_legacy_form(
+        [commit_a.id().clone()],
+        [commit_b.id().clone(), commit_c.id().clone()],
+    );
+    let normal = RefTarget::normal(commit_a.id().clone());
+
+    let mut parent_view1 = View::new(OpStoreView::empty());
+    parent_view1.set_local_bookmark_target("already-conflicted", conflict.clone());
+    parent_view1.set_local_bookmark_target("main", normal.clone());
+    let mut parent_view2 = View::new(OpStoreView::empty());
+    parent_view2.set_local_bookmark_target("main", normal.clone());
+    let mut view = View::new(OpStoreView::empty());
+    view.set_local_bookmark_target("already-conflicted", conflict.clone());
+    view.set_local_bookmark_target("main", conflict.clone());
+    view.set_local_bookmark_target("resolved", normal.clone());
+    view.set_remote_bookmark(
+        remote_symbol("main", "origin"),
+        RemoteRef {
+            target: conflict.clone(),
+            state: RemoteRefState::New,
+        },
+    );
+    view.set_tag_target("v1", conflict.clone());
+    view.set_git_ref_target("refs/heads/main", conflict.clone());
+    view.set_git_head_target(conflict.clone());
+
+    let new_conflicts = view.new_ref_conflicts(&[&parent_view1, &parent_view2]);
+    assert_eq!(
+        new_conflicts
+            .iter()
+            .map(|name| name.to_string())
+            .collect_vec(),
+        [
+            "bookmark main",
+            "remote bookmark main@origin",
+            "tag v1",
+            "git ref refs/heads/main",
+            "git HEAD",
+        ]
+    );
+    assert_eq!(
+        *view.get_ref_target(&ViewRefName::LocalBookmark("main".to_owned())),
+        conflict
+    );
+    assert_eq!(
+        *view.get_ref_target(&ViewRefName::RemoteBookmark(
+            remote_symbol("main", "origin").to_owned()
+        )),
+        conflict
+    );
+    assert!(view.new_ref_conflicts(&[&view]).is_empty());
+}
+
 #[test]
 fn test_merge_views_tags() {
     // Tests merging of tags (by performing divergent operations). See
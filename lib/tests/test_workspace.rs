@@ -92,6 +92,51 @@ fn test_init_additional_workspace() {
     assert_eq!(same_workspace.workspace_root(), ws2.workspace_root());
 }
 
+#[test]
+fn test_init_sandbox_workspace() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init_with_settings(&settings);
+    let repo = &test_workspace.repo;
+    assert!(!test_workspace.workspace.is_sandbox());
+
+    let sandbox_id = WorkspaceId::new("sandbox".to_string());
+    let sandbox_root = test_workspace.root_dir().join("sandbox_root");
+    std::fs::create_dir(&sandbox_root).unwrap();
+    let (sandbox, sandbox_repo) = Workspace::init_sandbox_with_existing_repo(
+        &sandbox_root,
+        test_workspace.repo_path(),
+        repo,
+        &*default_working_copy_factory(),
+        sandbox_id.clone(),
+    )
+    .unwrap();
+    assert!(sandbox.is_sandbox());
+    assert_eq!(
+        sandbox_repo.operation().parent_ids(),
+        [repo.op_id().clone()]
+    );
+    assert!(sandbox_repo.view().get_wc_commit_id(&sandbox_id).is_some());
+
+    // The operation isn't visible at the repo's head until merged
+    let head_repo = repo.reload_at_head().unwrap();
+    assert_eq!(head_repo.op_id(), repo.op_id());
+    let same_sandbox = Workspace::load(
+        &settings,
+        &sandbox_root,
+        &test_workspace.env.default_store_factories(),
+        &default_working_copy_factories(),
+    )
+    .unwrap();
+    assert!(same_sandbox.is_sandbox());
+    let reloaded_repo = same_sandbox.repo_loader().load_at_head().unwrap();
+    assert_eq!(reloaded_repo.op_id(), sandbox_repo.op_id());
+
+    let merged_op_ids = same_sandbox.merge_sandbox_op_heads(repo.loader()).unwrap();
+    assert_eq!(merged_op_ids, [sandbox_repo.op_id().clone()]);
+    let head_repo = repo.reload_at_head().unwrap();
+    assert_eq!(head_repo.op_id(), sandbox_repo.op_id());
+}
+
 /// Test cross-thread access to a workspace, which requires it to be Send
 #[test]
 fn test_sendable() {